@@ -1,5 +1,6 @@
 pub mod full_featured;
 pub mod non_unique_index;
+pub mod page_barrier;
 pub mod simple;
 pub mod unique_index;
 pub mod update_contention;
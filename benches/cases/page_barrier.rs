@@ -0,0 +1,69 @@
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+use tokio::task::JoinSet;
+
+use crate::common::*;
+
+/// Rows inserted per writer, more than a data page holds, so that the
+/// first row of each writer's range is on a page of its own.
+const ROWS_PER_WRITER: u64 = 1_000;
+
+/// In-place updates of distinct rows, each writer on its own row. Writes take
+/// the barrier stripe of the row's page: rows on one page share a stripe, as
+/// every write shared the single barrier before it was striped, so `one_page`
+/// is the baseline `distinct_pages` is compared with.
+fn page_barrier_contention(c: &mut Criterion) {
+    let mut group = c.benchmark_group("page_barrier_contention");
+
+    let rt = Runtime::new().unwrap();
+    let table = Arc::new(FullFeaturedWorkTable::default());
+
+    let pks: Vec<u64> = rt.block_on(async {
+        (0..32 * ROWS_PER_WRITER)
+            .map(|i| {
+                let row = FullFeaturedRow {
+                    id: table.get_next_pk().into(),
+                    val: 0,
+                    val1: i,
+                    another: "test".to_string(),
+                    something: 0,
+                };
+                table.insert(row).unwrap().into()
+            })
+            .collect()
+    });
+
+    for writers in [2, 4, 8, 16, 32] {
+        group.throughput(Throughput::Elements(writers as u64));
+
+        let one_page = pks[..writers].to_vec();
+        let distinct_pages = pks
+            .iter()
+            .step_by(ROWS_PER_WRITER as usize)
+            .take(writers)
+            .copied()
+            .collect::<Vec<_>>();
+        for (name, rows) in [("one_page", one_page), ("distinct_pages", distinct_pages)] {
+            group.bench_with_input(BenchmarkId::new(name, writers), &rows, |b, rows| {
+                b.to_async(&rt).iter(|| async {
+                    let mut join_set = JoinSet::new();
+                    for &pk in rows {
+                        let table_clone = table.clone();
+                        join_set.spawn(async move {
+                            black_box(table_clone.update_val_by_id_in_place(|val| *val += 1, pk).await)
+                        });
+                    }
+                    while join_set.join_next().await.is_some() {}
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = page_barrier_benchmarks;
+    config = crate::common::config::configure_criterion();
+    targets = page_barrier_contention,
+}
@@ -63,8 +63,8 @@ impl Columns {
                     gen_type = Some(row.gen_type)
                 }
                 let backend = row.index_backend.unwrap_or_default();
-                if let Some(existing) = primary_index_backend {
-                    if existing != backend {
+                if let Some(existing) = &primary_index_backend {
+                    if *existing != backend {
                         return Err(syn::Error::new(
                             row.name.span(),
                             "all columns in a composite primary key must use the same index backend",
@@ -1,7 +1,61 @@
-use proc_macro2::Ident;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{ToTokens, quote};
 
 #[derive(Debug, Default)]
 pub struct Config {
     pub page_size: Option<u32>,
     pub row_derives: Vec<Ident>,
+    pub compression: Option<Compression>,
+    pub memory_limit: Option<u64>,
+    pub on_memory_limit: Option<(OnMemoryLimit, Span)>,
+}
+
+impl Config {
+    /// `MemoryBudget` expression for `memory_limit` and `on_memory_limit`.
+    pub fn memory_budget(&self) -> Option<TokenStream> {
+        let limit = self.memory_limit?;
+        let policy = self.on_memory_limit.map(|(policy, _)| policy).unwrap_or_default();
+        Some(quote! {
+            MemoryBudget {
+                limit_bytes: #limit,
+                policy: #policy,
+            }
+        })
+    }
+
+    pub fn evicts_pages(&self) -> bool {
+        self.memory_limit.is_some() && matches!(self.on_memory_limit, Some((OnMemoryLimit::Evict, _)))
+    }
+}
+
+/// Data page compression, see `worktable::prelude::PageCompression`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compression {
+    Lz4,
+}
+
+impl ToTokens for Compression {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Self::Lz4 => quote! { PageCompression::Lz4 }.to_tokens(tokens),
+        }
+    }
+}
+
+/// What a table does over its `memory_limit`, see
+/// `worktable::prelude::MemoryLimitPolicy`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OnMemoryLimit {
+    #[default]
+    Reject,
+    Evict,
+}
+
+impl ToTokens for OnMemoryLimit {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Self::Reject => quote! { MemoryLimitPolicy::Reject }.to_tokens(tokens),
+            Self::Evict => quote! { MemoryLimitPolicy::EvictColdPages }.to_tokens(tokens),
+        }
+    }
 }
@@ -0,0 +1,12 @@
+use syn::Path;
+
+/// Functions declared in the `hooks` section of a table.
+#[derive(Debug, Default)]
+pub struct Hooks {
+    pub before_insert: Option<Path>,
+    pub after_insert: Option<Path>,
+    pub before_update: Option<Path>,
+    pub after_update: Option<Path>,
+    pub before_delete: Option<Path>,
+    pub after_delete: Option<Path>,
+}
@@ -5,26 +5,40 @@ use proc_macro2::Ident;
 /// `WorktablesIndex` is deliberately the default so existing declarations keep
 /// their current implementation and persistence semantics when `using` is
 /// absent. Vanilla upstream IndexSet is an explicit, parallel backend.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum IndexBackend {
     #[default]
     WorktablesIndex,
     Indexset,
     Congee,
     Arctic,
+    /// Unordered point-lookup map. Only unique indexes can use it, and the
+    /// table omits range queries over it.
+    Hash,
+    /// User-supplied unique map type, written without its `<Key, Link>`
+    /// parameters. The table asserts the backend trait set at compile time.
+    Custom(syn::Path),
 }
 
 impl IndexBackend {
-    pub fn requires_explicit_persistence(self) -> bool {
-        matches!(self, Self::Congee | Self::Arctic)
+    pub fn requires_explicit_persistence(&self) -> bool {
+        matches!(self, Self::Congee | Self::Arctic | Self::Hash | Self::Custom(_))
     }
 
-    pub fn name(self) -> &'static str {
+    /// Whether the generated table exposes `select_by_*_range` and
+    /// `delete_by_*_range` over this index.
+    pub fn supports_range(&self) -> bool {
+        *self != Self::Hash
+    }
+
+    pub fn name(&self) -> &'static str {
         match self {
             Self::WorktablesIndex => "worktables_index",
             Self::Indexset => "indexset",
             Self::Congee => "congee",
             Self::Arctic => "arctic",
+            Self::Hash => "hash",
+            Self::Custom(_) => "custom",
         }
     }
 }
@@ -34,5 +48,8 @@ pub struct Index {
     pub name: Ident,
     pub field: Ident,
     pub is_unique: bool,
+    /// Keeps a Bloom filter of the keys in front of a unique index, so
+    /// lookups of absent keys skip the index.
+    pub filter: bool,
     pub backend: IndexBackend,
 }
@@ -1,5 +1,6 @@
 mod column;
 mod config;
+mod hooks;
 mod index;
 pub mod operation;
 mod persistence;
@@ -7,7 +8,8 @@ mod primary_key;
 mod queries;
 
 pub use column::{Columns, Row};
-pub use config::Config;
+pub use config::{Compression, Config, OnMemoryLimit};
+pub use hooks::Hooks;
 pub use index::{Index, IndexBackend};
 pub use operation::Operation;
 pub use persistence::Persistence;
@@ -4,7 +4,7 @@ use proc_macro2::{Delimiter, TokenTree};
 use syn::spanned::Spanned;
 
 use crate::common::Parser;
-use crate::common::model::Config;
+use crate::common::model::{Compression, Config, OnMemoryLimit};
 
 const CONFIG_FIELD_NAME: &str = "config";
 
@@ -49,6 +49,9 @@ impl Parser {
         let mut parser = Parser::new(tt);
         let mut config = Config::default();
         parser.parse_config(&mut config)?;
+        if let (None, Some((_, span))) = (config.memory_limit, config.on_memory_limit) {
+            return Err(syn::Error::new(span, "`on_memory_limit` requires `memory_limit`"));
+        }
 
         Ok(config)
     }
@@ -86,8 +89,81 @@ impl Parser {
 
                     config.page_size = Some(u32::from_str(value.as_str()).unwrap())
                 }
+                "compression" => {
+                    let value = self.input_iter.next().ok_or(syn::Error::new(
+                        self.input.span(),
+                        "Expected compression value in declaration",
+                    ))?;
+                    let value = if let TokenTree::Ident(value) = value {
+                        value
+                    } else {
+                        return Err(syn::Error::new(value.span(), "Expected identifier."));
+                    };
+
+                    self.try_parse_comma()?;
+
+                    config.compression = match value.to_string().as_str() {
+                        "lz4" => Some(Compression::Lz4),
+                        other => {
+                            return Err(syn::Error::new(
+                                value.span(),
+                                format!("Unknown compression `{other}`, expected `lz4`"),
+                            ));
+                        }
+                    };
+                }
+                "memory_limit" => {
+                    let value = self.input_iter.next().ok_or(syn::Error::new(
+                        self.input.span(),
+                        "Expected memory limit value in declaration",
+                    ))?;
+                    let value = if let TokenTree::Literal(value) = value {
+                        value
+                    } else {
+                        return Err(syn::Error::new(value.span(), "Expected literal."));
+                    };
+
+                    self.try_parse_comma()?;
+
+                    let bytes = value.to_string().replace("_", "");
+                    config.memory_limit = Some(
+                        u64::from_str(bytes.as_str())
+                            .map_err(|_| syn::Error::new(value.span(), "Expected memory limit in bytes"))?,
+                    );
+                }
+                "on_memory_limit" => {
+                    let value = self.input_iter.next().ok_or(syn::Error::new(
+                        self.input.span(),
+                        "Expected memory limit policy in declaration",
+                    ))?;
+                    let value = if let TokenTree::Ident(value) = value {
+                        value
+                    } else {
+                        return Err(syn::Error::new(value.span(), "Expected identifier."));
+                    };
+
+                    self.try_parse_comma()?;
+
+                    let policy = match value.to_string().as_str() {
+                        "reject" => OnMemoryLimit::Reject,
+                        "evict" => OnMemoryLimit::Evict,
+                        other => {
+                            return Err(syn::Error::new(
+                                value.span(),
+                                format!("Unknown memory limit policy `{other}`, expected `reject` or `evict`"),
+                            ));
+                        }
+                    };
+                    config.on_memory_limit = Some((policy, value.span()));
+                }
                 "row_derives" => {
-                    const CONFIG_VARIANTS: [&str; 2] = ["page_size", "row_derives"];
+                    const CONFIG_VARIANTS: [&str; 5] = [
+                        "page_size",
+                        "row_derives",
+                        "compression",
+                        "memory_limit",
+                        "on_memory_limit",
+                    ];
 
                     let mut derives = vec![];
 
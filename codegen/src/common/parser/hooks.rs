@@ -0,0 +1,100 @@
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use syn::spanned::Spanned;
+
+use crate::common::Parser;
+use crate::common::model::Hooks;
+
+const HOOKS_FIELD_NAME: &str = "hooks";
+
+impl Parser {
+    pub fn parse_hooks(&mut self) -> syn::Result<Hooks> {
+        let ident = self.input_iter.next().ok_or(syn::Error::new(
+            self.input.span(),
+            format!("Expected `{HOOKS_FIELD_NAME}` field in declaration"),
+        ))?;
+
+        if let TokenTree::Ident(ident) = ident {
+            if ident.to_string().as_str() != HOOKS_FIELD_NAME {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!("Expected `{HOOKS_FIELD_NAME}` field in declaration"),
+                ));
+            }
+        } else {
+            return Err(syn::Error::new(ident.span(), "Expected field name identifier."));
+        };
+
+        self.parse_colon()?;
+
+        let tt = {
+            let group = self.input_iter.next().ok_or(syn::Error::new(
+                self.input.span(),
+                format!("Expected `{HOOKS_FIELD_NAME}` declarations"),
+            ))?;
+            if let TokenTree::Group(group) = group {
+                if group.delimiter() != Delimiter::Brace {
+                    return Err(syn::Error::new(group.span(), "Expected brace"));
+                }
+                group.stream()
+            } else {
+                return Err(syn::Error::new(
+                    group.span(),
+                    format!("Expected `{HOOKS_FIELD_NAME}` declarations"),
+                ));
+            }
+        };
+
+        let mut parser = Parser::new(tt);
+        let mut hooks = Hooks::default();
+        parser.parse_hook_declarations(&mut hooks)?;
+
+        self.try_parse_comma()?;
+
+        Ok(hooks)
+    }
+
+    fn parse_hook_declarations(&mut self, hooks: &mut Hooks) -> syn::Result<()> {
+        while let Some(ident) = self.input_iter.next() {
+            let name = if let TokenTree::Ident(ident) = ident {
+                ident
+            } else {
+                return Err(syn::Error::new(ident.span(), "Expected identifier."));
+            };
+
+            self.parse_colon()?;
+
+            let mut path = TokenStream::new();
+            while let Some(tt) = self.input_iter.peek() {
+                if matches!(tt, TokenTree::Punct(p) if p.as_char() == ',') {
+                    break;
+                }
+                path.extend(self.input_iter.next());
+            }
+            if path.is_empty() {
+                return Err(syn::Error::new(name.span(), "Expected hook function path"));
+            }
+            let path: syn::Path = syn::parse2(path)?;
+
+            self.try_parse_comma()?;
+
+            let slot = match name.to_string().as_str() {
+                "before_insert" => &mut hooks.before_insert,
+                "after_insert" => &mut hooks.after_insert,
+                "before_update" => &mut hooks.before_update,
+                "after_update" => &mut hooks.after_update,
+                "before_delete" => &mut hooks.before_delete,
+                "after_delete" => &mut hooks.after_delete,
+                _ => return Err(syn::Error::new(name.span(), "Unexpected identifier")),
+            };
+            if slot.is_some() {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("`{name}` hook is declared more than once"),
+                ));
+            }
+            *slot = Some(path);
+        }
+
+        Ok(())
+    }
+}
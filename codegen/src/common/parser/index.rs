@@ -18,7 +18,7 @@ impl Parser {
         let backend = self.input_iter.next().ok_or_else(|| {
             syn::Error::new(
                 using_span,
-                "expected an index backend after `using`: `worktables_index`, `indexset`, `congee`, or `arctic`",
+                "expected an index backend after `using`: `worktables_index`, `indexset`, `congee`, `arctic`, `hash`, or `custom(Type)`",
             )
         })?;
         let TokenTree::Ident(backend) = backend else {
@@ -33,13 +33,36 @@ impl Parser {
             "indexset" => Ok(Some(IndexBackend::Indexset)),
             "congee" => Ok(Some(IndexBackend::Congee)),
             "arctic" => Ok(Some(IndexBackend::Arctic)),
+            "hash" => Ok(Some(IndexBackend::Hash)),
+            "custom" => self.parse_custom_index_backend(&backend).map(Some),
             _ => Err(syn::Error::new(
                 backend.span(),
-                "unknown index backend; expected `worktables_index`, `indexset`, `congee`, or `arctic`",
+                "unknown index backend; expected `worktables_index`, `indexset`, `congee`, `arctic`, `hash`, or `custom(Type)`",
             )),
         }
     }
 
+    fn parse_custom_index_backend(&mut self, custom: &Ident) -> syn::Result<IndexBackend> {
+        let Some(TokenTree::Group(group)) = self
+            .input_iter
+            .next_if(|tt| matches!(tt, TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis))
+        else {
+            return Err(syn::Error::new(
+                custom.span(),
+                "expected the index type in parentheses: `custom(path::to::Index)`",
+            ));
+        };
+        let path = syn::parse2::<syn::Path>(group.stream())?;
+        let last = path.segments.last().expect("a parsed path has a segment");
+        if !last.arguments.is_none() {
+            return Err(syn::Error::new_spanned(
+                &last.arguments,
+                "write the custom index type without generic arguments; the table supplies `<Key, Link>`",
+            ));
+        }
+        Ok(IndexBackend::Custom(path))
+    }
+
     pub fn parse_indexes(&mut self) -> syn::Result<IndexMap<Ident, Index>> {
         let ident = self.input_iter.next().ok_or(syn::Error::new(
             self.input.span(),
@@ -121,6 +144,23 @@ impl Parser {
             false
         };
 
+        let filter = if let Some(TokenTree::Ident(filter)) = self.input_iter.peek() {
+            if filter.to_string().as_str() == "filter" {
+                if !is_unique {
+                    return Err(syn::Error::new(
+                        filter.span(),
+                        "`filter` is only supported on unique indexes: `value_idx: value unique filter`",
+                    ));
+                }
+                self.input_iter.next();
+                true
+            } else {
+                false
+            }
+        } else {
+            false
+        };
+
         let backend = self.try_parse_index_backend()?.unwrap_or_default();
 
         self.try_parse_comma()?;
@@ -131,6 +171,7 @@ impl Parser {
                 name: ident,
                 field: row_name,
                 is_unique,
+                filter,
                 backend,
             },
         ))
@@ -164,6 +205,7 @@ mod tests {
             ),
             (quote! { value_idx: value unique using congee, }, IndexBackend::Congee),
             (quote! { value_idx: value unique using arctic, }, IndexBackend::Arctic),
+            (quote! { value_idx: value unique using hash, }, IndexBackend::Hash),
         ] {
             let mut parser = Parser::new(tokens);
             let (_, index) = parser.parse_index().unwrap();
@@ -171,6 +213,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_custom_backend_path() {
+        let mut parser = Parser::new(quote! { value_idx: value unique using custom(my_crate::SkipIndex), });
+        let (_, index) = parser.parse_index().unwrap();
+        assert_eq!(
+            index.backend,
+            IndexBackend::Custom(syn::parse_quote!(my_crate::SkipIndex))
+        );
+
+        let mut parser = Parser::new(quote! { value_idx: value unique using custom, });
+        let error = parser.parse_index().unwrap_err();
+        assert!(error.to_string().contains("`custom(path::to::Index)`"));
+
+        let mut parser = Parser::new(quote! { value_idx: value unique using custom(SkipIndex<u64>), });
+        let error = parser.parse_index().unwrap_err();
+        assert!(error.to_string().contains("without generic arguments"));
+    }
+
+    #[test]
+    fn parses_filter_on_unique_indexes_only() {
+        let mut parser = Parser::new(quote! { value_idx: value unique filter using hash, });
+        let (_, index) = parser.parse_index().unwrap();
+        assert!(index.filter);
+        assert_eq!(index.backend, IndexBackend::Hash);
+
+        let mut parser = Parser::new(quote! { value_idx: value unique, });
+        let (_, index) = parser.parse_index().unwrap();
+        assert!(!index.filter);
+
+        let mut parser = Parser::new(quote! { value_idx: value filter, });
+        let error = parser.parse_index().unwrap_err();
+        assert!(error.to_string().contains("only supported on unique indexes"));
+    }
+
     #[test]
     fn rejects_unknown_backend() {
         let mut parser = Parser::new(quote! { value_idx: value unique using unknown, });
@@ -1,6 +1,7 @@
 mod attribute;
 mod columns;
 mod config;
+mod hooks;
 mod index;
 mod name;
 mod punct;
@@ -0,0 +1,92 @@
+use std::fmt::Display;
+
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+
+/// Variant of a mutation method that may wait for a row lock. Each such
+/// method is generated once per flavor: the async method, and a
+/// `<name>_blocking` twin that parks the calling thread instead of suspending,
+/// so it can be called from plain threads without an async runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Flavor {
+    Async,
+    Blocking,
+}
+
+impl Flavor {
+    pub(crate) const ALL: [Flavor; 2] = [Flavor::Async, Flavor::Blocking];
+
+    pub(crate) fn is_async(self) -> bool {
+        self == Flavor::Async
+    }
+
+    /// `async` for the async flavor, nothing for the blocking one.
+    pub(crate) fn asyncness(self) -> TokenStream {
+        match self {
+            Flavor::Async => quote! { async },
+            Flavor::Blocking => quote! {},
+        }
+    }
+
+    /// `.await` for the async flavor, nothing for the blocking one.
+    pub(crate) fn await_(self) -> TokenStream {
+        match self {
+            Flavor::Async => quote! { .await },
+            Flavor::Blocking => quote! {},
+        }
+    }
+
+    /// Name of the method `name` in this flavor.
+    pub(crate) fn ident(self, name: impl Display) -> Ident {
+        let name = name.to_string();
+        match self {
+            Flavor::Async => format_ident!("{}", name),
+            Flavor::Blocking => format_ident!("{}_blocking", name),
+        }
+    }
+
+    /// Doc lines appended to the docs of the public method `name`.
+    pub(crate) fn doc(self, name: impl Display) -> TokenStream {
+        match self {
+            Flavor::Async => quote! {},
+            Flavor::Blocking => {
+                let doc = format!(
+                    " Blocking version of [`Self::{name}`]. Parks the calling thread while \
+                     it waits for row locks, so it needs no async runtime."
+                );
+                quote! {
+                    #[doc = ""]
+                    #[doc = #doc]
+                }
+            }
+        }
+    }
+
+    /// Waits until the operation `op_lock` registered on the row `pk` may
+    /// run.
+    pub(crate) fn wait_for(self) -> TokenStream {
+        match self {
+            Flavor::Async => quote! {
+                self.0.lock_manager.wait_for(&pk, &op_lock, locks).await?;
+            },
+            Flavor::Blocking => quote! {
+                self.0.lock_manager.wait_for_blocking(&pk, &op_lock, locks)?;
+            },
+        }
+    }
+
+    /// Lets other tasks or threads run before retrying.
+    pub(crate) fn yield_now(self) -> TokenStream {
+        match self {
+            Flavor::Async => quote! { tokio::task::yield_now().await },
+            Flavor::Blocking => quote! { std::thread::yield_now() },
+        }
+    }
+
+    pub(crate) fn sleep(self, duration: TokenStream) -> TokenStream {
+        match self {
+            Flavor::Async => quote! { tokio::time::sleep(#duration).await },
+            Flavor::Blocking => quote! { std::thread::sleep(#duration) },
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+
+use crate::common::model::Hooks;
+
+/// Generates the hidden `table_hooks` constructor used wherever the table's
+/// inner `WorkTable` is built, so every construction path gets the hooks
+/// declared in the `hooks` section.
+pub(crate) fn gen_table_hooks_fn(hooks: Option<&Hooks>, row_type: &Ident) -> TokenStream {
+    let default = Hooks::default();
+    let hooks = hooks.unwrap_or(&default);
+    let slot = |path: &Option<syn::Path>| match path {
+        Some(path) => quote! { Some(#path) },
+        None => quote! { None },
+    };
+    let before_insert = slot(&hooks.before_insert);
+    let after_insert = slot(&hooks.after_insert);
+    let before_update = slot(&hooks.before_update);
+    let after_update = slot(&hooks.after_update);
+    let before_delete = slot(&hooks.before_delete);
+    let after_delete = slot(&hooks.after_delete);
+
+    quote! {
+        #[doc(hidden)]
+        pub fn table_hooks() -> TableHooks<#row_type> {
+            TableHooks {
+                before_insert: #before_insert,
+                after_insert: #after_insert,
+                before_update: #before_update,
+                after_update: #after_update,
+                before_delete: #before_delete,
+                after_delete: #after_delete,
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@ use quote::quote;
 
 use crate::common::name_generator::WorktableNameGenerator;
 use crate::generators::in_memory::InMemoryGenerator;
+use crate::generators::statistics::{index_info_distinct_keys, index_info_params};
 
 impl InMemoryGenerator {
     pub fn gen_secondary_index_info_impl_def(&mut self) -> TokenStream {
@@ -25,48 +26,61 @@ impl InMemoryGenerator {
             let index_field_name = &idx.name;
             let index_name_str = index_field_name.to_string();
 
-            if idx.is_unique {
-                let (capacity, node_count) = match idx.backend {
-                    crate::common::model::IndexBackend::WorktablesIndex
-                    | crate::common::model::IndexBackend::Indexset => (
-                        quote! { self.#index_field_name.capacity() },
-                        quote! { self.#index_field_name.node_count() },
-                    ),
-                    crate::common::model::IndexBackend::Congee | crate::common::model::IndexBackend::Arctic => (
-                        // Neither ART exposes allocator capacity or internal
-                        // node counts through its stable public API.
-                        quote! { self.#index_field_name.len() },
-                        quote! { 0 },
-                    ),
-                };
-                quote! {
-                    info.push(IndexInfo {
-                        name: #index_name_str.to_string(),
-                        index_type: IndexKind::Unique,
-                        key_count: self.#index_field_name.len(),
-                        capacity: #capacity,
-                        heap_size: self.#index_field_name.heap_size(),
-                        used_size: self.#index_field_name.used_size(),
-                        node_count: #node_count,
-                    });
+            let (capacity, node_count) = match idx.backend {
+                crate::common::model::IndexBackend::WorktablesIndex | crate::common::model::IndexBackend::Indexset => (
+                    quote! { self.#index_field_name.capacity() },
+                    quote! { self.#index_field_name.node_count() },
+                ),
+                crate::common::model::IndexBackend::Congee | crate::common::model::IndexBackend::Arctic => (
+                    // Neither ART exposes allocator capacity or internal
+                    // node counts through its stable public API.
+                    quote! { self.#index_field_name.len() },
+                    quote! { 0 },
+                ),
+                // A hash map has no nodes; its capacity is the shards' slot count.
+                crate::common::model::IndexBackend::Hash => {
+                    (quote! { self.#index_field_name.capacity() }, quote! { 0 })
                 }
-            } else {
-                quote! {
-                    info.push(IndexInfo {
-                        name: #index_name_str.to_string(),
-                        index_type: IndexKind::NonUnique,
-                        key_count: self.#index_field_name.len(),
-                        capacity: self.#index_field_name.capacity(),
-                        heap_size: self.#index_field_name.heap_size(),
-                        used_size: self.#index_field_name.used_size(),
-                        node_count: self.#index_field_name.node_count(),
-                    });
+                // The custom backend trait set has no notion of capacity or nodes.
+                crate::common::model::IndexBackend::Custom(_) => {
+                    (quote! { self.#index_field_name.len() }, quote! { 0 })
                 }
+            };
+            let filter_size = if idx.filter {
+                quote! { self.#index_field_name.filter_size() }
+            } else {
+                quote! { 0 }
+            };
+            let index_type = if idx.is_unique {
+                quote! { IndexKind::Unique }
+            } else {
+                quote! { IndexKind::NonUnique }
+            };
+            let distinct_keys = index_info_distinct_keys(idx);
+            quote! {
+                let distinct_keys = #distinct_keys;
+                info.push(IndexInfo {
+                    name: #index_name_str.to_string(),
+                    index_type: #index_type,
+                    key_count: self.#index_field_name.len(),
+                    capacity: #capacity,
+                    heap_size: self.#index_field_name.heap_size(),
+                    used_size: self.#index_field_name.used_size(),
+                    node_count: #node_count,
+                    filter_size: #filter_size,
+                    distinct_keys,
+                    avg_duplicates: if distinct_keys == 0 {
+                        0.0
+                    } else {
+                        self.#index_field_name.len() as f64 / distinct_keys as f64
+                    },
+                });
             }
         });
 
+        let params = index_info_params(self.columns.indexes.values());
         quote! {
-            fn index_info(&self) -> Vec<IndexInfo> {
+            fn index_info(&self, #params) -> Vec<IndexInfo> {
                 let mut info = Vec::new();
                 #(#rows)*
                 info
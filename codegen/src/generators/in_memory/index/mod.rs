@@ -4,7 +4,10 @@ mod usual;
 
 use crate::common::name_generator::{WorktableNameGenerator, is_float, is_unsized};
 use crate::generators::in_memory::InMemoryGenerator;
-use crate::generators::index_backend::unique_index_type;
+use crate::generators::index_backend::{
+    custom_index_assertions, filtered_index_type, multi_index_type, unique_index_init, unique_index_type,
+};
+use crate::generators::integrity::secondary_index_integrity_impl;
 use convert_case::{Case, Casing};
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -15,6 +18,12 @@ impl InMemoryGenerator {
         let type_def = self.gen_type_def()?;
         let impl_def = self.gen_secondary_index_impl_def();
         let info_def = self.gen_secondary_index_info_impl_def();
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let integrity_def = secondary_index_integrity_impl(
+            &name_generator.get_index_type_ident(),
+            &name_generator.get_row_type_ident(),
+            &self.columns,
+        );
         let cdc_impl_def = if false {
             self.gen_secondary_index_cdc_impl_def()
         } else {
@@ -27,6 +36,7 @@ impl InMemoryGenerator {
             #type_def
             #impl_def
             #info_def
+            #integrity_def
             #cdc_impl_def
             #default_impl
             #available_indexes
@@ -35,11 +45,12 @@ impl InMemoryGenerator {
 
     /// Generates table's secondary index struct definition. It has fields with index names and types varying on index
     /// uniqueness. For unique index it's `TreeIndex<T, Link`, for non-unique `TreeIndex<T, Arc<LockFreeSet<Link>>>`.
-    /// Index also derives `PersistIndex` and `MemStat` macro.
+    /// Index also derives `PersistIndex` and `MemStat` macro. Custom backends
+    /// are followed by the assertions of their trait set.
     fn gen_type_def(&mut self) -> syn::Result<TokenStream> {
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let ident = name_generator.get_index_type_ident();
-        let index_rows = self
+        let (index_rows, assertions): (Vec<_>, Vec<_>) = self
             .columns
             .indexes
             .iter()
@@ -57,7 +68,6 @@ impl InMemoryGenerator {
                 };
                 let i = &idx.name;
 
-                #[allow(clippy::collapsible_else_if)]
                 let res = if idx.is_unique {
                     let value_type = quote! { OffsetEqLink };
                     let worktables_node = if is_unsized(&t.to_string()) {
@@ -65,18 +75,25 @@ impl InMemoryGenerator {
                     } else {
                         None
                     };
-                    let index_type = unique_index_type(idx.backend, &t, &value_type, worktables_node)?;
-                    quote! { #i: #index_type }
+                    let index_type = unique_index_type(&idx.backend, &t, &value_type, worktables_node)?;
+                    let index_type = filtered_index_type(idx, &t, index_type);
+                    let assertions = custom_index_assertions(&idx.backend, &t, &value_type, false);
+                    (quote! { #i: #index_type }, assertions)
                 } else {
-                    if is_unsized(&t.to_string()) {
-                        quote! {#i: IndexMultiMap<#t, OffsetEqLink, UnsizedNode<IndexMultiPair<#t, OffsetEqLink>>>}
+                    let value_type = quote! { OffsetEqLink };
+                    let worktables_node = if is_unsized(&t.to_string()) {
+                        Some(quote! { UnsizedNode<IndexMultiPair<#t, OffsetEqLink>> })
                     } else {
-                        quote! {#i: IndexMultiMap<#t, OffsetEqLink>}
-                    }
+                        None
+                    };
+                    let index_type = multi_index_type(&idx.backend, &t, &value_type, worktables_node)?;
+                    (quote! { #i: #index_type }, quote! {})
                 };
                 Ok::<_, syn::Error>(res)
             })
-            .collect::<Result<Vec<_>, syn::Error>>()?;
+            .collect::<Result<Vec<_>, syn::Error>>()?
+            .into_iter()
+            .unzip();
 
         let derive = if false {
             if false {
@@ -100,6 +117,8 @@ impl InMemoryGenerator {
             pub struct #ident {
                 #(#index_rows),*
             }
+
+            #(#assertions)*
         })
     }
 
@@ -128,33 +147,43 @@ impl InMemoryGenerator {
 
                 #[allow(clippy::collapsible_else_if)]
                 let res = if idx.is_unique {
-                    match idx.backend {
+                    let init = match idx.backend {
                         crate::common::model::IndexBackend::WorktablesIndex => {
                             if is_unsized(&t.to_string()) {
-                                quote! { #i: IndexMap::with_maximum_node_size(#const_name), }
+                                quote! { IndexMap::with_maximum_node_size(#const_name) }
                             } else {
                                 quote! {
-                                    #i: IndexMap::with_maximum_node_size(
+                                    IndexMap::with_maximum_node_size(
                                         get_index_page_size_from_data_length::<#t>(#const_name)
-                                    ),
+                                    )
                                 }
                             }
                         }
                         crate::common::model::IndexBackend::Indexset => quote! {
-                            #i: UpstreamIndexMap::with_maximum_node_size(
+                            UpstreamIndexMap::with_maximum_node_size(
                                 get_index_page_size_from_data_length::<#t>(#const_name)
-                            ),
+                            )
                         },
+                        crate::common::model::IndexBackend::Congee
+                        | crate::common::model::IndexBackend::Arctic
+                        | crate::common::model::IndexBackend::Hash
+                        | crate::common::model::IndexBackend::Custom(_) => {
+                            quote! { Default::default() }
+                        }
+                    };
+                    unique_index_init(idx, init)
+                } else {
+                    match idx.backend {
                         crate::common::model::IndexBackend::Congee
                         | crate::common::model::IndexBackend::Arctic => {
                             quote! { #i: Default::default(), }
                         }
-                    }
-                } else {
-                    if is_unsized(&t.to_string()) {
-                        quote! {#i: IndexMultiMap::with_maximum_node_size(#const_name), }
-                    } else {
-                        quote! {#i: IndexMultiMap::with_maximum_node_size(get_index_page_size_from_data_length::<#t>(#const_name)),}
+                        _ if is_unsized(&t.to_string()) => {
+                            quote! {#i: IndexMultiMap::with_maximum_node_size(#const_name), }
+                        }
+                        _ => {
+                            quote! {#i: IndexMultiMap::with_maximum_node_size(get_index_page_size_from_data_length::<#t>(#const_name)),}
+                        }
                     }
                 };
 
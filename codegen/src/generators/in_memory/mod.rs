@@ -9,13 +9,14 @@ mod wrapper;
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
 
-use crate::common::model::{Columns, Config, PrimaryKey, Queries};
+use crate::common::model::{Columns, Config, Hooks, OnMemoryLimit, PrimaryKey, Queries};
 
 pub struct InMemoryGenerator {
     pub name: Ident,
     pub pk: Option<PrimaryKey>,
     pub queries: Option<Queries>,
     pub config: Option<Config>,
+    pub hooks: Option<Hooks>,
     pub columns: Columns,
 }
 
@@ -26,6 +27,7 @@ impl InMemoryGenerator {
             pk: None,
             queries: None,
             config: None,
+            hooks: None,
             columns,
         }
     }
@@ -37,6 +39,10 @@ impl InMemoryGenerator {
     pub fn set_config(&mut self, config: Config) {
         self.config = Some(config);
     }
+
+    pub fn set_hooks(&mut self, hooks: Hooks) {
+        self.hooks = Some(hooks);
+    }
 }
 
 #[allow(dead_code)]
@@ -46,6 +52,7 @@ pub fn expand(input: TokenStream) -> syn::Result<TokenStream> {
     let mut queries = None;
     let mut indexes = None;
     let mut config = None;
+    let mut hooks = None;
 
     let name = parser.parse_name()?;
     while let Some(ident) = parser.peek_next() {
@@ -66,6 +73,10 @@ pub fn expand(input: TokenStream) -> syn::Result<TokenStream> {
                 let res = parser.parse_configs()?;
                 config = Some(res)
             }
+            "hooks" => {
+                let res = parser.parse_hooks()?;
+                hooks = Some(res)
+            }
             "persist" => {
                 // Skip persist flag for in_memory - it's always false
                 let _ = parser.parse_persist()?;
@@ -79,7 +90,7 @@ pub fn expand(input: TokenStream) -> syn::Result<TokenStream> {
         columns.indexes = i
     }
 
-    expand_from_parsed(name, columns, queries, config)
+    expand_from_parsed(name, columns, queries, config, hooks)
 }
 
 pub fn expand_from_parsed(
@@ -87,7 +98,15 @@ pub fn expand_from_parsed(
     columns: crate::common::model::Columns,
     queries: Option<crate::common::model::Queries>,
     config: Option<crate::common::model::Config>,
+    hooks: Option<crate::common::model::Hooks>,
 ) -> syn::Result<TokenStream> {
+    if let Some((OnMemoryLimit::Evict, span)) = config.as_ref().and_then(|c| c.on_memory_limit) {
+        return Err(syn::Error::new(
+            span,
+            "`on_memory_limit: evict` requires `persist: true`, evicted pages are written next to the table files",
+        ));
+    }
+
     let mut generator = InMemoryGenerator::new(name, columns);
     if let Some(q) = queries {
         generator.set_queries(q);
@@ -95,6 +114,9 @@ pub fn expand_from_parsed(
     if let Some(c) = config {
         generator.set_config(c);
     }
+    if let Some(h) = hooks {
+        generator.set_hooks(h);
+    }
 
     let pk_def = generator.gen_primary_key_def()?;
     let row_def = generator.gen_row_def();
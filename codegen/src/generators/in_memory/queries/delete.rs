@@ -1,12 +1,13 @@
 use std::collections::HashMap;
 
 use convert_case::{Case, Casing};
-use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro2::{Ident, TokenStream};
 use quote::quote;
 
 use crate::common::model::Index;
 use crate::common::model::Operation;
 use crate::common::name_generator::{WorktableNameGenerator, is_float};
+use crate::generators::flavor::Flavor;
 use crate::generators::in_memory::InMemoryGenerator;
 
 impl InMemoryGenerator {
@@ -14,34 +15,47 @@ impl InMemoryGenerator {
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let table_ident = name_generator.get_work_table_ident();
 
-        let custom_deletes = if let Some(q) = &self.queries {
-            let custom_deletes = self.gen_custom_deletes(q.deletes.clone());
-            quote! {
-                #custom_deletes
+        let mut custom_deletes = Vec::new();
+        let mut full_row_deletes = Vec::new();
+        let mut predicate_deletes = Vec::new();
+        let mut range_deletes = Vec::new();
+        let deletes = self.queries.as_ref().map(|q| q.deletes.clone());
+        for flavor in Flavor::ALL {
+            if let Some(deletes) = &deletes {
+                custom_deletes.push(self.gen_custom_deletes(deletes.clone(), flavor));
             }
-        } else {
-            quote! {}
-        };
-        let full_row_delete = self.gen_full_row_delete();
+            full_row_deletes.push(self.gen_full_row_delete(flavor));
+            full_row_deletes.push(self.gen_full_row_delete_returning(flavor));
+            predicate_deletes.push(self.gen_predicate_delete(flavor));
+            range_deletes.push(self.gen_range_deletes(flavor)?);
+        }
         let full_row_delete_without_lock = self.gen_full_row_delete_without_lock();
+        let primary_keys_where = self.gen_primary_keys_where();
 
         Ok(quote! {
             impl #table_ident {
-                #full_row_delete
+                #(#full_row_deletes)*
                 #full_row_delete_without_lock
-                #custom_deletes
+                #primary_keys_where
+                #(#predicate_deletes)*
+                #(#range_deletes)*
+                #(#custom_deletes)*
             }
         })
     }
 
-    fn gen_full_row_delete(&mut self) -> TokenStream {
+    fn gen_full_row_delete(&mut self, flavor: Flavor) -> TokenStream {
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let pk_ident = name_generator.get_primary_key_type_ident();
         let delete_logic = self.gen_delete_logic(true);
-        let full_row_lock = self.gen_full_lock_for_update();
+        let full_row_lock = self.gen_full_lock_for_update(flavor);
+        let async_ = flavor.asyncness();
+        let delete = flavor.ident("delete");
+        let doc = flavor.doc("delete");
 
         quote! {
-            pub async fn delete<Pk>(&self, pk: Pk) -> core::result::Result<(), WorkTableError>
+            #doc
+            pub #async_ fn #delete<Pk>(&self, pk: Pk) -> core::result::Result<(), WorkTableError>
             where #pk_ident: From<Pk>
             {
                 let pk: #pk_ident = pk.into();
@@ -76,12 +90,51 @@ impl InMemoryGenerator {
         }
     }
 
-    fn gen_delete_logic(&self, is_locked: bool) -> TokenStream {
+    fn gen_full_row_delete_returning(&self, flavor: Flavor) -> TokenStream {
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let pk_ident = name_generator.get_primary_key_type_ident();
+        let row_ident = name_generator.get_row_type_ident();
+        let process = self.gen_delete_process();
+        let full_row_lock = self.gen_full_lock_for_update(flavor);
+        let async_ = flavor.asyncness();
+        let delete_returning = flavor.ident("delete_returning");
+        let doc = flavor.doc("delete_returning");
+
+        quote! {
+            /// Same as `delete`, but returns the deleted row version.
+            #doc
+            pub #async_ fn #delete_returning<Pk>(&self, pk: Pk) -> core::result::Result<#row_ident, WorkTableError>
+            where #pk_ident: From<Pk>
+            {
+                let pk: #pk_ident = pk.into();
+                let op_lock = { #full_row_lock };
+                let _guard = LockGuard::new_with_mutation(
+                    op_lock,
+                    self.0.lock_manager.clone(),
+                    pk.clone(),
+                );
+
+                let link: Link = self.0
+                    .primary_index
+                    .pk_map
+                    .get_value(&pk)
+                    .map(Into::into)
+                    .ok_or(WorkTableError::NotFound)?;
+                let row = self.0.select(pk.clone()).ok_or(WorkTableError::NotFound)?;
+                let row_old = row.clone();
+                #process
+
+                core::result::Result::Ok(row_old)
+            }
+        }
+    }
+
+    fn gen_delete_process(&self) -> TokenStream {
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let pk_ident = name_generator.get_primary_key_type_ident();
         let secondary_events_ident = name_generator.get_space_secondary_index_events_ident();
 
-        let process = if false {
+        if false {
             quote! {
                 let (secondary_keys_events, res) = self.0.indexes.delete_row_cdc(row, link);
                 res?;
@@ -101,11 +154,21 @@ impl InMemoryGenerator {
             }
         } else {
             quote! {
+                self.0.check_delete(&row)?;
+                let deleted_row = self.0.captures_deletes().then(|| row.clone());
                 self.0.indexes.delete_row(row, link)?;
                 self.0.primary_index.remove(&pk, link);
                 self.0.data.delete(link).map_err(WorkTableError::PagesError)?;
+                if let Some(row) = deleted_row {
+                    self.0.on_deleted(row);
+                }
             }
-        };
+        }
+    }
+
+    fn gen_delete_logic(&self, is_locked: bool) -> TokenStream {
+        let process = self.gen_delete_process();
+
         if is_locked {
             quote! {
                 let link = match self.0
@@ -141,24 +204,213 @@ impl InMemoryGenerator {
         }
     }
 
-    fn gen_custom_deletes(&mut self, deleted: HashMap<Ident, Operation>) -> TokenStream {
+    fn gen_primary_keys_where(&self) -> TokenStream {
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let row_ident = name_generator.get_row_type_ident();
+        let pk_ident = name_generator.get_primary_key_type_ident();
+
+        quote! {
+            /// Snapshots the primary keys of the rows matching `predicate`.
+            fn primary_keys_where<F>(&self, predicate: &F) -> core::result::Result<Vec<#pk_ident>, WorkTableError>
+            where
+                F: Fn(&#row_ident) -> bool,
+            {
+                let _read_guard = self.0.data.read_guard();
+                let links = self.0.primary_index.pk_map
+                    .iter_values()
+                    .map(|(_, link)| link.0)
+                    .collect::<Vec<_>>();
+                let mut pks = Vec::new();
+                for link in links {
+                    match self.0.data.select_non_ghosted(link) {
+                        core::result::Result::Ok(row) => {
+                            if predicate(&row) {
+                                pks.push(row.get_primary_key());
+                            }
+                        }
+                        core::result::Result::Err(e) if e.is_row_absent() => {}
+                        core::result::Result::Err(e) => {
+                            return core::result::Result::Err(WorkTableError::PagesError(e));
+                        }
+                    }
+                }
+                core::result::Result::Ok(pks)
+            }
+        }
+    }
+
+    fn gen_predicate_delete(&self, flavor: Flavor) -> TokenStream {
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let row_ident = name_generator.get_row_type_ident();
+        let pk_ident = name_generator.get_primary_key_type_ident();
+        let process = self.gen_delete_process();
+        let full_row_lock = self.gen_full_lock_for_update(flavor);
+        let async_ = flavor.asyncness();
+        let await_ = flavor.await_();
+        let delete_where = flavor.ident("delete_where");
+        let delete_primary_keys_where = flavor.ident("delete_primary_keys_where");
+        let doc = flavor.doc("delete_where");
+
+        quote! {
+            /// Deletes every row matching `predicate` and returns how many
+            /// rows were removed.
+            ///
+            /// Candidates are snapshotted as primary keys first. Each one is
+            /// then deleted under its full-row lock only if the predicate
+            /// still holds for the row read under that lock, so a concurrent
+            /// update that moved the row out of the predicate keeps it alive.
+            #doc
+            pub #async_ fn #delete_where<F>(&self, predicate: F) -> core::result::Result<usize, WorkTableError>
+            where
+                F: Fn(&#row_ident) -> bool,
+            {
+                let pks = self.primary_keys_where(&predicate)?;
+                self.#delete_primary_keys_where(pks, predicate)#await_
+            }
+
+            #async_ fn #delete_primary_keys_where<F>(
+                &self,
+                mut pks: Vec<#pk_ident>,
+                predicate: F,
+            ) -> core::result::Result<usize, WorkTableError>
+            where
+                F: Fn(&#row_ident) -> bool,
+            {
+                // Sorted keys give concurrent multi-row operations one global
+                // row-lock order.
+                pks.sort_unstable();
+                pks.dedup();
+                let mut deleted = 0usize;
+                for pk in pks {
+                    let op_lock = { #full_row_lock };
+                    let _guard = LockGuard::new_with_mutation(
+                        op_lock,
+                        self.0.lock_manager.clone(),
+                        pk.clone(),
+                    );
+
+                    // Deleted, or moved out of the predicate, after the
+                    // snapshot: this row is no longer a match.
+                    let link: Link = match self.0.primary_index.pk_map.get_value(&pk) {
+                        Some(link) => link.into(),
+                        None => continue,
+                    };
+                    let row = match self.0.select(pk.clone()) {
+                        Some(row) => row,
+                        None => continue,
+                    };
+                    if !predicate(&row) {
+                        continue;
+                    }
+                    #process
+                    deleted += 1;
+                }
+                core::result::Result::Ok(deleted)
+            }
+        }
+    }
+
+    fn gen_range_deletes(&self, flavor: Flavor) -> syn::Result<TokenStream> {
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let row_ident = name_generator.get_row_type_ident();
+        let async_ = flavor.asyncness();
+        let await_ = flavor.await_();
+        let delete_primary_keys_where = flavor.ident("delete_primary_keys_where");
+
+        let defs = self
+            .columns
+            .indexes
+            .iter()
+            .filter(|(_, idx)| idx.backend.supports_range())
+            .map(|(i, idx)| {
+                let type_ = self
+                    .columns
+                    .columns_map
+                    .get(i)
+                    .ok_or(syn::Error::new(i.span(), "Row not found"))?;
+                let fn_name = flavor.ident(format!("delete_by_{i}_range"));
+                let doc = flavor.doc(format!("delete_by_{i}_range"));
+                let index_field = &idx.name;
+                let row_field = &idx.field;
+                let range_arg = if is_float(type_.to_string().as_str()) {
+                    quote! {
+                        (
+                            predicate_range.0.as_ref().map(|v| OrderedFloat(*v)),
+                            predicate_range.1.as_ref().map(|v| OrderedFloat(*v)),
+                        )
+                    }
+                } else {
+                    quote! { predicate_range.clone() }
+                };
+                let links = if idx.is_unique {
+                    quote! { self.0.indexes.#index_field.range_links(#range_arg).map(|link| link.0) }
+                } else {
+                    quote! { self.0.indexes.#index_field.range(#range_arg).map(|(_, link)| link.0) }
+                };
+
+                Ok(quote! {
+                    /// Deletes every row whose indexed value falls into
+                    /// `range` and returns how many rows were removed.
+                    #doc
+                    pub #async_ fn #fn_name<R>(&self, range: R) -> core::result::Result<usize, WorkTableError>
+                    where
+                        R: std::ops::RangeBounds<#type_>,
+                    {
+                        let predicate_range = (
+                            range.start_bound().cloned(),
+                            range.end_bound().cloned(),
+                        );
+                        let predicate = |row: &#row_ident| {
+                            std::ops::RangeBounds::contains(&predicate_range, &row.#row_field)
+                        };
+                        let pks = {
+                            let _read_guard = self.0.data.read_guard();
+                            let links = #links.collect::<Vec<_>>();
+                            let mut pks = Vec::with_capacity(links.len());
+                            for link in links {
+                                match self.0.data.select_non_ghosted(link) {
+                                    core::result::Result::Ok(row) => {
+                                        if predicate(&row) {
+                                            pks.push(row.get_primary_key());
+                                        }
+                                    }
+                                    core::result::Result::Err(e) if e.is_row_absent() => {}
+                                    core::result::Result::Err(e) => {
+                                        return core::result::Result::Err(WorkTableError::PagesError(e));
+                                    }
+                                }
+                            }
+                            pks
+                        };
+                        self.#delete_primary_keys_where(pks, predicate)#await_
+                    }
+                })
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        Ok(quote! {
+            #(#defs)*
+        })
+    }
+
+    fn gen_custom_deletes(&mut self, deleted: HashMap<Ident, Operation>, flavor: Flavor) -> TokenStream {
         let defs = deleted
             .iter()
             .map(|(name, op)| {
                 let snake_case_name = name.to_string().from_case(Case::Pascal).to_case(Case::Snake);
-                let method_ident = Ident::new(format!("delete_{snake_case_name}").as_str(), Span::mixed_site());
+                let method_ident = format!("delete_{snake_case_name}");
                 let index = self.columns.indexes.values().find(|idx| idx.field == op.by);
                 let type_ = self.columns.columns_map.get(&op.by).unwrap();
                 if let Some(index) = index {
                     let index_name = &index.name;
 
                     if index.is_unique {
-                        Self::gen_unique_delete(type_, &method_ident, index_name)
+                        Self::gen_unique_delete(type_, &method_ident, index_name, flavor)
                     } else {
-                        Self::gen_non_unique_delete(type_, &method_ident, index)
+                        Self::gen_non_unique_delete(type_, &method_ident, index, flavor)
                     }
                 } else {
-                    Self::gen_brute_force_delete_field(&op.by, type_, &method_ident)
+                    self.gen_brute_force_delete_field(&op.by, type_, &method_ident, flavor)
                 }
             })
             .collect::<Vec<_>>();
@@ -168,26 +420,44 @@ impl InMemoryGenerator {
         }
     }
 
-    fn gen_brute_force_delete_field(field: &Ident, type_: &TokenStream, name: &Ident) -> TokenStream {
+    fn gen_brute_force_delete_field(
+        &self,
+        field: &Ident,
+        type_: &TokenStream,
+        name: &str,
+        flavor: Flavor,
+    ) -> TokenStream {
+        let row_ident = WorktableNameGenerator::from_table_name(self.name.to_string()).get_row_type_ident();
+        let method_ident = flavor.ident(name);
+        let doc = flavor.doc(name);
+        let async_ = flavor.asyncness();
+        let await_ = flavor.await_();
+        let delete = flavor.ident("delete");
+
         quote! {
-            pub async fn #name(&self, by: #type_) -> core::result::Result<(), WorkTableError> {
-                self.iter_with_async(|row| {
-                    if row.#field == by {
-                        futures::future::Either::Left(async move {
-                            self.delete::<_>(row.get_primary_key()).await
-                        })
-                    } else {
-                        futures::future::Either::Right(async {
-                            Ok(())
-                        })
+            #doc
+            pub #async_ fn #method_ident(&self, by: #type_) -> core::result::Result<(), WorkTableError> {
+                let mut pks = self.primary_keys_where(&|row: &#row_ident| row.#field == by)?;
+                pks.sort_unstable();
+                for pk in pks {
+                    match self.#delete(pk)#await_ {
+                        core::result::Result::Ok(()) => {}
+                        // Deleted concurrently after the snapshot.
+                        core::result::Result::Err(WorkTableError::NotFound) => {}
+                        core::result::Result::Err(e) => return core::result::Result::Err(e),
                     }
-                }).await?;
+                }
                 core::result::Result::Ok(())
             }
         }
     }
 
-    fn gen_non_unique_delete(type_: &TokenStream, name: &Ident, index: &Index) -> TokenStream {
+    fn gen_non_unique_delete(type_: &TokenStream, name: &str, index: &Index, flavor: Flavor) -> TokenStream {
+        let method_ident = flavor.ident(name);
+        let doc = flavor.doc(name);
+        let async_ = flavor.asyncness();
+        let await_ = flavor.await_();
+        let delete = flavor.ident("delete");
         let by_field = &index.field;
         let index = &index.name;
         let by = if is_float(type_.to_string().as_str()) {
@@ -200,7 +470,8 @@ impl InMemoryGenerator {
             }
         };
         quote! {
-            pub async fn #name(&self, by: #type_) -> core::result::Result<(), WorkTableError> {
+            #doc
+            pub #async_ fn #method_ident(&self, by: #type_) -> core::result::Result<(), WorkTableError> {
                 // Snapshot the matching rows as validated primary keys before
                 // deleting anything. Storage links are not stable identities:
                 // a concurrent delete can free a slot and an insert can reuse
@@ -234,7 +505,7 @@ impl InMemoryGenerator {
                 pks.sort_unstable();
                 pks.dedup();
                 for pk in pks {
-                    match self.delete(pk).await {
+                    match self.#delete(pk)#await_ {
                         core::result::Result::Ok(()) => {}
                         // Deleted concurrently after the snapshot: the goal
                         // state for this row is already reached.
@@ -247,7 +518,12 @@ impl InMemoryGenerator {
         }
     }
 
-    fn gen_unique_delete(type_: &TokenStream, name: &Ident, index: &Ident) -> TokenStream {
+    fn gen_unique_delete(type_: &TokenStream, name: &str, index: &Ident, flavor: Flavor) -> TokenStream {
+        let method_ident = flavor.ident(name);
+        let doc = flavor.doc(name);
+        let async_ = flavor.asyncness();
+        let await_ = flavor.await_();
+        let delete = flavor.ident("delete");
         let by = if is_float(type_.to_string().as_str()) {
             quote! {
                 &OrderedFloat(by)
@@ -258,11 +534,12 @@ impl InMemoryGenerator {
             }
         };
         quote! {
-            pub async fn #name(&self, by: #type_) -> core::result::Result<(), WorkTableError> {
+            #doc
+            pub #async_ fn #method_ident(&self, by: #type_) -> core::result::Result<(), WorkTableError> {
                 let row_to_update = self.0.indexes.#index.get_value(#by).map(Into::into);
                 if let Some(link) = row_to_update {
                     let row = self.0.data.select_non_ghosted(link).map_err(WorkTableError::PagesError)?;
-                    self.delete(row.get_primary_key()).await?;
+                    self.#delete(row.get_primary_key())#await_?;
                 }
                 core::result::Result::Ok(())
             }
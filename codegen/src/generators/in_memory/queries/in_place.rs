@@ -1,5 +1,6 @@
 use crate::common::model::Operation;
 use crate::common::name_generator::WorktableNameGenerator;
+use crate::generators::flavor::Flavor;
 use crate::generators::in_memory::InMemoryGenerator;
 use convert_case::{Case, Casing};
 use proc_macro2::{Ident, Span, TokenStream};
@@ -12,9 +13,12 @@ impl InMemoryGenerator {
         let table_ident = name_generator.get_work_table_ident();
 
         let custom_in_place = if let Some(q) = &self.queries {
-            let custom_in_place = self.gen_in_place_queries(q.in_place.clone());
+            let custom_in_place: Vec<_> = Flavor::ALL
+                .into_iter()
+                .map(|flavor| self.gen_in_place_queries(q.in_place.clone(), flavor))
+                .collect();
             quote! {
-                #custom_in_place
+                #(#custom_in_place)*
             }
         } else {
             quote! {}
@@ -27,7 +31,7 @@ impl InMemoryGenerator {
         })
     }
 
-    fn gen_in_place_queries(&self, in_place_queries: HashMap<Ident, Operation>) -> TokenStream {
+    fn gen_in_place_queries(&self, in_place_queries: HashMap<Ident, Operation>, flavor: Flavor) -> TokenStream {
         let defs = in_place_queries
             .iter()
             .map(|(name, op)| {
@@ -38,7 +42,7 @@ impl InMemoryGenerator {
 
                     if index.is_unique { todo!() } else { todo!() }
                 } else if self.columns.primary_keys.len() == 1 {
-                    self.gen_primary_key_in_place(snake_case_name, &op.columns)
+                    self.gen_primary_key_in_place(snake_case_name, &op.columns, flavor)
                 } else {
                     todo!()
                 }
@@ -50,13 +54,16 @@ impl InMemoryGenerator {
         }
     }
 
-    fn gen_primary_key_in_place(&self, snake_case_name: String, columns: &[Ident]) -> TokenStream {
+    fn gen_primary_key_in_place(&self, snake_case_name: String, columns: &[Ident], flavor: Flavor) -> TokenStream {
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let pk_type = name_generator.get_primary_key_type_ident();
         let lock_ident = WorktableNameGenerator::get_update_in_place_query_lock_ident(&snake_case_name);
 
-        let method_ident = Ident::new(
-            format!("update_{snake_case_name}_in_place").as_str(),
+        let method_ident = flavor.ident(format!("update_{snake_case_name}_in_place"));
+        let method_doc = flavor.doc(format!("update_{snake_case_name}_in_place"));
+        let async_ = flavor.asyncness();
+        let try_method_ident = Ident::new(
+            format!("try_update_{snake_case_name}_in_place").as_str(),
             Span::mixed_site(),
         );
 
@@ -94,10 +101,28 @@ impl InMemoryGenerator {
                 ( #(#columns),* )
             }
         };
-        let custom_lock = self.gen_custom_lock_for_update(lock_ident);
+        let custom_lock = self.gen_custom_lock_for_update(lock_ident, flavor);
+
+        let try_fn = flavor.is_async().then(|| {
+            quote! {
+                /// Same as the in-place update, but fails with
+                /// `WorkTableError::LockTimeout` instead of waiting when another
+                /// operation holds the updated columns.
+                pub async fn #try_method_ident<Pk, F: FnMut(#column_types)>(
+                    &self,
+                    f: F,
+                    by: Pk,
+                ) -> eyre::Result<()>
+                where #pk_type: From<Pk>
+                {
+                    without_waiting(self.#method_ident(f, by)).await
+                }
+            }
+        });
 
         quote! {
-            pub async fn #method_ident<Pk, F: FnMut(#column_types)>(
+            #method_doc
+            pub #async_ fn #method_ident<Pk, F: FnMut(#column_types)>(
                 &self,
                 mut f: F,
                 by: Pk,
@@ -117,15 +142,26 @@ impl InMemoryGenerator {
                     .get_value(&pk)
                     .map(Into::into)
                     .ok_or(WorkTableError::NotFound)?;
+                let change_old = if self.0.captures_updates() {
+                    Some(self.0.data.select_non_ghosted(link).map_err(WorkTableError::PagesError)?)
+                } else {
+                    None
+                };
                 unsafe {
                     self.0
                         .data
                         .with_mut_ref(link, move |archived| f(#column_fields))
                         .map_err(WorkTableError::PagesError)?
                     };
+                if let Some(old) = change_old {
+                    let new = self.0.data.select_non_ghosted(link).map_err(WorkTableError::PagesError)?;
+                    self.0.on_updated(old, new);
+                }
 
                 Ok(())
             }
+
+            #try_fn
         }
     }
 }
@@ -6,6 +6,7 @@ use quote::quote;
 
 use crate::common::model::Operation;
 use crate::common::name_generator::WorktableNameGenerator;
+use crate::generators::flavor::Flavor;
 use crate::generators::in_memory::InMemoryGenerator;
 
 impl InMemoryGenerator {
@@ -81,6 +82,7 @@ impl InMemoryGenerator {
     }
 
     fn gen_rows_lock_fn(columns: &[Ident], ident: Ident) -> TokenStream {
+        let column_names = columns.iter().map(|col| col.to_string());
         let inner = columns
             .iter()
             .map(|col| {
@@ -98,16 +100,17 @@ impl InMemoryGenerator {
             #[allow(clippy::mutable_key_type)]
             pub fn #ident(&mut self, id: u16) -> (std::collections::HashSet<std::sync::Arc<Lock>>,  std::sync::Arc<Lock>) {
                 let mut set = std::collections::HashSet::new();
-                let new_lock = std::sync::Arc::new(Lock::new(id));
+                let new_lock = std::sync::Arc::new(Lock::with_columns(id, &[#(#column_names),*]));
                 #(#inner)*
                 (set, new_lock)
             }
         }
     }
 
-    pub fn gen_full_lock_for_update(&self) -> TokenStream {
+    pub fn gen_full_lock_for_update(&self, flavor: Flavor) -> TokenStream {
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let lock_ident = name_generator.get_lock_type_ident();
+        let wait_for = flavor.wait_for();
 
         quote! {
             let lock_id = self.0.lock_manager.next_id();
@@ -116,18 +119,17 @@ impl InMemoryGenerator {
                 .0
                 .lock_manager
                 .get_or_insert_with(pk.clone(), #lock_ident::new);
-            let mut lock_guard = lock.write().await;
             #[allow(clippy::mutable_key_type)]
-            let (locks, op_lock) = lock_guard.lock(lock_id);
-            drop(lock_guard);
-            futures::future::join_all(locks.iter().map(|l| l.wait()).collect::<Vec<_>>()).await;
+            let (locks, op_lock) = lock.write().lock(lock_id);
+            #wait_for
             op_lock
         }
     }
 
-    pub fn gen_custom_lock_for_update(&self, ident: Ident) -> TokenStream {
+    pub fn gen_custom_lock_for_update(&self, ident: Ident, flavor: Flavor) -> TokenStream {
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let lock_ident = name_generator.get_lock_type_ident();
+        let wait_for = flavor.wait_for();
 
         quote! {
             let lock_id = self.0.lock_manager.next_id();
@@ -140,11 +142,9 @@ impl InMemoryGenerator {
                 .0
                 .lock_manager
                 .get_or_insert_with(pk.clone(), #lock_ident::new);
-            let mut lock_guard = lock.write().await;
             #[allow(clippy::mutable_key_type)]
-            let (locks, op_lock) = lock_guard.#ident(lock_id);
-            drop(lock_guard);
-            futures::future::join_all(locks.iter().map(|l| l.wait()).collect::<Vec<_>>()).await;
+            let (locks, op_lock) = lock.write().#ident(lock_id);
+            #wait_for
             op_lock
         }
     }
@@ -3,7 +3,7 @@ use quote::quote;
 
 use crate::common::name_generator::{WorktableNameGenerator, is_unsized_vec};
 use crate::generators::in_memory::InMemoryGenerator;
-use crate::generators::index_backend::unique_index_type;
+use crate::generators::index_backend::{custom_index_assertions, unique_index_type};
 
 mod impls;
 mod index_fns;
@@ -14,7 +14,7 @@ impl InMemoryGenerator {
         let page_size_consts = self.gen_page_size_consts();
         let type_ = self.gen_table_type()?;
         let default = self.gen_table_default();
-        let impl_ = self.gen_table_impl();
+        let impl_ = self.gen_table_impl()?;
         let index_fns = self.gen_table_index_fns()?;
         let select_query_executor_impl = self.gen_table_select_query_executor_impl();
         let column_range_type = self.gen_table_column_range_type();
@@ -34,12 +34,25 @@ impl InMemoryGenerator {
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let ident = name_generator.get_work_table_ident();
         let table_name = name_generator.get_work_table_literal_name();
+        let data_setup = self.config.as_ref().and_then(|c| c.compression).map(|compression| {
+            quote! {
+                inner.data = std::sync::Arc::new(DataPages::new().with_compression(#compression));
+            }
+        });
+        let budget_setup = self.config.as_ref().and_then(|c| c.memory_budget()).map(|budget| {
+            quote! {
+                inner.set_memory_budget(Some(#budget));
+            }
+        });
 
         quote! {
              impl Default for #ident {
                 fn default() -> Self {
                     let mut inner = WorkTable::default();
                     inner.table_name = #table_name;
+                    inner.hooks = Self::table_hooks();
+                    #data_setup
+                    #budget_setup
                     Self(inner)
                 }
             }
@@ -85,13 +98,14 @@ impl InMemoryGenerator {
             None
         };
         let node_type = unique_index_type(
-            self.columns.primary_index_backend,
+            &self.columns.primary_index_backend,
             &key_type,
             &value_type,
             worktables_node,
         )?;
+        let assertions = custom_index_assertions(&self.columns.primary_index_backend, &key_type, &value_type, true);
 
-        Ok(if self.config.as_ref().and_then(|c| c.page_size).is_some() {
+        let table = if self.config.as_ref().and_then(|c| c.page_size).is_some() {
             quote! {
                 #derive
                 pub struct #ident(
@@ -125,6 +139,11 @@ impl InMemoryGenerator {
                     >
                 );
             }
+        };
+
+        Ok(quote! {
+            #table
+            #assertions
         })
     }
 
@@ -1,12 +1,13 @@
 use proc_macro2::{Ident, TokenStream};
-use quote::quote;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
 
-use crate::common::model::IndexBackend;
+use crate::common::model::{Index, IndexBackend};
 
 /// Generates the concrete unique-map type selected by the DSL while keeping
 /// WorkTablesIndex's custom node type available for persisted/unsized indexes.
 pub(crate) fn unique_index_type(
-    backend: IndexBackend,
+    backend: &IndexBackend,
     key: &TokenStream,
     value: &TokenStream,
     worktables_node: Option<TokenStream>,
@@ -28,13 +29,36 @@ pub(crate) fn unique_index_type(
         }
         IndexBackend::Congee => Ok(quote! { CongeeIndex<#key, #value> }),
         IndexBackend::Arctic => Ok(quote! { ArcticIndex<#key, #value> }),
+        IndexBackend::Hash => Ok(quote! { HashIndex<#key, #value> }),
+        IndexBackend::Custom(path) => Ok(quote! { #path<#key, #value> }),
+    }
+}
+
+/// Wraps the type of a unique index declared with `filter` in
+/// `FilteredIndex`.
+pub(crate) fn filtered_index_type(index: &Index, key: &TokenStream, index_type: TokenStream) -> TokenStream {
+    if index.filter {
+        quote! { FilteredIndex<#key, #index_type> }
+    } else {
+        index_type
+    }
+}
+
+/// Generates the `Default` field initializer of a unique index from the
+/// expression building its map, wrapped like [`filtered_index_type`].
+pub(crate) fn unique_index_init(index: &Index, init: TokenStream) -> TokenStream {
+    let i = &index.name;
+    if index.filter {
+        quote! { #i: FilteredIndex::new(#init), }
+    } else {
+        quote! { #i: #init, }
     }
 }
 
 /// Generates the persisted-table variant. ART backends receive a write-side
 /// sequencing wrapper; memory-only tables keep the zero-overhead native type.
 pub(crate) fn persistent_unique_index_type(
-    backend: IndexBackend,
+    backend: &IndexBackend,
     key: &TokenStream,
     value: &TokenStream,
     worktables_node: Option<TokenStream>,
@@ -51,126 +75,271 @@ pub(crate) fn persistent_unique_index_type(
         }),
         IndexBackend::Congee => Ok(quote! { PersistentCongeeIndex<#key, #value> }),
         IndexBackend::Arctic => Ok(quote! { PersistentArcticIndex<#key, #value> }),
+        IndexBackend::Hash => Ok(quote! { PersistentHashIndex<#key, #value> }),
         _ => unique_index_type(backend, key, value, worktables_node),
     }
 }
 
-/// Generates the small codec needed when an ART is used for WorkTable's
-/// generated primary-key newtype. ART backends intentionally accept only the
-/// lossless, native integer shapes supported by their public APIs.
+/// Generates the concrete non-unique map type selected by the DSL. ART
+/// backends keep one link list per key; IndexSet, the hash map and custom
+/// backends have no multimap.
+pub(crate) fn multi_index_type(
+    backend: &IndexBackend,
+    key: &TokenStream,
+    value: &TokenStream,
+    worktables_node: Option<TokenStream>,
+) -> syn::Result<TokenStream> {
+    match backend {
+        IndexBackend::WorktablesIndex => Ok(match worktables_node {
+            Some(node) => quote! { IndexMultiMap<#key, #value, #node> },
+            None => quote! { IndexMultiMap<#key, #value> },
+        }),
+        IndexBackend::Indexset | IndexBackend::Hash | IndexBackend::Custom(_) => Err(syn::Error::new_spanned(
+            key,
+            format!(
+                "non-unique indexes cannot use `{}`; use `worktables_index`, `congee` or `arctic`",
+                backend.name()
+            ),
+        )),
+        IndexBackend::Congee => Ok(quote! { CongeeMultiIndex<#key, #value> }),
+        IndexBackend::Arctic => Ok(quote! { ArcticMultiIndex<#key, #value> }),
+    }
+}
+
+/// Persisted-table variant of [`multi_index_type`].
+pub(crate) fn persistent_multi_index_type(
+    backend: &IndexBackend,
+    key: &TokenStream,
+    value: &TokenStream,
+    worktables_node: Option<TokenStream>,
+) -> syn::Result<TokenStream> {
+    match backend {
+        IndexBackend::Congee => Ok(quote! { PersistentCongeeMultiIndex<#key, #value> }),
+        IndexBackend::Arctic => Ok(quote! { PersistentArcticMultiIndex<#key, #value> }),
+        _ => multi_index_type(backend, key, value, worktables_node),
+    }
+}
+
+/// Generates the codec needed when an ART is used for WorkTable's generated
+/// primary-key newtype. The newtype encodes as the concatenation of its
+/// fields' order-preserving images, stored in the backend's native integer.
+/// A hash primary key only needs the image when it is persisted, where it is
+/// the key format of the snapshot and WAL.
 pub(crate) fn primary_key_backend_impl(
-    backend: IndexBackend,
+    backend: &IndexBackend,
     primary_key: &Ident,
     fields: &[&TokenStream],
+    persisted: bool,
 ) -> syn::Result<(TokenStream, TokenStream)> {
     match backend {
-        IndexBackend::WorktablesIndex | IndexBackend::Indexset => Ok((quote! {}, quote! {})),
-        IndexBackend::Congee => {
-            let field = single_supported_field(backend, fields, &["u8", "u16", "u32", "u64", "usize"])?;
-            let width_guard = if primitive_name(field).as_deref() == Some("u64") {
-                quote! {
-                    #[cfg(not(target_pointer_width = "64"))]
-                    compile_error!("`using congee` with a `u64` primary key requires a 64-bit target");
+        IndexBackend::WorktablesIndex | IndexBackend::Indexset | IndexBackend::Custom(_) => {
+            return Ok((quote! {}, quote! {}));
+        }
+        IndexBackend::Hash if !persisted => return Ok((quote! {}, quote! {})),
+        _ => {}
+    }
+
+    let width = validate_art_key(backend, fields, false)?;
+    let indexes = (0..fields.len()).map(syn::Index::from).collect::<Vec<_>>();
+    let encode_impl = quote! {
+        impl ArtKeyEncode for #primary_key {
+            const WIDTH: usize = 0 #(+ <#fields as ArtKeyEncode>::WIDTH)*;
+
+            #[inline]
+            #[allow(unused_assignments)]
+            fn encode_art(&self, output: &mut [u8]) {
+                let mut offset = 0;
+                #(
+                    self.#indexes.encode_art(&mut output[offset..offset + <#fields as ArtKeyEncode>::WIDTH]);
+                    offset += <#fields as ArtKeyEncode>::WIDTH;
+                )*
+            }
+
+            #[inline]
+            #[allow(unused_assignments)]
+            fn decode_art(bytes: &[u8]) -> Self {
+                let mut offset = 0;
+                Self(#({
+                    let value = <#fields as ArtKeyEncode>::decode_art(&bytes[offset..offset + <#fields as ArtKeyEncode>::WIDTH]);
+                    offset += <#fields as ArtKeyEncode>::WIDTH;
+                    value
+                }),*)
+            }
+        }
+    };
+
+    if *backend == IndexBackend::Hash {
+        return Ok((quote! {}, encode_impl));
+    }
+
+    let backend_impl = if *backend == IndexBackend::Congee {
+        let width_guard = if width.is_some_and(|width| width > 4) {
+            quote! {
+                #[cfg(not(target_pointer_width = "64"))]
+                compile_error!("`using congee` with a primary key wider than 4 bytes requires a 64-bit target");
+            }
+        } else {
+            quote! {}
+        };
+        quote! {
+            #width_guard
+            impl CongeeKey for #primary_key {
+                #[inline]
+                fn into_congee(self) -> usize {
+                    encode_art_word(&self)
                 }
-            } else {
-                quote! {}
-            };
-
-            Ok((
-                quote! { Copy, },
-                quote! {
-                    #width_guard
-                    impl CongeeKey for #primary_key {
-                        #[inline]
-                        fn into_congee(self) -> usize {
-                            self.0 as usize
-                        }
-
-                        #[inline]
-                        fn from_congee(value: usize) -> Self {
-                            Self(value as #field)
-                        }
-                    }
-
-                    impl ArtPersistenceKey for #primary_key {
-                        const WIDTH: u8 = <#field as ArtPersistenceKey>::WIDTH;
-
-                        fn encode_art_key(&self, output: &mut Vec<u8>) {
-                            self.0.encode_art_key(output)
-                        }
-
-                        fn decode_art_key(bytes: &[u8]) -> eyre::Result<Self> {
-                            Ok(Self(<#field as ArtPersistenceKey>::decode_art_key(bytes)?))
-                        }
-                    }
-                },
-            ))
+
+                #[inline]
+                fn from_congee(value: usize) -> Self {
+                    decode_art_word(value)
+                }
+            }
         }
-        IndexBackend::Arctic => {
-            let field = single_supported_field(backend, fields, &["u16", "u32", "u64", "u128"])?;
-            Ok((
-                quote! { Copy, },
-                quote! {
-                    impl ArcticKey for #primary_key {
-                        type Raw = #field;
-
-                        #[inline]
-                        fn to_arctic(&self) -> Self::Raw {
-                            self.0
-                        }
-
-                        #[inline]
-                        fn from_arctic(value: Self::Raw) -> Self {
-                            Self(value)
-                        }
-                    }
-
-                    impl ArtPersistenceKey for #primary_key {
-                        const WIDTH: u8 = <#field as ArtPersistenceKey>::WIDTH;
-
-                        fn encode_art_key(&self, output: &mut Vec<u8>) {
-                            self.0.encode_art_key(output)
-                        }
-
-                        fn decode_art_key(bytes: &[u8]) -> eyre::Result<Self> {
-                            Ok(Self(<#field as ArtPersistenceKey>::decode_art_key(bytes)?))
-                        }
-                    }
-                },
-            ))
+    } else {
+        // The narrowest native key keeps single unsigned fields stored as
+        // themselves, so existing Arctic checkpoints stay readable.
+        let raw = match width {
+            Some(0..=2) => quote! { u16 },
+            Some(3..=4) => quote! { u32 },
+            Some(5..=8) => quote! { u64 },
+            _ => quote! { u128 },
+        };
+        quote! {
+            impl ArcticKey for #primary_key {
+                type Raw = #raw;
+
+                #[inline]
+                fn to_arctic(&self) -> Self::Raw {
+                    encode_art_word(self)
+                }
+
+                #[inline]
+                fn from_arctic(value: Self::Raw) -> Self {
+                    decode_art_word(value)
+                }
+            }
         }
+    };
+
+    Ok((
+        quote! { Copy, },
+        quote! {
+            #encode_impl
+            #backend_impl
+        },
+    ))
+}
+
+/// Asserts that a `using custom(Type)` map implements the backend trait set.
+/// Each trait is checked by its own bound, spanned at the declared type, so a
+/// missing impl is reported by trait name instead of from inside the table.
+/// The primary index is driven through `UniqueIndex` alone and so skips the
+/// `TableIndex` check.
+pub(crate) fn custom_index_assertions(
+    backend: &IndexBackend,
+    key: &TokenStream,
+    value: &TokenStream,
+    is_primary: bool,
+) -> TokenStream {
+    let IndexBackend::Custom(path) = backend else {
+        return quote! {};
+    };
+    let span = path.span();
+    let index = quote_spanned! {span=> #path<#key, #value> };
+    let table_index = if is_primary {
+        quote! {}
+    } else {
+        quote_spanned! {span=>
+            fn table_index<I: TableIndex<K>, K>() {}
+            table_index::<#index, #key>();
+        }
+    };
+    quote_spanned! {span=>
+        const _: fn() = || {
+            fn unique_index<I: UniqueIndex<K, V>, K: Clone + Ord, V: Clone>() {}
+            fn table_index_cdc<I: TableIndexCdc<K>, K>() {}
+            fn mem_stat<I: MemStat>() {}
+            unique_index::<#index, #key, #value>();
+            table_index_cdc::<#index, #key>();
+            mem_stat::<#index>();
+            #table_index
+        };
     }
 }
 
-fn single_supported_field<'a>(
-    backend: IndexBackend,
-    fields: &'a [&TokenStream],
-    supported: &[&str],
-) -> syn::Result<&'a TokenStream> {
-    let [field] = fields else {
-        return Err(syn::Error::new_spanned(
-            fields.first().copied().cloned().unwrap_or_default(),
-            format!("`using {}` requires a single-column primary key", backend.name()),
-        ));
+/// Checks that every key column has an order-preserving ART encoding and that
+/// the key fits the backend's native integer. Returns the key width when the
+/// macro can tell it from the type names; aliases and other named types are
+/// left to the `ArtKeyEncode` bound and the width assertion at compile time.
+pub(crate) fn validate_art_key(
+    backend: &IndexBackend,
+    fields: &[&TokenStream],
+    allow_float: bool,
+) -> syn::Result<Option<usize>> {
+    let mut total = Some(0);
+    for field in fields {
+        let width = match art_key_width(field, allow_float) {
+            Ok(width) => width,
+            Err(()) => {
+                let floats = if allow_float { "`f32`, `f64`, " } else { "" };
+                return Err(syn::Error::new_spanned(
+                    *field,
+                    format!(
+                        "`using {}` cannot index `{}`, which has no order-preserving key encoding; supported types: integers, {floats}`Uuid`, packed NanoIDs and other `ArtKeyEncode` types",
+                        backend.name(),
+                        field,
+                    ),
+                ));
+            }
+        };
+        total = total.zip(width).map(|(total, width)| total + width);
+    }
+
+    let limit = match backend {
+        IndexBackend::Congee => 8,
+        // Persisted hash keys only need to fit the one-byte width of the
+        // snapshot and WAL header.
+        IndexBackend::Hash => u8::MAX as usize,
+        _ => 16,
     };
-    let primitive = primitive_name(field);
-    if !primitive.as_deref().is_some_and(|name| supported.contains(&name)) {
+    if let Some(width) = total
+        && width > limit
+    {
+        let key = if let [field] = fields {
+            field.to_string()
+        } else {
+            format!(
+                "({})",
+                fields.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+            )
+        };
         return Err(syn::Error::new_spanned(
-            *field,
+            fields.first().copied().cloned().unwrap_or_default(),
             format!(
-                "`using {}` requires a directly named primitive primary-key type; found `{}`; supported types: {} (type aliases cannot be resolved by the macro)",
-                backend.name(),
-                field,
-                supported.join(", ")
+                "`using {}` keys are limited to {limit} bytes, but `{key}` encodes to {width}",
+                backend.name()
             ),
         ));
     }
-    Ok(field)
+    Ok(total)
 }
 
-fn primitive_name(field: &TokenStream) -> Option<String> {
-    let syn::Type::Path(type_path) = syn::parse2::<syn::Type>(field.clone()).ok()? else {
-        return None;
+fn art_key_width(field: &TokenStream, allow_float: bool) -> Result<Option<usize>, ()> {
+    let Ok(syn::Type::Path(type_path)) = syn::parse2::<syn::Type>(field.clone()) else {
+        return Err(());
+    };
+    let Some(name) = type_path.path.segments.last().map(|segment| segment.ident.to_string()) else {
+        return Err(());
     };
-    type_path.path.segments.last().map(|segment| segment.ident.to_string())
+    Ok(Some(match name.as_str() {
+        "u8" | "i8" => 1,
+        "u16" | "i16" => 2,
+        "u32" | "i32" => 4,
+        "u64" | "i64" | "usize" | "isize" => 8,
+        "u128" | "i128" | "Uuid" => 16,
+        "f32" if allow_float => 4,
+        "f64" if allow_float => 8,
+        "f32" | "f64" | "bool" | "char" | "str" | "String" | "Vec" | "Option" | "Box" => return Err(()),
+        _ => return Ok(None),
+    }))
 }
@@ -0,0 +1,157 @@
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::quote;
+
+use crate::common::model::{Columns, IndexBackend};
+use crate::common::name_generator::is_float;
+
+/// Generates the `TableSecondaryIndexIntegrity` impl shared by every table
+/// kind. An entry found inconsistent is looked up again and only reported if
+/// it is still in the index, since live tables are checked without locks.
+/// The lookups wait until the iteration is over: iterators hold node locks.
+pub(crate) fn secondary_index_integrity_impl(index_ident: &Ident, row_ident: &Ident, columns: &Columns) -> TokenStream {
+    if columns.indexes.is_empty() {
+        return quote! {
+            impl TableSecondaryIndexIntegrity<#row_ident> for #index_ident {
+                fn missing_entries(&self, _: &#row_ident, _: Link) -> Vec<&'static str> {
+                    vec![]
+                }
+
+                fn verify_entries(&self, _: &dyn Fn(Link) -> Option<#row_ident>) -> Vec<IntegrityDiscrepancy> {
+                    vec![]
+                }
+
+                fn entry_counts(&self) -> Vec<(&'static str, usize)> {
+                    vec![]
+                }
+            }
+        };
+    }
+
+    let (missing, verify): (Vec<_>, Vec<_>) = columns
+        .indexes
+        .iter()
+        .map(|(column, index)| {
+            let index_field = &index.name;
+            let row_field = &index.field;
+            let index_name = Literal::string(&index_field.to_string());
+            let field_type = columns
+                .columns_map
+                .get(column)
+                .expect("indexed column should exist")
+                .to_string();
+            let expected_key = if is_float(&field_type) {
+                quote! { OrderedFloat(row.#row_field) }
+            } else {
+                quote! { row.#row_field.clone() }
+            };
+
+            if index.is_unique {
+                let missing = quote! {
+                    if self.#index_field.lookup_for_select(&#expected_key).map(|link| link.0) != Some(link) {
+                        missing.push(#index_name);
+                    }
+                };
+                let verify = quote! {
+                    let mut suspects = vec![];
+                    for (indexed_key, offset_link) in self.#index_field.iter_values() {
+                        let discrepancy = match row_at(offset_link.0) {
+                            None => IntegrityDiscrepancy::DanglingSecondaryEntry {
+                                index: #index_name,
+                                key: format!("{indexed_key:?}"),
+                                link: offset_link.0,
+                            },
+                            Some(row) if #expected_key != indexed_key => IntegrityDiscrepancy::SecondaryKeyMismatch {
+                                index: #index_name,
+                                key: format!("{indexed_key:?}"),
+                                link: offset_link.0,
+                            },
+                            Some(_) => continue,
+                        };
+                        suspects.push((indexed_key, offset_link.0, discrepancy));
+                    }
+                    for (indexed_key, link, discrepancy) in suspects {
+                        if self.#index_field.lookup_for_select(&indexed_key).map(|link| link.0) == Some(link) {
+                            discrepancies.push(discrepancy);
+                        }
+                    }
+                };
+                (missing, verify)
+            } else {
+                // ART multimaps yield owned pairs; the B-tree yields
+                // references into its nodes.
+                let expected_key_ref = match index.backend {
+                    IndexBackend::Congee | IndexBackend::Arctic | IndexBackend::Hash | IndexBackend::Custom(_) => {
+                        quote! { expected_key }
+                    }
+                    IndexBackend::WorktablesIndex | IndexBackend::Indexset => quote! { &expected_key },
+                };
+                let missing = quote! {
+                    if !self.#index_field
+                        .get(&#expected_key)
+                        .any(|(_, candidate_link)| candidate_link.0 == link)
+                    {
+                        missing.push(#index_name);
+                    }
+                };
+                let verify = quote! {
+                    let mut suspects = vec![];
+                    for (indexed_key, offset_link) in self.#index_field.iter() {
+                        let discrepancy = match row_at(offset_link.0) {
+                            None => IntegrityDiscrepancy::DanglingSecondaryEntry {
+                                index: #index_name,
+                                key: format!("{indexed_key:?}"),
+                                link: offset_link.0,
+                            },
+                            Some(row) => {
+                                let expected_key = #expected_key;
+                                if indexed_key == #expected_key_ref {
+                                    continue;
+                                }
+                                IntegrityDiscrepancy::SecondaryKeyMismatch {
+                                    index: #index_name,
+                                    key: format!("{indexed_key:?}"),
+                                    link: offset_link.0,
+                                }
+                            }
+                        };
+                        suspects.push((indexed_key.clone(), offset_link.0, discrepancy));
+                    }
+                    for (indexed_key, link, discrepancy) in suspects {
+                        if self.#index_field
+                            .get(&indexed_key)
+                            .any(|(_, candidate_link)| candidate_link.0 == link)
+                        {
+                            discrepancies.push(discrepancy);
+                        }
+                    }
+                };
+                (missing, verify)
+            }
+        })
+        .unzip();
+    let entry_counts = columns.indexes.values().map(|index| {
+        let index_field = &index.name;
+        let index_name = Literal::string(&index_field.to_string());
+        quote! { (#index_name, self.#index_field.len()) }
+    });
+
+    quote! {
+        impl TableSecondaryIndexIntegrity<#row_ident> for #index_ident {
+            fn missing_entries(&self, row: &#row_ident, link: Link) -> Vec<&'static str> {
+                let mut missing = vec![];
+                #(#missing)*
+                missing
+            }
+
+            fn verify_entries(&self, row_at: &dyn Fn(Link) -> Option<#row_ident>) -> Vec<IntegrityDiscrepancy> {
+                let mut discrepancies = vec![];
+                #(#verify)*
+                discrepancies
+            }
+
+            fn entry_counts(&self) -> Vec<(&'static str, usize)> {
+                vec![#(#entry_counts),*]
+            }
+        }
+    }
+}
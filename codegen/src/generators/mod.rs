@@ -1,5 +1,9 @@
+pub(crate) mod flavor;
+pub(crate) mod hooks;
 pub mod in_memory;
 pub(crate) mod index_backend;
+pub(crate) mod integrity;
 pub mod persist;
 pub(crate) mod primary_key;
 pub mod read_only;
+pub(crate) mod statistics;
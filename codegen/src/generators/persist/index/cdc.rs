@@ -20,6 +20,7 @@ impl PersistGenerator {
         let delete_from_indexes_cdc = self.gen_delete_from_indexes_cdc_index_fn();
         let process_difference_insert_cdc = self.gen_process_difference_insert_cdc_index_fn();
         let process_difference_remove_cdc = self.gen_process_difference_remove_cdc_index_fn();
+        let insert_row_into_indexes_cdc = self.gen_insert_row_into_indexes_cdc_fn();
 
         quote! {
             impl TableSecondaryIndexCdc<#row_type_ident, #available_types_ident, #events_ident, #available_index_ident> for #index_type_ident {
@@ -30,6 +31,65 @@ impl PersistGenerator {
                 #process_difference_insert_cdc
                 #process_difference_remove_cdc
             }
+
+            impl #index_type_ident {
+                #insert_row_into_indexes_cdc
+            }
+        }
+    }
+
+    /// Generates the load-time builder of secondary indexes added to a
+    /// persisted table. Unlike `save_row_cdc`, it inserts only into the named
+    /// indexes and leaves the others, which already hold the row, untouched.
+    fn gen_insert_row_into_indexes_cdc_fn(&self) -> TokenStream {
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let row_type_ident = name_generator.get_row_type_ident();
+        let events_ident = name_generator.get_space_secondary_index_events_ident();
+        let available_index_ident = name_generator.get_available_indexes_ident();
+
+        let insert_rows = self
+            .columns
+            .indexes
+            .iter()
+            .map(|(i, idx)| {
+                let index_field_name = &idx.name;
+                let index_name_literal = Literal::string(index_field_name.to_string().as_str());
+                let camel_case_name = index_field_name
+                    .to_string()
+                    .from_case(Case::Snake)
+                    .to_case(Case::Pascal);
+                let index_variant: TokenStream = camel_case_name.parse().unwrap();
+
+                quote! {
+                    if indexes.iter().any(|name| name == #index_name_literal) {
+                        let Some(index_events) = self.#index_field_name.insert_checked_cdc(row.#i.clone(), link) else {
+                            return Err(IndexError::AlreadyExists {
+                                at: #available_index_ident::#index_variant,
+                                inserted_already: inserted_indexes,
+                            });
+                        };
+                        events.#index_field_name = index_events.into_iter().map(|ev| ev.into()).collect();
+                        inserted_indexes.push(#available_index_ident::#index_variant);
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        quote! {
+            /// Inserts `row` into the secondary indexes named in `indexes`
+            /// and returns their change events.
+            pub fn insert_row_into_indexes_cdc(
+                &self,
+                row: &#row_type_ident,
+                link: Link,
+                indexes: &[String],
+            ) -> Result<#events_ident, IndexError<#available_index_ident>> {
+                let mut inserted_indexes: Vec<#available_index_ident> = vec![];
+                let mut events = #events_ident::default();
+
+                #(#insert_rows)*
+                Ok(events)
+            }
         }
     }
 
@@ -1,3 +1,4 @@
+use crate::common::model::IndexBackend;
 use crate::common::name_generator::{WorktableNameGenerator, is_float};
 use crate::generators::persist::PersistGenerator;
 use crate::generators::persist::queries::r#type::map_to_uppercase;
@@ -19,6 +20,8 @@ impl PersistGenerator {
         let process_difference_insert_fn = self.gen_process_difference_insert_index_fn();
         let process_difference_remove_fn = self.gen_process_difference_remove_index_fn();
         let delete_from_indexes = self.gen_index_delete_from_indexes_fn();
+        let rebuild_filters_fn = self.gen_rebuild_filters_index_fn();
+        let clear_fn = self.gen_clear_index_fn();
 
         quote! {
             impl TableSecondaryIndex<#row_type_ident, #avt_type_ident, #avt_index_ident> for #index_type_ident {
@@ -28,6 +31,8 @@ impl PersistGenerator {
                 #process_difference_insert_fn
                 #process_difference_remove_fn
                 #delete_from_indexes
+                #rebuild_filters_fn
+                #clear_fn
             }
         }
     }
@@ -302,6 +307,79 @@ impl PersistGenerator {
         }
     }
 
+    /// Generates `rebuild_filters` function of `TableSecondaryIndex` trait for index when some index is declared
+    /// with `filter`. It rebuilds every such index's filter.
+    fn gen_rebuild_filters_index_fn(&self) -> TokenStream {
+        let rebuilds = self
+            .columns
+            .indexes
+            .values()
+            .filter(|idx| idx.filter)
+            .map(|idx| {
+                let index_field_name = &idx.name;
+                quote! {
+                    self.#index_field_name.rebuild_filter();
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if rebuilds.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                fn rebuild_filters(&self) {
+                    #(#rebuilds)*
+                }
+            }
+        }
+    }
+
+    /// Generates `clear` function of `TableSecondaryIndex` trait for index. Entries are collected before removal
+    /// because index iterators hold node locks, and no row is read.
+    fn gen_clear_index_fn(&self) -> TokenStream {
+        let clears = self
+            .columns
+            .indexes
+            .values()
+            .map(|idx| {
+                let index_field_name = &idx.name;
+                let entries = if idx.is_unique {
+                    quote! {
+                        self.#index_field_name.iter_values().map(|(key, link)| (key, link.0))
+                    }
+                } else {
+                    match idx.backend {
+                        IndexBackend::WorktablesIndex | IndexBackend::Indexset => quote! {
+                            self.#index_field_name.iter().map(|(key, link)| (key.clone(), link.0))
+                        },
+                        _ => quote! {
+                            self.#index_field_name.iter().map(|(key, link)| (key, link.0))
+                        },
+                    }
+                };
+                let rebuild_filter = if idx.filter {
+                    quote! {
+                        self.#index_field_name.rebuild_filter();
+                    }
+                } else {
+                    quote! {}
+                };
+                quote! {
+                    for (key, link) in #entries.collect::<Vec<_>>() {
+                        TableIndex::remove(&self.#index_field_name, &key, link);
+                    }
+                    #rebuild_filter
+                }
+            })
+            .collect::<Vec<_>>();
+
+        quote! {
+            fn clear(&self) {
+                #(#clears)*
+            }
+        }
+    }
+
     fn gen_index_delete_from_indexes_fn(&self) -> TokenStream {
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let avt_index_ident = name_generator.get_available_indexes_ident();
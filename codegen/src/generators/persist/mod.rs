@@ -9,7 +9,7 @@ mod wrapper;
 use proc_macro2::Ident;
 use quote::quote;
 
-use crate::common::model::{Columns, Config, Queries};
+use crate::common::model::{Columns, Config, Hooks, Queries};
 
 pub struct PersistGenerator {
     pub name: Ident,
@@ -17,6 +17,7 @@ pub struct PersistGenerator {
     pub pk: Option<crate::common::model::PrimaryKey>,
     pub queries: Option<Queries>,
     pub config: Option<Config>,
+    pub hooks: Option<Hooks>,
     pub version: u32,
 }
 
@@ -28,6 +29,7 @@ impl PersistGenerator {
             pk: None,
             queries: None,
             config: None,
+            hooks: None,
             version,
         }
     }
@@ -39,6 +41,10 @@ impl PersistGenerator {
     pub fn set_config(&mut self, config: Config) {
         self.config = Some(config);
     }
+
+    pub fn set_hooks(&mut self, hooks: Hooks) {
+        self.hooks = Some(hooks);
+    }
 }
 
 pub fn expand(
@@ -46,6 +52,7 @@ pub fn expand(
     columns: crate::common::model::Columns,
     queries: Option<Queries>,
     config: Option<Config>,
+    hooks: Option<Hooks>,
     version: u32,
 ) -> syn::Result<proc_macro2::TokenStream> {
     let mut generator = PersistGenerator::new(name, columns, version);
@@ -55,6 +62,9 @@ pub fn expand(
     if let Some(c) = config {
         generator.set_config(c);
     }
+    if let Some(h) = hooks {
+        generator.set_hooks(h);
+    }
 
     let pk_def = generator.gen_primary_key_def()?;
     let row_def = generator.gen_row_def();
@@ -63,7 +63,7 @@ impl PersistGenerator {
             quote! {}
         };
         let (backend_derive, backend_impl) =
-            primary_key_backend_impl(self.columns.primary_index_backend, &ident, types)?;
+            primary_key_backend_impl(&self.columns.primary_index_backend, &ident, types, true)?;
         let borrowed_impl = gen_borrowed_primary_key_impl(&ident, types);
 
         Ok(quote! {
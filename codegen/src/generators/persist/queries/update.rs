@@ -3,6 +3,7 @@ use std::collections::HashMap;
 
 use crate::common::model::{Index, Operation};
 use crate::common::name_generator::{WorktableNameGenerator, is_float};
+use crate::generators::flavor::Flavor;
 use crate::generators::persist::PersistGenerator;
 use convert_case::{Case, Casing};
 use proc_macro2::{Ident, Span, TokenStream};
@@ -10,28 +11,39 @@ use quote::quote;
 
 impl PersistGenerator {
     pub fn gen_query_update_impl(&mut self) -> syn::Result<TokenStream> {
-        let custom_updates = if let Some(q) = &self.queries {
-            let custom_updates = self.gen_custom_updates(q.updates.clone());
+        let custom_updates = if let Some(updates) = self.queries.as_ref().map(|q| q.updates.clone()) {
+            let custom_updates: Vec<_> = Flavor::ALL
+                .into_iter()
+                .map(|flavor| self.gen_custom_updates(updates.clone(), flavor))
+                .collect();
 
             quote! {
-                #custom_updates
+                #(#custom_updates)*
             }
         } else {
             quote! {}
         };
-        let full_row_update = self.gen_full_row_update();
+        let full_row_update: Vec<_> = Flavor::ALL
+            .into_iter()
+            .map(|flavor| self.gen_full_row_update(flavor))
+            .collect();
+        let predicate_update: Vec<_> = Flavor::ALL
+            .into_iter()
+            .map(|flavor| self.gen_predicate_update(flavor))
+            .collect();
 
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let table_ident = name_generator.get_work_table_ident();
         Ok(quote! {
             impl #table_ident {
-                #full_row_update
+                #(#full_row_update)*
+                #(#predicate_update)*
                 #custom_updates
             }
         })
     }
 
-    fn gen_full_row_update(&mut self) -> TokenStream {
+    fn gen_full_row_update(&mut self, flavor: Flavor) -> TokenStream {
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let row_ident = name_generator.get_row_type_ident();
         let lock_ident = name_generator.get_lock_type_ident();
@@ -54,35 +66,80 @@ impl PersistGenerator {
         let diff_process_remove = self.gen_process_diffs_remove_on_index(Some(&idents));
         let persist_call = self.gen_persist_call();
         let persist_op = self.gen_persist_op();
-        let full_row_lock = self.gen_full_lock_for_update();
+        let full_row_lock = self.gen_full_lock_for_update(flavor);
+        let async_ = flavor.asyncness();
+        let await_ = flavor.await_();
+        let update = flavor.ident("update");
+        let update_returning = flavor.ident("update_returning");
+        let update_with_guard = flavor.ident("update_with_guard");
+        let update_locked = flavor.ident("update_locked");
+        let reinsert_ident = flavor.ident("reinsert");
+        let update_doc = flavor.doc("update");
+        let update_returning_doc = flavor.doc("update_returning");
+        let hold_guard = if self.columns.is_sized {
+            quote! { _hold_guard }
+        } else {
+            quote! { hold_guard }
+        };
         let size_check = if self.columns.is_sized {
             quote! {}
         } else {
             quote! {
                 if true {
-                    drop(_guard);
-                    let op_lock = { #full_row_lock };
-                    let _guard = LockGuard::new_with_mutation(
-                        op_lock,
-                        self.0.lock_manager.clone(),
-                        pk.clone(),
-                    );
-                    let row_old = self.0.data.select_non_ghosted(link)?;
-                    if let Err(e) = self.reinsert(row_old, row).await {
-                        self.0.update_state.remove(&pk);
-
-                        return Err(e);
-                    }
-
+                    // `update` releases the row lock before reinserting and
+                    // re-reads the row once it holds it again; `upsert_with`
+                    // keeps it so that no other write lands between its merge
+                    // and the reinsert.
+                    let _guard = if hold_guard {
+                        _guard
+                    } else {
+                        drop(_guard);
+                        let op_lock = { #full_row_lock };
+                        let guard = LockGuard::new_with_mutation(
+                            op_lock,
+                            self.0.lock_manager.clone(),
+                            pk.clone(),
+                        );
+                        // A write that held the lock meanwhile may have moved
+                        // the row.
+                        match self.0.primary_index.pk_map.get_value(&pk) {
+                            Some(current) => link = current.into(),
+                            None => {
+                                self.0.update_state.remove(&pk);
+                                return core::result::Result::Err(WorkTableError::NotFound);
+                            }
+                        }
+                        guard
+                    };
+                    let row_old = self.0.data.select_non_ghosted(link);
                     self.0.update_state.remove(&pk);
-
-                    return core::result::Result::Ok(());
+                    let row_old = row_old?;
+                    self.#reinsert_ident(row_old.clone(), row)#await_?;
+                    return core::result::Result::Ok((row_old, _guard));
                 }
             }
         };
 
+        let try_fns = flavor.is_async().then(|| {
+            quote! {
+                /// Same as `update`, but fails with `WorkTableError::LockTimeout`
+                /// instead of waiting when another operation holds the row.
+                pub async fn try_update(&self, row: #row_ident) -> core::result::Result<(), WorkTableError> {
+                    without_waiting(self.update(row)).await
+                }
+
+                /// Same as `update_returning`, but fails with
+                /// `WorkTableError::LockTimeout` instead of waiting when another
+                /// operation holds the row.
+                pub async fn try_update_returning(&self, row: #row_ident) -> core::result::Result<UpdatedRow<#row_ident>, WorkTableError> {
+                    without_waiting(self.update_returning(row)).await
+                }
+            }
+        });
+
         quote! {
-            pub async fn update(&self, row: #row_ident) -> core::result::Result<(), WorkTableError> {
+            #update_doc
+            pub #async_ fn #update(&self, row: #row_ident) -> core::result::Result<(), WorkTableError> {
                 let pk = row.get_primary_key();
                 let op_lock = { #full_row_lock };
                 let guard = LockGuard::new_with_mutation(
@@ -91,15 +148,58 @@ impl PersistGenerator {
                     pk.clone(),
                 );
 
-                self.update_with_guard(row, guard).await
+                self.#update_with_guard(row, guard, false)#await_.map(|_| ())
             }
 
+            /// Same as `update`, but also returns the replaced row version.
+            #update_returning_doc
+            pub #async_ fn #update_returning(&self, row: #row_ident) -> core::result::Result<UpdatedRow<#row_ident>, WorkTableError> {
+                let pk = row.get_primary_key();
+                let op_lock = { #full_row_lock };
+                let guard = LockGuard::new_with_mutation(
+                    op_lock,
+                    self.0.lock_manager.clone(),
+                    pk.clone(),
+                );
+
+                let new = row.clone();
+                let old = self.#update_with_guard(row, guard, false)#await_?;
+                core::result::Result::Ok(UpdatedRow { old, new })
+            }
+
+            #try_fns
+
+            /// Updates the row under an already held full-row lock and
+            /// returns the replaced row version. `hold_guard` keeps the lock
+            /// while a row that changed size is reinserted instead of
+            /// releasing and re-acquiring it.
             #[inline]
-            async fn update_with_guard(
+            #async_ fn #update_with_guard(
+                &self,
+                row: #row_ident,
+                guard: LockGuard<#lock_ident, #pk_ident>,
+                hold_guard: bool,
+            ) -> core::result::Result<#row_ident, WorkTableError> {
+                let new = self.0.captures_updates().then(|| row.clone());
+                // The change is published before the row lock is released,
+                // so changes of one key keep their commit order.
+                let (old, _guard) = self.#update_locked(row, guard, hold_guard)#await_?;
+                if let Some(new) = new {
+                    self.0.on_updated(old.clone(), new);
+                }
+                core::result::Result::Ok(old)
+            }
+
+            /// Body of `update_with_guard`, run while the caller's full-row
+            /// lock is held. Returns the lock with the replaced row, as the
+            /// reinsert of a row that changed size may have re-acquired it.
+            #[inline]
+            #async_ fn #update_locked(
                 &self,
                 row: #row_ident,
                 _guard: LockGuard<#lock_ident, #pk_ident>,
-            ) -> core::result::Result<(), WorkTableError> {
+                #hold_guard: bool,
+            ) -> core::result::Result<(#row_ident, LockGuard<#lock_ident, #pk_ident>), WorkTableError> {
                 let pk = row.get_primary_key();
 
                 let mut link: Link = self.0
@@ -110,6 +210,7 @@ impl PersistGenerator {
                     .ok_or(WorkTableError::NotFound)?;
 
                 let row_old = self.0.data.select_non_ghosted(link)?;
+                self.0.check_update(&row_old, &row)?;
                 self.0.update_state.insert(pk.clone(), row_old);
 
                 let mut bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&row).map_err(|_| WorkTableError::SerializeError)?;
@@ -127,20 +228,85 @@ impl PersistGenerator {
 
                 #diff_process_remove
 
-                self.0.update_state.remove(&pk);
+                let row_old = self.0.update_state.remove(&pk).map(|(_, row_old)| row_old);
 
                 #persist_call
 
-                core::result::Result::Ok(())
+                row_old.map(|row_old| (row_old, _guard)).ok_or(WorkTableError::NotFound)
+            }
+        }
+    }
+
+    fn gen_predicate_update(&self, flavor: Flavor) -> TokenStream {
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let row_ident = name_generator.get_row_type_ident();
+        let full_row_lock = self.gen_full_lock_for_update(flavor);
+        let async_ = flavor.asyncness();
+        let await_ = flavor.await_();
+        let update_where = flavor.ident("update_where");
+        let update_with_guard = flavor.ident("update_with_guard");
+        let doc = flavor.doc("update_where");
+
+        quote! {
+            /// Applies `f` to every row matching `predicate` and returns how
+            /// many rows were updated.
+            ///
+            /// Each snapshotted row is re-read and re-checked against the
+            /// predicate under its full-row lock before `f` runs, and is then
+            /// written through the same path as `update`. `f` must not change
+            /// the primary key.
+            ///
+            /// The rows are not updated atomically: they are written one by one
+            /// in primary key order, and an error stops the loop with the rows
+            /// before it already updated and left so.
+            #doc
+            pub #async_ fn #update_where<P, F>(&self, predicate: P, mut f: F) -> core::result::Result<usize, WorkTableError>
+            where
+                P: Fn(&#row_ident) -> bool,
+                F: FnMut(&mut #row_ident),
+            {
+                let mut pks = self.primary_keys_where(&predicate)?;
+                pks.sort_unstable();
+                pks.dedup();
+                let mut updated = 0usize;
+                for pk in pks {
+                    let op_lock = { #full_row_lock };
+                    let guard = LockGuard::new_with_mutation(
+                        op_lock,
+                        self.0.lock_manager.clone(),
+                        pk.clone(),
+                    );
+
+                    let mut row = match self.0.select(pk.clone()) {
+                        Some(row) => row,
+                        None => continue,
+                    };
+                    if !predicate(&row) {
+                        continue;
+                    }
+                    f(&mut row);
+                    if row.get_primary_key() != pk {
+                        return core::result::Result::Err(WorkTableError::PrimaryUpdateTry);
+                    }
+                    // The unsized update path briefly releases the row lock
+                    // before reinserting; a concurrent delete winning that
+                    // window means the row is simply gone.
+                    match self.#update_with_guard(row, guard, false)#await_ {
+                        core::result::Result::Ok(_) => updated += 1,
+                        core::result::Result::Err(WorkTableError::NotFound) => {}
+                        core::result::Result::Err(WorkTableError::PagesError(e)) if e.is_row_absent() => {}
+                        core::result::Result::Err(e) => return core::result::Result::Err(e),
+                    }
+                }
+                core::result::Result::Ok(updated)
             }
         }
     }
 
-    fn gen_custom_updates(&mut self, updates: HashMap<Ident, Operation>) -> TokenStream {
+    fn gen_custom_updates(&mut self, updates: HashMap<Ident, Operation>, flavor: Flavor) -> TokenStream {
         let defs = updates
             .iter()
             .map(|(name, op)| {
-                let snake_case_name = name.to_string().from_case(Case::Pascal).to_case(Case::Snake);
                 let index = self.columns.indexes.values().find(|idx| idx.field == op.by);
 
                 let indexes_columns: Option<Vec<_>> = {
@@ -171,26 +337,26 @@ impl PersistGenerator {
 
                     if index.is_unique {
                         self.gen_unique_update(
-                            snake_case_name,
                             name,
                             index_name,
                             idents,
                             indexes_columns.as_ref(),
                             unsized_columns,
+                            flavor,
                         )
                     } else {
                         self.gen_non_unique_update(
-                            snake_case_name,
                             name,
                             index,
                             idents,
                             indexes_columns.as_ref(),
                             unsized_columns,
+                            flavor,
                         )
                     }
                 } else if self.columns.primary_keys.len() == 1 {
                     if *self.columns.primary_keys.first().unwrap() == op.by {
-                        self.gen_pk_update(snake_case_name, name, idents, indexes_columns.as_ref(), unsized_columns)
+                        self.gen_pk_update(name, idents, indexes_columns.as_ref(), unsized_columns, flavor)
                     } else {
                         todo!()
                     }
@@ -216,7 +382,9 @@ impl PersistGenerator {
         }
     }
 
-    fn gen_size_check(&self, unsized_fields: Option<Vec<&Ident>>, idents: &[Ident]) -> TokenStream {
+    fn gen_size_check(&self, unsized_fields: Option<Vec<&Ident>>, idents: &[Ident], flavor: Flavor) -> TokenStream {
+        let reinsert_ident = flavor.ident("reinsert");
+        let await_ = flavor.await_();
         if let Some(f) = unsized_fields {
             let fields_check: Vec<_> = f
                 .iter()
@@ -235,7 +403,8 @@ impl PersistGenerator {
                     }
                 })
                 .collect::<Vec<_>>();
-            let full_row_lock = self.gen_full_lock_for_update();
+            let full_row_lock = self.gen_full_lock_for_update(flavor);
+            let report_update = Self::gen_report_update();
 
             quote! {
                 let mut need_to_reinsert = true;
@@ -252,12 +421,15 @@ impl PersistGenerator {
                     let row_old = self.0.select(pk.clone()).expect("should not be deleted by other thread");
                     let mut row_new = row_old.clone();
                     #(#row_updates)*
-                    if let Err(e) = self.reinsert(row_old, row_new).await {
+                    self.0.check_update(&row_old, &row_new)?;
+                    let change_rows = (self.0.captures_updates() || images.is_some()).then(|| (row_old.clone(), row_new.clone()));
+                    if let Err(e) = self.#reinsert_ident(row_old, row_new)#await_ {
                         self.0.update_state.remove(&pk);
 
                         return Err(e);
                     }
 
+                    #report_update
                     return core::result::Result::Ok(());
                 }
             }
@@ -387,16 +559,95 @@ impl PersistGenerator {
         }
     }
 
+    /// Builds `change_rows` from the row version `replaced` read inside the
+    /// in-place write and the query's `row`, which only carries the updated
+    /// columns.
+    fn gen_replaced_rows(idents: &[Ident]) -> TokenStream {
+        quote! {
+            let change_rows = match replaced {
+                Some(old) => {
+                    let old = old.map_err(|_| WorkTableError::SerializeError)?;
+                    let mut new = old.clone();
+                    #(new.#idents = row.#idents.clone();)*
+                    Some((old, new))
+                }
+                None => None,
+            };
+        }
+    }
+
+    /// Hands the replaced and written row versions in `change_rows` to the
+    /// returning caller collecting them in `images` and to the update
+    /// subscribers.
+    fn gen_report_update() -> TokenStream {
+        quote! {
+            if let Some((old, new)) = change_rows {
+                if let Some(images) = images.as_deref_mut() {
+                    images.push(UpdatedRow { old: old.clone(), new: new.clone() });
+                }
+                if self.0.captures_updates() {
+                    self.0.on_updated(old, new);
+                }
+            }
+        }
+    }
+
+    /// Runs a `before_update` hook against the row version about to be
+    /// replaced in place at `link`, and decides whether the in-place write
+    /// has to capture the replaced row.
+    fn gen_check_update(idents: &[Ident]) -> TokenStream {
+        quote! {
+            if self.0.checks_updates() {
+                let old = self.0.data.select_non_ghosted(link).map_err(WorkTableError::PagesError)?;
+                let mut new = old.clone();
+                #(new.#idents = row.#idents.clone();)*
+                self.0.check_update(&old, &new)?;
+            }
+            let capture_images = self.0.captures_updates() || images.is_some();
+        }
+    }
+
+    /// Swaps the query's columns into the row at `link` and, if
+    /// `capture_images` is set, reads the replaced row in the same write.
+    fn gen_write_in_place(row_ident: &Ident, row_updates: &[TokenStream]) -> TokenStream {
+        quote! {
+            let replaced = unsafe {
+                self.0.data.with_mut_ref(link, |archived| {
+                    let old = capture_images
+                        .then(|| rkyv::deserialize::<#row_ident, rkyv::rancor::Error>(&archived.inner));
+                    #(#row_updates)*
+                    old
+                }).map_err(WorkTableError::PagesError)?
+            };
+        }
+    }
+
     fn gen_pk_update(
         &self,
-        snake_case_name: String,
         name: &Ident,
         idents: &[Ident],
         idx_idents: Option<&Vec<Ident>>,
         unsized_fields: Option<Vec<&Ident>>,
+        flavor: Flavor,
     ) -> TokenStream {
+        let snake_case_name = name.to_string().from_case(Case::Pascal).to_case(Case::Snake);
+        let async_ = flavor.asyncness();
+        let await_ = flavor.await_();
         let pk_ident = &self.pk.as_ref().unwrap().ident;
-        let method_ident = Ident::new(format!("update_{snake_case_name}").as_str(), Span::mixed_site());
+        let method_ident = flavor.ident(format!("update_{snake_case_name}"));
+        let method_doc = flavor.doc(format!("update_{snake_case_name}"));
+        let returning_ident = flavor.ident(format!("update_{snake_case_name}_returning"));
+        let returning_doc = flavor.doc(format!("update_{snake_case_name}_returning"));
+        let with_images_ident = flavor.ident(format!("update_{snake_case_name}_with_images"));
+        let try_method_ident = Ident::new(format!("try_update_{snake_case_name}").as_str(), Span::mixed_site());
+        let try_returning_ident = Ident::new(
+            format!("try_update_{snake_case_name}_returning").as_str(),
+            Span::mixed_site(),
+        );
+        let row_ident = WorktableNameGenerator::from_table_name(self.name.to_string()).get_row_type_ident();
+        let check_update = Self::gen_check_update(idents);
+        let replaced_rows = Self::gen_replaced_rows(idents);
+        let report_update = Self::gen_report_update();
         let query_ident = Ident::new(format!("{name}Query").as_str(), Span::mixed_site());
         let lock_ident = WorktableNameGenerator::get_update_query_lock_ident(&snake_case_name);
 
@@ -408,66 +659,130 @@ impl PersistGenerator {
                 }
             })
             .collect::<Vec<_>>();
+        let write_in_place = Self::gen_write_in_place(&row_ident, &row_updates);
 
-        let size_check = self.gen_size_check(unsized_fields, idents);
+        let size_check = self.gen_size_check(unsized_fields, idents, flavor);
         let diff_process_insert = self.gen_process_diffs_insert_on_index(idents, idx_idents);
         let diff_process_remove = self.gen_process_diffs_remove_on_index(idx_idents);
         let persist_call = self.gen_persist_call();
         let persist_op = self.gen_persist_op();
-        let custom_lock = self.gen_custom_lock_for_update(lock_ident);
+        let custom_lock = self.gen_custom_lock_for_update(lock_ident, flavor);
+
+        let try_fns = flavor.is_async().then(|| {
+            quote! {
+                /// Same as the plain update query, but fails with
+                /// `WorkTableError::LockTimeout` instead of waiting when another
+                /// operation holds the updated columns.
+                pub async fn #try_method_ident<Pk>(&self, row: #query_ident, pk: Pk) -> core::result::Result<(), WorkTableError>
+                where #pk_ident: From<Pk>
+                {
+                    without_waiting(self.#method_ident(row, pk)).await
+                }
+
+                /// Same as the returning update query, but fails with
+                /// `WorkTableError::LockTimeout` instead of waiting when another
+                /// operation holds the updated columns.
+                pub async fn #try_returning_ident<Pk>(&self, row: #query_ident, pk: Pk) -> core::result::Result<UpdatedRow<#row_ident>, WorkTableError>
+                where #pk_ident: From<Pk>
+                {
+                    without_waiting(self.#returning_ident(row, pk)).await
+                }
+            }
+        });
 
         quote! {
-            pub async fn #method_ident<Pk>(&self, row: #query_ident, pk: Pk) -> core::result::Result<(), WorkTableError>
-            where #pk_ident: From<Pk>
-            {
-                let pk: #pk_ident = pk.into();
-                let op_lock = { #custom_lock };
-                let _guard = LockGuard::new_with_mutation(
-                    op_lock,
-                    self.0.lock_manager.clone(),
-                    pk.clone(),
-                );
+                    #method_doc
+                    pub #async_ fn #method_ident<Pk>(&self, row: #query_ident, pk: Pk) -> core::result::Result<(), WorkTableError>
+                    where #pk_ident: From<Pk>
+                    {
+                        self.#with_images_ident(row, pk.into(), None)#await_
+                    }
 
-                let mut link: Link = self.0
-                        .primary_index
-                        .pk_map
-                        .get_value(&pk)
-                        .map(Into::into)
-                        .ok_or(WorkTableError::NotFound)?;
+                    /// Same as the plain update query, but also returns the replaced
+                    /// and the written row versions.
+                    #returning_doc
+                    pub #async_ fn #returning_ident<Pk>(&self, row: #query_ident, pk: Pk) -> core::result::Result<UpdatedRow<#row_ident>, WorkTableError>
+                    where #pk_ident: From<Pk>
+                    {
+                        let mut images = Vec::new();
+                        self.#with_images_ident(row, pk.into(), Some(&mut images))#await_?;
+                        images.pop().ok_or(WorkTableError::NotFound)
+                    }
 
-                let mut bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&row).map_err(|_| WorkTableError::SerializeError)?;
-                let mut archived_row = unsafe { rkyv::access_unchecked_mut::<<#query_ident as rkyv::Archive>::Archived>(&mut bytes[..]).unseal_unchecked() };
+                    #try_fns
 
-                let op_id = OperationId::Single(uuid::Uuid::now_v7());
-                #size_check
-                #diff_process_insert
-                #persist_op
+                    #async_ fn #with_images_ident(
+                        &self,
+                        row: #query_ident,
+                        pk: #pk_ident,
+                        mut images: Option<&mut Vec<UpdatedRow<#row_ident>>>,
+                    ) -> core::result::Result<(), WorkTableError> {
+                        let op_lock = { #custom_lock };
+                        let _guard = LockGuard::new_with_mutation(
+                            op_lock,
+                            self.0.lock_manager.clone(),
+                            pk.clone(),
+                        );
 
-                unsafe { self.0.data.with_mut_ref(link, |archived| {
-                    #(#row_updates)*
-                }).map_err(WorkTableError::PagesError)? };
+                        let mut link: Link = self.0
+                                .primary_index
+                                .pk_map
+                                .get_value(&pk)
+                                .map(Into::into)
+                                .ok_or(WorkTableError::NotFound)?;
 
-                #diff_process_remove
+                        let mut bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&row).map_err(|_| WorkTableError::SerializeError)?;
+                        let mut archived_row = unsafe { rkyv::access_unchecked_mut::<<#query_ident as rkyv::Archive>::Archived>(&mut bytes[..]).unseal_unchecked() };
 
-                #persist_call
+                        let op_id = OperationId::Single(uuid::Uuid::now_v7());
+                        #size_check
+                        #check_update
+                        #diff_process_insert
+                        #persist_op
 
-                core::result::Result::Ok(())
-            }
-        }
+                        #write_in_place
+
+                        #diff_process_remove
+
+                        #persist_call
+
+                        #replaced_rows
+        #report_update
+
+                        core::result::Result::Ok(())
+                    }
+                }
     }
 
     fn gen_non_unique_update(
         &self,
-        snake_case_name: String,
         name: &Ident,
         index: &Index,
         idents: &[Ident],
         idx_idents: Option<&Vec<Ident>>,
         unsized_fields: Option<Vec<&Ident>>,
+        flavor: Flavor,
     ) -> TokenStream {
+        let snake_case_name = name.to_string().from_case(Case::Pascal).to_case(Case::Snake);
+        let async_ = flavor.asyncness();
+        let await_ = flavor.await_();
+        let reinsert_ident = flavor.ident("reinsert");
         let by_field = &index.field;
         let index = &index.name;
-        let method_ident = Ident::new(format!("update_{snake_case_name}").as_str(), Span::mixed_site());
+        let method_ident = flavor.ident(format!("update_{snake_case_name}"));
+        let method_doc = flavor.doc(format!("update_{snake_case_name}"));
+        let returning_ident = flavor.ident(format!("update_{snake_case_name}_returning"));
+        let returning_doc = flavor.doc(format!("update_{snake_case_name}_returning"));
+        let with_images_ident = flavor.ident(format!("update_{snake_case_name}_with_images"));
+        let try_method_ident = Ident::new(format!("try_update_{snake_case_name}").as_str(), Span::mixed_site());
+        let try_returning_ident = Ident::new(
+            format!("try_update_{snake_case_name}_returning").as_str(),
+            Span::mixed_site(),
+        );
+        let row_ident = WorktableNameGenerator::from_table_name(self.name.to_string()).get_row_type_ident();
+        let check_update = Self::gen_check_update(idents);
+        let replaced_rows = Self::gen_replaced_rows(idents);
+        let report_update = Self::gen_report_update();
 
         let query_ident = Ident::new(format!("{name}Query").as_str(), Span::mixed_site());
         let by_ident = Ident::new(format!("{name}By").as_str(), Span::mixed_site());
@@ -481,6 +796,7 @@ impl PersistGenerator {
                 }
             })
             .collect::<Vec<_>>();
+        let write_in_place = Self::gen_write_in_place(&row_ident, &row_updates);
 
         let size_check = if let Some(f) = unsized_fields {
             let fields_check: Vec<_> = f
@@ -500,7 +816,7 @@ impl PersistGenerator {
                     }
                 })
                 .collect::<Vec<_>>();
-            let full_row_lock = self.gen_full_lock_for_update();
+            let full_row_lock = self.gen_full_lock_for_update(flavor);
 
             quote! {
                 let mut need_to_reinsert = true;
@@ -519,11 +835,14 @@ impl PersistGenerator {
                     let row_old = self.0.select(pk.clone()).expect("should not be deleted by other thread");
                     let mut row_new = row_old.clone();
                     #(#row_updates)*
-                    if let Err(e) = self.reinsert(row_old, row_new).await {
+                    self.0.check_update(&row_old, &row_new)?;
+                    let change_rows = (self.0.captures_updates() || images.is_some()).then(|| (row_old.clone(), row_new.clone()));
+                    if let Err(e) = self.#reinsert_ident(row_old, row_new)#await_ {
                         self.0.update_state.remove(&pk);
                         return Err(e);
                     }
 
+                    #report_update
                     continue;
                 }
             }
@@ -543,99 +862,155 @@ impl PersistGenerator {
                 &by
             }
         };
-        let custom_lock = self.gen_custom_lock_for_update(lock_ident);
+        let custom_lock = self.gen_custom_lock_for_update(lock_ident, flavor);
 
-        quote! {
-            pub async fn #method_ident(&self, row: #query_ident, by: #by_ident) -> core::result::Result<(), WorkTableError> {
-                // Snapshot the matching rows' primary keys once; the same set
-                // is locked and then processed. Locking one index scan and
-                // processing a fresh second scan would let rows that joined the
-                // range in between be processed without a held lock. The keys
-                // are sorted so concurrent multi-row operations acquire row
-                // locks in one global order (a non-unique index iterates equal
-                // keys in random-discriminator order, which would otherwise
-                // also make the partially-updated subset on a mid-way failure
-                // nondeterministic).
-                let mut pks: Vec<_> = Vec::new();
-                for link in self.0.indexes.#index.get(#by).map(|(_, l)| l.0) {
-                    match self.0.data.select_non_ghosted(link) {
-                        core::result::Result::Ok(r) => pks.push(r.get_primary_key()),
-                        // The row vanished between the index read and the
-                        // resolve; it is simply not part of the snapshot.
-                        core::result::Result::Err(e) if e.is_row_absent() => {}
-                        // Anything else (corrupt page, invalid link, ...) is a
-                        // real storage error, not an empty snapshot slot.
-                        core::result::Result::Err(e) => {
-                            return core::result::Result::Err(WorkTableError::PagesError(e));
-                        }
-                    }
+        let try_fns = flavor.is_async().then(|| {
+            quote! {
+                /// Same as the plain update query, but fails with
+                /// `WorkTableError::LockTimeout` instead of waiting when another
+                /// operation holds the updated columns of a matching row.
+                pub async fn #try_method_ident(&self, row: #query_ident, by: #by_ident) -> core::result::Result<(), WorkTableError> {
+                    without_waiting(self.#method_ident(row, by)).await
                 }
-                pks.sort_unstable();
-                pks.dedup();
 
-                let mut guards: std::collections::HashMap<_, _> = std::collections::HashMap::new();
-                for pk in pks.iter() {
-                    let pk = pk.clone();
-                    let op_lock = { #custom_lock };
-                    guards.insert(pk.clone(), LockGuard::new(op_lock, self.0.lock_manager.clone(), pk));
+                /// Same as the returning update query, but fails with
+                /// `WorkTableError::LockTimeout` instead of waiting when another
+                /// operation holds the updated columns of a matching row.
+                pub async fn #try_returning_ident(&self, row: #query_ident, by: #by_ident) -> core::result::Result<Vec<UpdatedRow<#row_ident>>, WorkTableError> {
+                    without_waiting(self.#returning_ident(row, by)).await
                 }
+            }
+        });
 
-                let op_id = OperationId::Multi(uuid::Uuid::now_v7());
-                for pk in pks.into_iter() {
-                    // Re-resolve and re-validate under the held lock. The
-                    // query's lock set includes the predicate column, so the
-                    // value read here cannot be rewritten concurrently while
-                    // the lock is held. Rows deleted or updated out of the
-                    // matched range before their lock was acquired are
-                    // skipped; rows that joined the range after the snapshot
-                    // are not touched.
-                    let link: Link = match self.0.primary_index.pk_map.get_value(&pk) {
-                        Some(v) => v.into(),
-                        None => continue,
-                    };
-                    if self.0.data.select_non_ghosted(link)?.#by_field != by {
-                        continue;
+        quote! {
+                    #method_doc
+                    pub #async_ fn #method_ident(&self, row: #query_ident, by: #by_ident) -> core::result::Result<(), WorkTableError> {
+                        self.#with_images_ident(row, by, None)#await_
                     }
-                    let _mutation_guard = self.0.lock_manager.mutation_guard(&pk);
-                    let mut bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&row)
-                        .map_err(|_| WorkTableError::SerializeError)?;
 
-                    let mut archived_row = unsafe {
-                        rkyv::access_unchecked_mut::<<#query_ident as rkyv::Archive>::Archived>(&mut bytes[..])
-                            .unseal_unchecked()
-                    };
+                    /// Same as the plain update query, but also returns the replaced
+                    /// and the written version of every updated row.
+                    #returning_doc
+                    pub #async_ fn #returning_ident(&self, row: #query_ident, by: #by_ident) -> core::result::Result<Vec<UpdatedRow<#row_ident>>, WorkTableError> {
+                        let mut images = Vec::new();
+                        self.#with_images_ident(row, by, Some(&mut images))#await_?;
+                        core::result::Result::Ok(images)
+                    }
+
+                    #try_fns
+
+                    #async_ fn #with_images_ident(
+                        &self,
+                        row: #query_ident,
+                        by: #by_ident,
+                        mut images: Option<&mut Vec<UpdatedRow<#row_ident>>>,
+                    ) -> core::result::Result<(), WorkTableError> {
+                        // Snapshot the matching rows' primary keys once; the same set
+                        // is locked and then processed. Locking one index scan and
+                        // processing a fresh second scan would let rows that joined the
+                        // range in between be processed without a held lock. The keys
+                        // are sorted so concurrent multi-row operations acquire row
+                        // locks in one global order (a non-unique index iterates equal
+                        // keys in random-discriminator order, which would otherwise
+                        // also make the partially-updated subset on a mid-way failure
+                        // nondeterministic).
+                        let mut pks: Vec<_> = Vec::new();
+                        for link in self.0.indexes.#index.get(#by).map(|(_, l)| l.0) {
+                            match self.0.data.select_non_ghosted(link) {
+                                core::result::Result::Ok(r) => pks.push(r.get_primary_key()),
+                                // The row vanished between the index read and the
+                                // resolve; it is simply not part of the snapshot.
+                                core::result::Result::Err(e) if e.is_row_absent() => {}
+                                // Anything else (corrupt page, invalid link, ...) is a
+                                // real storage error, not an empty snapshot slot.
+                                core::result::Result::Err(e) => {
+                                    return core::result::Result::Err(WorkTableError::PagesError(e));
+                                }
+                            }
+                        }
+                        pks.sort_unstable();
+                        pks.dedup();
+
+                        let mut guards: std::collections::HashMap<_, _> = std::collections::HashMap::new();
+                        for pk in pks.iter() {
+                            let pk = pk.clone();
+                            let op_lock = { #custom_lock };
+                            guards.insert(pk.clone(), LockGuard::new(op_lock, self.0.lock_manager.clone(), pk));
+                        }
 
-                    #size_check
-                    #diff_process_insert
-                    #persist_op
+                        let op_id = OperationId::Multi(uuid::Uuid::now_v7());
+                        for pk in pks.into_iter() {
+                            // Re-resolve and re-validate under the held lock. The
+                            // query's lock set includes the predicate column, so the
+                            // value read here cannot be rewritten concurrently while
+                            // the lock is held. Rows deleted or updated out of the
+                            // matched range before their lock was acquired are
+                            // skipped; rows that joined the range after the snapshot
+                            // are not touched.
+                            let link: Link = match self.0.primary_index.pk_map.get_value(&pk) {
+                                Some(v) => v.into(),
+                                None => continue,
+                            };
+                            let current = self.0.data.select_non_ghosted(link)?;
+                            if current.#by_field != by {
+                                continue;
+                            }
+                            let _mutation_guard = self.0.lock_manager.mutation_guard(&pk);
+                            let mut bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&row)
+                                .map_err(|_| WorkTableError::SerializeError)?;
 
-                    unsafe {
-                        self.0.data.with_mut_ref(link, |archived| {
-                            #(#row_updates)*
-                        }).map_err(WorkTableError::PagesError)?;
-                    }
+                            let mut archived_row = unsafe {
+                                rkyv::access_unchecked_mut::<<#query_ident as rkyv::Archive>::Archived>(&mut bytes[..])
+                                    .unseal_unchecked()
+                            };
+
+                            #size_check
+                            #check_update
+                            #diff_process_insert
+                            #persist_op
+
+                            #write_in_place
 
-                    #diff_process_remove
+                            #diff_process_remove
 
-                    #persist_call
+                            #persist_call
 
-                    guards.remove(&pk);
+                            #replaced_rows
+        #report_update
+
+                            guards.remove(&pk);
+                        }
+                        core::result::Result::Ok(())
+                    }
                 }
-                core::result::Result::Ok(())
-            }
-        }
     }
 
     fn gen_unique_update(
         &self,
-        snake_case_name: String,
         name: &Ident,
         index: &Ident,
         idents: &[Ident],
         idx_idents: Option<&Vec<Ident>>,
         unsized_fields: Option<Vec<&Ident>>,
+        flavor: Flavor,
     ) -> TokenStream {
-        let method_ident = Ident::new(format!("update_{snake_case_name}").as_str(), Span::mixed_site());
+        let snake_case_name = name.to_string().from_case(Case::Pascal).to_case(Case::Snake);
+        let async_ = flavor.asyncness();
+        let await_ = flavor.await_();
+        let method_ident = flavor.ident(format!("update_{snake_case_name}"));
+        let method_doc = flavor.doc(format!("update_{snake_case_name}"));
+        let returning_ident = flavor.ident(format!("update_{snake_case_name}_returning"));
+        let returning_doc = flavor.doc(format!("update_{snake_case_name}_returning"));
+        let with_images_ident = flavor.ident(format!("update_{snake_case_name}_with_images"));
+        let try_method_ident = Ident::new(format!("try_update_{snake_case_name}").as_str(), Span::mixed_site());
+        let try_returning_ident = Ident::new(
+            format!("try_update_{snake_case_name}_returning").as_str(),
+            Span::mixed_site(),
+        );
+        let row_ident = WorktableNameGenerator::from_table_name(self.name.to_string()).get_row_type_ident();
+        let check_update = Self::gen_check_update(idents);
+        let replaced_rows = Self::gen_replaced_rows(idents);
+        let report_update = Self::gen_report_update();
 
         let query_ident = Ident::new(format!("{name}Query").as_str(), Span::mixed_site());
         let by_ident = Ident::new(format!("{name}By").as_str(), Span::mixed_site());
@@ -649,7 +1024,8 @@ impl PersistGenerator {
                 }
             })
             .collect::<Vec<_>>();
-        let size_check = self.gen_size_check(unsized_fields, idents);
+        let write_in_place = Self::gen_write_in_place(&row_ident, &row_updates);
+        let size_check = self.gen_size_check(unsized_fields, idents, flavor);
         let diff_process_insert = self.gen_process_diffs_insert_on_index(idents, idx_idents);
         let diff_process_remove = self.gen_process_diffs_remove_on_index(idx_idents);
         let persist_call = self.gen_persist_call();
@@ -663,66 +1039,102 @@ impl PersistGenerator {
                 &by
             }
         };
-        let custom_lock = self.gen_custom_lock_for_update(lock_ident);
+        let custom_lock = self.gen_custom_lock_for_update(lock_ident, flavor);
+
+        let try_fns = flavor.is_async().then(|| {
+            quote! {
+                /// Same as the plain update query, but fails with
+                /// `WorkTableError::LockTimeout` instead of waiting when another
+                /// operation holds the updated columns of a matching row.
+                pub async fn #try_method_ident(&self, row: #query_ident, by: #by_ident) -> core::result::Result<(), WorkTableError> {
+                    without_waiting(self.#method_ident(row, by)).await
+                }
+
+                /// Same as the returning update query, but fails with
+                /// `WorkTableError::LockTimeout` instead of waiting when another
+                /// operation holds the updated columns of a matching row.
+                pub async fn #try_returning_ident(&self, row: #query_ident, by: #by_ident) -> core::result::Result<UpdatedRow<#row_ident>, WorkTableError> {
+                    without_waiting(self.#returning_ident(row, by)).await
+                }
+            }
+        });
 
         quote! {
-            pub async fn #method_ident(&self, row: #query_ident, by: #by_ident) -> core::result::Result<(), WorkTableError> {
-                 let mut bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&row)
-                    .map_err(|_| WorkTableError::SerializeError)?;
+                    #method_doc
+                    pub #async_ fn #method_ident(&self, row: #query_ident, by: #by_ident) -> core::result::Result<(), WorkTableError> {
+                        self.#with_images_ident(row, by, None)#await_
+                    }
 
-                let mut archived_row = unsafe {
-                    rkyv::access_unchecked_mut::<<#query_ident as rkyv::Archive>::Archived>(&mut bytes[..])
-                        .unseal_unchecked()
-                };
+                    /// Same as the plain update query, but also returns the replaced
+                    /// and the written row versions.
+                    #returning_doc
+                    pub #async_ fn #returning_ident(&self, row: #query_ident, by: #by_ident) -> core::result::Result<UpdatedRow<#row_ident>, WorkTableError> {
+                        let mut images = Vec::new();
+                        self.#with_images_ident(row, by, Some(&mut images))#await_?;
+                        images.pop().ok_or(WorkTableError::NotFound)
+                    }
 
-                let mut link: Link = self.0.indexes
-                    .#index
-                    .get_value(#by)
-                    .map(Into::into)
-                    .ok_or(WorkTableError::NotFound)?;
+                    #try_fns
 
-                let pk = self.0.data.select_non_ghosted(link)?.get_primary_key().clone();
+                    #async_ fn #with_images_ident(
+                        &self,
+                        row: #query_ident,
+                        by: #by_ident,
+                        mut images: Option<&mut Vec<UpdatedRow<#row_ident>>>,
+                    ) -> core::result::Result<(), WorkTableError> {
+                        let mut bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&row)
+                            .map_err(|_| WorkTableError::SerializeError)?;
 
-                let op_lock = { #custom_lock };
-                let _guard = LockGuard::new_with_mutation(
-                    op_lock,
-                    self.0.lock_manager.clone(),
-                    pk.clone(),
-                );
+                        let mut archived_row = unsafe {
+                            rkyv::access_unchecked_mut::<<#query_ident as rkyv::Archive>::Archived>(&mut bytes[..])
+                                .unseal_unchecked()
+                        };
 
-                let link = loop {
-                    let link = self.0.indexes.#index
-                        .get_value(#by)
-                        .map(Into::into)
-                        .ok_or(WorkTableError::NotFound)?;
+                        let mut link: Link = self.0.indexes
+                            .#index
+                            .get_value(#by)
+                            .map(Into::into)
+                            .ok_or(WorkTableError::NotFound)?;
+
+                        let pk = self.0.data.select_non_ghosted(link)?.get_primary_key().clone();
+
+                        let op_lock = { #custom_lock };
+                        let _guard = LockGuard::new_with_mutation(
+                            op_lock,
+                            self.0.lock_manager.clone(),
+                            pk.clone(),
+                        );
+
+                        let link = loop {
+                            let link = self.0.indexes.#index
+                                .get_value(#by)
+                                .map(Into::into)
+                                .ok_or(WorkTableError::NotFound)?;
+
+                            match self.0.data.select_non_vacuumed(link) {
+                                core::result::Result::Ok(_) => break link,
+                                core::result::Result::Err(e) if e.is_vacuumed() => continue,
+                                core::result::Result::Err(e) => return Err(e.into()),
+                            }
+                        };
 
-                    if let Err(e) = self.0.data.select_non_vacuumed(link) {
-                        if e.is_vacuumed() {
-                            continue;
-                        }
-                        return Err(e.into());
-                    } else  {
-                        break link;
-                    }
-                };
+                        let op_id = OperationId::Single(uuid::Uuid::now_v7());
+                        #size_check
+                        #check_update
+                        #diff_process_insert
+                        #persist_op
 
-                let op_id = OperationId::Single(uuid::Uuid::now_v7());
-                #size_check
-                #diff_process_insert
-                #persist_op
+                        #write_in_place
 
-                unsafe {
-                    self.0.data.with_mut_ref(link, |archived| {
-                        #(#row_updates)*
-                    }).map_err(WorkTableError::PagesError)?;
-                }
+                        #diff_process_remove
 
-                #diff_process_remove
+                        #persist_call
 
-                #persist_call
+                        #replaced_rows
+        #report_update
 
-                core::result::Result::Ok(())
-            }
-        }
+                        core::result::Result::Ok(())
+                    }
+                }
     }
 }
@@ -2,52 +2,81 @@ use convert_case::{Case, Casing};
 use proc_macro2::{Ident, Literal, Span, TokenStream};
 use quote::quote;
 
-use crate::common::model::GeneratorType;
+use crate::common::model::{GeneratorType, IndexBackend};
 use crate::common::name_generator::{WorktableNameGenerator, is_float, is_unsized_vec};
+use crate::generators::flavor::Flavor;
+use crate::generators::hooks::gen_table_hooks_fn;
 use crate::generators::persist::PersistGenerator;
 
 impl PersistGenerator {
-    pub fn gen_table_impl(&self) -> TokenStream {
+    pub fn gen_table_impl(&self) -> syn::Result<TokenStream> {
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let ident = name_generator.get_work_table_ident();
 
         let persisted_impl = self.gen_table_new_fn();
         let name_fn = self.gen_table_name_fn();
         let version_fn = self.gen_table_version_fn();
+        let page_compression_fn = self.gen_table_page_compression_fn();
         let select_fn = self.gen_table_select_fn();
         let select_range_fn = self.gen_table_select_range_fn();
         let insert_fn = self.gen_table_insert_fn();
-        let reinsert_fn = self.gen_table_reinsert_fn();
-        let upsert_fn = self.gen_table_upsert_fn();
+        let reinsert_fn: Vec<_> = Flavor::ALL
+            .into_iter()
+            .map(|flavor| self.gen_table_reinsert_fn(flavor))
+            .collect();
+        let insert_or_ignore_fn = self.gen_table_insert_or_ignore_fn()?;
+        let upsert_fn: Vec<_> = Flavor::ALL
+            .into_iter()
+            .map(|flavor| self.gen_table_upsert_fn(flavor))
+            .collect();
+        let upsert_with_fn: Vec<_> = Flavor::ALL
+            .into_iter()
+            .map(|flavor| self.gen_table_upsert_with_fn(flavor))
+            .collect();
+        let truncate_fn = self.gen_table_truncate_fn();
+        let subscribe_fn = self.gen_table_subscribe_fn();
+        let lock_diagnostics_fns = self.gen_table_lock_diagnostics_fns();
+        let hooks_fn = gen_table_hooks_fn(self.hooks.as_ref(), &name_generator.get_row_type_ident());
         let get_next_fn = self.gen_table_get_next_fn();
         let pk_gen_state_fn = self.gen_table_pk_gen_state_fn();
         let iter_with_fn = self.gen_table_iter_with_fn();
+        let iter_with_ref_fn = self.gen_table_iter_with_ref_fn();
         let iter_with_async_fn = self.gen_table_iter_with_async_fn();
         let count_fn = self.gen_table_count_fn();
         let system_info_fn = self.gen_system_info_fn();
         let vacuum_fn = self.gen_table_vacuum_fn();
         let validate_loaded_secondary_state_fn = self.gen_validate_loaded_secondary_state_fn();
+        let rebuild_indexes_fn = self.gen_table_rebuild_indexes_fn();
 
-        quote! {
+        Ok(quote! {
             #persisted_impl
             impl #ident {
                 #name_fn
                 #version_fn
+                #page_compression_fn
                 #select_fn
                 #select_range_fn
                 #insert_fn
-                #reinsert_fn
-                #upsert_fn
+                #insert_or_ignore_fn
+                #(#reinsert_fn)*
+                #(#upsert_fn)*
+                #(#upsert_with_fn)*
+                #truncate_fn
+                #subscribe_fn
+                #lock_diagnostics_fns
+                #hooks_fn
                 #count_fn
                 #get_next_fn
                 #pk_gen_state_fn
                 #iter_with_fn
+                #iter_with_ref_fn
                 #iter_with_async_fn
                 #system_info_fn
                 #vacuum_fn
                 #validate_loaded_secondary_state_fn
+                #rebuild_indexes_fn
             }
-        }
+        })
     }
 
     fn gen_validate_loaded_secondary_state_fn(&self) -> TokenStream {
@@ -163,6 +192,14 @@ impl PersistGenerator {
                         }
                     }
                 } else {
+                    // ART multimaps yield owned pairs; the B-tree yields
+                    // references into its nodes.
+                    let expected_key_ref = match index.backend {
+                        IndexBackend::Congee | IndexBackend::Arctic | IndexBackend::Hash | IndexBackend::Custom(_) => {
+                            quote! { expected_key }
+                        }
+                        IndexBackend::WorktablesIndex | IndexBackend::Indexset => quote! { &expected_key },
+                    };
                     quote! {
                         for (indexed_key, offset_link) in self.0.indexes.#index_field.iter() {
                             let row = self.0.data.select_non_ghosted_checked(offset_link.0).map_err(|error| {
@@ -172,7 +209,7 @@ impl PersistGenerator {
                                 )
                             })?;
                             let expected_key = #expected_key;
-                            if indexed_key != &expected_key {
+                            if indexed_key != #expected_key_ref {
                                 return Err(PersistenceLoadError::corrupt(
                                     path,
                                     format!("secondary index {} key does not match its referenced row", #index_name),
@@ -191,7 +228,7 @@ impl PersistGenerator {
                 mode: LoadMode,
             ) -> Result<(), PersistenceLoadError> {
                 match mode {
-                    LoadMode::Strict => {
+                    LoadMode::Strict | LoadMode::Rebuild => {
                         let primary_count = self.0.primary_index.pk_map.len();
                         #(#entry_counts)*
                         for (primary_key, offset_link) in self.0.primary_index.pk_map.iter_values() {
@@ -207,6 +244,11 @@ impl PersistGenerator {
                     LoadMode::Recovery => {
                         #(#recovery_entries)*
                     }
+                    LoadMode::Lazy => {
+                        // Rows stay on disk, so only the index sizes are compared.
+                        let primary_count = self.0.primary_index.pk_map.len();
+                        #(#entry_counts)*
+                    }
                 }
                 Ok(())
             }
@@ -277,6 +319,15 @@ impl PersistGenerator {
                         reverse_pk_map: IndexMap::new(),
                     });
                 },
+                crate::common::model::IndexBackend::Hash => quote! {
+                    inner.primary_index = std::sync::Arc::new(PrimaryIndex {
+                        pk_map: PersistentHashIndex::<#pk_type, OffsetEqLink<#const_name>>::default(),
+                        reverse_pk_map: IndexMap::new(),
+                    });
+                },
+                crate::common::model::IndexBackend::Custom(_) => {
+                    unreachable!("custom index backends are rejected for persisted tables")
+                }
             }
         };
 
@@ -295,6 +346,8 @@ impl PersistGenerator {
             {
                 async fn new(mut engine: E) -> eyre::Result<Self> {
                     let schema = Self::space_info_default().inner;
+                    engine.set_page_compression(Self::page_compression());
+                    engine.set_deleted_flag_offset(Self::deleted_flag_offset());
                     engine
                         .ensure_schema(
                             schema.row_schema,
@@ -304,10 +357,17 @@ impl PersistGenerator {
                         .await?;
                     let mut inner = WorkTable::default();
                     inner.table_name = #table_name;
+                    let mut data = DataPages::new().with_compression(Self::page_compression());
+                    let page_store = Self::page_store(engine.config().table_path());
+                    if let Some((store, _)) = &page_store {
+                        data = data.with_page_store(store.clone());
+                    }
+                    inner.data = std::sync::Arc::new(data);
                     #index_setup
+                    inner.set_memory_budget(Self::memory_budget());
                     core::result::Result::Ok(Self(
                         inner,
-                        #task::run_engine(engine)
+                        #task::run_engine_with_unpersisted(engine, page_store.map(|(_, unpersisted)| unpersisted))
                     ))
                 }
 
@@ -317,6 +377,8 @@ impl PersistGenerator {
 
                 async fn load_with(mut engine: E, mode: LoadMode) -> eyre::Result<Self> {
                     let schema = Self::space_info_default().inner;
+                    engine.set_page_compression(Self::page_compression());
+                    engine.set_deleted_flag_offset(Self::deleted_flag_offset());
                     engine
                         .validate_schema(
                             schema.row_schema,
@@ -329,7 +391,7 @@ impl PersistGenerator {
                         return Self::new(engine).await;
                     };
                     let table = load_persisted_state(&table_path, async {
-                        let space = #space_ident::parse_file(&table_path).await?;
+                        let space = #space_ident::parse_file_with_mode(&table_path, mode).await?;
                         Ok::<_, eyre::Report>(space.into_worktable_with_mode(engine, &table_path, mode).await?)
                     }).await?;
                     Ok(table)
@@ -338,6 +400,68 @@ impl PersistGenerator {
         }
     }
 
+    fn gen_table_rebuild_indexes_fn(&self) -> TokenStream {
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let space_ident = name_generator.get_space_file_ident();
+        let row_type = name_generator.get_row_type_ident();
+        let pk_type = name_generator.get_primary_key_type_ident();
+        let secondary_index_events = name_generator.get_space_secondary_index_events_ident();
+        let avt_index_ident = name_generator.get_available_indexes_ident();
+
+        quote! {
+            /// Opens the persisted table at `config` with every index rebuilt
+            /// from its data pages, for a store whose `.wt.idx` files are lost
+            /// or damaged.
+            ///
+            /// The index files are deleted before the engine opens them, so a
+            /// file it cannot parse does not block the rebuild; the rebuilt
+            /// indexes are written in their place. Unlike
+            /// [`LoadMode::Rebuild`], rows with a duplicate unique key and
+            /// written bytes that hold no row do not fail the rebuild: they
+            /// stay out of the indexes and are listed in the report.
+            pub async fn rebuild_indexes<E, C>(config: C) -> eyre::Result<(Self, IndexRebuildReport<#row_type>)>
+            where
+                E: PersistenceEngine<
+                    <<#pk_type as TablePrimaryKey>::Generator as PrimaryKeyGeneratorState>::State,
+                    #pk_type,
+                    #secondary_index_events,
+                    #avt_index_ident,
+                    Config=C
+                > + Send
+                    + 'static,
+                C: Clone + PersistenceConfig,
+            {
+                let schema = Self::space_info_default().inner;
+                let table_path = config.table_path().to_owned();
+                let space = load_persisted_state(&table_path, #space_ident::parse_file_with_mode(&table_path, LoadMode::Rebuild)).await?;
+                // Refuse another table's files before deleting anything.
+                let stored = &space.data_info.inner;
+                if !stored.row_schema.is_empty()
+                    && (stored.row_schema != schema.row_schema || stored.primary_key_fields != schema.primary_key_fields)
+                {
+                    return Err(PersistenceLoadError::corrupt(
+                        &table_path,
+                        "stored row schema or primary key does not match the table declaration",
+                    )
+                    .into());
+                }
+                remove_index_files(&table_path).await?;
+
+                let mut engine = E::new(config).await?;
+                engine.set_page_compression(Self::page_compression());
+                engine.set_deleted_flag_offset(Self::deleted_flag_offset());
+                engine
+                    .validate_schema(
+                        schema.row_schema,
+                        schema.primary_key_fields,
+                        schema.secondary_index_types,
+                    )
+                    .await?;
+                Ok(space.into_rebuilt_worktable(engine, &table_path).await?)
+            }
+        }
+    }
+
     fn gen_table_name_fn(&self) -> TokenStream {
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let dir_name = name_generator.get_dir_name();
@@ -353,6 +477,57 @@ impl PersistGenerator {
         }
     }
 
+    fn gen_table_page_compression_fn(&self) -> TokenStream {
+        let compression = match self.config.as_ref().and_then(|c| c.compression) {
+            Some(compression) => quote! { #compression },
+            None => quote! { PageCompression::None },
+        };
+
+        let archived_wrapper =
+            WorktableNameGenerator::from_table_name(self.name.to_string()).get_archived_wrapper_type_ident();
+        let memory_budget = match self.config.as_ref().and_then(|c| c.memory_budget()) {
+            Some(budget) => quote! { Some(#budget) },
+            None => quote! { None },
+        };
+        let page_store = if self.config.as_ref().is_some_and(|c| c.evicts_pages()) {
+            let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+            let page_const_name = name_generator.get_page_size_const_ident();
+            let inner_const_name = name_generator.get_page_inner_size_const_ident();
+            quote! {
+                let unpersisted = std::sync::Arc::new(UnpersistedPages::new(#inner_const_name));
+                let store = SpaceDataPages::<{ #page_const_name as u32 }>::evicting(table_path, unpersisted.clone());
+                Some((std::sync::Arc::new(store), unpersisted))
+            }
+        } else {
+            quote! { None }
+        };
+
+        quote! {
+            pub fn page_compression() -> PageCompression {
+                #compression
+            }
+
+            pub fn memory_budget() -> Option<MemoryBudget> {
+                #memory_budget
+            }
+
+            /// Store the pages evicted over the memory budget are read back
+            /// from, with the pages the persistence engine has yet to write,
+            /// which are not evicted.
+            pub fn page_store(
+                table_path: &str,
+            ) -> Option<(std::sync::Arc<dyn PageStore>, std::sync::Arc<UnpersistedPages>)> {
+                #page_store
+            }
+
+            /// Bytes from the deleted flag of a stored row to the row's end,
+            /// where its archived wrapper sits.
+            pub fn deleted_flag_offset() -> u32 {
+                (core::mem::size_of::<#archived_wrapper>() - core::mem::offset_of!(#archived_wrapper, is_deleted)) as u32
+            }
+        }
+    }
+
     fn gen_table_version_fn(&self) -> TokenStream {
         let version = self.version;
 
@@ -373,6 +548,14 @@ impl PersistGenerator {
             where #primary_key_type: From<Pk> {
                 self.0.select(pk.into())
             }
+
+            pub fn select_with<Pk, F, R>(&self, pk: Pk, f: F) -> Option<R>
+            where
+                #primary_key_type: From<Pk>,
+                F: FnOnce(&rkyv::Archived<#row_type>) -> R,
+            {
+                self.0.select_with(pk.into(), move |row| f(&row.inner))
+            }
         }
     }
 
@@ -383,6 +566,10 @@ impl PersistGenerator {
         let column_range_type = name_generator.get_column_range_type_ident();
         let row_fields_ident = name_generator.get_row_fields_enum_ident();
 
+        if !self.columns.primary_index_backend.supports_range() {
+            return quote! {};
+        }
+
         let pk_sorted_by = if self.columns.primary_keys.len() == 1 {
             let pk_field = &self.columns.primary_keys[0];
             let pk_pascal = Ident::new(&pk_field.to_string().to_case(Case::Pascal), Span::mixed_site());
@@ -435,23 +622,84 @@ impl PersistGenerator {
         quote! {
             pub fn insert(&self, row: #row_type) -> core::result::Result<#primary_key_type, WorkTableError> {
                 self.1.ensure_running()?;
-                let (op, res) = self.0.insert_cdc::<#secondary_events_ident>(row);
-                if let Some(op) = op {
-                    self.1.apply_operation(op)?;
-                }
-                res
+                self.0.insert_cdc_with::<#secondary_events_ident, _>(row, |op| {
+                    self.1.apply_operation(op).map_err(WorkTableError::from)
+                })
             }
         }
     }
 
-    fn gen_table_reinsert_fn(&self) -> TokenStream {
+    fn gen_table_insert_or_ignore_fn(&self) -> syn::Result<TokenStream> {
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let row_type = name_generator.get_row_type_ident();
+        let primary_key_type = name_generator.get_primary_key_type_ident();
+
+        let unique_indexes = self
+            .columns
+            .indexes
+            .iter()
+            .filter(|(_, idx)| idx.is_unique)
+            .map(|(i, idx)| {
+                let type_ = self
+                    .columns
+                    .columns_map
+                    .get(i)
+                    .ok_or(syn::Error::new(i.span(), "Row not found"))?;
+                let index_field = &idx.name;
+                let value = Ident::new(format!("{i}_value").as_str(), Span::mixed_site());
+                let by = if is_float(type_.to_string().as_str()) {
+                    quote! { &OrderedFloat(#value) }
+                } else {
+                    quote! { &#value }
+                };
+                Ok((
+                    quote! { let #value = row.#i.clone(); },
+                    quote! {
+                        if let Some(link) = self.0.indexes.#index_field.get_value(#by) {
+                            if let core::result::Result::Ok(existing) = self.0.data.select_non_ghosted(link.into()) {
+                                return core::result::Result::Ok(existing.get_primary_key());
+                            }
+                        }
+                    },
+                ))
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+        let (unique_values, unique_lookups): (Vec<_>, Vec<_>) = unique_indexes.into_iter().unzip();
+
+        Ok(quote! {
+            /// Inserts the row unless it conflicts with an existing one and
+            /// returns the primary key of the inserted or the existing row.
+            ///
+            /// Both a primary key and a unique index conflict count as an
+            /// existing row. A unique index conflict whose row is gone by the
+            /// time it is looked up is returned as `AlreadyExists`.
+            pub fn insert_or_ignore(&self, row: #row_type) -> core::result::Result<#primary_key_type, WorkTableError> {
+                let pk = row.get_primary_key();
+                #(#unique_values)*
+                match self.insert(row) {
+                    core::result::Result::Err(WorkTableError::PrimaryAlreadyExists) => core::result::Result::Ok(pk),
+                    core::result::Result::Err(WorkTableError::AlreadyExists(at)) => {
+                        #(#unique_lookups)*
+                        core::result::Result::Err(WorkTableError::AlreadyExists(at))
+                    }
+                    other => other,
+                }
+            }
+        })
+    }
+
+    fn gen_table_reinsert_fn(&self, flavor: Flavor) -> TokenStream {
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let row_type = name_generator.get_row_type_ident();
         let primary_key_type = name_generator.get_primary_key_type_ident();
         let secondary_events_ident = name_generator.get_space_secondary_index_events_ident();
+        let async_ = flavor.asyncness();
+        let reinsert = flavor.ident("reinsert");
+        let doc = flavor.doc("reinsert");
 
         quote! {
-            pub async fn reinsert(&self, row_old: #row_type, row_new: #row_type) -> core::result::Result<#primary_key_type, WorkTableError> {
+            #doc
+            pub #async_ fn #reinsert(&self, row_old: #row_type, row_new: #row_type) -> core::result::Result<#primary_key_type, WorkTableError> {
                 self.1.ensure_running()?;
                 let (op, res) = self.0.reinsert_cdc::<#secondary_events_ident>(row_old, row_new);
                 if let Some(op) = op {
@@ -462,10 +710,18 @@ impl PersistGenerator {
         }
     }
 
-    fn gen_table_upsert_fn(&self) -> TokenStream {
+    fn gen_table_upsert_fn(&self, flavor: Flavor) -> TokenStream {
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let row_type = name_generator.get_row_type_ident();
-        let full_row_lock = self.gen_full_lock_for_update();
+        let full_row_lock = self.gen_full_lock_for_update(flavor);
+        let backoff = self.gen_upsert_backoff(flavor);
+        let async_ = flavor.asyncness();
+        let await_ = flavor.await_();
+        let upsert = flavor.ident("upsert");
+        let upsert_returning = flavor.ident("upsert_returning");
+        let update_with_guard = flavor.ident("update_with_guard");
+        let upsert_doc = flavor.doc("upsert");
+        let upsert_returning_doc = flavor.doc("upsert_returning");
 
         quote! {
             /// Inserts the row if its primary key is absent, updates it
@@ -476,11 +732,19 @@ impl PersistGenerator {
             /// share a per-key mutation gate; existing keys and insertion
             /// collisions also acquire the full-row lock across the repeated
             /// existence check and selected mutation.
-            pub async fn upsert(&self, row: #row_type) -> core::result::Result<(), WorkTableError> {
+            #upsert_doc
+            pub #async_ fn #upsert(&self, row: #row_type) -> core::result::Result<(), WorkTableError> {
+                self.#upsert_returning(row)#await_.map(|_| ())
+            }
+
+            /// Same as `upsert`, but returns the replaced row version, or
+            /// `None` if the row was inserted.
+            #upsert_returning_doc
+            pub #async_ fn #upsert_returning(&self, row: #row_type) -> core::result::Result<Option<#row_type>, WorkTableError> {
                 let pk = row.get_primary_key();
                 if !self.0.primary_index.pk_map.contains_key(&pk) {
                     match self.insert(row.clone()) {
-                        core::result::Result::Ok(_) => return core::result::Result::Ok(()),
+                        core::result::Result::Ok(_) => return core::result::Result::Ok(None),
                         core::result::Result::Err(WorkTableError::PrimaryAlreadyExists) => {}
                         core::result::Result::Err(e) => return core::result::Result::Err(e),
                     }
@@ -499,7 +763,7 @@ impl PersistGenerator {
                     );
 
                     let result = if self.0.primary_index.pk_map.contains_key(&pk) {
-                        self.update_with_guard(row.clone(), guard).await
+                        self.#update_with_guard(row.clone(), guard, false)#await_.map(Some)
                     } else {
                         // `insert` acquires the same per-key mutation gate as
                         // `guard`; release the row operation before entering
@@ -507,7 +771,7 @@ impl PersistGenerator {
                         // locked decision if another writer won the race.
                         drop(guard);
                         match self.insert(row.clone()) {
-                            core::result::Result::Ok(_) => core::result::Result::Ok(()),
+                            core::result::Result::Ok(_) => core::result::Result::Ok(None),
                             core::result::Result::Err(WorkTableError::PrimaryAlreadyExists) =>
                                 core::result::Result::Err(WorkTableError::NotFound),
                             core::result::Result::Err(e) => core::result::Result::Err(e),
@@ -519,19 +783,151 @@ impl PersistGenerator {
                         core::result::Result::Err(WorkTableError::PagesError(e)) if e.is_row_absent() => {}
                         other => return other,
                     }
-                    if backoff_spins < 8 {
-                        backoff_spins = backoff_spins.saturating_add(1);
-                        tokio::task::yield_now().await;
+                    #backoff
+                }
+            }
+        }
+    }
+
+    fn gen_table_lock_diagnostics_fns(&self) -> TokenStream {
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let primary_key_type = name_generator.get_primary_key_type_ident();
+
+        quote! {
+            /// Sets how long updates and deletes wait for a row or column lock
+            /// held by another operation before failing with
+            /// `WorkTableError::LockTimeout`. `None` waits indefinitely, which
+            /// is the default.
+            pub fn set_lock_timeout(&self, timeout: Option<std::time::Duration>) {
+                self.0.lock_manager.set_wait_timeout(timeout)
+            }
+
+            /// Lists the row and column locks held right now together with
+            /// the operations waiting for them, for diagnosing hangs.
+            pub fn held_locks(&self) -> Vec<HeldLock<#primary_key_type>> {
+                self.0.lock_manager.held_locks()
+            }
+        }
+    }
+
+    fn gen_table_subscribe_fn(&self) -> TokenStream {
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let row_type = name_generator.get_row_type_ident();
+        let primary_key_type = name_generator.get_primary_key_type_ident();
+
+        quote! {
+            /// Subscribes to the rows inserted, updated and deleted after this
+            /// call. Vacuum relocations and the low-level `reinsert` are not
+            /// reported.
+            pub fn subscribe(&self) -> ChangeSubscription<#row_type, #primary_key_type> {
+                self.0.changes.subscribe()
+            }
+        }
+    }
+
+    fn gen_table_truncate_fn(&self) -> TokenStream {
+        quote! {
+            /// Removes every row from the table. The primary key generator is
+            /// not reset.
+            ///
+            /// Runs under a table-wide writer barrier; see
+            /// `WorkTable::truncate_with`. A single truncate operation is
+            /// queued behind every earlier operation, and resets the data and
+            /// index files once they are persisted. Must not run concurrently
+            /// with vacuum of this table.
+            pub fn truncate(&self) -> core::result::Result<(), WorkTableError> {
+                self.1.ensure_running()?;
+                self.0.truncate_with(|| self.1.truncate().map_err(WorkTableError::from))
+            }
+        }
+    }
+
+    fn gen_upsert_backoff(&self, flavor: Flavor) -> TokenStream {
+        let yield_now = flavor.yield_now();
+        let sleep = flavor.sleep(quote! { std::time::Duration::from_micros(micros) });
+
+        quote! {
+            if backoff_spins < 8 {
+                backoff_spins = backoff_spins.saturating_add(1);
+                #yield_now;
+            } else {
+                // Cap the exponent BEFORE shifting: `1u64 << 64` panics
+                // (overflow) in debug/test builds. Clamp the shift to a
+                // 256µs ceiling and saturate the counter so a long
+                // starvation streak can never overflow.
+                let exponent = core::cmp::min(backoff_spins - 8, 8);
+                let micros = core::cmp::min(1u64 << exponent, 256);
+                backoff_spins = backoff_spins.saturating_add(1);
+                #sleep;
+            }
+        }
+    }
+
+    fn gen_table_upsert_with_fn(&self, flavor: Flavor) -> TokenStream {
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let row_type = name_generator.get_row_type_ident();
+        let full_row_lock = self.gen_full_lock_for_update(flavor);
+        let backoff = self.gen_upsert_backoff(flavor);
+        let async_ = flavor.asyncness();
+        let await_ = flavor.await_();
+        let upsert_with = flavor.ident("upsert_with");
+        let update_with_guard = flavor.ident("update_with_guard");
+        let doc = flavor.doc("upsert_with");
+
+        quote! {
+            /// Inserts the row if its primary key is absent, otherwise merges
+            /// it into the existing row with `f`.
+            ///
+            /// `f` gets the current row and the incoming one and runs under
+            /// the full-row lock, so concurrent merges of one key never lose
+            /// each other's changes. It must not change the primary key. The
+            /// merged row is written through the same path as `update`, so a
+            /// unique index conflict is returned as `AlreadyExists`. `f` may
+            /// run again if the row is deleted concurrently.
+            #doc
+            pub #async_ fn #upsert_with<F>(&self, row: #row_type, mut f: F) -> core::result::Result<(), WorkTableError>
+            where
+                F: FnMut(&mut #row_type, #row_type),
+            {
+                let pk = row.get_primary_key();
+                if !self.0.primary_index.pk_map.contains_key(&pk) {
+                    match self.insert(row.clone()) {
+                        core::result::Result::Ok(_) => return core::result::Result::Ok(()),
+                        core::result::Result::Err(WorkTableError::PrimaryAlreadyExists) => {}
+                        core::result::Result::Err(e) => return core::result::Result::Err(e),
+                    }
+                }
+                let mut backoff_spins: u32 = 0;
+                loop {
+                    let op_lock = { #full_row_lock };
+                    let guard = LockGuard::new_with_mutation(
+                        op_lock,
+                        self.0.lock_manager.clone(),
+                        pk.clone(),
+                    );
+
+                    let result = if let Some(mut existing) = self.0.select(pk.clone()) {
+                        f(&mut existing, row.clone());
+                        if existing.get_primary_key() != pk {
+                            return core::result::Result::Err(WorkTableError::PrimaryUpdateTry);
+                        }
+                        self.#update_with_guard(existing, guard, true)#await_.map(|_| ())
                     } else {
-                        // Cap the exponent BEFORE shifting: `1u64 << 64` panics
-                        // (overflow) in debug/test builds. Clamp the shift to a
-                        // 256µs ceiling and saturate the counter so a long
-                        // starvation streak can never overflow.
-                        let exponent = core::cmp::min(backoff_spins - 8, 8);
-                        let micros = core::cmp::min(1u64 << exponent, 256);
-                        backoff_spins = backoff_spins.saturating_add(1);
-                        tokio::time::sleep(std::time::Duration::from_micros(micros)).await;
+                        drop(guard);
+                        match self.insert(row.clone()) {
+                            core::result::Result::Ok(_) => core::result::Result::Ok(()),
+                            core::result::Result::Err(WorkTableError::PrimaryAlreadyExists) =>
+                                core::result::Result::Err(WorkTableError::NotFound),
+                            core::result::Result::Err(e) => core::result::Result::Err(e),
+                        }
+                    };
+
+                    match result {
+                        core::result::Result::Err(WorkTableError::NotFound) => {}
+                        core::result::Result::Err(WorkTableError::PagesError(e)) if e.is_row_absent() => {}
+                        other => return other,
                     }
+                    #backoff
                 }
             }
         }
@@ -582,6 +978,30 @@ impl PersistGenerator {
         }
     }
 
+    fn gen_table_iter_with_ref_fn(&self) -> TokenStream {
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let row_type = name_generator.get_row_type_ident();
+
+        quote! {
+            pub fn iter_with_ref<
+                F: Fn(&rkyv::Archived<#row_type>) -> core::result::Result<(), WorkTableError>
+            >(&self, f: F) -> core::result::Result<(), WorkTableError> {
+                let _read_guard = self.0.data.read_guard();
+                let links = self.0.primary_index.pk_map
+                    .iter_values()
+                    .map(|(_, link)| link.0)
+                    .collect::<Vec<_>>();
+                for link in links {
+                    self.0.data
+                        .with_non_ghosted_ref(link, |row| f(&row.inner))
+                        .map_err(WorkTableError::PagesError)??;
+                }
+
+                core::result::Result::Ok(())
+            }
+        }
+    }
+
     fn gen_table_iter_with_async_fn(&self) -> TokenStream {
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let row_type = name_generator.get_row_type_ident();
@@ -630,10 +1050,39 @@ impl PersistGenerator {
     }
 
     fn gen_system_info_fn(&self) -> TokenStream {
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let pk_type = name_generator.get_primary_key_type_ident();
+
         quote! {
             pub fn system_info(&self) -> SystemInfo {
                 self.0.system_info()
             }
+
+            /// See `WorkTable::refresh_statistics`.
+            pub fn refresh_statistics(&self) {
+                self.0.refresh_statistics()
+            }
+
+            /// See `WorkTable::verify_integrity`.
+            pub fn verify_integrity(&self, check: IntegrityCheck<#pk_type>) -> IntegrityReport<#pk_type> {
+                self.0.verify_integrity(check)
+            }
+
+            /// See `WorkTable::set_memory_budget`.
+            pub fn set_memory_budget(&self, budget: Option<MemoryBudget>) {
+                self.0.set_memory_budget(budget)
+            }
+
+            /// See `WorkTable::shrink_to_fit`.
+            pub fn shrink_to_fit(&self) -> u64 {
+                self.0.shrink_to_fit()
+            }
+
+            /// Zeroes the lock contention counters reported in
+            /// `SystemInfo::lock_info`.
+            pub fn reset_lock_stats(&self) {
+                self.0.lock_manager.reset_lock_stats()
+            }
         }
     }
 
@@ -14,7 +14,7 @@ impl PersistGenerator {
         let page_size_consts = self.gen_page_size_consts();
         let version_const = self.gen_version_const();
         let type_ = self.gen_table_type()?;
-        let impl_ = self.gen_table_impl();
+        let impl_ = self.gen_table_impl()?;
         let index_fns = self.gen_table_index_fns()?;
         let select_query_executor_impl = self.gen_table_select_query_executor_impl();
         let column_range_type = self.gen_table_column_range_type();
@@ -84,7 +84,7 @@ impl PersistGenerator {
             })
             .collect::<Vec<_>>();
         let pk_types_unsized = is_unsized_vec(pk_types);
-        let derive = match (pk_types_unsized, self.columns.primary_index_backend) {
+        let derive = match (pk_types_unsized, &self.columns.primary_index_backend) {
             (true, crate::common::model::IndexBackend::Indexset) => quote! {
                 #[derive(Debug, PersistTable)]
                 #[table(pk_unsized, pk_upstream)]
@@ -113,6 +113,10 @@ impl PersistGenerator {
                 #[derive(Debug, PersistTable)]
                 #[table(pk_congee)]
             },
+            (false, crate::common::model::IndexBackend::Hash) => quote! {
+                #[derive(Debug, PersistTable)]
+                #[table(pk_hash)]
+            },
             (false, crate::common::model::IndexBackend::WorktablesIndex)
                 if cfg!(feature = "logical-index-persistence") =>
             {
@@ -124,6 +128,9 @@ impl PersistGenerator {
             (false, crate::common::model::IndexBackend::WorktablesIndex) => quote! {
                 #[derive(Debug, PersistTable)]
             },
+            (false, crate::common::model::IndexBackend::Custom(_)) => {
+                unreachable!("custom index backends are rejected for persisted tables")
+            }
         };
 
         let key_type = quote! { #primary_key_type };
@@ -136,7 +143,7 @@ impl PersistGenerator {
             None
         };
         let node_type = persistent_unique_index_type(
-            self.columns.primary_index_backend,
+            &self.columns.primary_index_backend,
             &key_type,
             &value_type,
             worktables_node,
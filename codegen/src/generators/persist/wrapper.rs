@@ -98,6 +98,9 @@ impl PersistGenerator {
                 fn delete(&mut self) {
                     self.is_deleted = true;
                 }
+                fn is_ghosted(&self) -> bool {
+                    self.is_ghosted
+                }
                 fn is_deleted(&self) -> bool {
                     self.is_deleted
                 }
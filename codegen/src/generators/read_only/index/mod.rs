@@ -2,7 +2,8 @@ mod info;
 mod usual;
 
 use crate::common::name_generator::{WorktableNameGenerator, is_float, is_unsized};
-use crate::generators::index_backend::unique_index_type;
+use crate::generators::index_backend::{filtered_index_type, multi_index_type, unique_index_init, unique_index_type};
+use crate::generators::integrity::secondary_index_integrity_impl;
 use crate::generators::read_only::ReadOnlyGenerator;
 use convert_case::{Case, Casing};
 use proc_macro2::TokenStream;
@@ -13,6 +14,12 @@ impl ReadOnlyGenerator {
         let type_def = self.gen_type_def()?;
         let impl_def = self.gen_secondary_index_impl_def();
         let info_def = self.gen_secondary_index_info_impl_def();
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let integrity_def = secondary_index_integrity_impl(
+            &name_generator.get_index_type_ident(),
+            &name_generator.get_row_type_ident(),
+            &self.columns,
+        );
         let default_impl = self.gen_index_default_impl()?;
         let available_indexes = self.gen_available_indexes();
 
@@ -20,6 +27,7 @@ impl ReadOnlyGenerator {
             #type_def
             #impl_def
             #info_def
+            #integrity_def
             #default_impl
             #available_indexes
         })
@@ -46,7 +54,6 @@ impl ReadOnlyGenerator {
                 };
                 let i = &idx.name;
 
-                #[allow(clippy::collapsible_else_if)]
                 let res = if idx.is_unique {
                     let value_type = quote! { OffsetEqLink };
                     let worktables_node = if is_unsized(&t.to_string()) {
@@ -54,14 +61,18 @@ impl ReadOnlyGenerator {
                     } else {
                         None
                     };
-                    let index_type = unique_index_type(idx.backend, &t, &value_type, worktables_node)?;
+                    let index_type = unique_index_type(&idx.backend, &t, &value_type, worktables_node)?;
+                    let index_type = filtered_index_type(idx, &t, index_type);
                     quote! { #i: #index_type }
                 } else {
-                    if is_unsized(&t.to_string()) {
-                        quote! {#i: IndexMultiMap<#t, OffsetEqLink, UnsizedNode<IndexMultiPair<#t, OffsetEqLink>>>}
+                    let value_type = quote! { OffsetEqLink };
+                    let worktables_node = if is_unsized(&t.to_string()) {
+                        Some(quote! { UnsizedNode<IndexMultiPair<#t, OffsetEqLink>> })
                     } else {
-                        quote! {#i: IndexMultiMap<#t, OffsetEqLink>}
-                    }
+                        None
+                    };
+                    let index_type = multi_index_type(&idx.backend, &t, &value_type, worktables_node)?;
+                    quote! { #i: #index_type }
                 };
                 Ok::<_, syn::Error>(res)
             })
@@ -105,33 +116,43 @@ impl ReadOnlyGenerator {
 
                 #[allow(clippy::collapsible_else_if)]
                 let res = if idx.is_unique {
-                    match idx.backend {
+                    let init = match idx.backend {
                         crate::common::model::IndexBackend::WorktablesIndex => {
                             if is_unsized(&t.to_string()) {
-                                quote! { #i: IndexMap::with_maximum_node_size(#const_name), }
+                                quote! { IndexMap::with_maximum_node_size(#const_name) }
                             } else {
                                 quote! {
-                                    #i: IndexMap::with_maximum_node_size(
+                                    IndexMap::with_maximum_node_size(
                                         get_index_page_size_from_data_length::<#t>(#const_name)
-                                    ),
+                                    )
                                 }
                             }
                         }
                         crate::common::model::IndexBackend::Indexset => quote! {
-                            #i: UpstreamIndexMap::with_maximum_node_size(
+                            UpstreamIndexMap::with_maximum_node_size(
                                 get_index_page_size_from_data_length::<#t>(#const_name)
-                            ),
+                            )
                         },
+                        crate::common::model::IndexBackend::Congee
+                        | crate::common::model::IndexBackend::Arctic
+                        | crate::common::model::IndexBackend::Hash
+                        | crate::common::model::IndexBackend::Custom(_) => {
+                            quote! { Default::default() }
+                        }
+                    };
+                    unique_index_init(idx, init)
+                } else {
+                    match idx.backend {
                         crate::common::model::IndexBackend::Congee
                         | crate::common::model::IndexBackend::Arctic => {
                             quote! { #i: Default::default(), }
                         }
-                    }
-                } else {
-                    if is_unsized(&t.to_string()) {
-                        quote! {#i: IndexMultiMap::with_maximum_node_size(#const_name), }
-                    } else {
-                        quote! {#i: IndexMultiMap::with_maximum_node_size(get_index_page_size_from_data_length::<#t>(#const_name)),}
+                        _ if is_unsized(&t.to_string()) => {
+                            quote! {#i: IndexMultiMap::with_maximum_node_size(#const_name), }
+                        }
+                        _ => {
+                            quote! {#i: IndexMultiMap::with_maximum_node_size(get_index_page_size_from_data_length::<#t>(#const_name)),}
+                        }
                     }
                 };
 
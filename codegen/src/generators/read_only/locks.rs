@@ -60,6 +60,8 @@ impl ReadOnlyGenerator {
         let with_lock_fn = self.gen_with_lock_fn();
         let lock_fn = self.gen_lock_fn();
         let merge_fn = self.gen_merge_fn();
+        let locks_fn = self.gen_locks_fn();
+        let columns_fn = self.gen_columns_fn();
 
         quote! {
             impl RowLock for #lock_ident {
@@ -70,6 +72,8 @@ impl ReadOnlyGenerator {
                 #lock_fn
                 #with_lock_fn
                 #merge_fn
+                #locks_fn
+                #columns_fn
             }
         }
     }
@@ -164,6 +168,42 @@ impl ReadOnlyGenerator {
         }
     }
 
+    fn gen_locks_fn(&self) -> TokenStream {
+        let rows: Vec<_> = self
+            .columns
+            .columns_map
+            .keys()
+            .map(|i| {
+                let col = Ident::new(format!("{i}_lock").as_str(), Span::mixed_site());
+                quote! {
+                    if let Some(lock) = &self.#col {
+                        if !locks.iter().any(|l| std::sync::Arc::ptr_eq(l, lock)) {
+                            locks.push(lock.clone());
+                        }
+                    }
+                }
+            })
+            .collect();
+
+        quote! {
+            fn locks(&self) -> Vec<std::sync::Arc<Lock>> {
+                let mut locks: Vec<std::sync::Arc<Lock>> = Vec::new();
+                #(#rows)*
+                locks
+            }
+        }
+    }
+
+    fn gen_columns_fn(&self) -> TokenStream {
+        let names = self.columns.columns_map.keys().map(|i| i.to_string());
+
+        quote! {
+            fn columns() -> &'static [&'static str] {
+                &[#(#names),*]
+            }
+        }
+    }
+
     fn gen_merge_fn(&self) -> TokenStream {
         let rows: Vec<_> = self
             .columns
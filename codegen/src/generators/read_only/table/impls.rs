@@ -2,7 +2,9 @@ use convert_case::{Case, Casing};
 use proc_macro2::{Ident, Literal, Span, TokenStream};
 use quote::quote;
 
+use crate::common::model::IndexBackend;
 use crate::common::name_generator::{WorktableNameGenerator, is_float, is_unsized_vec};
+use crate::generators::hooks::gen_table_hooks_fn;
 use crate::generators::read_only::ReadOnlyGenerator;
 
 impl ReadOnlyGenerator {
@@ -18,9 +20,12 @@ impl ReadOnlyGenerator {
         let insert_fn = self.gen_table_insert_fn();
         let reinsert_fn = self.gen_table_reinsert_fn();
         let upsert_fn = self.gen_table_upsert_fn();
+        // Version tables are only migrated from, so they never carry hooks.
+        let hooks_fn = gen_table_hooks_fn(None, &name_generator.get_row_type_ident());
         let get_next_fn = self.gen_table_get_next_fn();
         let pk_gen_state_fn = self.gen_table_pk_gen_state_fn();
         let iter_with_fn = self.gen_table_iter_with_fn();
+        let iter_with_ref_fn = self.gen_table_iter_with_ref_fn();
         let iter_with_async_fn = self.gen_table_iter_with_async_fn();
         let count_fn = self.gen_table_count_fn();
         let system_info_fn = self.gen_system_info_fn();
@@ -37,10 +42,12 @@ impl ReadOnlyGenerator {
                 #insert_fn
                 #reinsert_fn
                 #upsert_fn
+                #hooks_fn
                 #count_fn
                 #get_next_fn
                 #pk_gen_state_fn
                 #iter_with_fn
+                #iter_with_ref_fn
                 #iter_with_async_fn
                 #system_info_fn
                 #vacuum_fn
@@ -162,6 +169,14 @@ impl ReadOnlyGenerator {
                         }
                     }
                 } else {
+                    // ART multimaps yield owned pairs; the B-tree yields
+                    // references into its nodes.
+                    let expected_key_ref = match index.backend {
+                        IndexBackend::Congee | IndexBackend::Arctic | IndexBackend::Hash | IndexBackend::Custom(_) => {
+                            quote! { expected_key }
+                        }
+                        IndexBackend::WorktablesIndex | IndexBackend::Indexset => quote! { &expected_key },
+                    };
                     quote! {
                         for (indexed_key, offset_link) in self.0.indexes.#index_field.iter() {
                             let row = self.0.data.select_non_ghosted_checked(offset_link.0).map_err(|error| {
@@ -171,7 +186,7 @@ impl ReadOnlyGenerator {
                                 )
                             })?;
                             let expected_key = #expected_key;
-                            if indexed_key != &expected_key {
+                            if indexed_key != #expected_key_ref {
                                 return Err(PersistenceLoadError::corrupt(
                                     path,
                                     format!("secondary index {} key does not match its referenced row", #index_name),
@@ -190,7 +205,7 @@ impl ReadOnlyGenerator {
                 mode: LoadMode,
             ) -> Result<(), PersistenceLoadError> {
                 match mode {
-                    LoadMode::Strict => {
+                    LoadMode::Strict | LoadMode::Rebuild => {
                         let primary_count = self.0.primary_index.pk_map.len();
                         #(#entry_counts)*
                         for (primary_key, offset_link) in self.0.primary_index.pk_map.iter_values() {
@@ -206,6 +221,11 @@ impl ReadOnlyGenerator {
                     LoadMode::Recovery => {
                         #(#recovery_entries)*
                     }
+                    LoadMode::Lazy => {
+                        // Rows stay on disk, so only the index sizes are compared.
+                        let primary_count = self.0.primary_index.pk_map.len();
+                        #(#entry_counts)*
+                    }
                 }
                 Ok(())
             }
@@ -282,12 +302,15 @@ impl ReadOnlyGenerator {
                 }
 
                 async fn load_with(engine: E, mode: LoadMode) -> eyre::Result<Self> {
+                    if mode == LoadMode::Rebuild {
+                        return Err(eyre::eyre!("a read-only table cannot write the indexes rebuilt by LoadMode::Rebuild"));
+                    }
                     let table_path = engine.config().table_path().to_owned();
                     if !std::path::Path::new(&table_path).exists() {
                         return Self::new(engine).await;
                     };
                     let table = load_persisted_state(&table_path, async {
-                        let space = #space_ident::parse_file(&table_path).await?;
+                        let space = #space_ident::parse_file_with_mode(&table_path, mode).await?;
                         Ok::<_, eyre::Report>(space.into_worktable_with_mode(&table_path, mode)?)
                     }).await?;
                     Ok(table)
@@ -331,6 +354,14 @@ impl ReadOnlyGenerator {
             where #primary_key_type: From<Pk> {
                 self.0.select(pk.into())
             }
+
+            pub fn select_with<Pk, F, R>(&self, pk: Pk, f: F) -> Option<R>
+            where
+                #primary_key_type: From<Pk>,
+                F: FnOnce(&rkyv::Archived<#row_type>) -> R,
+            {
+                self.0.select_with(pk.into(), move |row| f(&row.inner))
+            }
         }
     }
 
@@ -341,6 +372,10 @@ impl ReadOnlyGenerator {
         let column_range_type = name_generator.get_column_range_type_ident();
         let row_fields_ident = name_generator.get_row_fields_enum_ident();
 
+        if !self.columns.primary_index_backend.supports_range() {
+            return quote! {};
+        }
+
         let pk_sorted_by = if self.columns.primary_keys.len() == 1 {
             let pk_field = &self.columns.primary_keys[0];
             let pk_pascal = Ident::new(&pk_field.to_string().to_case(Case::Pascal), Span::mixed_site());
@@ -441,6 +476,30 @@ impl ReadOnlyGenerator {
         }
     }
 
+    fn gen_table_iter_with_ref_fn(&self) -> TokenStream {
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let row_type = name_generator.get_row_type_ident();
+
+        quote! {
+            pub fn iter_with_ref<
+                F: Fn(&rkyv::Archived<#row_type>) -> core::result::Result<(), WorkTableError>
+            >(&self, f: F) -> core::result::Result<(), WorkTableError> {
+                let _read_guard = self.0.data.read_guard();
+                let links = self.0.primary_index.pk_map
+                    .iter_values()
+                    .map(|(_, link)| link.0)
+                    .collect::<Vec<_>>();
+                for link in links {
+                    self.0.data
+                        .with_non_ghosted_ref(link, |row| f(&row.inner))
+                        .map_err(WorkTableError::PagesError)??;
+                }
+
+                core::result::Result::Ok(())
+            }
+        }
+    }
+
     fn gen_table_iter_with_async_fn(&self) -> TokenStream {
         let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
         let row_type = name_generator.get_row_type_ident();
@@ -489,10 +548,29 @@ impl ReadOnlyGenerator {
     }
 
     fn gen_system_info_fn(&self) -> TokenStream {
+        let name_generator = WorktableNameGenerator::from_table_name(self.name.to_string());
+        let pk_type = name_generator.get_primary_key_type_ident();
+
         quote! {
             pub fn system_info(&self) -> SystemInfo {
                 self.0.system_info()
             }
+
+            /// See `WorkTable::refresh_statistics`.
+            pub fn refresh_statistics(&self) {
+                self.0.refresh_statistics()
+            }
+
+            /// See `WorkTable::verify_integrity`.
+            pub fn verify_integrity(&self, check: IntegrityCheck<#pk_type>) -> IntegrityReport<#pk_type> {
+                self.0.verify_integrity(check)
+            }
+
+            /// Zeroes the lock contention counters reported in
+            /// `SystemInfo::lock_info`.
+            pub fn reset_lock_stats(&self) {
+                self.0.lock_manager.reset_lock_stats()
+            }
         }
     }
 
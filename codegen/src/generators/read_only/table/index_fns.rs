@@ -7,6 +7,7 @@ use quote::quote;
 use crate::common::model::Index;
 use crate::common::name_generator::{WorktableNameGenerator, is_float};
 use crate::generators::read_only::ReadOnlyGenerator;
+use crate::generators::statistics::gen_statistics_index_fns;
 
 impl ReadOnlyGenerator {
     pub fn gen_table_index_fns(&self) -> syn::Result<TokenStream> {
@@ -34,16 +35,22 @@ impl ReadOnlyGenerator {
                     )?
                 };
 
-                let range_fn = Self::gen_range_index_fn(
-                    i,
-                    idx,
-                    &self.columns.columns_map,
-                    row_ident.clone(),
-                    &column_range_type,
-                    &row_fields_ident,
-                )?;
+                let range_fn = if idx.backend.supports_range() {
+                    Self::gen_range_index_fn(
+                        i,
+                        idx,
+                        &self.columns.columns_map,
+                        row_ident.clone(),
+                        &column_range_type,
+                        &row_fields_ident,
+                    )?
+                } else {
+                    quote! {}
+                };
+
+                let statistics_fn = gen_statistics_index_fns(i, idx, &self.columns.columns_map)?;
 
-                Ok(quote! { #point_fn #range_fn })
+                Ok(quote! { #point_fn #range_fn #statistics_fn })
             })
             .collect::<Result<Vec<_>, syn::Error>>()?;
 
@@ -105,11 +112,55 @@ impl ReadOnlyGenerator {
             None
         };
 
+        let with_fn_name = Ident::new(format!("select_by_{i}_with").as_str(), Span::mixed_site());
+        let key_matches = if is_float {
+            quote! {
+                OrderedFloat(key).eq(&OrderedFloat(by))
+            }
+        } else {
+            quote! {
+                key.eq(&by)
+            }
+        };
+
         Ok(quote! {
             pub fn #fn_name(&self, by: #type_) -> Option<#row_ident> {
                 let _read_guard = self.0.data.read_guard();
                 #select
             }
+
+            pub fn #with_fn_name<F, R>(&self, by: #type_, f: F) -> Option<R>
+            where
+                F: FnOnce(&rkyv::Archived<#row_ident>) -> R,
+            {
+                let _read_guard = self.0.data.read_guard();
+                let mut f = Some(f);
+                for _ in 0..64 {
+                    let link: Link = self.0.indexes.#field_ident
+                        .lookup_for_select(#by)
+                        .map(Into::into)?;
+                    let res = self.0.data.with_non_ghosted_ref(link, |row| {
+                        let key = rkyv::deserialize::<#type_, rkyv::rancor::Error>(&row.inner.#row_field_ident).ok()?;
+                        if #key_matches {
+                            Some(f.take().expect("f runs once")(&row.inner))
+                        } else {
+                            None
+                        }
+                    });
+                    if let Ok(Some(res)) = res {
+                        return Some(res);
+                    }
+
+                    let current_link: Option<Link> = self.0.indexes.#field_ident
+                        .lookup_for_select(#by)
+                        .map(Into::into);
+                    if current_link == Some(link) {
+                        return None;
+                    }
+                    std::hint::spin_loop();
+                }
+                None
+            }
         })
     }
 
@@ -123,6 +174,7 @@ impl ReadOnlyGenerator {
     ) -> syn::Result<TokenStream> {
         let type_ = columns_map.get(i).ok_or(syn::Error::new(i.span(), "Row not found"))?;
         let fn_name = Ident::new(format!("select_by_{i}").as_str(), Span::mixed_site());
+        let with_fn_name = Ident::new(format!("select_by_{i}_with").as_str(), Span::mixed_site());
         let field_ident = &idx.name;
         let row_field_ident = &idx.field;
         let by = if is_float(type_.to_string().as_str()) {
@@ -155,6 +207,26 @@ impl ReadOnlyGenerator {
 
                 SelectQueryBuilder::new(rows)
             }
+
+            pub fn #with_fn_name<F, R>(&self, by: #type_, mut f: F) -> Vec<R>
+            where
+                F: FnMut(&rkyv::Archived<#row_ident>) -> R,
+            {
+                let _read_guard = self.0.data.read_guard();
+                self.0.indexes.#field_ident
+                    .get(#by)
+                    .into_iter()
+                    .filter_map(|(_, link)| {
+                        self.0.data
+                            .with_non_ghosted_ref(link.0, |row| {
+                                let key = rkyv::deserialize::<#type_, rkyv::rancor::Error>(&row.inner.#row_field_ident).ok()?;
+                                (key == by).then(|| f(&row.inner))
+                            })
+                            .ok()
+                            .flatten()
+                    })
+                    .collect()
+            }
         })
     }
 
@@ -113,7 +113,7 @@ impl ReadOnlyGenerator {
             None
         };
         let node_type = unique_index_type(
-            self.columns.primary_index_backend,
+            &self.columns.primary_index_backend,
             &key_type,
             &value_type,
             worktables_node,
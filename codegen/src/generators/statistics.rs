@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+
+use crate::common::model::{Index, IndexBackend};
+use crate::common::name_generator::is_float;
+
+/// Generates the expression computing the `IndexStatistics` of the index
+/// `index` evaluates to. WorkTablesIndex maps are summarized node by node,
+/// other backends are scanned in key order.
+pub(crate) fn index_statistics(idx: &Index, index: TokenStream) -> TokenStream {
+    let unique = idx.is_unique;
+    match idx.backend {
+        IndexBackend::WorktablesIndex => quote! {
+            IndexStatistics::from_nodes(#index.iter_nodes(), #unique)
+        },
+        _ if unique => quote! {
+            IndexStatistics::from_sorted_keys(
+                UniqueIndex::len(&#index),
+                UniqueIndex::iter_values(&#index).map(|(key, _)| key),
+            )
+        },
+        _ => quote! {
+            IndexStatistics::from_sorted_keys(#index.len(), #index.iter().map(|(key, _)| key))
+        },
+    }
+}
+
+/// Generates the expression taking the `IndexStatistics` of the index from
+/// the `StatisticsCache` `cache` evaluates to, computing them from `index` if
+/// they are stale at write count `writes`.
+fn cached_index_statistics(idx: &Index, index: TokenStream, cache: TokenStream, writes: TokenStream) -> TokenStream {
+    let name = idx.name.to_string();
+    let statistics = index_statistics(idx, index);
+    quote! {
+        #cache.get_or_compute(#name, #writes, || #statistics)
+    }
+}
+
+/// Generates the expression counting the distinct keys of the index in
+/// field `i` of `self` for its `IndexInfo`. A unique index has one entry per
+/// key and an ART multimap counts its keys, so only a non-unique
+/// WorkTablesIndex map is sampled, through the `statistics` cache.
+pub(crate) fn index_info_distinct_keys(idx: &Index) -> TokenStream {
+    let i = &idx.name;
+    match idx.backend {
+        _ if idx.is_unique => quote! { self.#i.len() },
+        IndexBackend::WorktablesIndex => {
+            let statistics = cached_index_statistics(idx, quote! { self.#i }, quote! { statistics }, quote! { writes });
+            quote! { #statistics.distinct_keys }
+        }
+        _ => quote! { self.#i.key_count() },
+    }
+}
+
+/// Generates the parameters of `index_info`, named only if one of `indexes`
+/// takes its distinct keys from the statistics cache.
+pub(crate) fn index_info_params<'a>(mut indexes: impl Iterator<Item = &'a Index>) -> TokenStream {
+    if indexes.any(|idx| !idx.is_unique && matches!(idx.backend, IndexBackend::WorktablesIndex)) {
+        quote! { statistics: &StatisticsCache, writes: u64 }
+    } else {
+        quote! { _: &StatisticsCache, _: u64 }
+    }
+}
+
+/// Generates `statistics_by_{i}` and `estimate_rows_by_{i}` of a table. The
+/// statistics are cached in the table until enough rows were written.
+pub(crate) fn gen_statistics_index_fns(
+    i: &Ident,
+    idx: &Index,
+    columns_map: &HashMap<Ident, TokenStream>,
+) -> syn::Result<TokenStream> {
+    let type_ = columns_map.get(i).ok_or(syn::Error::new(i.span(), "Row not found"))?;
+    let statistics_fn = Ident::new(format!("statistics_by_{i}").as_str(), Span::mixed_site());
+    let estimate_fn = Ident::new(format!("estimate_rows_by_{i}").as_str(), Span::mixed_site());
+    let field_ident = &idx.name;
+    let statistics = cached_index_statistics(
+        idx,
+        quote! { self.0.indexes.#field_ident },
+        quote! { self.0.statistics },
+        quote! { self.0.data.write_count() },
+    );
+    let (key_type, range_arg) = if is_float(type_.to_string().as_str()) {
+        (
+            quote! { OrderedFloat<#type_> },
+            quote! {
+                (
+                    range.start_bound().map(|v| OrderedFloat(*v)),
+                    range.end_bound().map(|v| OrderedFloat(*v)),
+                )
+            },
+        )
+    } else {
+        (quote! { #type_ }, quote! { range })
+    };
+
+    Ok(quote! {
+        pub fn #statistics_fn(&self) -> IndexStatistics<#key_type> {
+            IndexStatistics::clone(&#statistics)
+        }
+
+        pub fn #estimate_fn<R>(&self, range: R) -> usize
+        where
+            R: std::ops::RangeBounds<#type_>,
+        {
+            #statistics.estimate_rows(#range_arg)
+        }
+    })
+}
@@ -10,6 +10,37 @@ fn gen_used_size_body(data: &Data) -> Result<TokenStream> {
     gen_mem_fn_body(data, quote! { used_size() }, quote! { std::mem::size_of::<Self>() })
 }
 
+/// Forwards `shrink_to_fit` to the fields of a struct. Enums and structs of
+/// primitives keep the default, which does nothing.
+fn gen_shrink_to_fit_fn(data: &Data) -> TokenStream {
+    let Data::Struct(data_struct) = data else {
+        return quote! {};
+    };
+    let fields = match &data_struct.fields {
+        Fields::Named(named) => named.named.iter().collect::<Vec<_>>(),
+        Fields::Unnamed(unnamed) => unnamed.unnamed.iter().collect::<Vec<_>>(),
+        Fields::Unit => vec![],
+    };
+    if fields.iter().all(|f| is_copy_primitive(&f.ty)) {
+        return quote! {};
+    }
+    let calls = fields.iter().enumerate().map(|(i, f)| {
+        let accessor = match &f.ident {
+            Some(ident) => quote! { self.#ident },
+            None => {
+                let index = syn::Index::from(i);
+                quote! { self.#index }
+            }
+        };
+        quote! { #accessor.shrink_to_fit(); }
+    });
+    quote! {
+        fn shrink_to_fit(&self) {
+            #(#calls)*
+        }
+    }
+}
+
 fn gen_mem_fn_body(data: &Data, method: TokenStream, default_for_copy: TokenStream) -> Result<TokenStream> {
     match data {
         Data::Struct(data_struct) => {
@@ -97,6 +128,7 @@ pub fn expand(input: proc_macro2::TokenStream) -> Result<TokenStream> {
 
     let heap = gen_heap_size_body(&input.data)?;
     let used = gen_used_size_body(&input.data)?;
+    let shrink_to_fit = gen_shrink_to_fit_fn(&input.data);
 
     Ok(quote! {
         impl MemStat for #name {
@@ -106,6 +138,7 @@ pub fn expand(input: proc_macro2::TokenStream) -> Result<TokenStream> {
             fn used_size(&self) -> usize {
                 #used
             }
+            #shrink_to_fit
         }
     })
 }
@@ -25,26 +25,56 @@ pub(super) struct IndexLayout {
     uses_upstream: bool,
     pub(super) art_backend: Option<ArtBackend>,
     pub(super) logical_wti: bool,
+    /// The index type inside a `FilteredIndex`, or the field type.
+    index_type: syn::Type,
+    /// Whether the field wraps the index in a `FilteredIndex`.
+    filtered: bool,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub(super) enum ArtBackend {
     Arctic,
     Congee,
+    Hash,
 }
 
 pub(super) fn index_layout(field: &Field) -> syn::Result<IndexLayout> {
-    let syn::Type::Path(type_path) = &field.ty else {
-        return Err(syn::Error::new_spanned(
-            &field.ty,
-            "index field must be a concrete index type",
-        ));
+    let mut layout = type_layout(&field.ty)?;
+    if layout.type_ident == "FilteredIndex" {
+        let syn::Type::Path(type_path) = &field.ty else {
+            unreachable!("the layout was read from a type path");
+        };
+        let inner = match &type_path.path.segments.last().expect("checked above").arguments {
+            syn::PathArguments::AngleBracketed(arguments) => arguments.args.iter().nth(1),
+            _ => None,
+        };
+        let Some(syn::GenericArgument::Type(inner)) = inner else {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "expected `FilteredIndex<Key, Index>`",
+            ));
+        };
+        layout = type_layout(inner)?;
+        if !layout.is_unique {
+            return Err(syn::Error::new_spanned(
+                inner,
+                "`FilteredIndex` only wraps unique indexes",
+            ));
+        }
+        layout.filtered = true;
+    }
+    Ok(layout)
+}
+
+fn type_layout(ty: &syn::Type) -> syn::Result<IndexLayout> {
+    let syn::Type::Path(type_path) = ty else {
+        return Err(syn::Error::new_spanned(ty, "index field must be a concrete index type"));
     };
     let type_ident = type_path
         .path
         .segments
         .last()
-        .ok_or_else(|| syn::Error::new_spanned(&field.ty, "index type path cannot be empty"))?
+        .ok_or_else(|| syn::Error::new_spanned(ty, "index type path cannot be empty"))?
         .ident
         .clone();
     let (is_unique, uses_upstream, art_backend, logical_wti) = match type_ident.to_string().as_str() {
@@ -54,9 +84,14 @@ pub(super) fn index_layout(field: &Field) -> syn::Result<IndexLayout> {
         "IndexMultiMap" | "TreeMultiIndex" => (false, false, None, false),
         "PersistentArcticIndex" => (true, false, Some(ArtBackend::Arctic), false),
         "PersistentCongeeIndex" => (true, false, Some(ArtBackend::Congee), false),
+        "PersistentArcticMultiIndex" => (false, false, Some(ArtBackend::Arctic), false),
+        "PersistentCongeeMultiIndex" => (false, false, Some(ArtBackend::Congee), false),
+        "PersistentHashIndex" => (true, false, Some(ArtBackend::Hash), false),
+        // Resolved by `index_layout`.
+        "FilteredIndex" => (true, false, None, false),
         _ => {
             return Err(syn::Error::new_spanned(
-                &field.ty,
+                ty,
                 "unsupported persisted index type; use a WorkTable-generated index backend directly",
             ));
         }
@@ -67,9 +102,25 @@ pub(super) fn index_layout(field: &Field) -> syn::Result<IndexLayout> {
         uses_upstream,
         art_backend,
         logical_wti,
+        index_type: ty.clone(),
+        filtered: false,
     })
 }
 
+impl IndexLayout {
+    /// Disk-side checkpoint/WAL type of a native ART index.
+    pub(super) fn art_space_type(&self) -> Option<TokenStream> {
+        Some(match (self.art_backend?, self.is_unique) {
+            (ArtBackend::Arctic, true) => quote! { SpaceArcticIndex },
+            (ArtBackend::Congee, true) => quote! { SpaceCongeeIndex },
+            (ArtBackend::Arctic, false) => quote! { SpaceArcticMultiIndex },
+            (ArtBackend::Congee, false) => quote! { SpaceCongeeMultiIndex },
+            // The DSL rejects non-unique hash indexes before this point.
+            (ArtBackend::Hash, _) => quote! { SpaceHashIndex },
+        })
+    }
+}
+
 impl WorktableNameGenerator {
     pub fn from_index_ident(index_ident: &Ident) -> Self {
         Self {
@@ -144,8 +195,8 @@ impl Generator {
                 let i = field.ident.as_ref().expect("index fields should be named");
                 let t = self.field_types.get(i).expect("field type was collected");
                 if layout.art_backend.is_some() {
-                    let field_type = &field.ty;
-                    Ok(quote! { #i: #field_type, })
+                    let index_type = &layout.index_type;
+                    Ok(quote! { #i: #index_type, })
                 } else if is_unsized(&t.to_string()) {
                     let const_size = name_generator.get_page_inner_size_const_ident();
                     Ok(quote! {
@@ -203,16 +254,9 @@ impl Generator {
                 let i = field.ident.as_ref().expect("index fields should be named");
                 let ty = self.field_types.get(i).expect("field type was collected");
                 let index_name_literal = Literal::string(i.to_string().as_str());
-                Ok(match layout.art_backend {
-                    Some(ArtBackend::Arctic) => quote! {
-                        SpaceArcticIndex::<#ty, { #inner_const_name as u32 }>::write_checkpoint(
-                            format!("{}/{}{}", path, #index_name_literal, #index_extension),
-                            #version_const_name,
-                            &mut self.#i,
-                        ).await?;
-                    },
-                    Some(ArtBackend::Congee) => quote! {
-                        SpaceCongeeIndex::<#ty, { #inner_const_name as u32 }>::write_checkpoint(
+                Ok(match layout.art_space_type() {
+                    Some(space) => quote! {
+                        #space::<#ty, { #inner_const_name as u32 }>::write_checkpoint(
                             format!("{}/{}{}", path, #index_name_literal, #index_extension),
                             #version_const_name,
                             &mut self.#i,
@@ -264,15 +308,9 @@ impl Generator {
                 let i = field.ident.as_ref().expect("index fields should be named");
                 let ty = self.field_types.get(i).expect("field type was collected");
                 let literal = Literal::string(i.to_string().as_str());
-                Ok(match layout.art_backend {
-                    Some(ArtBackend::Arctic) => quote! {
-                        let #i = SpaceArcticIndex::<#ty, { #inner_const_name as u32 }>::load_index(
-                            format!("{}/{}{}", path, #literal, #index_extension),
-                            #version_const_name,
-                        ).await?;
-                    },
-                    Some(ArtBackend::Congee) => quote! {
-                        let #i = SpaceCongeeIndex::<#ty, { #inner_const_name as u32 }>::load_index(
+                Ok(match layout.art_space_type() {
+                    Some(space) => quote! {
+                        let #i = #space::<#ty, { #inner_const_name as u32 }>::load_index(
                             format!("{}/{}{}", path, #literal, #index_extension),
                             #version_const_name,
                         ).await?;
@@ -371,14 +409,22 @@ impl Generator {
                     .field_types
                     .get(i)
                     .expect("should be available as constructed from same values");
-                if layout.art_backend.is_some() {
-                    let field_type = &field.ty;
+                if layout.art_backend.is_some() && layout.is_unique {
+                    let index_type = &layout.index_type;
                     Ok(quote! {
-                        let #i: #field_type = Default::default();
+                        let #i: #index_type = Default::default();
                         for (key, value) in self.#i.iter_values() {
                             #i.insert_value(key, value);
                         }
                     })
+                } else if layout.art_backend.is_some() {
+                    let index_type = &layout.index_type;
+                    Ok(quote! {
+                        let #i: #index_type = Default::default();
+                        for (key, value) in self.#i.iter() {
+                            #i.insert(key, value);
+                        }
+                    })
                 } else if is_unsized(&ty.to_string()) {
                     Ok(quote! {
                         let mut pages = vec![];
@@ -532,7 +578,7 @@ impl Generator {
                     }
                 };
 
-                if layout.art_backend.is_some() {
+                let index = if layout.art_backend.is_some() {
                     Ok(quote! {
                         let #i = persisted.#i;
                     })
@@ -574,6 +620,17 @@ impl Generator {
                         let #i: #t<_, OffsetEqLink> = #t::with_maximum_node_size(size);
                         #body
                     })
+                };
+                // The filter is not persisted; it is built from the loaded keys.
+                if layout.filtered {
+                    index.map(|index| {
+                        quote! {
+                            #index
+                            let #i = FilteredIndex::new(#i);
+                        }
+                    })
+                } else {
+                    index
                 }
             })
             .collect::<syn::Result<Vec<_>>>()?;
@@ -595,7 +652,7 @@ mod tests {
     use proc_macro2::{Ident, Span};
     use quote::quote;
 
-    use crate::persist_index::generator::{Generator, PersistIndexAttributes};
+    use crate::persist_index::generator::{ArtBackend, Generator, PersistIndexAttributes, index_layout};
     use crate::persist_index::parser::Parser;
 
     #[test]
@@ -665,6 +722,26 @@ mod tests {
         assert!(!attrs.read_only);
     }
 
+    #[test]
+    fn resolves_the_index_inside_a_filter() {
+        let input = quote! {
+            #[derive(Debug, Default, Clone)]
+            pub struct TestIndex {
+                test_idx: FilteredIndex<u64, PersistentHashIndex<u64, OffsetEqLink>>,
+            }
+        };
+        let struct_ = Parser::parse_struct(input).unwrap();
+        let field = struct_.fields.iter().next().unwrap();
+        let layout = index_layout(field).unwrap();
+
+        assert!(layout.filtered);
+        assert_eq!(layout.art_backend, Some(ArtBackend::Hash));
+        assert_eq!(
+            layout.art_space_type().unwrap().to_string(),
+            quote! { SpaceHashIndex }.to_string()
+        );
+    }
+
     #[test]
     fn rejects_aliases_instead_of_guessing_the_persisted_layout() {
         let input = quote! {
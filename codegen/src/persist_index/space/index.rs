@@ -2,7 +2,7 @@ use proc_macro2::{Literal, TokenStream};
 use quote::quote;
 
 use crate::common::name_generator::{WorktableNameGenerator, is_unsized};
-use crate::persist_index::generator::{ArtBackend, Generator, index_layout};
+use crate::persist_index::generator::{Generator, index_layout};
 
 impl Generator {
     pub fn gen_space_secondary_index_type(&self) -> TokenStream {
@@ -18,12 +18,9 @@ impl Generator {
                 let layout = index_layout(field)?;
                 let i = field.ident.as_ref().expect("index fields should be named");
                 let t = self.field_types.get(i).expect("field type was collected");
-                Ok(match layout.art_backend {
-                    Some(ArtBackend::Arctic) => quote! {
-                        #i: SpaceArcticIndex<#t, { #inner_const_name as u32}>,
-                    },
-                    Some(ArtBackend::Congee) => quote! {
-                        #i: SpaceCongeeIndex<#t, { #inner_const_name as u32}>,
+                Ok(match layout.art_space_type() {
+                    Some(space) => quote! {
+                        #i: #space<#t, { #inner_const_name as u32}>,
                     },
                     None if layout.logical_wti && is_unsized(&t.to_string()) => quote! {
                         #i: SpaceLogicalIndexUnsized<#t, { #inner_const_name as u32}>,
@@ -78,12 +75,9 @@ impl Generator {
                 let i = field.ident.as_ref().expect("index fields should be named");
                 let t = self.field_types.get(i).expect("field type was collected");
                 let literal_name = Literal::string(i.to_string().as_str());
-                Ok(match layout.art_backend {
-                    Some(ArtBackend::Arctic) => quote! {
-                        #i: SpaceArcticIndex::secondary_from_table_files_path(path, #literal_name, version).await?,
-                    },
-                    Some(ArtBackend::Congee) => quote! {
-                        #i: SpaceCongeeIndex::secondary_from_table_files_path(path, #literal_name, version).await?,
+                Ok(match layout.art_space_type() {
+                    Some(space) => quote! {
+                        #i: #space::secondary_from_table_files_path(path, #literal_name, version).await?,
                     },
                     None if layout.logical_wti && is_unsized(&t.to_string()) => quote! {
                         #i: SpaceLogicalIndexUnsized::secondary_from_table_files_path(path, #literal_name, version).await?,
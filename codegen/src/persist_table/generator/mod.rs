@@ -14,6 +14,7 @@ pub struct PersistTableAttributes {
     pub pk_upstream: bool,
     pub pk_arctic: bool,
     pub pk_congee: bool,
+    pub pk_hash: bool,
     pub pk_wti_logical: bool,
     pub row_schema: Vec<(String, String)>,
     pub primary_key_fields: Vec<String>,
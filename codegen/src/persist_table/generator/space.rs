@@ -56,6 +56,10 @@ impl Generator {
             quote! {
                 SpaceCongeeIndex<#primary_key_type, { #inner_const_name as u32 }>,
             }
+        } else if self.attributes.pk_hash {
+            quote! {
+                SpaceHashIndex<#primary_key_type, { #inner_const_name as u32 }>,
+            }
         } else {
             quote! {
                 SpaceIndex<#primary_key_type, { #inner_const_name as u32 }>,
@@ -40,6 +40,10 @@ impl Generator {
             quote! {
                 pub primary_index: PersistentCongeeIndex<#pk_type, OffsetEqLink<#inner_const_name>>,
             }
+        } else if self.attributes.pk_hash {
+            quote! {
+                pub primary_index: PersistentHashIndex<#pk_type, OffsetEqLink<#inner_const_name>>,
+            }
         } else {
             quote! {
                 pub primary_index: (Vec<GeneralPage<TableOfContentsPage<(#pk_type, Link)>>>, Vec<GeneralPage<IndexPage<#pk_type>>>),
@@ -52,6 +56,8 @@ impl Generator {
                 #primary_index
                 pub indexes: #index_persisted_ident,
                 pub data: Vec<GeneralPage<DataPage<#inner_const_name>>>,
+                /// Headers of the data pages left on disk by [`LoadMode::Lazy`].
+                pub data_headers: Vec<GeneralHeader>,
                 pub data_info: GeneralPage<SpaceInfoPage<<<#pk_type as TablePrimaryKey>::Generator as PrimaryKeyGeneratorState>::State>>,
             }
         }
@@ -61,7 +67,7 @@ impl Generator {
         let name_generator = WorktableNameGenerator::from_struct_ident(&self.struct_def.ident);
         let literal_name = name_generator.get_work_table_literal_name();
         let version_const = name_generator.get_version_const_ident();
-        let primary_page_count = if self.attributes.pk_arctic || self.attributes.pk_congee {
+        let primary_page_count = if self.attributes.pk_arctic || self.attributes.pk_congee || self.attributes.pk_hash {
             quote! { 1 }
         } else {
             quote! { self.primary_index.0.len() as u32 + self.primary_index.1.len() as u32 }
@@ -137,9 +143,11 @@ impl Generator {
         let const_name = name_generator.get_page_inner_size_const_ident();
         let pk_type = name_generator.get_primary_key_type_ident();
         let lock_type = name_generator.get_lock_type_ident();
+        let page_const_name = name_generator.get_page_size_const_ident();
         let table_name = name_generator.get_work_table_literal_name();
         let secondary_index_events = name_generator.get_space_secondary_index_events_ident();
         let avt_index_ident = name_generator.get_available_indexes_ident();
+        let row_type = name_generator.get_row_type_ident();
 
         let primary_index_init = if self.attributes.pk_unsized {
             let pk_ident = &self.pk_ident;
@@ -170,7 +178,7 @@ impl Generator {
                 }
                 let primary_index = PrimaryIndex { pk_map, reverse_pk_map };
             }
-        } else if self.attributes.pk_arctic || self.attributes.pk_congee {
+        } else if self.attributes.pk_arctic || self.attributes.pk_congee || self.attributes.pk_hash {
             quote! {
                 let pk_map = self.primary_index;
                 let reverse_pk_map = IndexMap::<OffsetEqLink<#const_name>, #pk_type>::new();
@@ -227,17 +235,31 @@ impl Generator {
                     path: &str,
                     mode: LoadMode,
                 ) -> Result<#wt_ident, PersistenceLoadError> {
-                    let mut page_id = 1;
-                    let data = self.data.into_iter().map(|p| {
-                        let mut data = Data::from_data_page(p);
-                        data.set_page_id(page_id.into());
-                        page_id += 1;
-
-                        std::sync::Arc::new(data)
-                    })
-                        .collect();
-                    let data = DataPages::from_data(data)
-                        .with_empty_links(self.data_info.inner.empty_links_list);
+                    if mode == LoadMode::Rebuild {
+                        return Err(PersistenceLoadError::corrupt(
+                            path,
+                            "a read-only table cannot write the indexes rebuilt by LoadMode::Rebuild",
+                        ));
+                    }
+                    let data = if mode == LoadMode::Lazy {
+                        let store = SpaceDataPages::<{ #page_const_name as u32 }>::open(path)
+                            .map_err(|error| PersistenceLoadError::corrupt(path, format!("data file cannot be opened: {error}")))?;
+                        DataPages::lazy(&self.data_headers, std::sync::Arc::new(store))
+                    } else {
+                        let mut page_id = 1;
+                        let data = self.data.into_iter().map(|p| {
+                            let mut data = Data::from_data_page(p).map_err(|_| {
+                                PersistenceLoadError::corrupt(path, format!("compressed data page {page_id} is corrupt"))
+                            })?;
+                            data.set_page_id(page_id.into());
+                            page_id += 1;
+
+                            Ok(std::sync::Arc::new(data))
+                        })
+                            .collect::<Result<_, PersistenceLoadError>>()?;
+                        DataPages::from_data(data)
+                    };
+                    let data = data.with_empty_links(self.data_info.inner.empty_links_list);
                     let indexes = #index_ident::from_persisted(self.indexes);
 
                     #primary_index_init
@@ -250,10 +272,17 @@ impl Generator {
                         lock_manager: std::sync::Arc::new(LockMap::<#lock_type, #pk_type>::default()),
                         update_state: IndexMap::default(),
                         table_name: #table_name,
+                        changes: Default::default(),
+                        hooks: #wt_ident::table_hooks(),
+                        statistics: Default::default(),
                         pk_phantom: std::marker::PhantomData,
                     };
 
-                    table.validate_persisted_state(path)?;
+                    if mode == LoadMode::Lazy {
+                        table.validate_persisted_links(path)?;
+                    } else {
+                        table.validate_persisted_state(path)?;
+                    }
                     let worktable = #wt_ident(table);
                     worktable.validate_loaded_secondary_state(path, mode)?;
                     Ok(worktable)
@@ -297,17 +326,82 @@ impl Generator {
                         + 'static,
                     C: Clone + PersistenceConfig,
                 {
-                    let mut page_id = 1;
-                    let data = self.data.into_iter().map(|p| {
-                        let mut data = Data::from_data_page(p);
-                        data.set_page_id(page_id.into());
-                        page_id += 1;
-
-                        std::sync::Arc::new(data)
-                    })
-                        .collect();
-                    let data = DataPages::from_data(data)
-                        .with_empty_links(self.data_info.inner.empty_links_list);
+                    let (worktable, _) = self.load_into_worktable(engine, path, mode, true).await?;
+                    Ok(worktable)
+                }
+
+                /// Rebuilds every index from the data pages like
+                /// [`LoadMode::Rebuild`], but leaves rows with a duplicate unique
+                /// key and unreadable bytes out of the indexes and reports them
+                /// instead of failing. The space file must be parsed with
+                /// [`LoadMode::Rebuild`].
+                pub async fn into_rebuilt_worktable<E, C>(
+                    self,
+                    engine: E,
+                    path: &str,
+                ) -> Result<(#wt_ident, IndexRebuildReport<#row_type>), PersistenceLoadError>
+                where
+                    E: PersistenceEngine<
+                        <<#pk_type as TablePrimaryKey>::Generator as PrimaryKeyGeneratorState>::State,
+                        #pk_type,
+                        #secondary_index_events,
+                        #avt_index_ident,
+                        Config=C
+                    > + Send
+                        + 'static,
+                    C: Clone + PersistenceConfig,
+                {
+                    let (worktable, report) = self.load_into_worktable(engine, path, LoadMode::Rebuild, false).await?;
+                    Ok((worktable, report.expect("rebuild load returns a report")))
+                }
+
+                async fn load_into_worktable<E, C>(
+                    self,
+                    mut engine: E,
+                    path: &str,
+                    mode: LoadMode,
+                    reject_incomplete_rebuild: bool,
+                ) -> Result<(#wt_ident, Option<IndexRebuildReport<#row_type>>), PersistenceLoadError>
+                where
+                    E: PersistenceEngine<
+                        <<#pk_type as TablePrimaryKey>::Generator as PrimaryKeyGeneratorState>::State,
+                        #pk_type,
+                        #secondary_index_events,
+                        #avt_index_ident,
+                        Config=C
+                    > + Send
+                        + 'static,
+                    C: Clone + PersistenceConfig,
+                {
+                    // Stores written before schema metadata keep an empty row
+                    // schema; their index list is not recorded and never migrated.
+                    let schema_recorded = !self.data_info.inner.row_schema.is_empty();
+                    let stored_indexes = self.data_info.inner.secondary_index_types;
+                    let data = if mode == LoadMode::Lazy {
+                        let store = SpaceDataPages::<{ #page_const_name as u32 }>::open(path)
+                            .map_err(|error| PersistenceLoadError::corrupt(path, format!("data file cannot be opened: {error}")))?;
+                        DataPages::lazy(&self.data_headers, std::sync::Arc::new(store))
+                    } else {
+                        let mut page_id = 1;
+                        let data = self.data.into_iter().map(|p| {
+                            let mut data = Data::from_data_page(p).map_err(|_| {
+                                PersistenceLoadError::corrupt(path, format!("compressed data page {page_id} is corrupt"))
+                            })?;
+                            data.set_page_id(page_id.into());
+                            page_id += 1;
+
+                            Ok(std::sync::Arc::new(data))
+                        })
+                            .collect::<Result<_, PersistenceLoadError>>()?;
+                        DataPages::from_data(data)
+                    };
+                    let mut data = data
+                        .with_empty_links(self.data_info.inner.empty_links_list)
+                        .with_compression(#wt_ident::page_compression());
+                    let page_store = #wt_ident::page_store(path);
+                    if let Some((store, _)) = &page_store {
+                        data = data.with_page_store(store.clone());
+                    }
                     let indexes = #index_ident::from_persisted(self.indexes);
 
                     #primary_index_init
@@ -320,16 +414,91 @@ impl Generator {
                         lock_manager: std::sync::Arc::new(LockMap::<#lock_type, #pk_type>::default()),
                         update_state: IndexMap::default(),
                         table_name: #table_name,
+                        changes: Default::default(),
+                        hooks: #wt_ident::table_hooks(),
+                        statistics: Default::default(),
                         pk_phantom: std::marker::PhantomData,
                     };
 
-                    table.validate_persisted_state(path)?;
+                    let rebuilt = if mode == LoadMode::Rebuild {
+                        let (report, primary_key_events, secondary_events) =
+                            table.rebuild_indexes_cdc::<#secondary_index_events>();
+                        if reject_incomplete_rebuild && !report.is_complete() {
+                            return Err(PersistenceLoadError::corrupt(
+                                path,
+                                format!(
+                                    "cannot rebuild indexes: {} rows have a duplicate key and {} written spans hold no row",
+                                    report.conflicts.len(),
+                                    report.unreadable.len(),
+                                ),
+                            ));
+                        }
+                        Some((report, primary_key_events, secondary_events))
+                    } else {
+                        None
+                    };
+                    if mode == LoadMode::Lazy {
+                        table.validate_persisted_links(path)?;
+                    } else {
+                        table.validate_persisted_state(path)?;
+                    }
+                    // Indexes added to or dropped from the declaration since
+                    // the store was written: fill the added ones from the rows
+                    // and let the engine record the new list.
+                    let generated_indexes = #wt_ident::space_info_default().inner.secondary_index_types;
+                    let report = if let Some((report, primary_key_events, secondary_events)) = rebuilt {
+                        engine
+                            .rebuild_indexes(primary_key_events, secondary_events)
+                            .await
+                            .map_err(|error| PersistenceLoadError::corrupt(path, format!("{error:#}")))?;
+                        // The rebuilt indexes already follow the declaration.
+                        if schema_recorded && stored_indexes != generated_indexes {
+                            engine
+                                .migrate_secondary_indexes(generated_indexes.clone(), #secondary_index_events::default())
+                                .await
+                                .map_err(|error| PersistenceLoadError::corrupt(path, format!("{error:#}")))?;
+                        }
+                        Some(report)
+                    } else {
+                        None
+                    };
+                    if report.is_none() && schema_recorded && stored_indexes != generated_indexes {
+                        let added = generated_indexes
+                            .iter()
+                            .filter(|(name, _)| !stored_indexes.iter().any(|(stored_name, _)| stored_name == name))
+                            .map(|(name, _)| name.clone())
+                            .collect::<Vec<_>>();
+                        let mut events = #secondary_index_events::default();
+                        for (primary_key, offset_link) in table.primary_index.pk_map.iter_values() {
+                            let row = table.data.select_non_ghosted_checked(offset_link.0).map_err(|error| {
+                                PersistenceLoadError::corrupt(
+                                    path,
+                                    format!("primary key {primary_key:?} references an invalid row: {error}"),
+                                )
+                            })?;
+                            let row_events = table.indexes.insert_row_into_indexes_cdc(&row, offset_link.0, &added).map_err(|error| {
+                                PersistenceLoadError::corrupt(
+                                    path,
+                                    format!("cannot build added secondary indexes {added:?} for primary key {primary_key:?}: {error:?}"),
+                                )
+                            })?;
+                            events.extend(row_events);
+                        }
+                        engine
+                            .migrate_secondary_indexes(generated_indexes, events)
+                            .await
+                            .map_err(|error| PersistenceLoadError::corrupt(path, format!("{error:#}")))?;
+                    }
                     let worktable = #wt_ident(
                         table,
-                        #task_ident::run_engine(engine)
+                        #task_ident::run_engine_with_unpersisted(engine, page_store.map(|(_, unpersisted)| unpersisted))
                     );
                     worktable.validate_loaded_secondary_state(path, mode)?;
-                    Ok(worktable)
+                    // Loaded pages are raw, so validation reads them cheaply.
+                    // Pages left on disk are not raw and stay there.
+                    worktable.0.data.compress_cold_pages();
+                    worktable.0.set_memory_budget(#wt_ident::memory_budget());
+                    Ok((worktable, report))
                 }
             }
         }
@@ -369,6 +538,13 @@ impl Generator {
                     #version_const_name,
                 ).await?
             }
+        } else if self.attributes.pk_hash {
+            quote! {
+                SpaceHashIndex::<#pk_type, { #inner_const_name as u32 }>::load_index::<#inner_const_name>(
+                    format!("{}/primary{}", path, #index_extension),
+                    #version_const_name,
+                ).await?
+            }
         } else {
             quote! {
                 {
@@ -390,26 +566,41 @@ impl Generator {
 
         quote! {
             pub async fn parse_file(path: &str) -> eyre::Result<Self> {
-                let primary_index = #parse_primary;
+                Self::parse_file_with_mode(path, LoadMode::Strict).await
+            }
 
-                let indexes = #persisted_index_name::parse_from_file(path).await?;
-                let (data, data_info) = {
+            /// Parses the table files. [`LoadMode::Lazy`] reads only the
+            /// headers of the data pages, and [`LoadMode::Rebuild`] leaves the
+            /// indexes empty without reading the index files.
+            pub async fn parse_file_with_mode(path: &str, mode: LoadMode) -> eyre::Result<Self> {
+                let (primary_index, indexes) = if mode == LoadMode::Rebuild {
+                    (Default::default(), #persisted_index_name::default())
+                } else {
+                    (#parse_primary, #persisted_index_name::parse_from_file(path).await?)
+                };
+                let (data, data_headers, data_info) = {
                     let mut data = vec![];
+                    let mut data_headers = vec![];
                     let mut data_file = tokio::fs::File::open(format!("{}/{}", path, #data_extension)).await?;
                     let info = parse_page::<SpaceInfoPage<<<#pk_type as TablePrimaryKey>::Generator as PrimaryKeyGeneratorState>::State>, { #page_const_name as u32 }>(&mut data_file, 0).await?;
                     let file_length = data_file.metadata().await?.len();
                     let count = file_length / (#inner_const_name as u64 + GENERAL_HEADER_SIZE as u64);
                     for page_id in 1..=count {
-                        let index = parse_data_page::<{ #page_const_name as u32}, { #inner_const_name as usize }>(&mut data_file, page_id as u32).await?;
-                        data.push(index);
+                        if mode == LoadMode::Lazy {
+                            data_headers.push(parse_general_header_by_index(&mut data_file, page_id as u32).await?);
+                        } else {
+                            let index = parse_data_page::<{ #page_const_name as u32}, { #inner_const_name as usize }>(&mut data_file, page_id as u32).await?;
+                            data.push(index);
+                        }
                     }
-                    (data, info)
+                    (data, data_headers, info)
                 };
 
                 Ok(Self {
                     primary_index,
                     indexes,
                     data,
+                    data_headers,
                     data_info
                 })
             }
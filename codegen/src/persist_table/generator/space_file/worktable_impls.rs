@@ -138,8 +138,8 @@ impl Generator {
         let name_generator = WorktableNameGenerator::from_struct_ident(&self.struct_def.ident);
         let pk_type = name_generator.get_primary_key_type_ident();
         let const_name = name_generator.get_page_inner_size_const_ident();
-        if self.attributes.pk_arctic || self.attributes.pk_congee {
-            // ART durability is maintained incrementally by its native
+        if self.attributes.pk_arctic || self.attributes.pk_congee || self.attributes.pk_hash {
+            // ART and hash durability is maintained incrementally by their
             // checkpoint/WAL file rather than materialized as DataBucket pages.
             quote! {}
         } else if self.attributes.pk_unsized {
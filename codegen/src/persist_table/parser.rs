@@ -32,6 +32,7 @@ impl Parser {
             pk_upstream: false,
             pk_arctic: false,
             pk_congee: false,
+            pk_hash: false,
             pk_wti_logical: false,
             row_schema: vec![],
             primary_key_fields: vec![],
@@ -61,6 +62,10 @@ impl Parser {
                         res.pk_congee = true;
                         return Ok(());
                     }
+                    if meta.path.is_ident("pk_hash") {
+                        res.pk_hash = true;
+                        return Ok(());
+                    }
                     if meta.path.is_ident("pk_wti_logical") {
                         res.pk_wti_logical = true;
                         return Ok(());
@@ -2,6 +2,7 @@ use proc_macro2::TokenStream;
 
 use crate::common::Parser;
 use crate::common::model::{Columns, IndexBackend, Persistence};
+use crate::generators::index_backend::validate_art_key;
 
 pub fn expand(input: TokenStream) -> syn::Result<TokenStream> {
     let mut parser = Parser::new(input);
@@ -9,6 +10,7 @@ pub fn expand(input: TokenStream) -> syn::Result<TokenStream> {
     let mut queries = None;
     let mut indexes = None;
     let mut config = None;
+    let mut hooks = None;
 
     let name = parser.parse_name()?;
     let version = parser.parse_version()?.unwrap_or(1);
@@ -31,6 +33,10 @@ pub fn expand(input: TokenStream) -> syn::Result<TokenStream> {
                 let res = parser.parse_configs()?;
                 config = Some(res)
             }
+            "hooks" => {
+                let res = parser.parse_hooks()?;
+                hooks = Some(res)
+            }
             "version" => {
                 return Err(syn::Error::new(
                     ident.span(),
@@ -55,16 +61,16 @@ pub fn expand(input: TokenStream) -> syn::Result<TokenStream> {
     validate_index_backends(&columns, persistence)?;
 
     if persistence.is_persisted() {
-        crate::generators::persist::expand(name, columns, queries, config, version)
+        crate::generators::persist::expand(name, columns, queries, config, hooks, version)
     } else {
-        crate::generators::in_memory::expand_from_parsed(name, columns, queries, config)
+        crate::generators::in_memory::expand_from_parsed(name, columns, queries, config, hooks)
     }
 }
 
 fn validate_index_backends(columns: &Columns, persistence: Persistence) -> syn::Result<()> {
     let explicit_backend = if columns.primary_index_backend.requires_explicit_persistence() {
         Some((
-            columns.primary_index_backend,
+            &columns.primary_index_backend,
             columns.primary_keys.first().expect("primary key exists"),
             true,
         ))
@@ -73,7 +79,7 @@ fn validate_index_backends(columns: &Columns, persistence: Persistence) -> syn::
             .indexes
             .values()
             .find(|index| index.backend.requires_explicit_persistence())
-            .map(|index| (index.backend, &index.name, false))
+            .map(|index| (&index.backend, &index.name, false))
     };
 
     if let Some((backend, ident, is_primary)) = explicit_backend {
@@ -93,15 +99,38 @@ fn validate_index_backends(columns: &Columns, persistence: Persistence) -> syn::
         }
     }
 
-    if let Some(index) = columns
-        .indexes
-        .values()
-        .find(|index| !index.is_unique && index.backend != IndexBackend::WorktablesIndex)
-    {
+    if persistence.is_persisted() {
+        let custom = if matches!(columns.primary_index_backend, IndexBackend::Custom(_)) {
+            Some((
+                columns.primary_keys.first().expect("primary key exists"),
+                "primary index",
+            ))
+        } else {
+            columns
+                .indexes
+                .values()
+                .find(|index| matches!(index.backend, IndexBackend::Custom(_)))
+                .map(|index| (&index.name, "index"))
+        };
+        if let Some((ident, kind)) = custom {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!("{kind} `{ident}` uses a custom backend, which cannot be persisted; declare `persist: false`"),
+            ));
+        }
+    }
+
+    if let Some(index) = columns.indexes.values().find(|index| {
+        !index.is_unique
+            && matches!(
+                index.backend,
+                IndexBackend::Indexset | IndexBackend::Hash | IndexBackend::Custom(_)
+            )
+    }) {
         return Err(syn::Error::new(
             index.name.span(),
             format!(
-                "non-unique index `{}` cannot use `{}`; non-unique indexes currently require `worktables_index`",
+                "non-unique index `{}` cannot use `{}`; use `worktables_index`, `congee` or `arctic`",
                 index.name,
                 index.backend.name()
             ),
@@ -109,28 +138,17 @@ fn validate_index_backends(columns: &Columns, persistence: Persistence) -> syn::
     }
 
     for (column, index) in &columns.indexes {
-        let key_type = columns
-            .columns_map
-            .get(column)
-            .expect("an index always references a validated column")
-            .to_string();
-        let supported = match index.backend {
-            IndexBackend::Congee => Some(&["u8", "u16", "u32", "u64", "usize"][..]),
-            IndexBackend::Arctic => Some(&["u16", "u32", "u64", "u128"][..]),
-            IndexBackend::WorktablesIndex | IndexBackend::Indexset => None,
+        let encoded = match index.backend {
+            IndexBackend::Congee | IndexBackend::Arctic => true,
+            IndexBackend::Hash => persistence.is_persisted(),
+            IndexBackend::WorktablesIndex | IndexBackend::Indexset | IndexBackend::Custom(_) => false,
         };
-        if let Some(supported) = supported
-            && !supported.contains(&key_type.as_str())
-        {
-            return Err(syn::Error::new(
-                index.name.span(),
-                format!(
-                    "index `{}` uses `{}`, which does not support key type `{key_type}`; supported types: {}",
-                    index.name,
-                    index.backend.name(),
-                    supported.join(", ")
-                ),
-            ));
+        if encoded {
+            let key_type = columns
+                .columns_map
+                .get(column)
+                .expect("an index always references a validated column");
+            validate_art_key(&index.backend, &[key_type], true)?;
         }
     }
 
@@ -160,6 +178,40 @@ mod tests {
         assert!(error.to_string().contains("keep `primary_key`"));
     }
 
+    #[test]
+    fn hooks_section_rejects_duplicate_and_unknown_hooks() {
+        let duplicate = expand(quote! {
+            name: DuplicateHook,
+            persist: false,
+            columns: {
+                id: u64 primary_key,
+            },
+            hooks: {
+                after_insert: a::first,
+                after_insert: a::second,
+            },
+        })
+        .unwrap_err();
+        assert!(
+            duplicate
+                .to_string()
+                .contains("`after_insert` hook is declared more than once")
+        );
+
+        let unknown = expand(quote! {
+            name: UnknownHook,
+            persist: false,
+            columns: {
+                id: u64 primary_key,
+            },
+            hooks: {
+                before_select: a::hook,
+            },
+        })
+        .unwrap_err();
+        assert!(unknown.to_string().contains("Unexpected identifier"));
+    }
+
     fn assert_composite_primary_key_field_order(output: proc_macro2::TokenStream) {
         let output = output.to_string();
         let get_primary_key = output
@@ -238,7 +290,7 @@ mod tests {
         .to_string();
 
         let update = output
-            .split("pub async fn update_balance")
+            .split("async fn update_balance_with_images")
             .nth(1)
             .expect("generated balance update");
         assert!(
@@ -363,66 +415,237 @@ mod tests {
     }
 
     #[test]
-    fn memory_backend_rejects_non_unique_indexes() {
+    fn art_backends_accept_non_unique_indexes() {
+        let output = expand(quote! {
+            name: NonUniqueArt,
+            persist: true,
+            columns: {
+                id: u64 primary_key,
+                arctic_value: u64,
+                congee_value: u64,
+            },
+            indexes: {
+                arctic_idx: arctic_value using arctic,
+                congee_idx: congee_value using congee,
+            },
+        })
+        .unwrap()
+        .to_string();
+
+        assert!(output.contains("PersistentArcticMultiIndex"));
+        assert!(output.contains("PersistentCongeeMultiIndex"));
+    }
+
+    #[test]
+    fn indexset_rejects_non_unique_indexes() {
         let error = expand(quote! {
-            name: NonUniqueArctic,
+            name: NonUniqueIndexset,
             persist: false,
             columns: {
                 id: u64 primary_key,
                 value: u64,
             },
             indexes: {
-                value_idx: value using arctic,
+                value_idx: value using indexset,
             },
         })
         .unwrap_err();
 
-        assert!(error.to_string().contains("non-unique indexes currently require"));
+        assert!(
+            error
+                .to_string()
+                .contains("non-unique index `value_idx` cannot use `indexset`")
+        );
     }
 
     #[test]
-    fn congee_rejects_non_machine_word_secondary_keys() {
+    fn hash_backend_omits_range_queries() {
+        let output = expand(quote! {
+            name: HashOnly,
+            persist: false,
+            columns: {
+                id: u64 primary_key autoincrement using hash,
+                session: String,
+                value: u64,
+            },
+            indexes: {
+                session_idx: session unique using hash,
+                value_idx: value unique,
+            },
+        })
+        .unwrap()
+        .to_string();
+
+        assert!(output.contains("HashIndex < String"));
+        assert!(output.contains("select_by_session"));
+        assert!(!output.contains("select_by_session_range"));
+        assert!(!output.contains("delete_by_session_range"));
+        assert!(!output.contains("select_by_pk_range"));
+        assert!(output.contains("select_by_value_range"));
+    }
+
+    #[test]
+    fn hash_backend_rejects_non_unique_indexes() {
         let error = expand(quote! {
-            name: StringCongee,
+            name: NonUniqueHash,
             persist: false,
             columns: {
                 id: u64 primary_key,
-                name: String,
+                value: u64,
             },
             indexes: {
-                name_idx: name unique using congee,
+                value_idx: value using hash,
             },
         })
         .unwrap_err();
 
-        assert!(error.to_string().contains("does not support key type `String`"));
+        assert!(
+            error
+                .to_string()
+                .contains("non-unique index `value_idx` cannot use `hash`")
+        );
+    }
+
+    #[test]
+    fn custom_backend_asserts_its_trait_set() {
+        let output = expand(quote! {
+            name: CustomOnly,
+            persist: false,
+            columns: {
+                id: u64 primary_key autoincrement using custom(my_crate::SkipIndex),
+                value: u64,
+            },
+            indexes: {
+                value_idx: value unique using custom(my_crate::SkipIndex),
+            },
+        })
+        .unwrap()
+        .to_string();
+
+        assert!(output.contains("value_idx : my_crate :: SkipIndex < u64 , OffsetEqLink >"));
+        assert!(
+            output.contains("unique_index :: < my_crate :: SkipIndex < u64 , OffsetEqLink > , u64 , OffsetEqLink >")
+        );
+        assert!(output.contains("table_index :: < my_crate :: SkipIndex < u64 , OffsetEqLink > , u64 >"));
+        assert!(output.contains("mem_stat :: < my_crate :: SkipIndex < CustomOnlyPrimaryKey"));
+        assert!(output.contains("select_by_value_range"));
+        assert!(output.contains("select_by_pk_range"));
+        // The primary index is only driven through `UniqueIndex`.
+        assert!(!output.contains("table_index :: < my_crate :: SkipIndex < CustomOnlyPrimaryKey"));
     }
 
     #[test]
-    fn arctic_rejects_unsupported_secondary_keys() {
+    fn custom_backend_is_memory_only_and_unique_only() {
+        let persisted = expand(quote! {
+            name: PersistedCustom,
+            persist: true,
+            columns: {
+                id: u64 primary_key,
+                value: u64,
+            },
+            indexes: {
+                value_idx: value unique using custom(SkipIndex),
+            },
+        })
+        .unwrap_err();
+        assert!(
+            persisted
+                .to_string()
+                .contains("index `value_idx` uses a custom backend, which cannot be persisted")
+        );
+
+        let omitted = expand(quote! {
+            name: OmittedCustom,
+            columns: {
+                id: u64 primary_key using custom(SkipIndex),
+            },
+        })
+        .unwrap_err();
+        assert!(
+            omitted
+                .to_string()
+                .contains("requires an explicit `persist: true` or `persist: false`")
+        );
+
+        let non_unique = expand(quote! {
+            name: NonUniqueCustom,
+            persist: false,
+            columns: {
+                id: u64 primary_key,
+                value: u64,
+            },
+            indexes: {
+                value_idx: value using custom(SkipIndex),
+            },
+        })
+        .unwrap_err();
+        assert!(
+            non_unique
+                .to_string()
+                .contains("non-unique index `value_idx` cannot use `custom`")
+        );
+    }
+
+    #[test]
+    fn persisted_hash_keys_need_a_fixed_width_encoding() {
+        let output = expand(quote! {
+            name: PersistedHash,
+            persist: true,
+            columns: {
+                id: u64 primary_key using hash,
+                external: Uuid,
+            },
+            indexes: {
+                external_idx: external unique using hash,
+            },
+        })
+        .unwrap()
+        .to_string();
+        assert!(output.contains("impl ArtKeyEncode for PersistedHashPrimaryKey"));
+        assert!(output.contains("PersistentHashIndex < Uuid"));
+        assert!(output.contains("pk_hash"));
+
         let error = expand(quote! {
-            name: ByteArctic,
+            name: PersistedStringHash,
+            persist: true,
+            columns: {
+                id: u64 primary_key,
+                session: String,
+            },
+            indexes: {
+                session_idx: session unique using hash,
+            },
+        })
+        .unwrap_err();
+        assert!(error.to_string().contains("`using hash` cannot index `String`"));
+    }
+
+    #[test]
+    fn congee_rejects_non_machine_word_secondary_keys() {
+        let error = expand(quote! {
+            name: StringCongee,
             persist: false,
             columns: {
                 id: u64 primary_key,
-                value: u8,
+                name: String,
             },
             indexes: {
-                value_idx: value unique using arctic,
+                name_idx: name unique using congee,
             },
         })
         .unwrap_err();
 
-        assert!(error.to_string().contains("supported types: u16, u32, u64, u128"));
+        assert!(error.to_string().contains("cannot index `String`"));
     }
 
     #[test]
-    fn congee_rejects_unsupported_primary_keys() {
+    fn arctic_rejects_keys_wider_than_its_native_key() {
         let error = expand(quote! {
-            name: StringPrimaryCongee,
+            name: WideArctic,
             persist: false,
             columns: {
-                id: String primary_key using congee,
+                id: u128 primary_key using arctic,
+                sequence: u16 primary_key using arctic,
             },
         })
         .unwrap_err();
@@ -430,7 +653,57 @@ mod tests {
         assert!(
             error
                 .to_string()
-                .contains("requires a directly named primitive primary-key type; found `String`")
+                .contains("`using arctic` keys are limited to 16 bytes, but `(u128, u16)` encodes to 18")
         );
     }
+
+    #[test]
+    fn congee_rejects_unsupported_primary_keys() {
+        let error = expand(quote! {
+            name: StringPrimaryCongee,
+            persist: false,
+            columns: {
+                id: String primary_key using congee,
+            },
+        })
+        .unwrap_err();
+
+        assert!(error.to_string().contains("`using congee` cannot index `String`"));
+
+        let error = expand(quote! {
+            name: UuidPrimaryCongee,
+            persist: false,
+            columns: {
+                id: Uuid primary_key using congee,
+            },
+        })
+        .unwrap_err();
+
+        assert!(error.to_string().contains("`using congee` keys are limited to 8 bytes"));
+    }
+
+    #[test]
+    fn art_backends_accept_encoded_keys() {
+        let output = expand(quote! {
+            name: EncodedArt,
+            persist: true,
+            columns: {
+                account: i32 primary_key using congee,
+                venue: u16 primary_key using congee,
+                price: f64,
+                external: Uuid,
+                session: PackedSessionId,
+            },
+            indexes: {
+                price_idx: price using congee,
+                external_idx: external unique using arctic,
+                session_idx: session unique using arctic,
+            },
+        })
+        .unwrap()
+        .to_string();
+
+        assert!(output.contains("impl ArtKeyEncode for EncodedArtPrimaryKey"));
+        assert!(output.contains("PersistentCongeeMultiIndex < OrderedFloat < f64 >"));
+    }
 }
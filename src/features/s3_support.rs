@@ -10,10 +10,11 @@ use url::Url;
 use walkdir::WalkDir;
 
 use crate::TableSecondaryIndexEventsOps;
+use crate::in_memory::PageCompression;
 use crate::persistence::operation::{BatchOperation, Operation};
 use crate::persistence::{
-    DiskConfig, DiskPersistenceEngine, PersistenceConfig, PersistenceEngine, SpaceDataOps, SpaceIndexOps,
-    SpaceSecondaryIndexOps,
+    BatchChangeEvent, DiskConfig, DiskPersistenceEngine, PersistenceConfig, PersistenceEngine, SpaceDataOps,
+    SpaceIndexOps, SpaceSecondaryIndexOps,
 };
 use crate::prelude::{PrimaryKeyGeneratorState, TablePrimaryKey, WT_DATA_EXTENSION, WT_INDEX_EXTENSION};
 
@@ -291,6 +292,32 @@ where
         Ok(())
     }
 
+    async fn truncate(&mut self) -> eyre::Result<()> {
+        self.inner.truncate().await?;
+        self.sync_to_s3().await?;
+        Ok(())
+    }
+
+    async fn rebuild_indexes(
+        &mut self,
+        primary_key_events: BatchChangeEvent<PrimaryKey>,
+        secondary_index_events: SecondaryIndexEvents,
+    ) -> eyre::Result<()> {
+        self.inner
+            .rebuild_indexes(primary_key_events, secondary_index_events)
+            .await?;
+        self.sync_to_s3().await?;
+        Ok(())
+    }
+
+    fn set_page_compression(&mut self, compression: PageCompression) {
+        self.inner.set_page_compression(compression);
+    }
+
+    fn set_deleted_flag_offset(&mut self, offset: u32) {
+        self.inner.set_deleted_flag_offset(offset);
+    }
+
     fn config(&self) -> &Self::Config {
         &self.config
     }
@@ -0,0 +1,125 @@
+//! Memory budget of a table and the storage its evicted pages go to.
+
+use std::fmt::Debug;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+
+use data_bucket::page::PageId;
+use parking_lot::Mutex;
+
+/// Memory a table may use for its data pages and indexes, see
+/// [`crate::WorkTable::set_memory_budget`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MemoryBudget {
+    /// Limit on resident page bytes plus index heap bytes.
+    pub limit_bytes: u64,
+    pub policy: MemoryLimitPolicy,
+}
+
+/// What a table does when it needs another data page over its
+/// [`MemoryBudget`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MemoryLimitPolicy {
+    /// The mutation fails with `WorkTableError::MemoryLimitExceeded`.
+    #[default]
+    Reject,
+    /// The least recently used pages are handed to the table's
+    /// [`PageStore`] and read back on access. Growth is rejected as with
+    /// [`Self::Reject`] when evicting cannot make room, for example when the
+    /// table has no page store or the store refuses the pages.
+    EvictColdPages,
+}
+
+/// Storage that evicted [`Data`] pages are written to and read back from.
+///
+/// `store_page` may refuse a page it cannot take yet with
+/// [`io::ErrorKind::WouldBlock`]; the page then stays resident.
+///
+/// [`Data`]: crate::in_memory::Data
+pub trait PageStore: Debug + Send + Sync {
+    fn store_page(&self, page_id: PageId, bytes: &[u8]) -> io::Result<()>;
+    fn load_page(&self, page_id: PageId, bytes: &mut [u8]) -> io::Result<()>;
+}
+
+/// [`PageStore`] backed by a scratch file that holds every page at a fixed
+/// slot. The file is created on the first eviction, truncating a file left
+/// by a previous process, and removed when the store is dropped.
+#[derive(Debug)]
+pub struct SpillFile {
+    path: PathBuf,
+    file: Mutex<Option<File>>,
+}
+
+impl SpillFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            file: Mutex::new(None),
+        }
+    }
+
+    fn slot(page_id: PageId, page_length: usize) -> u64 {
+        u64::from(u32::from(page_id).saturating_sub(1)) * page_length as u64
+    }
+}
+
+impl PageStore for SpillFile {
+    fn store_page(&self, page_id: PageId, bytes: &[u8]) -> io::Result<()> {
+        let mut file = self.file.lock();
+        if file.is_none() {
+            *file = Some(
+                OpenOptions::new()
+                    .read(true)
+                    .write(true)
+                    .create(true)
+                    .truncate(true)
+                    .open(&self.path)?,
+            );
+        }
+        let file = file.as_mut().expect("opened above");
+        file.seek(SeekFrom::Start(Self::slot(page_id, bytes.len())))?;
+        file.write_all(bytes)
+    }
+
+    fn load_page(&self, page_id: PageId, bytes: &mut [u8]) -> io::Result<()> {
+        let mut file = self.file.lock();
+        let file = file
+            .as_mut()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no page was evicted to the spill file"))?;
+        file.seek(SeekFrom::Start(Self::slot(page_id, bytes.len())))?;
+        file.read_exact(bytes)
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        if self.file.get_mut().take().is_some() {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PageStore, SpillFile};
+
+    #[test]
+    fn spill_file_round_trips_pages_and_is_removed_on_drop() {
+        let path = std::env::temp_dir().join(format!("worktable-spill-{}", std::process::id()));
+        let store = SpillFile::new(&path);
+        let mut page = [0; 64];
+        assert!(store.load_page(1.into(), &mut page).is_err());
+
+        store.store_page(3.into(), &[3; 64]).unwrap();
+        store.store_page(1.into(), &[1; 64]).unwrap();
+        store.load_page(3.into(), &mut page).unwrap();
+        assert_eq!(page, [3; 64]);
+        store.load_page(1.into(), &mut page).unwrap();
+        assert_eq!(page, [1; 64]);
+        assert!(path.exists());
+
+        drop(store);
+        assert!(!path.exists());
+    }
+}
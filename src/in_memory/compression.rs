@@ -0,0 +1,115 @@
+//! Compression of cold [`Data`] pages.
+//!
+//! A compressed page image is the LZ4 block of the page's used bytes,
+//! prefixed with their count as a little-endian `u32`. Bytes past the used
+//! part are not kept, they are zeroes once the page is decompressed.
+//!
+//! On disk a compressed page has [`COMPRESSED_PAGE_FLAG`] set in the
+//! `data_length` of its header, whose other bits still hold the used length.
+//! Its inner bytes start with the image length as a little-endian `u32`,
+//! followed by the image.
+//!
+//! [`Data`]: crate::in_memory::Data
+
+/// `data_length` bit of a page header that marks a compressed data page.
+pub const COMPRESSED_PAGE_FLAG: u32 = 1 << 31;
+
+/// Compression of the table's cold data pages, set with
+/// `config: { compression: lz4 }`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PageCompression {
+    /// Pages are always kept raw.
+    #[default]
+    None,
+    /// Cold pages are compressed with LZ4.
+    Lz4,
+}
+
+impl PageCompression {
+    /// Compresses the `used` bytes of a page of `page_length` bytes.
+    ///
+    /// Returns `None` when compression is off or the image would not save at
+    /// least a quarter of the page.
+    pub fn compress(self, used: &[u8], page_length: usize) -> Option<Vec<u8>> {
+        match self {
+            Self::None => None,
+            Self::Lz4 => {
+                let block = lz4_flex::block::compress(used);
+                if 4 + block.len() > page_length / 4 * 3 {
+                    return None;
+                }
+                let mut image = Vec::with_capacity(4 + block.len());
+                image.extend_from_slice(&(used.len() as u32).to_le_bytes());
+                image.extend_from_slice(&block);
+                Some(image)
+            }
+        }
+    }
+}
+
+/// Decompresses a page image into the start of `page`, returning the count of
+/// used bytes, or `None` if the image is corrupt or does not fit.
+pub fn decompress_page(image: &[u8], page: &mut [u8]) -> Option<usize> {
+    let (used, block) = image.split_first_chunk::<4>()?;
+    let used = u32::from_le_bytes(*used) as usize;
+    let out = page.get_mut(..used)?;
+    let written = lz4_flex::block::decompress_into(block, out).ok()?;
+    (written == used).then_some(used)
+}
+
+/// Inner bytes of a compressed page on disk.
+pub fn disk_page_bytes(image: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(4 + image.len());
+    bytes.extend_from_slice(&(image.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(image);
+    bytes
+}
+
+/// Image stored in the inner bytes of a compressed page on disk.
+pub fn disk_page_image(bytes: &[u8]) -> Option<&[u8]> {
+    let (length, rest) = bytes.split_first_chunk::<4>()?;
+    rest.get(..u32::from_le_bytes(*length) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PageCompression, decompress_page, disk_page_bytes, disk_page_image};
+
+    #[test]
+    fn repetitive_page_round_trips() {
+        let used = b"BTCUSDT".repeat(300);
+        let image = PageCompression::Lz4.compress(&used, 4096).unwrap();
+        assert!(image.len() < used.len() / 4);
+
+        let mut page = [0xff; 4096];
+        assert_eq!(decompress_page(&image, &mut page), Some(used.len()));
+        assert_eq!(&page[..used.len()], used.as_slice());
+
+        let on_disk = disk_page_bytes(&image);
+        assert_eq!(disk_page_image(&on_disk), Some(image.as_slice()));
+        assert_eq!(disk_page_image(&on_disk[..on_disk.len() - 1]), None);
+    }
+
+    #[test]
+    fn incompressible_or_disabled_pages_stay_raw() {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let noise: Vec<u8> = (0..4096)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+        assert_eq!(PageCompression::Lz4.compress(&noise, 4096), None);
+        assert_eq!(PageCompression::None.compress(&[0; 64], 4096), None);
+    }
+
+    #[test]
+    fn corrupt_image_is_rejected() {
+        let image = PageCompression::Lz4.compress(&[7; 1000], 4096).unwrap();
+        let mut page = [0; 512];
+        assert_eq!(decompress_page(&image, &mut page), None);
+        assert_eq!(decompress_page(&image[..3], &mut [0; 4096]), None);
+    }
+}
@@ -2,24 +2,28 @@ use std::cell::UnsafeCell;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 
 use data_bucket::page::INNER_PAGE_SIZE;
 use data_bucket::page::PageId;
 use data_bucket::{DataPage, GeneralPage};
 use derive_more::{Display, Error};
+use parking_lot::Mutex;
 #[cfg(feature = "perf_measurements")]
 use performance_measurement_codegen::performance_measurement;
 use rkyv::{
-    Archive, Deserialize, Portable, Serialize,
+    Archive, Archived, Deserialize, Place, Portable, Resolver, Serialize,
     api::high::HighDeserializer,
-    rancor::Strategy,
+    rancor::{Fallible, Source, Strategy},
     seal::Seal,
     ser::{Serializer, allocator::ArenaHandle, sharing::Share},
     util::AlignedVec,
-    with::{AtomicLoad, Relaxed, Skip, Unsafe},
+    with::{ArchiveWith, AtomicLoad, DeserializeWith, Relaxed, SerializeWith, Skip},
 };
 
+use crate::in_memory::budget::PageStore;
+use crate::in_memory::compression::{COMPRESSED_PAGE_FLAG, PageCompression, decompress_page, disk_page_image};
 use crate::prelude::Link;
 
 /// Length of the [`Data`] page header.
@@ -46,6 +50,102 @@ impl<const N: usize> DerefMut for AlignedBytes<N> {
     }
 }
 
+/// Advanced by every eviction pass; pages remember the value at their last
+/// access, so pages with the smallest stamp are the least recently used.
+static ACCESS_CLOCK: AtomicU64 = AtomicU64::new(0);
+
+/// Bytes of a [`Data`] page, raw, as a compressed image, see
+/// [`crate::in_memory::compression`], or evicted to a [`PageStore`].
+pub enum PageBuffer<const N: usize> {
+    Raw(Box<AlignedBytes<N>>),
+    Compressed(Box<[u8]>),
+    Evicted {
+        store: Arc<dyn PageStore>,
+        page_id: PageId,
+    },
+    /// Bytes of an empty page, dropped until the page is reused.
+    Released,
+}
+
+impl<const N: usize> PageBuffer<N> {
+    fn zeroed() -> Self {
+        Self::Raw(Box::new(AlignedBytes([0; N])))
+    }
+
+    fn decompress(image: &[u8]) -> Box<AlignedBytes<N>> {
+        let mut bytes = Box::new(AlignedBytes([0; N]));
+        decompress_page(image, &mut bytes.0).expect("in-memory page images are produced by `Data::compress`");
+        bytes
+    }
+
+    /// Reads an evicted page back.
+    fn load(store: &dyn PageStore, page_id: PageId) -> std::io::Result<Box<AlignedBytes<N>>> {
+        let mut bytes = Box::new(AlignedBytes([0; N]));
+        store.load_page(page_id, &mut bytes.0)?;
+        Ok(bytes)
+    }
+
+    /// Raw bytes of a page that is not raw.
+    fn materialize(&self) -> std::io::Result<Box<AlignedBytes<N>>> {
+        Ok(match self {
+            Self::Raw(bytes) => bytes.clone(),
+            Self::Compressed(image) => Self::decompress(image),
+            Self::Evicted { store, page_id } => Self::load(store.as_ref(), *page_id)?,
+            Self::Released => Box::new(AlignedBytes([0; N])),
+        })
+    }
+}
+
+/// Archives a [`PageBuffer`] as the raw page bytes it holds.
+pub struct RawPage;
+
+impl<const N: usize> ArchiveWith<UnsafeCell<PageBuffer<N>>> for RawPage {
+    type Archived = Archived<AlignedBytes<N>>;
+    type Resolver = Resolver<AlignedBytes<N>>;
+
+    fn resolve_with(field: &UnsafeCell<PageBuffer<N>>, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        match unsafe { &*field.get() } {
+            PageBuffer::Raw(bytes) => bytes.as_ref().resolve(resolver, out),
+            buffer => buffer
+                .materialize()
+                .expect("`serialize_with` read the page back just before")
+                .as_ref()
+                .resolve(resolver, out),
+        }
+    }
+}
+
+impl<const N: usize, S: Fallible + ?Sized> SerializeWith<UnsafeCell<PageBuffer<N>>, S> for RawPage
+where
+    AlignedBytes<N>: Serialize<S>,
+    S::Error: Source,
+{
+    fn serialize_with(field: &UnsafeCell<PageBuffer<N>>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        match unsafe { &*field.get() } {
+            PageBuffer::Raw(bytes) => bytes.as_ref().serialize(serializer),
+            buffer => buffer
+                .materialize()
+                .map_err(S::Error::new)?
+                .as_ref()
+                .serialize(serializer),
+        }
+    }
+}
+
+impl<const N: usize, D: Fallible + ?Sized> DeserializeWith<Archived<AlignedBytes<N>>, UnsafeCell<PageBuffer<N>>, D>
+    for RawPage
+where
+    Archived<AlignedBytes<N>>: Deserialize<AlignedBytes<N>, D>,
+{
+    fn deserialize_with(
+        field: &Archived<AlignedBytes<N>>,
+        deserializer: &mut D,
+    ) -> Result<UnsafeCell<PageBuffer<N>>, D::Error> {
+        let bytes = field.deserialize(deserializer)?;
+        Ok(UnsafeCell::new(PageBuffer::Raw(Box::new(bytes))))
+    }
+}
+
 #[derive(Archive, Deserialize, Debug, Serialize)]
 pub struct Data<Row, const DATA_LENGTH: usize = DATA_INNER_LENGTH> {
     /// [`PageId`] of the general page represented by this [`Data`] block.
@@ -57,8 +157,20 @@ pub struct Data<Row, const DATA_LENGTH: usize = DATA_INNER_LENGTH> {
     pub free_offset: AtomicU32,
 
     /// Inner array of bytes where deserialized `Row`s will be stored.
-    #[rkyv(with = Unsafe)]
-    inner_data: UnsafeCell<AlignedBytes<DATA_LENGTH>>,
+    #[rkyv(with = RawPage)]
+    inner_data: UnsafeCell<PageBuffer<DATA_LENGTH>>,
+
+    /// Whether `inner_data` holds a compressed image or is evicted.
+    #[rkyv(with = Skip)]
+    cold: AtomicBool,
+
+    /// Serializes making a cold page raw with readers of its image.
+    #[rkyv(with = Skip)]
+    thaw_lock: Mutex<()>,
+
+    /// [`ACCESS_CLOCK`] at the last access of the page.
+    #[rkyv(with = Skip)]
+    last_access: AtomicU64,
 
     /// `Row` phantom data.
     _phantom: PhantomData<Row>,
@@ -72,16 +184,48 @@ impl<Row, const DATA_LENGTH: usize> Data<Row, DATA_LENGTH> {
         Self {
             id,
             free_offset: AtomicU32::default(),
-            inner_data: UnsafeCell::new(AlignedBytes::<DATA_LENGTH>([0; DATA_LENGTH])),
+            inner_data: UnsafeCell::new(PageBuffer::zeroed()),
+            cold: AtomicBool::new(false),
+            thaw_lock: Mutex::new(()),
+            last_access: AtomicU64::new(ACCESS_CLOCK.load(Ordering::Relaxed)),
             _phantom: PhantomData,
         }
     }
 
-    pub fn from_data_page(page: GeneralPage<DataPage<DATA_LENGTH>>) -> Self {
-        Self {
+    /// Creates a raw [`Data`] page from a persisted one. Fails if the page
+    /// is compressed and its image is corrupt.
+    pub fn from_data_page(page: GeneralPage<DataPage<DATA_LENGTH>>) -> Result<Self, ExecutionError> {
+        let data_length = page.header.data_length;
+        let bytes = if data_length & COMPRESSED_PAGE_FLAG == 0 {
+            Box::new(AlignedBytes::<DATA_LENGTH>(page.inner.data))
+        } else {
+            let mut bytes = Box::new(AlignedBytes::<DATA_LENGTH>([0; DATA_LENGTH]));
+            disk_page_image(&page.inner.data)
+                .and_then(|image| decompress_page(image, &mut bytes.0))
+                .ok_or(ExecutionError::DeserializeError)?;
+            bytes
+        };
+        Ok(Self {
             id: page.header.page_id,
-            free_offset: AtomicU32::from(page.header.data_length),
-            inner_data: UnsafeCell::new(AlignedBytes::<DATA_LENGTH>(page.inner.data)),
+            free_offset: AtomicU32::from(data_length & !COMPRESSED_PAGE_FLAG),
+            inner_data: UnsafeCell::new(PageBuffer::Raw(bytes)),
+            cold: AtomicBool::new(false),
+            thaw_lock: Mutex::new(()),
+            last_access: AtomicU64::new(ACCESS_CLOCK.load(Ordering::Relaxed)),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Creates a [`Data`] page whose bytes are still in `store`, read back on
+    /// first access like an evicted page.
+    pub fn lazy(id: PageId, free_offset: u32, store: Arc<dyn PageStore>) -> Self {
+        Self {
+            id,
+            free_offset: AtomicU32::new(free_offset),
+            inner_data: UnsafeCell::new(PageBuffer::Evicted { store, page_id: id }),
+            cold: AtomicBool::new(true),
+            thaw_lock: Mutex::new(()),
+            last_access: AtomicU64::new(ACCESS_CLOCK.load(Ordering::Relaxed)),
             _phantom: PhantomData,
         }
     }
@@ -112,7 +256,7 @@ impl<Row, const DATA_LENGTH: usize> Data<Row, DATA_LENGTH> {
             });
         }
 
-        let inner_data = unsafe { &mut *self.inner_data.get() };
+        let inner_data = unsafe { self.bytes_mut() }?;
         inner_data[offset as usize..][..length as usize].copy_from_slice(bytes.as_slice());
 
         let link = Link {
@@ -140,7 +284,7 @@ impl<Row, const DATA_LENGTH: usize> Data<Row, DATA_LENGTH> {
             "slot length was checked before archived bytes are overwritten"
         );
 
-        let inner_data = unsafe { &mut *self.inner_data.get() };
+        let inner_data = unsafe { self.bytes_mut() }?;
         inner_data[link.offset as usize..][..link.length as usize].copy_from_slice(bytes.as_slice());
 
         Ok(link)
@@ -169,7 +313,7 @@ impl<Row, const DATA_LENGTH: usize> Data<Row, DATA_LENGTH> {
             None
         };
 
-        let inner_data = unsafe { &mut *self.inner_data.get() };
+        let inner_data = unsafe { self.bytes_mut() }?;
         inner_data[link.offset as usize..][..link.length as usize].copy_from_slice(bytes.as_slice());
 
         Ok((link, link_left))
@@ -184,7 +328,7 @@ impl<Row, const DATA_LENGTH: usize> Data<Row, DATA_LENGTH> {
         Row: Archive,
         <Row as Archive>::Archived: Portable,
     {
-        let inner_data = unsafe { &mut *self.inner_data.get() };
+        let inner_data = unsafe { self.bytes_mut() }?;
         let bytes = &mut inner_data[link.offset as usize..(link.offset + link.length) as usize];
         Ok(unsafe { rkyv::access_unchecked_mut::<<Row as Archive>::Archived>(&mut bytes[..]) })
     }
@@ -194,18 +338,38 @@ impl<Row, const DATA_LENGTH: usize> Data<Row, DATA_LENGTH> {
     where
         Row: Archive,
     {
-        let inner_data = unsafe { &*self.inner_data.get() };
+        let inner_data = unsafe { self.bytes_mut() }?;
         let bytes = &inner_data[link.offset as usize..(link.offset + link.length) as usize];
         Ok(unsafe { rkyv::access_unchecked::<<Row as Archive>::Archived>(bytes) })
     }
 
+    /// Runs `op` over the archived row at `link`. A compressed page stays
+    /// compressed.
+    pub fn with_row_ref<Res>(
+        &self,
+        link: Link,
+        op: impl FnOnce(&<Row as Archive>::Archived) -> Res,
+    ) -> Result<Res, ExecutionError>
+    where
+        Row: Archive,
+    {
+        self.with_image(|inner_data| {
+            let bytes = &inner_data[link.offset as usize..(link.offset + link.length) as usize];
+            op(unsafe { rkyv::access_unchecked::<<Row as Archive>::Archived>(bytes) })
+        })
+    }
+
+    /// Deserializes the row at `link`. A compressed page stays compressed.
     pub fn get_row(&self, link: Link) -> Result<Row, ExecutionError>
     where
         Row: Archive,
         <Row as Archive>::Archived: Deserialize<Row, HighDeserializer<rkyv::rancor::Error>>,
     {
-        let row = self.get_row_ref(link)?;
-        rkyv::deserialize::<_, rkyv::rancor::Error>(row).map_err(|_| ExecutionError::DeserializeError)
+        self.with_image(|inner_data| {
+            let bytes = &inner_data[link.offset as usize..(link.offset + link.length) as usize];
+            let row = unsafe { rkyv::access_unchecked::<<Row as Archive>::Archived>(bytes) };
+            rkyv::deserialize::<_, rkyv::rancor::Error>(row).map_err(|_| ExecutionError::DeserializeError)
+        })?
     }
 
     /// Validates persisted bytes before deserializing them.
@@ -231,16 +395,15 @@ impl<Row, const DATA_LENGTH: usize> Data<Row, DATA_LENGTH> {
             return Err(ExecutionError::InvalidLink);
         }
 
-        let inner_data = unsafe { &*self.inner_data.get() };
-        let archived = rkyv::access::<<Row as Archive>::Archived, rkyv::rancor::Error>(&inner_data[start..end])
-            .map_err(|_| ExecutionError::DeserializeError)?;
-        rkyv::deserialize::<_, rkyv::rancor::Error>(archived).map_err(|_| ExecutionError::DeserializeError)
+        self.with_image(|inner_data| {
+            let archived = rkyv::access::<<Row as Archive>::Archived, rkyv::rancor::Error>(&inner_data[start..end])
+                .map_err(|_| ExecutionError::DeserializeError)?;
+            rkyv::deserialize::<_, rkyv::rancor::Error>(archived).map_err(|_| ExecutionError::DeserializeError)
+        })?
     }
 
     pub fn get_raw_row(&self, link: Link) -> Result<Vec<u8>, ExecutionError> {
-        let inner_data = unsafe { &mut *self.inner_data.get() };
-        let bytes = &mut inner_data[link.offset as usize..(link.offset + link.length) as usize];
-        Ok(bytes.to_vec())
+        self.with_image(|inner_data| inner_data[link.offset as usize..(link.offset + link.length) as usize].to_vec())
     }
 
     /// Moves data within the page from one location to another.
@@ -256,7 +419,7 @@ impl<Row, const DATA_LENGTH: usize> Data<Row, DATA_LENGTH> {
             return Err(ExecutionError::InvalidLink);
         }
 
-        let inner_data = unsafe { &mut *self.inner_data.get() };
+        let inner_data = unsafe { self.bytes_mut() }?;
         let src_offset = from.offset as usize;
         let dst_offset = to.offset as usize;
         let length = from.length as usize;
@@ -293,7 +456,7 @@ impl<Row, const DATA_LENGTH: usize> Data<Row, DATA_LENGTH> {
             });
         }
 
-        let inner_data = unsafe { &mut *self.inner_data.get() };
+        let inner_data = unsafe { self.bytes_mut() }?;
         inner_data[offset as usize..][..length as usize].copy_from_slice(data);
 
         Ok(Link {
@@ -303,9 +466,189 @@ impl<Row, const DATA_LENGTH: usize> Data<Row, DATA_LENGTH> {
         })
     }
 
-    pub fn get_bytes(&self) -> [u8; DATA_LENGTH] {
-        let data = unsafe { &*self.inner_data.get() };
-        data.0
+    /// Overwrites the bytes at `link` with already-serialized data of the same
+    /// length.
+    ///
+    /// # Safety
+    /// The caller must ensure that no other references to the bytes at `link`
+    /// exist while they are overwritten.
+    pub unsafe fn save_raw_row_by_link(&self, data: &[u8], link: Link) -> Result<Link, ExecutionError> {
+        if data.len() != link.length as usize || (link.offset + link.length) as usize > DATA_LENGTH {
+            return Err(ExecutionError::InvalidLink);
+        }
+
+        let inner_data = unsafe { self.bytes_mut() }?;
+        inner_data[link.offset as usize..][..link.length as usize].copy_from_slice(data);
+
+        Ok(link)
+    }
+
+    pub fn get_bytes(&self) -> Result<[u8; DATA_LENGTH], ExecutionError> {
+        self.with_image(|data| data.0)
+    }
+
+    /// Replaces the page bytes with their compressed image. Returns `false` if
+    /// the page is not raw or the image is not worth keeping, see
+    /// [`PageCompression::compress`].
+    ///
+    /// # Safety
+    /// The caller must have exclusive access to the page: no reference into
+    /// its bytes may exist and no other thread may access them meanwhile.
+    pub unsafe fn compress(&self, compression: PageCompression) -> bool {
+        let buffer = unsafe { &mut *self.inner_data.get() };
+        let PageBuffer::Raw(bytes) = buffer else {
+            return false;
+        };
+        let used = (self.free_offset.load(Ordering::Acquire) as usize).min(DATA_LENGTH);
+        let Some(image) = compression.compress(&bytes[..used], DATA_LENGTH) else {
+            return false;
+        };
+        let _guard = self.thaw_lock.lock();
+        *buffer = PageBuffer::Compressed(image.into_boxed_slice());
+        self.cold.store(true, Ordering::Release);
+        true
+    }
+
+    /// Writes the page bytes to `store` and drops them from memory, returning
+    /// how many resident bytes were freed. The next access reads them back.
+    ///
+    /// # Safety
+    /// Same contract as [`Self::compress`].
+    pub unsafe fn evict(&self, store: &Arc<dyn PageStore>) -> std::io::Result<usize> {
+        let buffer = unsafe { &mut *self.inner_data.get() };
+        let freed = match buffer {
+            PageBuffer::Raw(bytes) => {
+                store.store_page(self.id, &bytes.0)?;
+                DATA_LENGTH
+            }
+            PageBuffer::Compressed(image) => {
+                store.store_page(self.id, &PageBuffer::<DATA_LENGTH>::decompress(image).0)?;
+                image.len()
+            }
+            PageBuffer::Evicted { .. } | PageBuffer::Released => return Ok(0),
+        };
+        let _guard = self.thaw_lock.lock();
+        *buffer = PageBuffer::Evicted {
+            store: store.clone(),
+            page_id: self.id,
+        };
+        self.cold.store(true, Ordering::Release);
+        Ok(freed)
+    }
+
+    /// Drops the bytes of an empty page, returning how many resident bytes
+    /// were freed. The page reads as zeroed until it is written again.
+    ///
+    /// # Safety
+    /// Same contract as [`Self::compress`].
+    pub unsafe fn release(&self) -> usize {
+        let buffer = unsafe { &mut *self.inner_data.get() };
+        let freed = match buffer {
+            PageBuffer::Raw(_) => DATA_LENGTH,
+            PageBuffer::Compressed(image) => image.len(),
+            PageBuffer::Evicted { .. } | PageBuffer::Released => 0,
+        };
+        let _guard = self.thaw_lock.lock();
+        *buffer = PageBuffer::Released;
+        self.reset();
+        self.cold.store(true, Ordering::Release);
+        freed
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.cold.load(Ordering::Acquire)
+            && self.with_buffer(|buffer| buffer.is_some_and(|b| matches!(b, PageBuffer::Compressed(_))))
+    }
+
+    pub fn is_evicted(&self) -> bool {
+        self.cold.load(Ordering::Acquire)
+            && self.with_buffer(|buffer| buffer.is_some_and(|b| matches!(b, PageBuffer::Evicted { .. })))
+    }
+
+    /// [`ACCESS_CLOCK`] value at the last access of the page.
+    pub fn last_access(&self) -> u64 {
+        self.last_access.load(Ordering::Relaxed)
+    }
+
+    /// Starts a new period of page accesses, so pages accessed from now on
+    /// count as more recently used than every page accessed before.
+    pub fn advance_access_clock() {
+        ACCESS_CLOCK.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Size of the page bytes as currently held in memory.
+    pub fn resident_size(&self) -> usize {
+        if !self.cold.load(Ordering::Acquire) {
+            return DATA_LENGTH;
+        }
+        self.with_buffer(|buffer| match buffer {
+            None | Some(PageBuffer::Raw(_)) => DATA_LENGTH,
+            Some(PageBuffer::Compressed(image)) => image.len(),
+            Some(PageBuffer::Evicted { .. } | PageBuffer::Released) => 0,
+        })
+    }
+
+    /// Runs `op` over the buffer of a cold page under the thaw lock, or over
+    /// `None` if the page is raw.
+    fn with_buffer<Res>(&self, op: impl FnOnce(Option<&PageBuffer<DATA_LENGTH>>) -> Res) -> Res {
+        let _guard = self.thaw_lock.lock();
+        match unsafe { &*self.inner_data.get() } {
+            PageBuffer::Raw(_) => op(None),
+            buffer => op(Some(buffer)),
+        }
+    }
+
+    fn touch(&self) {
+        let now = ACCESS_CLOCK.load(Ordering::Relaxed);
+        if self.last_access.load(Ordering::Relaxed) != now {
+            self.last_access.store(now, Ordering::Relaxed);
+        }
+    }
+
+    /// Runs `op` over the page bytes. A compressed page is decompressed into
+    /// a scratch copy and stays compressed, an evicted page is read back.
+    fn with_image<Res>(&self, op: impl FnOnce(&AlignedBytes<DATA_LENGTH>) -> Res) -> Result<Res, ExecutionError> {
+        self.touch();
+        if !self.cold.load(Ordering::Acquire) {
+            return Ok(op(unsafe { &*self.bytes_mut()? }));
+        }
+        let _guard = self.thaw_lock.lock();
+        let buffer = unsafe { &mut *self.inner_data.get() };
+        if let PageBuffer::Evicted { store, page_id } = buffer {
+            let bytes = PageBuffer::<DATA_LENGTH>::load(store.as_ref(), *page_id).map_err(ExecutionError::page_load)?;
+            *buffer = PageBuffer::Raw(bytes);
+            self.cold.store(false, Ordering::Release);
+        }
+        Ok(match buffer {
+            PageBuffer::Raw(bytes) => op(bytes),
+            PageBuffer::Compressed(image) => op(&PageBuffer::<DATA_LENGTH>::decompress(image)),
+            PageBuffer::Released => op(&AlignedBytes([0; DATA_LENGTH])),
+            PageBuffer::Evicted { .. } => unreachable!("evicted pages are read back above"),
+        })
+    }
+
+    /// Returns the raw page bytes, making a cold page raw for good. Fails if
+    /// an evicted page cannot be read back.
+    ///
+    /// # Safety
+    /// The caller must follow the aliasing rules of the method it serves:
+    /// only writers with exclusive access to the affected bytes may mutate
+    /// them.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn bytes_mut(&self) -> Result<&mut AlignedBytes<DATA_LENGTH>, ExecutionError> {
+        self.touch();
+        if self.cold.load(Ordering::Acquire) {
+            let _guard = self.thaw_lock.lock();
+            let buffer = unsafe { &mut *self.inner_data.get() };
+            if !matches!(buffer, PageBuffer::Raw(_)) {
+                *buffer = PageBuffer::Raw(buffer.materialize().map_err(ExecutionError::page_load)?);
+                self.cold.store(false, Ordering::Release);
+            }
+        }
+        match unsafe { &mut *self.inner_data.get() } {
+            PageBuffer::Raw(bytes) => Ok(bytes),
+            _ => unreachable!("cold pages are made raw above"),
+        }
     }
 
     pub fn free_space(&self) -> usize {
@@ -336,17 +679,31 @@ pub enum ExecutionError {
 
     /// Link provided for saving `Row` is invalid.
     InvalidLink,
+
+    /// Error of reading an evicted [`Data`] page back from its [`PageStore`].
+    #[display("failed to read page back: {_0}")]
+    PageLoad(#[error(not(source))] std::io::ErrorKind),
+}
+
+impl ExecutionError {
+    fn page_load(e: std::io::Error) -> Self {
+        Self::PageLoad(e.kind())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::io;
     use std::sync::atomic::Ordering;
     use std::sync::{Arc, mpsc};
     use std::thread;
 
+    use data_bucket::page::PageId;
+
     use rkyv::{Archive, Deserialize, Serialize};
 
     use crate::in_memory::DATA_INNER_LENGTH;
+    use crate::in_memory::budget::PageStore;
     use crate::in_memory::data::{Data, ExecutionError, INNER_PAGE_SIZE};
     use crate::prelude::Link;
 
@@ -381,7 +738,7 @@ mod tests {
         assert_eq!(page.free_offset.load(Ordering::Relaxed), link.length);
         assert_eq!(page.free_space(), initial_free - link.length as usize);
 
-        let inner_data = unsafe { &mut *page.inner_data.get() };
+        let inner_data = unsafe { page.bytes_mut() }.unwrap();
         let bytes = &inner_data[link.offset as usize..link.length as usize];
         let archived = unsafe { rkyv::access_unchecked::<ArchivedTestRow>(bytes) };
         assert_eq!(archived, &row)
@@ -399,7 +756,7 @@ mod tests {
 
         assert_eq!(res, link);
 
-        let inner_data = unsafe { &mut *page.inner_data.get() };
+        let inner_data = unsafe { page.bytes_mut() }.unwrap();
         let bytes = &inner_data[link.offset as usize..link.length as usize];
         let archived = unsafe { rkyv::access_unchecked::<ArchivedTestRow>(bytes) };
         assert_eq!(archived, &new_row)
@@ -476,7 +833,7 @@ mod tests {
 
         assert_eq!(page.free_space(), initial_free - total_used);
 
-        let inner_data = unsafe { &mut *page.inner_data.get() };
+        let inner_data = unsafe { page.bytes_mut() }.unwrap();
 
         for (i, link) in links.into_iter().enumerate() {
             let link = link.unwrap();
@@ -695,4 +1052,34 @@ mod tests {
         let retrieved = page.get_row(link3).unwrap();
         assert_eq!(retrieved, row3);
     }
+
+    #[derive(Debug)]
+    struct LostPages;
+
+    impl PageStore for LostPages {
+        fn store_page(&self, _: PageId, _: &[u8]) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn load_page(&self, _: PageId, _: &mut [u8]) -> io::Result<()> {
+            Err(io::ErrorKind::UnexpectedEof.into())
+        }
+    }
+
+    #[test]
+    fn evicted_page_that_cannot_be_read_back_is_an_error() {
+        let page = Data::<TestRow>::new(1.into());
+        let link = page.save_row(&TestRow { a: 10, b: 20 }).unwrap();
+        let store: Arc<dyn PageStore> = Arc::new(LostPages);
+        unsafe { page.evict(&store) }.unwrap();
+
+        let expected = ExecutionError::PageLoad(io::ErrorKind::UnexpectedEof);
+        assert_eq!(page.get_row(link), Err(expected));
+        assert_eq!(page.get_bytes().err(), Some(expected));
+        assert_eq!(
+            unsafe { page.save_row_by_link(&TestRow { a: 1, b: 2 }, link) },
+            Err(expected)
+        );
+        assert!(page.is_evicted());
+    }
 }
@@ -185,6 +185,15 @@ impl<const DATA_LENGTH: usize> EmptyLinkRegistry<DATA_LENGTH> {
         self.index_ord_links.iter().map(|l| l.0)
     }
 
+    /// Removes every registered link.
+    pub fn clear(&self) {
+        let _g = self.op_lock.lock();
+        let links = self.iter().collect::<Vec<_>>();
+        for l in links {
+            self.remove_link(l);
+        }
+    }
+
     pub fn get_empty_links_size_bytes(&self) -> u32 {
         self.sum_links_len.load(Ordering::Acquire)
     }
@@ -192,6 +201,11 @@ impl<const DATA_LENGTH: usize> EmptyLinkRegistry<DATA_LENGTH> {
     pub async fn lock_vacuum(&self) -> tokio::sync::MutexGuard<'_, ()> {
         self.vacuum_lock.lock().await
     }
+
+    /// Takes the vacuum lock if no vacuum holds it.
+    pub fn try_lock_vacuum(&self) -> Option<tokio::sync::MutexGuard<'_, ()>> {
+        self.vacuum_lock.try_lock().ok()
+    }
 }
 
 #[cfg(test)]
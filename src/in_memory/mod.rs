@@ -1,10 +1,16 @@
+mod budget;
+mod compression;
 mod data;
 mod empty_link_registry;
+mod overflow;
 mod pages;
 mod publication;
 mod row;
 
+pub use budget::{MemoryBudget, MemoryLimitPolicy, PageStore, SpillFile};
+pub use compression::{COMPRESSED_PAGE_FLAG, PageCompression, decompress_page, disk_page_bytes, disk_page_image};
 pub use data::{DATA_INNER_LENGTH, Data, ExecutionError as DataExecutionError};
 pub use empty_link_registry::EmptyLinkRegistry;
+pub use overflow::{is_overflow_link, overflow_segments};
 pub use pages::{DataPages, ExecutionError as PagesExecutionError, ReadGuard as DataPagesReadGuard};
 pub use row::{ArchivedRowWrapper, PublicationSafe, Query, RowWrapper, StorableRow};
@@ -0,0 +1,71 @@
+//! Links of rows that do not fit into a single [`Data`] page.
+//!
+//! A row whose serialized size exceeds the page length is stored in a chain
+//! of consecutive pages allocated for it alone. Its link points to offset `0`
+//! of the first page and carries the full row length, so any link longer than
+//! a page is an overflow link. Every page of the chain holds the next
+//! `DATA_LENGTH` bytes of the row from its start, the last one the remainder.
+//!
+//! [`Data`]: crate::in_memory::Data
+
+use data_bucket::Link;
+
+/// Returns `true` if `link` spans a chain of overflow pages.
+pub fn is_overflow_link<const DATA_LENGTH: usize>(link: Link) -> bool {
+    link.length as usize > DATA_LENGTH
+}
+
+/// Splits `link` into one link per page it covers. A link that fits into one
+/// page is returned as is.
+pub fn overflow_segments<const DATA_LENGTH: usize>(link: Link) -> impl Iterator<Item = Link> {
+    let first_page: u32 = link.page_id.into();
+    let page_count = if is_overflow_link::<DATA_LENGTH>(link) {
+        link.length.div_ceil(DATA_LENGTH as u32)
+    } else {
+        1
+    };
+    (0..page_count).map(move |i| {
+        if page_count == 1 {
+            return link;
+        }
+        let offset = i * DATA_LENGTH as u32;
+        Link {
+            page_id: (first_page + i).into(),
+            offset: 0,
+            length: (link.length - offset).min(DATA_LENGTH as u32),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use data_bucket::Link;
+
+    use super::{is_overflow_link, overflow_segments};
+
+    fn link(page_id: u32, offset: u32, length: u32) -> Link {
+        Link {
+            page_id: page_id.into(),
+            offset,
+            length,
+        }
+    }
+
+    #[test]
+    fn page_sized_links_are_one_segment() {
+        let l = link(3, 40, 60);
+        assert!(!is_overflow_link::<100>(l));
+        assert_eq!(overflow_segments::<100>(l).collect::<Vec<_>>(), vec![l]);
+        assert!(!is_overflow_link::<100>(link(3, 0, 100)));
+    }
+
+    #[test]
+    fn overflow_links_split_per_page() {
+        let l = link(3, 0, 250);
+        assert!(is_overflow_link::<100>(l));
+        assert_eq!(
+            overflow_segments::<100>(l).collect::<Vec<_>>(),
+            vec![link(3, 0, 100), link(4, 0, 100), link(5, 0, 50)]
+        );
+    }
+}
@@ -1,7 +1,8 @@
+use data_bucket::GeneralHeader;
 use data_bucket::page::PageId;
 use derive_more::{Display, Error, From};
 use parking_lot::Mutex;
-use parking_lot::RwLock;
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 #[cfg(feature = "perf_measurements")]
 use performance_measurement_codegen::performance_measurement;
 use rkyv::{
@@ -11,7 +12,7 @@ use rkyv::{
     ser::{Serializer, allocator::ArenaHandle, sharing::Share},
     util::AlignedVec,
 };
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use std::hash::{BuildHasherDefault, Hasher};
 use std::marker::PhantomData;
 use std::sync::atomic::AtomicUsize;
@@ -27,7 +28,8 @@ use crate::prelude::ArchivedRowWrapper;
 use crate::util::OffsetEqLink;
 use crate::{
     in_memory::{
-        DATA_INNER_LENGTH, Data, DataExecutionError,
+        COMPRESSED_PAGE_FLAG, DATA_INNER_LENGTH, Data, DataExecutionError, MemoryBudget, MemoryLimitPolicy,
+        PageCompression, PageStore, is_overflow_link, overflow_segments,
         row::{RowWrapper, StorableRow},
     },
     prelude::Link,
@@ -38,6 +40,7 @@ fn page_id_mapper(page_id: usize) -> usize {
 }
 
 const PUBLICATION_SHARD_COUNT: usize = 64;
+const PAGE_STRIPE_COUNT: usize = 64;
 const RETIREMENT_BACKLOG_WARN_AT: usize = 1_024;
 
 fn mix_publication_offset(mut value: u64) -> u64 {
@@ -103,6 +106,84 @@ fn queue_retirement<T>(queue: &Mutex<Vec<T>>, pending_retirements: &AtomicUsize,
     }
 }
 
+/// Mutable page image barrier, striped by page id.
+///
+/// Accesses to one page take that page's stripe, so writes to rows on
+/// different pages proceed in parallel. Operations that span two pages take
+/// their stripes in ascending stripe order; whole-storage operations take every
+/// stripe in that order.
+#[derive(Debug)]
+struct PageBarrier {
+    stripes: [RwLock<()>; PAGE_STRIPE_COUNT],
+}
+
+impl Default for PageBarrier {
+    fn default() -> Self {
+        Self {
+            stripes: std::array::from_fn(|_| RwLock::new(())),
+        }
+    }
+}
+
+impl PageBarrier {
+    fn stripe(page_id: PageId) -> usize {
+        u32::from(page_id) as usize % PAGE_STRIPE_COUNT
+    }
+
+    fn read(&self, page_id: PageId) -> RwLockReadGuard<'_, ()> {
+        self.stripes[Self::stripe(page_id)].read()
+    }
+
+    fn write(&self, page_id: PageId) -> RwLockWriteGuard<'_, ()> {
+        self.stripes[Self::stripe(page_id)].write()
+    }
+
+    /// Takes the stripes of two pages in ascending stripe order, once if both
+    /// pages share a stripe.
+    fn write_pair(&self, first: PageId, second: PageId) -> Vec<RwLockWriteGuard<'_, ()>> {
+        let (first, second) = (Self::stripe(first), Self::stripe(second));
+        let (low, high) = (first.min(second), first.max(second));
+        let mut guards = vec![self.stripes[low].write()];
+        if high != low {
+            guards.push(self.stripes[high].write());
+        }
+        guards
+    }
+
+    /// Distinct stripes of the pages a link covers, in ascending order.
+    fn link_stripes<const DATA_LENGTH: usize>(link: Link) -> BTreeSet<usize> {
+        overflow_segments::<DATA_LENGTH>(link)
+            .map(|segment| Self::stripe(segment.page_id))
+            .collect()
+    }
+
+    /// Takes the stripes of every page a link covers for reading, in
+    /// ascending stripe order.
+    fn read_link<const DATA_LENGTH: usize>(&self, link: Link) -> Vec<RwLockReadGuard<'_, ()>> {
+        Self::link_stripes::<DATA_LENGTH>(link)
+            .into_iter()
+            .map(|stripe| self.stripes[stripe].read())
+            .collect()
+    }
+
+    /// Takes the stripes of every page a link covers for writing, in
+    /// ascending stripe order.
+    fn write_link<const DATA_LENGTH: usize>(&self, link: Link) -> Vec<RwLockWriteGuard<'_, ()>> {
+        Self::link_stripes::<DATA_LENGTH>(link)
+            .into_iter()
+            .map(|stripe| self.stripes[stripe].write())
+            .collect()
+    }
+
+    fn read_all(&self) -> Vec<RwLockReadGuard<'_, ()>> {
+        self.stripes.iter().map(|stripe| stripe.read()).collect()
+    }
+
+    fn write_all(&self) -> Vec<RwLockWriteGuard<'_, ()>> {
+        self.stripes.iter().map(|stripe| stripe.write()).collect()
+    }
+}
+
 pub struct ReadGuard<'a> {
     active_readers: &'a AtomicU64,
     marker: PhantomData<&'a ()>,
@@ -125,7 +206,9 @@ impl Drop for ReadGuard<'_> {
 /// link.
 ///
 /// Locks are acquired in this order when more than one is needed:
-/// `page_access` -> `pages` -> one `published_rows` shard. Reclamation holds the
+/// `page_access` stripes in ascending order -> `pages` -> one `published_rows`
+/// shard. Only page list changes take `pages` exclusively, so they are the one
+/// coarse step on the insert and allocation paths. Reclamation holds the
 /// retirement queues, then briefly acquires individual publication shards and
 /// the empty-link/page registries. Callers must not invoke reclamation while
 /// retaining a retirement-queue guard.
@@ -139,8 +222,9 @@ where
     published_rows: PublicationShards<Row, DATA_LENGTH>,
 
     /// Protects the mutable page image used by writers, vacuum, and
-    /// persistence. Application reads use `published_rows` after hydration.
-    page_access: RwLock<()>,
+    /// persistence, one stripe per group of pages. Application reads use
+    /// `published_rows` after hydration.
+    page_access: PageBarrier,
 
     /// Read-side grace period protecting the interval from index lookup until
     /// an immutable row version has been acquired.
@@ -166,9 +250,79 @@ where
     /// Count of saved rows.
     row_count: AtomicU64,
 
+    /// Count of row writes, see [`DataPages::write_count`].
+    writes: AtomicU64,
+
     last_page_id: AtomicU32,
 
     current_page_id: AtomicU32,
+
+    /// Compression of pages that stop being current. Writes decompress a
+    /// compressed page, which then stays raw until the next
+    /// [`DataPages::compress_cold_pages`].
+    compression: PageCompression,
+
+    /// Where [`DataPages::evict_cold_pages`] writes pages to.
+    page_store: Option<Arc<dyn PageStore>>,
+
+    /// Pages read back from a page store since the last budget check.
+    faults: Arc<AtomicU64>,
+
+    /// Checked whenever the storage needs another page.
+    memory_budget: RwLock<Option<PageBudget>>,
+}
+
+/// [`MemoryBudget`] of a [`DataPages`] with the measure of the indexes of its
+/// table.
+struct PageBudget {
+    budget: MemoryBudget,
+    index_bytes: Box<dyn Fn() -> u64 + Send + Sync>,
+    /// Page count and index bytes at the last index measurement. Measuring
+    /// walks every index, so in between the sample is scaled by the page
+    /// count. It is repeated once the storage grew by an eighth, and before
+    /// growth is refused.
+    index_sample: Mutex<(usize, u64)>,
+}
+
+impl Debug for PageBudget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PageBudget")
+            .field("budget", &self.budget)
+            .field("index_sample", &self.index_sample)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PageBudget {
+    fn index_bytes(&self, page_count: usize, fresh: bool) -> u64 {
+        let mut sample = self.index_sample.lock();
+        let (sampled_pages, bytes) = *sample;
+        if sampled_pages == page_count || (!fresh && page_count <= sampled_pages + sampled_pages / 8) {
+            return bytes * page_count as u64 / sampled_pages.max(1) as u64;
+        }
+        let bytes = (self.index_bytes)();
+        *sample = (page_count, bytes);
+        bytes
+    }
+}
+
+/// [`PageStore`] counting the pages read back through it, so reads can
+/// trim the resident pages once enough of them came back.
+#[derive(Debug)]
+struct FaultCounter {
+    store: Arc<dyn PageStore>,
+    faults: Arc<AtomicU64>,
+}
+
+impl PageStore for FaultCounter {
+    fn store_page(&self, page_id: PageId, bytes: &[u8]) -> std::io::Result<()> {
+        self.store.store_page(page_id, bytes)
+    }
+
+    fn load_page(&self, page_id: PageId, bytes: &mut [u8]) -> std::io::Result<()> {
+        self.faults.fetch_add(1, Ordering::Relaxed);
+        self.store.load_page(page_id, bytes)
+    }
 }
 
 impl<Row, const DATA_LENGTH: usize> Default for DataPages<Row, DATA_LENGTH>
@@ -201,6 +355,7 @@ where
     }
 
     fn publish_wrapped_row(&self, link: Link, wrapped: <Row as StorableRow>::WrappedRow) {
+        self.writes.fetch_add(1, Ordering::Relaxed);
         let flags = Self::publication_flags(&wrapped);
         let row = wrapped.get_inner();
         let key = OffsetEqLink(link);
@@ -232,17 +387,30 @@ where
         if let Some(slot) = self.published_slot(link) {
             return Ok(slot);
         }
+        let slot = self.hydrate_published_slot(link)?;
+        self.trim_read_back_pages();
+        Ok(slot)
+    }
 
-        let _page_access = self.page_access.read();
+    fn hydrate_published_slot(&self, link: Link) -> Result<Arc<PublishedRow<Row>>, ExecutionError>
+    where
+        <<Row as StorableRow>::WrappedRow as Archive>::Archived:
+            Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>,
+    {
+        let _page_access = self.page_access.read_link::<DATA_LENGTH>(link);
         if let Some(slot) = self.published_slot(link) {
             return Ok(slot);
         }
 
         let pages = self.pages.read();
-        let page = pages
-            .get(page_id_mapper(link.page_id.into()))
-            .ok_or(ExecutionError::PageNotFound(link.page_id))?;
-        let wrapped = page.get_row(link).map_err(ExecutionError::DataPageError)?;
+        let wrapped = if is_overflow_link::<DATA_LENGTH>(link) {
+            Self::deserialize_overflow(&Self::read_overflow_bytes(&pages, link)?)?
+        } else {
+            let page = pages
+                .get(page_id_mapper(link.page_id.into()))
+                .ok_or(ExecutionError::PageNotFound(link.page_id))?;
+            page.get_row(link).map_err(ExecutionError::DataPageError)?
+        };
         let flags = Self::publication_flags(&wrapped);
         let slot = Arc::new(PublishedRow::new(wrapped.get_inner(), flags));
         let key = OffsetEqLink(link);
@@ -250,6 +418,58 @@ where
         Ok(published_rows.entry(key).or_insert(slot).clone())
     }
 
+    /// Copies the bytes of an overflow row out of its page chain. The caller
+    /// must hold the stripes of every page of the chain.
+    fn read_overflow_bytes(
+        pages: &[Arc<Data<<Row as StorableRow>::WrappedRow, DATA_LENGTH>>],
+        link: Link,
+    ) -> Result<AlignedVec, ExecutionError> {
+        let mut bytes = AlignedVec::with_capacity(link.length as usize);
+        for segment in overflow_segments::<DATA_LENGTH>(link) {
+            let page = pages
+                .get(page_id_mapper(segment.page_id.into()))
+                .ok_or(ExecutionError::PageNotFound(segment.page_id))?;
+            bytes.extend_from_slice(&page.get_raw_row(segment).map_err(ExecutionError::DataPageError)?);
+        }
+        Ok(bytes)
+    }
+
+    /// Writes the bytes of an overflow row back into its page chain.
+    ///
+    /// # Safety
+    /// The caller must hold the write stripes of every page of the chain.
+    unsafe fn write_overflow_bytes(
+        pages: &[Arc<Data<<Row as StorableRow>::WrappedRow, DATA_LENGTH>>],
+        link: Link,
+        bytes: &[u8],
+    ) -> Result<(), ExecutionError> {
+        if bytes.len() != link.length as usize {
+            return Err(ExecutionError::DataPageError(DataExecutionError::InvalidLink));
+        }
+        let mut written = 0;
+        for segment in overflow_segments::<DATA_LENGTH>(link) {
+            let page = pages
+                .get(page_id_mapper(segment.page_id.into()))
+                .ok_or(ExecutionError::PageNotFound(segment.page_id))?;
+            let end = written + segment.length as usize;
+            unsafe { page.save_raw_row_by_link(&bytes[written..end], segment) }
+                .map_err(ExecutionError::DataPageError)?;
+            written = end;
+        }
+        Ok(())
+    }
+
+    fn deserialize_overflow(bytes: &[u8]) -> Result<<Row as StorableRow>::WrappedRow, ExecutionError>
+    where
+        <<Row as StorableRow>::WrappedRow as Archive>::Archived:
+            Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>,
+    {
+        let archived =
+            unsafe { rkyv::access_unchecked::<<<Row as StorableRow>::WrappedRow as Archive>::Archived>(bytes) };
+        rkyv::deserialize::<_, rkyv::rancor::Error>(archived)
+            .map_err(|_| ExecutionError::DataPageError(DataExecutionError::DeserializeError))
+    }
+
     pub fn read_guard(&self) -> ReadGuard<'_> {
         self.active_readers.fetch_add(1, Ordering::SeqCst);
 
@@ -281,7 +501,19 @@ where
         for link in retired_links.drain(..) {
             let key = OffsetEqLink(link);
             self.published_rows[publication_shard(&key)].write().remove(&key);
-            if !whole_pages.contains(&link.page_id) {
+            if is_overflow_link::<DATA_LENGTH>(link) {
+                // Overflow chain pages hold nothing else, so each one is
+                // freed whole for reuse or reclamation by vacuum.
+                for segment in overflow_segments::<DATA_LENGTH>(link) {
+                    if !whole_pages.contains(&segment.page_id) {
+                        self.empty_links.push(Link {
+                            page_id: segment.page_id,
+                            offset: 0,
+                            length: DATA_LENGTH as u32,
+                        });
+                    }
+                }
+            } else if !whole_pages.contains(&link.page_id) {
                 self.empty_links.push(link);
             }
         }
@@ -298,7 +530,7 @@ where
     pub fn new() -> Self {
         Self {
             published_rows: std::array::from_fn(|_| RwLock::new(PublicationMap::default())),
-            page_access: RwLock::new(()),
+            page_access: PageBarrier::default(),
             active_readers: AtomicU64::new(0),
             retired_links: Mutex::new(Vec::new()),
             retired_pages: Mutex::new(Vec::new()),
@@ -309,8 +541,13 @@ where
             empty_links: EmptyLinkRegistry::<DATA_LENGTH>::default(),
             empty_pages: Default::default(),
             row_count: AtomicU64::new(0),
+            writes: AtomicU64::new(0),
             last_page_id: AtomicU32::new(1),
             current_page_id: AtomicU32::new(1),
+            compression: PageCompression::None,
+            page_store: None,
+            faults: Arc::default(),
+            memory_budget: RwLock::new(None),
         }
     }
 
@@ -322,7 +559,7 @@ where
             let last_page_id = vec.len();
             Self {
                 published_rows: std::array::from_fn(|_| RwLock::new(PublicationMap::default())),
-                page_access: RwLock::new(()),
+                page_access: PageBarrier::default(),
                 active_readers: AtomicU64::new(0),
                 retired_links: Mutex::new(Vec::new()),
                 retired_pages: Mutex::new(Vec::new()),
@@ -332,10 +569,140 @@ where
                 empty_links: EmptyLinkRegistry::default(),
                 empty_pages: Default::default(),
                 row_count: AtomicU64::new(0),
+                writes: AtomicU64::new(0),
                 last_page_id: AtomicU32::new(last_page_id as u32),
                 current_page_id: AtomicU32::new(last_page_id as u32),
+                compression: PageCompression::None,
+                page_store: None,
+                faults: Arc::default(),
+                memory_budget: RwLock::new(None),
+            }
+        }
+    }
+
+    /// Creates storage of pages still in `store`, each read back on its
+    /// first access. `headers` are the headers of the persisted pages, in
+    /// page order.
+    pub fn lazy(headers: &[GeneralHeader], store: Arc<dyn PageStore>) -> Self {
+        let faults = Arc::new(AtomicU64::new(0));
+        let store: Arc<dyn PageStore> = Arc::new(FaultCounter {
+            store,
+            faults: faults.clone(),
+        });
+        let data = headers
+            .iter()
+            .zip(1u32..)
+            .map(|(header, page_id)| {
+                Arc::new(Data::lazy(
+                    page_id.into(),
+                    header.data_length & !COMPRESSED_PAGE_FLAG,
+                    store.clone(),
+                ))
+            })
+            .collect();
+        let mut pages = Self::from_data(data);
+        pages.faults = faults;
+        pages
+    }
+
+    pub fn with_compression(mut self, compression: PageCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    pub fn compression(&self) -> PageCompression {
+        self.compression
+    }
+
+    /// Sets where [`Self::evict_cold_pages`] writes pages to.
+    pub fn with_page_store(mut self, store: Arc<dyn PageStore>) -> Self {
+        self.page_store = Some(Arc::new(FaultCounter {
+            store,
+            faults: self.faults.clone(),
+        }));
+        self
+    }
+
+    /// Sets the budget checked whenever the storage needs another page.
+    /// `index_bytes` measures the indexes of the table, which count against
+    /// the budget together with [`Self::resident_bytes`].
+    pub fn set_memory_budget(
+        &self,
+        budget: Option<MemoryBudget>,
+        index_bytes: impl Fn() -> u64 + Send + Sync + 'static,
+    ) {
+        *self.memory_budget.write() = budget.map(|budget| PageBudget {
+            budget,
+            index_bytes: Box::new(index_bytes),
+            index_sample: Mutex::new((0, 0)),
+        });
+    }
+
+    pub fn memory_budget(&self) -> Option<MemoryBudget> {
+        self.memory_budget.read().as_ref().map(|b| b.budget)
+    }
+
+    /// Evicts cold pages until the storage fits its memory budget again if
+    /// the budget evicts, returning the bytes freed.
+    pub fn evict_over_budget(&self) -> u64 {
+        self.evict_over(true)
+    }
+
+    /// Evicts over the budget once the pages read back since the last check
+    /// add up to an eighth of the limit, so reads keep the recently used
+    /// pages resident without checking the budget on every read.
+    fn trim_read_back_pages(&self) {
+        let threshold = match self.memory_budget.read().as_ref() {
+            Some(b) if b.budget.policy == MemoryLimitPolicy::EvictColdPages => {
+                (b.budget.limit_bytes / 8 / DATA_LENGTH as u64).max(1)
             }
+            _ => return,
+        };
+        if self.faults.load(Ordering::Relaxed) < threshold || self.faults.swap(0, Ordering::Relaxed) < threshold {
+            return;
+        }
+        self.evict_over(false);
+    }
+
+    fn evict_over(&self, fresh: bool) -> u64 {
+        let budget = self.memory_budget.read();
+        let Some(budget) = budget
+            .as_ref()
+            .filter(|b| b.budget.policy == MemoryLimitPolicy::EvictColdPages)
+        else {
+            return 0;
+        };
+        let used = self.resident_bytes() + budget.index_bytes(self.get_page_count(), fresh);
+        match used.checked_sub(budget.budget.limit_bytes) {
+            Some(excess) if excess > 0 => self.evict_cold_pages(excess),
+            _ => 0,
+        }
+    }
+
+    /// Checks that `count` more pages fit into the memory budget, evicting
+    /// cold pages first if the budget allows it.
+    fn reserve_pages(&self, count: u32) -> Result<(), ExecutionError> {
+        let budget = self.memory_budget.read();
+        let Some(budget) = budget.as_ref() else {
+            return Ok(());
+        };
+        let limit = budget.budget.limit_bytes;
+        let needed = u64::from(count) * DATA_LENGTH as u64;
+        let page_count = self.get_page_count();
+        if self.resident_bytes() + budget.index_bytes(page_count, false) + needed <= limit {
+            return Ok(());
+        }
+        let mut used = self.resident_bytes() + budget.index_bytes(page_count, true);
+        if used + needed > limit && budget.budget.policy == MemoryLimitPolicy::EvictColdPages {
+            // Frees an eighth of the limit more than needed, so the next
+            // pages do not evict again right away.
+            self.evict_cold_pages(used + needed - limit + limit / 8);
+            used = self.resident_bytes() + budget.index_bytes(page_count, false);
+        }
+        if used + needed > limit {
+            return Err(ExecutionError::MemoryLimitExceeded);
         }
+        Ok(())
     }
 
     pub fn insert(&self, row: Row) -> Result<Link, ExecutionError>
@@ -351,7 +718,7 @@ where
         self.reclaim_retired();
 
         if let Some(link) = self.empty_links.pop_max() {
-            let _page_access = self.page_access.write();
+            let _page_access = self.page_access.write(link.page_id);
             let pages = self.pages.read();
             let current_page: usize = page_id_mapper(link.page_id.into());
             let page = &pages[current_page];
@@ -371,16 +738,23 @@ where
                     DataExecutionError::PageIsFull { .. }
                     | DataExecutionError::PageTooSmall { .. }
                     | DataExecutionError::SerializeError
-                    | DataExecutionError::DeserializeError => return Err(e.into()),
+                    | DataExecutionError::DeserializeError
+                    | DataExecutionError::PageLoad(_) => return Err(e.into()),
                 },
             }
         }
 
         loop {
+            let current_page_id = self.current_page_id.load(Ordering::Acquire);
             let (link, tried_page) = {
-                let _page_access = self.page_access.write();
+                let _page_access = self.page_access.write(current_page_id.into());
+                // Another insert may have switched pages while this one waited
+                // for the stripe. Only the current page accepts appended rows.
+                if self.current_page_id.load(Ordering::Acquire) != current_page_id {
+                    continue;
+                }
                 let pages = self.pages.read();
-                let current_page = page_id_mapper(self.current_page_id.load(Ordering::Acquire) as usize);
+                let current_page = page_id_mapper(current_page_id as usize);
                 let page = &pages[current_page];
 
                 (page.save_row(&general_row), current_page)
@@ -400,24 +774,85 @@ where
                                 // the read-side grace period completes. Reset
                                 // only after reclamation made the page
                                 // available for reuse.
-                                let _page_access = self.page_access.write();
+                                let _page_access = self.page_access.write(page_id);
                                 let pages = self.pages.read();
                                 pages[page_id_mapper(page_id.into())].reset();
                                 self.current_page_id.store(page_id.into(), Ordering::Release);
                             } else {
+                                self.reserve_pages(1)?;
                                 self.add_next_page(tried_page);
                             }
+                            self.compress_page(current_page_id.into());
                         }
                     }
-                    DataExecutionError::PageTooSmall { .. }
-                    | DataExecutionError::SerializeError
+                    DataExecutionError::PageTooSmall { .. } => return self.insert_overflow(&general_row, row),
+                    DataExecutionError::SerializeError
                     | DataExecutionError::DeserializeError
-                    | DataExecutionError::InvalidLink => return Err(e.into()),
+                    | DataExecutionError::InvalidLink
+                    | DataExecutionError::PageLoad(_) => return Err(e.into()),
                 },
             };
         }
     }
 
+    /// Stores a row that does not fit into one page in a chain of consecutive
+    /// fresh pages, see [`overflow_segments`].
+    ///
+    /// The chain pages are marked full, so they never become the current page
+    /// or take other rows until the row is deleted.
+    fn insert_overflow(&self, general_row: &<Row as StorableRow>::WrappedRow, row: Row) -> Result<Link, ExecutionError>
+    where
+        <Row as StorableRow>::WrappedRow:
+            Archive + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+    {
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(general_row)
+            .map_err(|_| ExecutionError::DataPageError(DataExecutionError::SerializeError))?;
+        let length = u32::try_from(bytes.len()).map_err(|_| {
+            ExecutionError::DataPageError(DataExecutionError::PageTooSmall {
+                need: bytes.len(),
+                allowed: u32::MAX as usize,
+            })
+        })?;
+        let page_count = length.div_ceil(DATA_LENGTH as u32);
+        self.reserve_pages(page_count)?;
+
+        let link = {
+            let mut pages = self.pages.write();
+            let first_page_id = self.last_page_id.fetch_add(page_count, Ordering::AcqRel) + 1;
+            for (i, chunk) in bytes.chunks(DATA_LENGTH).enumerate() {
+                let page = Data::new((first_page_id + i as u32).into());
+                page.save_raw_row(chunk).expect("a fresh page fits one page of bytes");
+                page.free_offset.store(DATA_LENGTH as u32, Ordering::Release);
+                // Not yet reachable by any other thread.
+                unsafe { page.compress(self.compression) };
+                pages.push(Arc::new(page));
+            }
+            Link {
+                page_id: first_page_id.into(),
+                offset: 0,
+                length,
+            }
+        };
+        self.stage_published_row(link, row);
+        self.row_count.fetch_add(1, Ordering::Relaxed);
+        Ok(link)
+    }
+
+    /// Keeps the current page out of the overflow chain of `link`.
+    ///
+    /// A loaded table appends to its last page, which is the end of a chain
+    /// when the last stored row was an overflow row. Called for every live
+    /// link while a persisted table is loaded.
+    pub fn seal_overflow_chain(&self, link: Link) {
+        if !is_overflow_link::<DATA_LENGTH>(link) {
+            return;
+        }
+        let current_page_id = self.current_page_id.load(Ordering::Acquire);
+        if overflow_segments::<DATA_LENGTH>(link).any(|segment| u32::from(segment.page_id) == current_page_id) {
+            self.add_next_page(page_id_mapper(current_page_id as usize));
+        }
+    }
+
     pub fn insert_cdc(&self, row: Row) -> Result<(Link, Vec<u8>), ExecutionError>
     where
         Row: Archive
@@ -444,6 +879,136 @@ where
         }
     }
 
+    /// Compresses `page_id` unless it is the current page.
+    fn compress_page(&self, page_id: PageId) -> bool {
+        if self.compression == PageCompression::None {
+            return false;
+        }
+        let _page_access = self.page_access.write(page_id);
+        if u32::from(page_id) == self.current_page_id.load(Ordering::Acquire) {
+            return false;
+        }
+        let pages = self.pages.read();
+        pages
+            .get(page_id_mapper(page_id.into()))
+            .is_some_and(|page| unsafe { page.compress(self.compression) })
+    }
+
+    /// Compresses every raw page except the current one, returning how many
+    /// were compressed. Pages whose image would not save a quarter of the
+    /// page stay raw.
+    pub fn compress_cold_pages(&self) -> usize {
+        if self.compression == PageCompression::None {
+            return 0;
+        }
+        let page_ids = self.pages.read().iter().map(|page| page.id).collect::<Vec<_>>();
+        page_ids
+            .into_iter()
+            .filter(|page_id| self.compress_page(*page_id))
+            .count()
+    }
+
+    /// Drops the bytes of every empty page and the spare capacity of the page
+    /// registries, returning the resident bytes freed. Empty pages stay
+    /// allocated as page ids and get fresh bytes when they are reused.
+    pub fn shrink_to_fit(&self) -> u64 {
+        self.reclaim_retired();
+        let empty_pages = self.empty_pages.read().iter().copied().collect::<Vec<_>>();
+        let mut freed = 0;
+        for page_id in empty_pages {
+            let _page_access = self.page_access.write(page_id);
+            if u32::from(page_id) == self.current_page_id.load(Ordering::Acquire)
+                || !self.empty_pages.read().contains(&page_id)
+            {
+                continue;
+            }
+            if let Some(page) = self.pages.read().get(page_id_mapper(page_id.into())) {
+                freed += unsafe { page.release() } as u64;
+            }
+        }
+        self.pages.write().shrink_to_fit();
+        self.empty_pages.write().shrink_to_fit();
+        for shard in &self.published_rows {
+            shard.write().shrink_to_fit();
+        }
+        freed
+    }
+
+    pub fn compressed_page_count(&self) -> usize {
+        self.pages.read().iter().filter(|page| page.is_compressed()).count()
+    }
+
+    /// Writes the least recently used pages except the current one to the
+    /// page store until at least `bytes` resident bytes are freed, returning
+    /// the bytes freed. Does nothing without a page store. Pages the store
+    /// refuses with `WouldBlock` are skipped.
+    ///
+    /// Row versions published for readers are dropped with their pages; the
+    /// next read of such a row reads its page back.
+    pub fn evict_cold_pages(&self, bytes: u64) -> u64 {
+        let Some(store) = &self.page_store else {
+            return 0;
+        };
+        let mut candidates = self
+            .pages
+            .read()
+            .iter()
+            .filter(|page| !page.is_evicted())
+            .map(|page| (page.last_access(), page.id))
+            .collect::<Vec<_>>();
+        candidates.sort_unstable();
+        Data::<<Row as StorableRow>::WrappedRow, DATA_LENGTH>::advance_access_clock();
+
+        let mut freed = 0;
+        let mut evicted = HashSet::new();
+        for (_, page_id) in candidates {
+            if freed >= bytes {
+                break;
+            }
+            let _page_access = self.page_access.write(page_id);
+            if u32::from(page_id) == self.current_page_id.load(Ordering::Acquire) {
+                continue;
+            }
+            let Some(page) = self.pages.read().get(page_id_mapper(page_id.into())).cloned() else {
+                continue;
+            };
+            match unsafe { page.evict(store) } {
+                Ok(0) => {}
+                Ok(page_freed) => {
+                    freed += page_freed as u64;
+                    evicted.insert(page_id);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(e) => {
+                    tracing::warn!(?page_id, error = %e, "failed to evict page");
+                    break;
+                }
+            }
+        }
+        if !evicted.is_empty() {
+            for shard in &self.published_rows {
+                shard.write().retain(|key, _| !evicted.contains(&key.0.page_id));
+            }
+        }
+        freed
+    }
+
+    /// Row writes since the storage was created, as a measure of how much
+    /// the rows changed. Storing, updating, deleting and moving a row by
+    /// vacuum are all writes.
+    pub fn write_count(&self) -> u64 {
+        self.writes.load(Ordering::Relaxed)
+    }
+
+    pub fn evicted_page_count(&self) -> usize {
+        self.pages.read().iter().filter(|page| page.is_evicted()).count()
+    }
+
+    /// Bytes the page images take in memory, see [`Data::resident_size`].
+    pub fn resident_bytes(&self) -> u64 {
+        self.pages.read().iter().map(|page| page.resident_size() as u64).sum()
+    }
+
     /// Allocates a new page or reuses a free page from `empty_pages`.
     /// Does **NOT** set the page as `current`.
     pub fn allocate_new_or_pop_free(&self) -> Arc<Data<<Row as StorableRow>::WrappedRow, DATA_LENGTH>> {
@@ -455,7 +1020,7 @@ where
         };
 
         if let Some(page_id) = page_id {
-            let _page_access = self.page_access.write();
+            let _page_access = self.page_access.write(page_id);
             let pages = self.pages.read();
             let index = page_id_mapper(page_id.into());
             let page = pages[index].clone();
@@ -516,14 +1081,31 @@ where
             + for<'a> rkyv::bytecheck::CheckBytes<rkyv::api::high::HighValidator<'a, rkyv::rancor::Error>>,
     {
         let pages = self.pages.read();
-        let page_id: usize = link.page_id.into();
-        let page_index = page_id
-            .checked_sub(1)
-            .ok_or(ExecutionError::PageNotFound(link.page_id))?;
-        let page = pages
-            .get(page_index)
-            .ok_or(ExecutionError::PageNotFound(link.page_id))?;
-        let wrapped = page.get_row_checked(link).map_err(ExecutionError::DataPageError)?;
+        let page_by_id = |page_id: PageId| {
+            usize::from(page_id)
+                .checked_sub(1)
+                .and_then(|index| pages.get(index))
+                .ok_or(ExecutionError::PageNotFound(page_id))
+        };
+        let wrapped = if is_overflow_link::<DATA_LENGTH>(link) {
+            let mut bytes = AlignedVec::<16>::with_capacity(link.length as usize);
+            for segment in overflow_segments::<DATA_LENGTH>(link) {
+                let page = page_by_id(segment.page_id)?;
+                if segment.length > page.free_offset.load(Ordering::Acquire) {
+                    return Err(ExecutionError::DataPageError(DataExecutionError::InvalidLink));
+                }
+                bytes.extend_from_slice(&page.get_raw_row(segment).map_err(ExecutionError::DataPageError)?);
+            }
+            let archived =
+                rkyv::access::<<<Row as StorableRow>::WrappedRow as Archive>::Archived, rkyv::rancor::Error>(&bytes)
+                    .map_err(|_| ExecutionError::DataPageError(DataExecutionError::DeserializeError))?;
+            rkyv::deserialize::<_, rkyv::rancor::Error>(archived)
+                .map_err(|_| ExecutionError::DataPageError(DataExecutionError::DeserializeError))?
+        } else {
+            page_by_id(link.page_id)?
+                .get_row_checked(link)
+                .map_err(ExecutionError::DataPageError)?
+        };
         if wrapped.is_ghosted() {
             return Err(ExecutionError::Ghosted);
         }
@@ -533,6 +1115,267 @@ where
         Ok(wrapped.get_inner())
     }
 
+    /// Finds the rows stored in the pages without consulting an index, so the
+    /// indexes of a persisted table can be rebuilt from `.wt.data`.
+    ///
+    /// Rows are written back to back from the start of a page, so the written
+    /// bytes between registered empty links are split into runs that each
+    /// pass checked rkyv decoding and serialize back to the same bytes. A
+    /// stored row is never ghosted, which rules out most runs that only look
+    /// like a row, and the split backtracks until the whole span is covered.
+    /// Deleted rows are not returned. A full page that starts with no row is
+    /// tried as the start of an overflow chain. A span that cannot be split
+    /// is scanned run by run, and written bytes that hold no row are returned
+    /// as unreadable links instead of ending the scan.
+    #[allow(clippy::type_complexity)]
+    pub fn scan_rows_checked(&self) -> (Vec<(Link, Row)>, Vec<Link>)
+    where
+        <Row as StorableRow>::WrappedRow:
+            Archive + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+        <<Row as StorableRow>::WrappedRow as Archive>::Archived: Portable
+            + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>
+            + for<'a> rkyv::bytecheck::CheckBytes<rkyv::api::high::HighValidator<'a, rkyv::rancor::Error>>,
+    {
+        let pages = self.pages.read();
+        let mut empty_links: HashMap<PageId, Vec<Link>> = HashMap::new();
+        for link in self.empty_links.iter() {
+            empty_links.entry(link.page_id).or_default().push(link);
+        }
+        let mut rows = vec![];
+        let mut unreadable = vec![];
+
+        let mut page_index = 0;
+        'pages: while page_index < pages.len() {
+            let page = &pages[page_index];
+            page_index += 1;
+            let written = (page.free_offset.load(Ordering::Acquire) as usize).min(DATA_LENGTH);
+            let Ok(bytes) = page.get_raw_row(Link {
+                page_id: page.id,
+                offset: 0,
+                length: written as u32,
+            }) else {
+                continue;
+            };
+            let mut aligned = AlignedVec::<16>::with_capacity(written);
+            aligned.extend_from_slice(&bytes);
+            let free = empty_links.remove(&page.id).unwrap_or_default();
+
+            let mut offset = 0;
+            while offset < written {
+                if let Some(link) = free
+                    .iter()
+                    .find(|link| link.offset as usize <= offset && offset < (link.offset + link.length) as usize)
+                {
+                    offset = (link.offset + link.length) as usize;
+                    continue;
+                }
+                let end = free
+                    .iter()
+                    .map(|link| link.offset as usize)
+                    .filter(|start| *start > offset)
+                    .fold(written, usize::min);
+                let mut span = vec![];
+                if Self::split_span(&aligned, offset, end, &mut HashSet::new(), &mut span) {
+                    for (offset, length, wrapped) in span {
+                        if !wrapped.is_deleted() {
+                            let link = Link {
+                                page_id: page.id,
+                                offset: offset as u32,
+                                length: length as u32,
+                            };
+                            rows.push((link, wrapped.get_inner()));
+                        }
+                    }
+                    offset = end;
+                    continue;
+                }
+                let found = (Self::min_row_length()..=end - offset).find_map(|length| {
+                    Self::decode_stored(&aligned[offset..offset + length]).map(|wrapped| (length, wrapped))
+                });
+                if let Some((length, wrapped)) = found {
+                    let link = Link {
+                        page_id: page.id,
+                        offset: offset as u32,
+                        length: length as u32,
+                    };
+                    if !wrapped.is_deleted() {
+                        rows.push((link, wrapped.get_inner()));
+                    }
+                    offset += length;
+                    continue;
+                }
+                if offset == 0
+                    && written == DATA_LENGTH
+                    && let Some((link, wrapped, page_count)) = Self::scan_overflow_row(&pages[page_index - 1..])
+                {
+                    if !wrapped.is_deleted() {
+                        rows.push((link, wrapped.get_inner()));
+                    }
+                    page_index += page_count - 1;
+                    continue 'pages;
+                }
+                unreadable.push(Link {
+                    page_id: page.id,
+                    offset: offset as u32,
+                    length: (end - offset) as u32,
+                });
+                offset = end;
+            }
+        }
+
+        (rows, unreadable)
+    }
+
+    /// Splits `bytes[offset..end]` into stored rows, pushing them to `rows`
+    /// in order. Offsets in `dead_ends` are known to start no split; an
+    /// all-zero remainder ends the span as well.
+    #[allow(clippy::type_complexity)]
+    fn split_span(
+        bytes: &[u8],
+        offset: usize,
+        end: usize,
+        dead_ends: &mut HashSet<usize>,
+        rows: &mut Vec<(usize, usize, <Row as StorableRow>::WrappedRow)>,
+    ) -> bool
+    where
+        <Row as StorableRow>::WrappedRow:
+            Archive + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+        <<Row as StorableRow>::WrappedRow as Archive>::Archived: Portable
+            + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>
+            + for<'a> rkyv::bytecheck::CheckBytes<rkyv::api::high::HighValidator<'a, rkyv::rancor::Error>>,
+    {
+        if offset == end {
+            return true;
+        }
+        if dead_ends.contains(&offset) {
+            return false;
+        }
+        for length in Self::min_row_length()..=end - offset {
+            let Some(wrapped) = Self::decode_stored(&bytes[offset..offset + length]) else {
+                continue;
+            };
+            rows.push((offset, length, wrapped));
+            if Self::split_span(bytes, offset + length, end, dead_ends, rows) {
+                return true;
+            }
+            rows.pop();
+        }
+        if bytes[offset..end].iter().all(|byte| *byte == 0) {
+            return true;
+        }
+        dead_ends.insert(offset);
+        false
+    }
+
+    fn min_row_length() -> usize {
+        size_of::<<<Row as StorableRow>::WrappedRow as Archive>::Archived>().max(1)
+    }
+
+    /// Decodes `bytes` as one stored row: exactly its encoding, and not
+    /// ghosted, as ghosted rows never reach the data file.
+    fn decode_stored(bytes: &[u8]) -> Option<<Row as StorableRow>::WrappedRow>
+    where
+        <Row as StorableRow>::WrappedRow:
+            Archive + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+        <<Row as StorableRow>::WrappedRow as Archive>::Archived: Portable
+            + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>
+            + for<'a> rkyv::bytecheck::CheckBytes<rkyv::api::high::HighValidator<'a, rkyv::rancor::Error>>,
+    {
+        Self::decode_exact(bytes).filter(|wrapped| !wrapped.is_ghosted())
+    }
+
+    /// Decodes `bytes` as one stored row, if they are exactly its encoding.
+    fn decode_exact(bytes: &[u8]) -> Option<<Row as StorableRow>::WrappedRow>
+    where
+        <Row as StorableRow>::WrappedRow:
+            Archive + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+        <<Row as StorableRow>::WrappedRow as Archive>::Archived: Portable
+            + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>
+            + for<'a> rkyv::bytecheck::CheckBytes<rkyv::api::high::HighValidator<'a, rkyv::rancor::Error>>,
+    {
+        let archived =
+            rkyv::access::<<<Row as StorableRow>::WrappedRow as Archive>::Archived, rkyv::rancor::Error>(bytes).ok()?;
+        let wrapped = rkyv::deserialize::<_, rkyv::rancor::Error>(archived).ok()?;
+        let encoded = rkyv::to_bytes::<rkyv::rancor::Error>(&wrapped).ok()?;
+        (encoded.as_slice() == bytes).then_some(wrapped)
+    }
+
+    /// Looks for an overflow row starting at the first of `pages`. Every page
+    /// of a chain but the last is full, and the last is zeroed after the
+    /// row's end. Returns the row's link, the row and the number of pages it
+    /// spans.
+    #[allow(clippy::type_complexity)]
+    fn scan_overflow_row(
+        pages: &[Arc<Data<<Row as StorableRow>::WrappedRow, DATA_LENGTH>>],
+    ) -> Option<(Link, <Row as StorableRow>::WrappedRow, usize)>
+    where
+        <Row as StorableRow>::WrappedRow:
+            Archive + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+        <<Row as StorableRow>::WrappedRow as Archive>::Archived: Portable
+            + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>
+            + for<'a> rkyv::bytecheck::CheckBytes<rkyv::api::high::HighValidator<'a, rkyv::rancor::Error>>,
+    {
+        let first_page_id = pages.first()?.id;
+        let mut bytes = AlignedVec::<16>::new();
+        for (index, page) in pages.iter().enumerate() {
+            let full = page.free_offset.load(Ordering::Acquire) as usize >= DATA_LENGTH;
+            if index == 0 && !full {
+                return None;
+            }
+            let page_bytes = page
+                .get_raw_row(Link {
+                    page_id: page.id,
+                    offset: 0,
+                    length: DATA_LENGTH as u32,
+                })
+                .ok()?;
+            bytes.extend_from_slice(&page_bytes);
+            if index == 0 {
+                continue;
+            }
+            let used = page_bytes
+                .iter()
+                .rposition(|byte| *byte != 0)
+                .map_or(1, |last| last + 1);
+            for length in index * DATA_LENGTH + used..=bytes.len() {
+                if let Some(wrapped) = Self::decode_stored(&bytes[..length]) {
+                    let link = Link {
+                        page_id: first_page_id,
+                        offset: 0,
+                        length: length as u32,
+                    };
+                    return Some((link, wrapped, index + 1));
+                }
+            }
+            if !full {
+                return None;
+            }
+        }
+        None
+    }
+
+    /// Checks that `link` lies within the written part of its pages without
+    /// reading the row, for loads that leave pages on disk.
+    pub fn check_link_bounds(&self, link: Link) -> Result<(), ExecutionError> {
+        let pages = self.pages.read();
+        let segments: Vec<Link> = if is_overflow_link::<DATA_LENGTH>(link) {
+            overflow_segments::<DATA_LENGTH>(link).collect()
+        } else {
+            vec![link]
+        };
+        for segment in segments {
+            let page = usize::from(segment.page_id)
+                .checked_sub(1)
+                .and_then(|index| pages.get(index))
+                .ok_or(ExecutionError::PageNotFound(segment.page_id))?;
+            let end = u64::from(segment.offset) + u64::from(segment.length);
+            if end > u64::from(page.free_offset.load(Ordering::Acquire)) {
+                return Err(ExecutionError::DataPageError(DataExecutionError::InvalidLink));
+            }
+        }
+        Ok(())
+    }
+
     pub fn select_non_vacuumed(&self, link: Link) -> Result<Row, ExecutionError>
     where
         Row: Archive
@@ -561,7 +1404,15 @@ where
         Row: Archive + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
         Op: Fn(&<<Row as StorableRow>::WrappedRow as Archive>::Archived) -> Res,
     {
-        let _page_access = self.page_access.read();
+        if is_overflow_link::<DATA_LENGTH>(link) {
+            let _page_access = self.page_access.read_link::<DATA_LENGTH>(link);
+            let bytes = Self::read_overflow_bytes(&self.pages.read(), link)?;
+            let gen_row =
+                unsafe { rkyv::access_unchecked::<<<Row as StorableRow>::WrappedRow as Archive>::Archived>(&bytes) };
+            return Ok(op(gen_row));
+        }
+
+        let _page_access = self.page_access.read(link.page_id);
         let pages = self.pages.read();
         let page = pages
             .get::<usize>(page_id_mapper(link.page_id.into()))
@@ -571,6 +1422,55 @@ where
         Ok(res)
     }
 
+    /// Runs `op` over the archived row at `link` without deserializing it.
+    ///
+    /// Ghosted and deleted rows are rejected like in
+    /// [`Self::select_non_ghosted`]. Page bytes and the publication change
+    /// together under the page's write stripe, so the bytes seen under the read
+    /// stripe are the published version. A compressed page stays compressed.
+    ///
+    /// `op` runs without the page table lock, so a slow closure does not stall
+    /// page allocation, but the page's read stripe is still held: `op` must not
+    /// call back into the table.
+    #[cfg_attr(feature = "perf_measurements", performance_measurement(prefix_name = "DataPages"))]
+    pub fn with_non_ghosted_ref<Op, Res>(&self, link: Link, op: Op) -> Result<Res, ExecutionError>
+    where
+        <<Row as StorableRow>::WrappedRow as Archive>::Archived: ArchivedRowWrapper,
+        Op: FnOnce(&<<Row as StorableRow>::WrappedRow as Archive>::Archived) -> Res,
+    {
+        let check = |row: &<<Row as StorableRow>::WrappedRow as Archive>::Archived| {
+            if row.is_ghosted() {
+                return Err(ExecutionError::Ghosted);
+            }
+            if row.is_deleted() {
+                return Err(ExecutionError::Deleted);
+            }
+            Ok(())
+        };
+
+        let res = if is_overflow_link::<DATA_LENGTH>(link) {
+            let _page_access = self.page_access.read_link::<DATA_LENGTH>(link);
+            let bytes = Self::read_overflow_bytes(&self.pages.read(), link)?;
+            let gen_row =
+                unsafe { rkyv::access_unchecked::<<<Row as StorableRow>::WrappedRow as Archive>::Archived>(&bytes) };
+            check(gen_row).map(|_| op(gen_row))
+        } else {
+            let _page_access = self.page_access.read(link.page_id);
+            let page = self
+                .pages
+                .read()
+                .get(page_id_mapper(link.page_id.into()))
+                .cloned()
+                .ok_or(ExecutionError::PageNotFound(link.page_id))?;
+            match page.with_row_ref(link, |gen_row| check(gen_row).map(|_| op(gen_row))) {
+                Ok(res) => res,
+                Err(e) => Err(e.into()),
+            }
+        };
+        self.trim_read_back_pages();
+        res
+    }
+
     #[allow(clippy::missing_safety_doc)]
     #[cfg_attr(feature = "perf_measurements", performance_measurement(prefix_name = "DataPages"))]
     pub unsafe fn with_mut_ref<Op, Res>(&self, link: Link, mut op: Op) -> Result<Res, ExecutionError>
@@ -581,7 +1481,25 @@ where
             Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>,
         Op: FnMut(&mut <<Row as StorableRow>::WrappedRow as Archive>::Archived) -> Res,
     {
-        let _page_access = self.page_access.write();
+        if is_overflow_link::<DATA_LENGTH>(link) {
+            // The chain is not contiguous in memory, so the row is mutated in
+            // a copy that is written back as a whole.
+            let _page_access = self.page_access.write_link::<DATA_LENGTH>(link);
+            let pages = self.pages.read();
+            let mut bytes = Self::read_overflow_bytes(&pages, link)?;
+            let res = {
+                let gen_row = unsafe {
+                    rkyv::access_unchecked_mut::<<<Row as StorableRow>::WrappedRow as Archive>::Archived>(&mut bytes)
+                        .unseal_unchecked()
+                };
+                op(gen_row)
+            };
+            unsafe { Self::write_overflow_bytes(&pages, link, &bytes)? };
+            self.publish_wrapped_row(link, Self::deserialize_overflow(&bytes)?);
+            return Ok(res);
+        }
+
+        let _page_access = self.page_access.write(link.page_id);
         let pages = self.pages.read();
         let page = pages
             .get(page_id_mapper(link.page_id.into()))
@@ -615,7 +1533,17 @@ where
         <Row as StorableRow>::WrappedRow:
             Archive + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
     {
-        let _page_access = self.page_access.write();
+        if is_overflow_link::<DATA_LENGTH>(link) {
+            let gen_row = <Row as StorableRow>::WrappedRow::from_inner(row.clone());
+            let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&gen_row)
+                .map_err(|_| ExecutionError::DataPageError(DataExecutionError::SerializeError))?;
+            let _page_access = self.page_access.write_link::<DATA_LENGTH>(link);
+            unsafe { Self::write_overflow_bytes(&self.pages.read(), link, &bytes)? };
+            self.stage_published_row(link, row);
+            return Ok(link);
+        }
+
+        let _page_access = self.page_access.write(link.page_id);
         let pages = self.pages.read();
         let page = pages
             .get(page_id_mapper(link.page_id.into()))
@@ -663,7 +1591,22 @@ where
         <<Row as StorableRow>::WrappedRow as Archive>::Archived:
             Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>,
     {
-        let _page_access = self.page_access.write();
+        if is_overflow_link::<DATA_LENGTH>(link) {
+            let gen_row = <Row as StorableRow>::WrappedRow::from_inner(row);
+            let mut bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&gen_row)
+                .map_err(|_| ExecutionError::DataPageError(DataExecutionError::SerializeError))?;
+            unsafe {
+                rkyv::access_unchecked_mut::<<<Row as StorableRow>::WrappedRow as Archive>::Archived>(&mut bytes)
+                    .unseal_unchecked()
+                    .unghost();
+            }
+            let _page_access = self.page_access.write_link::<DATA_LENGTH>(link);
+            unsafe { Self::write_overflow_bytes(&self.pages.read(), link, &bytes)? };
+            self.publish_wrapped_row(link, Self::deserialize_overflow(&bytes)?);
+            return Ok(());
+        }
+
+        let _page_access = self.page_access.write(link.page_id);
         let pages = self.pages.read();
         let page = pages
             .get(page_id_mapper(link.page_id.into()))
@@ -710,7 +1653,12 @@ where
     }
 
     pub fn select_raw(&self, link: Link) -> Result<Vec<u8>, ExecutionError> {
-        let _page_access = self.page_access.read();
+        if is_overflow_link::<DATA_LENGTH>(link) {
+            let _page_access = self.page_access.read_link::<DATA_LENGTH>(link);
+            return Ok(Self::read_overflow_bytes(&self.pages.read(), link)?.into_vec());
+        }
+
+        let _page_access = self.page_access.read(link.page_id);
         let pages = self.pages.read();
         let page = pages
             .get(page_id_mapper(link.page_id.into()))
@@ -766,12 +1714,12 @@ where
         Some(page.clone())
     }
 
-    pub fn get_bytes(&self) -> Vec<([u8; DATA_LENGTH], u32)> {
-        let _page_access = self.page_access.read();
+    pub fn get_bytes(&self) -> Result<Vec<([u8; DATA_LENGTH], u32)>, ExecutionError> {
+        let _page_access = self.page_access.read_all();
         let pages = self.pages.read();
         pages
             .iter()
-            .map(|p| (p.get_bytes(), p.free_offset.load(Ordering::Relaxed)))
+            .map(|p| Ok((p.get_bytes()?, p.free_offset.load(Ordering::Relaxed))))
             .collect()
     }
 
@@ -797,7 +1745,7 @@ where
             + Portable
             + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>,
     {
-        let _page_access = self.page_access.write();
+        let _page_access = self.page_access.write_pair(from_link.page_id, to_page_id);
         let pages = self.pages.read();
         let from_page = pages
             .get(page_id_mapper(from_link.page_id.into()))
@@ -863,6 +1811,55 @@ where
     pub fn current_page_id(&self) -> PageId {
         self.current_page_id.load(Ordering::Acquire).into()
     }
+
+    /// Drops every row and page, leaving the storage in the state of
+    /// [`DataPages::new`].
+    ///
+    /// Fails with [`ExecutionError::Locked`] without touching anything while a
+    /// vacuum runs; the vacuum lock is held until the reset is over. `unlink`
+    /// must remove every index reference. It runs once vacuum is excluded, and
+    /// readers that resolved a link before it are waited out through the
+    /// read-side grace period before any page is dropped; immutable row
+    /// versions they already acquired stay valid. The caller must exclude all
+    /// writers.
+    pub fn truncate(&self, unlink: impl FnOnce()) -> Result<(), ExecutionError> {
+        let _vacuum = self.empty_links.try_lock_vacuum().ok_or(ExecutionError::Locked)?;
+        unlink();
+
+        let mut spins = 0u32;
+        while self.active_readers.load(Ordering::SeqCst) != 0 {
+            if spins < 16 {
+                spins += 1;
+                std::hint::spin_loop();
+            } else {
+                std::thread::yield_now();
+            }
+        }
+
+        {
+            let mut retired_links = self.retired_links.lock();
+            let mut retired_pages = self.retired_pages.lock();
+            let mut retired_publications = self.retired_publications.lock();
+            retired_links.clear();
+            retired_pages.clear();
+            retired_publications.clear();
+            self.pending_retirements.store(0, Ordering::Release);
+        }
+
+        let _page_access = self.page_access.write_all();
+        let mut pages = self.pages.write();
+        *pages = vec![Arc::new(Data::new(1.into()))];
+        for shard in &self.published_rows {
+            shard.write().clear();
+        }
+        self.empty_links.clear();
+        self.empty_pages.write().clear();
+        self.row_count.store(0, Ordering::Release);
+        self.last_page_id.store(1, Ordering::Release);
+        self.current_page_id.store(1, Ordering::Release);
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Display, Error, From, PartialEq)]
@@ -871,6 +1868,9 @@ pub enum ExecutionError {
 
     PageNotFound(#[error(not(source))] PageId),
 
+    /// Another page would exceed the [`MemoryBudget`].
+    MemoryLimitExceeded,
+
     Locked,
 
     Ghosted,
@@ -899,7 +1899,7 @@ impl ExecutionError {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use std::collections::{HashMap, HashSet};
     use std::sync::Arc;
     use std::sync::atomic::{AtomicBool, Ordering};
     use std::sync::mpsc;
@@ -913,9 +1913,12 @@ mod tests {
 
     use crate::in_memory::data::Data;
     use crate::in_memory::pages::{DataPages, ExecutionError};
-    use crate::in_memory::{DATA_INNER_LENGTH, PagesExecutionError, RowWrapper, StorableRow};
+    use crate::in_memory::{
+        DATA_INNER_LENGTH, MemoryBudget, MemoryLimitPolicy, PageStore, PagesExecutionError, RowWrapper, StorableRow,
+    };
     use crate::prelude::ArchivedRowWrapper;
     use data_bucket::Link;
+    use data_bucket::page::PageId;
 
     #[derive(Archive, Copy, Clone, Deserialize, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
     struct TestRow {
@@ -991,6 +1994,9 @@ mod tests {
         fn is_deleted(&self) -> bool {
             self.deleted
         }
+        fn is_ghosted(&self) -> bool {
+            self.is_ghosted
+        }
     }
 
     #[test]
@@ -1042,6 +2048,18 @@ mod tests {
         assert_eq!(res.err(), Some(PagesExecutionError::Ghosted))
     }
 
+    #[test]
+    fn row_ref_closure_runs_without_the_page_table_lock() {
+        let pages = DataPages::<TestRow>::new();
+        let link = pages.insert(TestRow { a: 7, b: 9 }).unwrap();
+        unsafe {
+            pages.with_mut_ref(link, |archived| archived.unghost()).unwrap();
+        }
+
+        let res = pages.with_non_ghosted_ref(link, |_| pages.pages.try_write().is_some());
+        assert_eq!(res, Ok(true));
+    }
+
     #[test]
     fn versioned_insert_stays_hidden_until_unghost() {
         let pages = DataPages::<TestRow>::new();
@@ -1096,6 +2114,63 @@ mod tests {
         assert_eq!(pages.select_non_ghosted(link), Ok(TestRow { a: 1, b: 1 }));
     }
 
+    #[test]
+    fn page_writes_only_serialize_within_page_stripe() {
+        let pages = Arc::new(DataPages::<TestRow>::new());
+        let first = pages.insert(TestRow { a: 0, b: 0 }).unwrap();
+        let other = loop {
+            let link = pages.insert(TestRow { a: 1, b: 1 }).unwrap();
+            if link.page_id != first.page_id {
+                break link;
+            }
+        };
+        let same_page = Link {
+            offset: first.offset + first.length,
+            ..first
+        };
+
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel();
+        let writer_pages = pages.clone();
+        let writer = thread::spawn(move || unsafe {
+            writer_pages
+                .with_mut_ref(first, |_| {
+                    locked_tx.send(()).unwrap();
+                    release_rx.recv().unwrap();
+                })
+                .unwrap();
+        });
+        locked_rx.recv().unwrap();
+
+        let spawn_write = |link: Link| {
+            let (done_tx, done_rx) = mpsc::channel();
+            let pages = pages.clone();
+            let handle = thread::spawn(move || {
+                let res = unsafe { pages.with_mut_ref(link, |row| row.unghost()) };
+                done_tx.send(res.is_ok()).unwrap();
+            });
+            (handle, done_rx)
+        };
+
+        let (other_writer, other_done) = spawn_write(other);
+        assert_eq!(
+            other_done.recv_timeout(Duration::from_secs(1)),
+            Ok(true),
+            "a write to another page must not wait for the stalled page"
+        );
+        let (same_writer, same_done) = spawn_write(same_page);
+        assert!(
+            same_done.recv_timeout(Duration::from_millis(100)).is_err(),
+            "a write to the stalled page must wait for its stripe"
+        );
+
+        release_tx.send(()).unwrap();
+        assert_eq!(same_done.recv_timeout(Duration::from_secs(1)), Ok(true));
+        writer.join().unwrap();
+        other_writer.join().unwrap();
+        same_writer.join().unwrap();
+    }
+
     #[test]
     fn failed_exact_length_update_preserves_page_bytes_and_publication() {
         let pages = DataPages::<TestRow>::new();
@@ -1704,4 +2779,165 @@ mod tests {
 
         assert!(pages.get_empty_links().is_empty());
     }
+
+    #[test]
+    fn overflow_row_spans_page_chain() {
+        let pages = DataPages::<TestRow, 16>::new();
+        let row = TestRow { a: 10, b: 20 };
+        let link = pages.insert(row).unwrap();
+
+        assert_eq!(link.page_id, 2.into());
+        assert_eq!(link.offset, 0);
+        assert_eq!(link.length, 24);
+        assert_eq!(pages.get_page_count(), 3);
+        assert_eq!(pages.current_page_id(), 1.into());
+        assert_eq!(pages.select(link).unwrap(), row);
+
+        unsafe {
+            pages.with_mut_ref(link, |archived| archived.unghost()).unwrap();
+            pages
+                .with_mut_ref(link, |archived| archived.inner.b = 30.into())
+                .unwrap();
+        }
+        let updated = TestRow { a: 10, b: 30 };
+        assert_eq!(pages.select_non_ghosted(link), Ok(updated));
+        assert_eq!(pages.select_non_ghosted_checked(link), Ok(updated));
+        assert_eq!(pages.with_ref(link, |archived| archived.inner.b), Ok(30.into()));
+        assert_eq!(pages.select_raw(link).unwrap().len(), 24);
+    }
+
+    #[test]
+    fn deleted_overflow_row_frees_whole_pages() {
+        let pages = DataPages::<TestRow, 16>::new();
+        let link = pages.insert(TestRow { a: 1, b: 2 }).unwrap();
+        pages.delete(link).unwrap();
+
+        let mut empty_links = pages.get_empty_links();
+        empty_links.sort_by_key(|l| u32::from(l.page_id));
+        assert_eq!(
+            empty_links,
+            vec![
+                Link {
+                    page_id: 2.into(),
+                    offset: 0,
+                    length: 16
+                },
+                Link {
+                    page_id: 3.into(),
+                    offset: 0,
+                    length: 16
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn sealing_overflow_chain_moves_current_page() {
+        let pages = DataPages::<TestRow, 16>::new();
+        let link = pages.insert(TestRow { a: 1, b: 2 }).unwrap();
+        // A reloaded table appends to its last page.
+        pages.current_page_id.store(3, Ordering::Release);
+
+        pages.seal_overflow_chain(link);
+        assert_eq!(pages.current_page_id(), 4.into());
+        pages.seal_overflow_chain(link);
+        assert_eq!(pages.current_page_id(), 4.into());
+    }
+
+    /// [`PageStore`] keeping evicted pages in memory.
+    #[derive(Debug, Default)]
+    struct MemoryPageStore(parking_lot::Mutex<HashMap<PageId, Vec<u8>>>);
+
+    impl PageStore for MemoryPageStore {
+        fn store_page(&self, page_id: PageId, bytes: &[u8]) -> std::io::Result<()> {
+            self.0.lock().insert(page_id, bytes.to_vec());
+            Ok(())
+        }
+
+        fn load_page(&self, page_id: PageId, bytes: &mut [u8]) -> std::io::Result<()> {
+            bytes.copy_from_slice(&self.0.lock()[&page_id]);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn evicted_pages_are_read_back() {
+        let pages = DataPages::<TestRow, 48>::new().with_page_store(Arc::new(MemoryPageStore::default()));
+        let links = (0..6)
+            .map(|a| pages.insert(TestRow { a, b: a * 2 }).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(pages.get_page_count(), 3);
+
+        // The current page stays resident.
+        assert_eq!(pages.evict_cold_pages(u64::MAX), 96);
+        assert_eq!(pages.evicted_page_count(), 2);
+        assert_eq!(pages.resident_bytes(), 48);
+
+        for (a, link) in (0..).zip(links) {
+            assert_eq!(pages.select(link), Ok(TestRow { a, b: a * 2 }));
+        }
+        assert_eq!(pages.evicted_page_count(), 0);
+    }
+
+    /// [`PageStore`] refusing the first page, like one whose writes have not
+    /// reached the file yet.
+    #[derive(Debug, Default)]
+    struct BehindPageStore(MemoryPageStore);
+
+    impl PageStore for BehindPageStore {
+        fn store_page(&self, page_id: PageId, bytes: &[u8]) -> std::io::Result<()> {
+            if page_id == 1.into() {
+                return Err(std::io::ErrorKind::WouldBlock.into());
+            }
+            self.0.store_page(page_id, bytes)
+        }
+
+        fn load_page(&self, page_id: PageId, bytes: &mut [u8]) -> std::io::Result<()> {
+            self.0.load_page(page_id, bytes)
+        }
+    }
+
+    #[test]
+    fn refused_pages_stay_resident() {
+        let pages = DataPages::<TestRow, 48>::new().with_page_store(Arc::new(BehindPageStore::default()));
+        let links = (0..6)
+            .map(|a| pages.insert(TestRow { a, b: a * 2 }).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(pages.evict_cold_pages(u64::MAX), 48);
+        assert_eq!(pages.evicted_page_count(), 1);
+        for (a, link) in (0..).zip(links) {
+            assert_eq!(pages.select(link), Ok(TestRow { a, b: a * 2 }));
+        }
+    }
+
+    #[test]
+    fn memory_budget_rejects_or_evicts_new_pages() {
+        let budget = |policy| {
+            Some(MemoryBudget {
+                limit_bytes: 96,
+                policy,
+            })
+        };
+
+        let pages = DataPages::<TestRow, 48>::new();
+        pages.set_memory_budget(budget(MemoryLimitPolicy::Reject), || 0);
+        for a in 0..4 {
+            pages.insert(TestRow { a, b: a }).unwrap();
+        }
+        assert_eq!(
+            pages.insert(TestRow { a: 4, b: 4 }),
+            Err(ExecutionError::MemoryLimitExceeded)
+        );
+        assert_eq!(pages.get_page_count(), 2);
+
+        let pages = DataPages::<TestRow, 48>::new().with_page_store(Arc::new(MemoryPageStore::default()));
+        pages.set_memory_budget(budget(MemoryLimitPolicy::EvictColdPages), || 0);
+        for a in 0..10 {
+            pages.insert(TestRow { a, b: a }).unwrap();
+        }
+        assert_eq!(pages.get_page_count(), 5);
+        assert!(pages.evicted_page_count() > 0);
+        assert!(pages.resident_bytes() <= 96);
+    }
 }
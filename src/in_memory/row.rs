@@ -25,6 +25,7 @@ pub trait ArchivedRowWrapper {
     fn unghost(&mut self);
     fn set_in_vacuum_process(&mut self);
     fn delete(&mut self);
+    fn is_ghosted(&self) -> bool;
     fn is_deleted(&self) -> bool;
 }
 
@@ -6,13 +6,20 @@ use std::ops::{Bound, RangeBounds};
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use arctic::{ConcurrentMap, Key, Order};
+use ordered_float::OrderedFloat;
+use psc_nanoid::PackedNanoid;
+use psc_nanoid::packed::AlphabetPackExt;
+use uuid::Uuid;
 
 use super::UniqueIndex;
+use super::art_key::{ArtKeyEncode, decode_art_word, encode_art_word};
 
 /// Lossless conversion between a WorkTable key and a native Arctic key.
 ///
 /// Keeping this trait local lets generated primary-key newtypes delegate to
 /// their underlying integer without implementing Arctic's low-level key API.
+/// Keys other than `u16..=u128` are stored as their [`ArtKeyEncode`] image in
+/// the narrowest integer that holds it.
 pub trait ArcticKey: Clone + Debug + Ord + Send + Sync + 'static {
     type Raw: ArcticRawKey;
 
@@ -62,6 +69,71 @@ macro_rules! impl_arctic_key {
 
 impl_arctic_key!(u16, u32, u64, u128);
 
+macro_rules! impl_encoded_arctic_key {
+    ($($ty:ty => $raw:ty),* $(,)?) => {
+        $(
+            impl ArcticKey for $ty {
+                type Raw = $raw;
+
+                #[inline]
+                fn to_arctic(&self) -> Self::Raw { encode_art_word(self) }
+
+                #[inline]
+                fn from_arctic(value: Self::Raw) -> Self { decode_art_word(value) }
+            }
+        )*
+    };
+}
+
+impl_encoded_arctic_key!(
+    u8 => u16,
+    usize => u64,
+    i8 => u16,
+    i16 => u16,
+    i32 => u32,
+    i64 => u64,
+    i128 => u128,
+    isize => u64,
+    OrderedFloat<f32> => u32,
+    OrderedFloat<f64> => u64,
+    Uuid => u128,
+);
+
+impl<const N: usize, const B: usize, A: AlphabetPackExt + 'static> ArcticKey for PackedNanoid<N, B, A> {
+    type Raw = u128;
+
+    #[inline]
+    fn to_arctic(&self) -> Self::Raw {
+        encode_art_word(self)
+    }
+
+    #[inline]
+    fn from_arctic(value: Self::Raw) -> Self {
+        decode_art_word(value)
+    }
+}
+
+macro_rules! impl_tuple_arctic_key {
+    ($(($($name:ident),+)),* $(,)?) => {
+        $(
+            impl<$($name),+> ArcticKey for ($($name,)+)
+            where
+                $($name: ArtKeyEncode + Clone + Debug + Ord + Send + Sync + 'static,)+
+            {
+                type Raw = u128;
+
+                #[inline]
+                fn to_arctic(&self) -> Self::Raw { encode_art_word(self) }
+
+                #[inline]
+                fn from_arctic(value: Self::Raw) -> Self { decode_art_word(value) }
+            }
+        )*
+    };
+}
+
+impl_tuple_arctic_key!((A, B), (A, B, C), (A, B, C, D));
+
 /// Arctic's lock-free adaptive radix tree with WorkTable's unique-index
 /// contract.
 ///
@@ -0,0 +1,299 @@
+//! Order-preserving key encodings for the ART backends.
+//!
+//! Congee and Arctic compare keys as native unsigned integers. A key type
+//! whose [`ArtKeyEncode`] image fits the backend's integer is stored as that
+//! image read big-endian, so the tree order matches the key's `Ord`.
+
+use ordered_float::OrderedFloat;
+use psc_nanoid::PackedNanoid;
+use psc_nanoid::packed::AlphabetPackExt;
+use uuid::Uuid;
+
+/// Widest image the ART backends can hold, Arctic's `u128`.
+const MAX_WIDTH: usize = 16;
+
+/// Fixed-width byte encoding whose lexicographic order matches `Ord`.
+///
+/// Unsigned integers are written big-endian, signed integers with their sign
+/// bit flipped and floats in the usual IEEE total-order form. Tuples and
+/// generated composite primary keys concatenate their fields, which keeps the
+/// field-by-field order because every image has a fixed width.
+pub trait ArtKeyEncode: Sized {
+    /// Number of bytes written by [`Self::encode_art`].
+    const WIDTH: usize;
+
+    /// Writes exactly [`Self::WIDTH`] bytes into `output`.
+    fn encode_art(&self, output: &mut [u8]);
+
+    /// Reads a key back from exactly [`Self::WIDTH`] bytes.
+    fn decode_art(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_unsigned_art_key_encode {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl ArtKeyEncode for $ty {
+                const WIDTH: usize = size_of::<Self>();
+
+                #[inline]
+                fn encode_art(&self, output: &mut [u8]) {
+                    output.copy_from_slice(&self.to_be_bytes());
+                }
+
+                #[inline]
+                fn decode_art(bytes: &[u8]) -> Self {
+                    Self::from_be_bytes(bytes.try_into().expect("ART key image has the encoded width"))
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned_art_key_encode!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_signed_art_key_encode {
+    ($($ty:ty => $unsigned:ty),* $(,)?) => {
+        $(
+            impl ArtKeyEncode for $ty {
+                const WIDTH: usize = size_of::<Self>();
+
+                #[inline]
+                fn encode_art(&self, output: &mut [u8]) {
+                    // Flipping the sign bit moves negative values below
+                    // positive ones without disturbing either half's order.
+                    ((*self as $unsigned) ^ !(<$unsigned>::MAX >> 1)).encode_art(output)
+                }
+
+                #[inline]
+                fn decode_art(bytes: &[u8]) -> Self {
+                    (<$unsigned>::decode_art(bytes) ^ !(<$unsigned>::MAX >> 1)) as Self
+                }
+            }
+        )*
+    };
+}
+
+impl_signed_art_key_encode!(
+    i8 => u8,
+    i16 => u16,
+    i32 => u32,
+    i64 => u64,
+    i128 => u128,
+    isize => usize,
+);
+
+macro_rules! impl_float_art_key_encode {
+    ($($ty:ty => $bits:ty),* $(,)?) => {
+        $(
+            impl ArtKeyEncode for OrderedFloat<$ty> {
+                const WIDTH: usize = size_of::<$ty>();
+
+                #[inline]
+                fn encode_art(&self, output: &mut [u8]) {
+                    // `OrderedFloat` treats every NaN as one value above
+                    // infinity and `-0.0` as `0.0`, so both are canonicalised
+                    // before the bits are taken.
+                    let value = if self.0.is_nan() {
+                        <$ty>::NAN
+                    } else if self.0 == 0.0 {
+                        0.0
+                    } else {
+                        self.0
+                    };
+                    let bits = value.to_bits();
+                    let sign = !(<$bits>::MAX >> 1);
+                    let ordered = if bits & sign == 0 { bits | sign } else { !bits };
+                    ordered.encode_art(output)
+                }
+
+                #[inline]
+                fn decode_art(bytes: &[u8]) -> Self {
+                    let ordered = <$bits>::decode_art(bytes);
+                    let sign = !(<$bits>::MAX >> 1);
+                    let bits = if ordered & sign == 0 { !ordered } else { ordered & !sign };
+                    OrderedFloat(<$ty>::from_bits(bits))
+                }
+            }
+        )*
+    };
+}
+
+impl_float_art_key_encode!(f32 => u32, f64 => u64);
+
+impl ArtKeyEncode for Uuid {
+    const WIDTH: usize = 16;
+
+    #[inline]
+    fn encode_art(&self, output: &mut [u8]) {
+        output.copy_from_slice(self.as_bytes());
+    }
+
+    #[inline]
+    fn decode_art(bytes: &[u8]) -> Self {
+        Uuid::from_slice(bytes).expect("ART key image has the encoded width")
+    }
+}
+
+impl<const N: usize, const B: usize, A: AlphabetPackExt> ArtKeyEncode for PackedNanoid<N, B, A> {
+    const WIDTH: usize = B;
+
+    #[inline]
+    fn encode_art(&self, output: &mut [u8]) {
+        output.copy_from_slice(self.as_bytes());
+    }
+
+    #[inline]
+    fn decode_art(bytes: &[u8]) -> Self {
+        let packed: [u8; B] = bytes.try_into().expect("ART key image has the encoded width");
+        // The archived form of a packed NanoID is its byte array, and
+        // deserializing it is the only public way back from bytes.
+        rkyv::deserialize::<Self, rkyv::rancor::Error>(&packed).expect("ART key image holds a packed NanoID")
+    }
+}
+
+macro_rules! impl_tuple_art_key_encode {
+    ($(($($name:ident $value:ident),+)),* $(,)?) => {
+        $(
+            impl<$($name: ArtKeyEncode),+> ArtKeyEncode for ($($name,)+) {
+                const WIDTH: usize = 0 $(+ $name::WIDTH)+;
+
+                #[inline]
+                #[allow(unused_assignments)]
+                fn encode_art(&self, output: &mut [u8]) {
+                    let ($($value,)+) = self;
+                    let mut offset = 0;
+                    $(
+                        $value.encode_art(&mut output[offset..offset + $name::WIDTH]);
+                        offset += $name::WIDTH;
+                    )+
+                }
+
+                #[inline]
+                #[allow(unused_assignments)]
+                fn decode_art(bytes: &[u8]) -> Self {
+                    let mut offset = 0;
+                    ($({
+                        let value = $name::decode_art(&bytes[offset..offset + $name::WIDTH]);
+                        offset += $name::WIDTH;
+                        value
+                    },)+)
+                }
+            }
+        )*
+    };
+}
+
+impl_tuple_art_key_encode!(
+    (A a, B b),
+    (A a, B b, C c),
+    (A a, B b, C c, D d),
+);
+
+/// Native unsigned integer an ART backend stores an encoded key as.
+#[doc(hidden)]
+pub trait ArtWord: Copy {
+    const BYTES: usize;
+
+    fn from_image(image: [u8; MAX_WIDTH]) -> Self;
+    fn into_image(self) -> [u8; MAX_WIDTH];
+}
+
+macro_rules! impl_art_word {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl ArtWord for $ty {
+                const BYTES: usize = size_of::<Self>();
+
+                #[inline]
+                fn from_image(image: [u8; MAX_WIDTH]) -> Self {
+                    Self::from_be_bytes(image[MAX_WIDTH - Self::BYTES..].try_into().expect("word fits the image"))
+                }
+
+                #[inline]
+                fn into_image(self) -> [u8; MAX_WIDTH] {
+                    let mut image = [0; MAX_WIDTH];
+                    image[MAX_WIDTH - Self::BYTES..].copy_from_slice(&self.to_be_bytes());
+                    image
+                }
+            }
+        )*
+    };
+}
+
+impl_art_word!(u16, u32, u64, u128, usize);
+
+/// Converts a key to the native integer holding its [`ArtKeyEncode`] image.
+///
+/// The image fills the low bytes of the word, so word order is key order.
+/// Keys wider than the word fail to compile.
+#[doc(hidden)]
+#[inline]
+pub fn encode_art_word<K: ArtKeyEncode, W: ArtWord>(key: &K) -> W {
+    const { assert!(K::WIDTH <= W::BYTES, "key is wider than the ART backend's native key") };
+    let mut image = [0; MAX_WIDTH];
+    key.encode_art(&mut image[MAX_WIDTH - K::WIDTH..]);
+    W::from_image(image)
+}
+
+/// Reverses [`encode_art_word`].
+#[doc(hidden)]
+#[inline]
+pub fn decode_art_word<K: ArtKeyEncode, W: ArtWord>(word: W) -> K {
+    const { assert!(K::WIDTH <= W::BYTES, "key is wider than the ART backend's native key") };
+    K::decode_art(&word.into_image()[MAX_WIDTH - K::WIDTH..])
+}
+
+#[cfg(test)]
+mod tests {
+    use ordered_float::OrderedFloat;
+
+    use super::{ArtKeyEncode, decode_art_word, encode_art_word};
+
+    fn image<K: ArtKeyEncode>(key: &K) -> Vec<u8> {
+        let mut bytes = vec![0; K::WIDTH];
+        key.encode_art(&mut bytes);
+        bytes
+    }
+
+    fn assert_order_preserved<K: ArtKeyEncode + Clone + Ord + std::fmt::Debug>(mut keys: Vec<K>) {
+        keys.sort();
+        for pair in keys.windows(2) {
+            assert_eq!(pair[0].cmp(&pair[1]), image(&pair[0]).cmp(&image(&pair[1])), "{pair:?}");
+        }
+        for key in keys {
+            assert_eq!(K::decode_art(&image(&key)), key);
+        }
+    }
+
+    #[test]
+    fn signed_integers_keep_their_order() {
+        assert_order_preserved(vec![i64::MIN, -300, -1, 0, 1, 255, i64::MAX]);
+        assert_order_preserved(vec![i8::MIN, -1, 0, i8::MAX]);
+    }
+
+    #[test]
+    fn floats_keep_the_ordered_float_order() {
+        assert_order_preserved(
+            [f64::NEG_INFINITY, -2.5, -0.0, 1e-300, 3.0, f64::INFINITY, f64::NAN]
+                .into_iter()
+                .map(OrderedFloat)
+                .collect(),
+        );
+        assert_eq!(image(&OrderedFloat(-0.0f32)), image(&OrderedFloat(0.0f32)));
+    }
+
+    #[test]
+    fn tuples_order_field_by_field() {
+        assert_order_preserved(vec![(-1i32, 7u16), (-1, 9), (0, 0), (4, u16::MAX), (5, 0)]);
+        assert_order_preserved(vec![(uuid::Uuid::nil(), -4i64, 1u8), (uuid::Uuid::max(), i64::MIN, 0)]);
+    }
+
+    #[test]
+    fn narrow_images_fill_the_low_bytes_of_a_word() {
+        assert_eq!(encode_art_word::<u32, u64>(&7), 7);
+        let word: u64 = encode_art_word(&(-1i16, 3u8));
+        assert_eq!(word, 0x007f_ff03);
+        assert_eq!(decode_art_word::<(i16, u8), u64>(word), (-1, 3));
+        assert!(encode_art_word::<i32, u32>(&-1) < encode_art_word(&0i32));
+    }
+}
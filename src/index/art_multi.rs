@@ -0,0 +1,218 @@
+//! Non-unique adapter for the Congee and Arctic WorkTable indexes.
+
+use std::array;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::RangeBounds;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use parking_lot::Mutex;
+
+use super::{ArcticIndex, CongeeIndex, UniqueIndex};
+
+const MUTATION_STRIPES: usize = 64;
+
+/// A non-unique index over a unique ART.
+///
+/// Every key is stored once, with the compact list of its links as the ART
+/// value, so a point lookup costs one native ART read. A mutation replaces the
+/// list of its key as a whole under that key's stripe; readers see either the
+/// old or the new list and never a partially edited one.
+pub struct ArtMultiIndex<I, K, V> {
+    inner: I,
+    mutation_stripes: [Mutex<()>; MUTATION_STRIPES],
+    len: AtomicUsize,
+    marker: PhantomData<fn() -> (K, V)>,
+}
+
+/// Non-unique Congee index selected by the generated DSL.
+pub type CongeeMultiIndex<K, V> = ArtMultiIndex<CongeeIndex<K, Vec<V>>, K, V>;
+
+/// Non-unique Arctic index selected by the generated DSL.
+pub type ArcticMultiIndex<K, V> = ArtMultiIndex<ArcticIndex<K, Vec<V>>, K, V>;
+
+impl<I: Debug, K, V> Debug for ArtMultiIndex<I, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArtMultiIndex")
+            .field("inner", &self.inner)
+            .field("len", &self.len.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I, K, V> Default for ArtMultiIndex<I, K, V>
+where
+    K: Clone + Ord + Hash,
+    V: Clone + PartialEq,
+    I: UniqueIndex<K, Vec<V>>,
+{
+    fn default() -> Self {
+        Self::from_inner(I::default())
+    }
+}
+
+impl<I, K, V> ArtMultiIndex<I, K, V>
+where
+    K: Clone + Ord + Hash,
+    V: Clone + PartialEq,
+    I: UniqueIndex<K, Vec<V>>,
+{
+    /// Wraps a unique ART whose values are link lists, e.g. one reconstructed
+    /// from a checkpoint.
+    pub fn from_inner(inner: I) -> Self {
+        let len = inner.iter_values().map(|(_, values)| values.len()).sum();
+        Self {
+            inner,
+            mutation_stripes: array::from_fn(|_| Mutex::new(())),
+            len: AtomicUsize::new(len),
+            marker: PhantomData,
+        }
+    }
+
+    /// Exclusively borrows the native ART.
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.inner
+    }
+
+    /// Borrows the native ART.
+    pub fn inner(&self) -> &I {
+        &self.inner
+    }
+
+    fn mutation_stripe(&self, key: &K) -> &Mutex<()> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        &self.mutation_stripes[hasher.finish() as usize % MUTATION_STRIPES]
+    }
+
+    /// Adds `value` to the values of `key`. An already present pair is left
+    /// as is and `value` is returned back.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let _stripe = self.mutation_stripe(&key).lock();
+        let mut values = self.inner.get_value(&key).unwrap_or_default();
+        if values.contains(&value) {
+            return Some(value);
+        }
+        values.push(value);
+        self.inner.insert_value(key, values);
+        self.len.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    /// Removes one `key`/`value` pair. The key itself is removed with its last
+    /// value.
+    pub fn remove(&self, key: &K, value: &V) -> Option<(K, V)> {
+        let _stripe = self.mutation_stripe(key).lock();
+        let mut values = self.inner.get_value(key)?;
+        let position = values.iter().position(|candidate| candidate == value)?;
+        let removed = values.remove(position);
+        if values.is_empty() {
+            self.inner.remove_value(key);
+        } else {
+            self.inner.insert_value(key.clone(), values);
+        }
+        self.len.fetch_sub(1, Ordering::Relaxed);
+        Some((key.clone(), removed))
+    }
+
+    /// Returns every pair of `key` in insertion order.
+    pub fn get(&self, key: &K) -> std::vec::IntoIter<(K, V)> {
+        self.inner
+            .with_value(key, |values| {
+                values
+                    .iter()
+                    .map(|value| (key.clone(), value.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+            .into_iter()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.inner.contains_key(key)
+    }
+
+    pub fn range<'a, R>(&'a self, range: R) -> impl DoubleEndedIterator<Item = (K, V)> + 'a
+    where
+        R: RangeBounds<K> + 'a,
+    {
+        self.inner
+            .range_values(range)
+            .flat_map(|(key, values)| values.into_iter().map(move |value| (key.clone(), value)))
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (K, V)> + '_ {
+        self.inner
+            .iter_values()
+            .flat_map(|(key, values)| values.into_iter().map(move |value| (key.clone(), value)))
+    }
+
+    /// Number of pairs.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of distinct keys.
+    pub fn key_count(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Barrier};
+
+    use super::{ArcticMultiIndex, CongeeMultiIndex};
+
+    #[test]
+    fn keeps_every_value_of_a_key() {
+        let index = CongeeMultiIndex::<u64, u64>::default();
+        assert_eq!(index.insert(1, 10), None);
+        assert_eq!(index.insert(1, 11), None);
+        assert_eq!(index.insert(1, 10), Some(10));
+        assert_eq!(index.insert(2, 20), None);
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.key_count(), 2);
+        assert_eq!(index.get(&1).collect::<Vec<_>>(), vec![(1, 10), (1, 11)]);
+        assert_eq!(index.range(1..=2).collect::<Vec<_>>(), vec![(1, 10), (1, 11), (2, 20)]);
+
+        assert_eq!(index.remove(&1, &10), Some((1, 10)));
+        assert_eq!(index.remove(&1, &10), None);
+        assert_eq!(index.remove(&1, &11), Some((1, 11)));
+        assert!(!index.contains_key(&1));
+        assert_eq!(index.iter().collect::<Vec<_>>(), vec![(2, 20)]);
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn concurrent_inserts_to_one_key_are_all_kept() {
+        let index = Arc::new(ArcticMultiIndex::<u64, u64>::default());
+        let barrier = Arc::new(Barrier::new(8));
+        let threads = (0..8)
+            .map(|thread| {
+                let index = Arc::clone(&index);
+                let barrier = Arc::clone(&barrier);
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    for value in 0..100 {
+                        index.insert(7, thread * 100 + value);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(index.len(), 800);
+        let mut values = index.get(&7).map(|(_, value)| value).collect::<Vec<_>>();
+        values.sort();
+        assert_eq!(values, (0..800).collect::<Vec<_>>());
+    }
+}
@@ -6,17 +6,20 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use congee::{CongeeRaw, DefaultAllocator};
+use ordered_float::OrderedFloat;
 use parking_lot::Mutex;
 
 use super::UniqueIndex;
+use super::art_key::{ArtKeyEncode, decode_art_word, encode_art_word};
 
 const INITIAL_RANGE_CAPACITY: usize = 64;
 
 /// Lossless conversion between a WorkTable key and Congee's machine-word key.
 ///
-/// Congee 0.4 stores keys as one `usize`, so this backend intentionally accepts
-/// only integer keys that fit without truncation. NanoID and composite keys
-/// should use WorkTablesIndex, IndexSet, or Arctic.
+/// Congee 0.4 stores keys as one `usize`. Unsigned integers are stored as
+/// themselves; signed, float and tuple keys store their [`ArtKeyEncode`]
+/// image, which must fit the word. Wider keys such as UUIDs and NanoIDs
+/// should use Arctic.
 pub trait CongeeKey: Copy + Debug + Ord + Send + Sync + 'static {
     fn into_congee(self) -> usize;
     fn from_congee(value: usize) -> Self;
@@ -41,6 +44,44 @@ impl_congee_key!(u8, u16, u32, usize);
 #[cfg(target_pointer_width = "64")]
 impl_congee_key!(u64);
 
+macro_rules! impl_encoded_congee_key {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl CongeeKey for $ty {
+                #[inline]
+                fn into_congee(self) -> usize { encode_art_word(&self) }
+
+                #[inline]
+                fn from_congee(value: usize) -> Self { decode_art_word(value) }
+            }
+        )*
+    };
+}
+
+impl_encoded_congee_key!(i8, i16, i32, isize, OrderedFloat<f32>);
+
+#[cfg(target_pointer_width = "64")]
+impl_encoded_congee_key!(i64, OrderedFloat<f64>);
+
+macro_rules! impl_tuple_congee_key {
+    ($(($($name:ident),+)),* $(,)?) => {
+        $(
+            impl<$($name),+> CongeeKey for ($($name,)+)
+            where
+                $($name: ArtKeyEncode + Copy + Debug + Ord + Send + Sync + 'static,)+
+            {
+                #[inline]
+                fn into_congee(self) -> usize { encode_art_word(&self) }
+
+                #[inline]
+                fn from_congee(value: usize) -> Self { decode_art_word(value) }
+            }
+        )*
+    };
+}
+
+impl_tuple_congee_key!((A, B), (A, B, C), (A, B, C, D));
+
 /// A Congee adaptive radix tree with WorkTable's unique-index contract.
 ///
 /// Values are held through `Arc` pointers because Congee's payload is one
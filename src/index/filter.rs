@@ -0,0 +1,411 @@
+//! Bloom filter in front of a unique index, so a lookup of an absent key
+//! usually returns without walking the index.
+
+use std::fmt::{self, Debug};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+use std::ops::{Deref, RangeBounds};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use data_bucket::Link;
+use indexset::cdc::change::ChangeEvent;
+use indexset::core::pair::Pair;
+use parking_lot::{Mutex, RwLock};
+use rustc_hash::FxBuildHasher;
+
+use crate::util::OffsetEqLink;
+use crate::{TableIndex, TableIndexCdc, UniqueIndex};
+
+/// Filter bits per key the filter is sized for. With [`PROBES`] probes a
+/// filter holding that many keys passes about one absent key in a hundred.
+const BITS_PER_KEY: usize = 10;
+const PROBES: u64 = 7;
+/// Keys the smallest filter is sized for.
+const MIN_KEYS: usize = 1024;
+
+struct BloomFilter {
+    words: Box<[AtomicU64]>,
+    bits: u64,
+    /// Keys the filter is sized for.
+    capacity: usize,
+    /// Keys added since the filter was built, including removed ones.
+    added: AtomicUsize,
+}
+
+impl BloomFilter {
+    fn with_capacity(keys: usize) -> Self {
+        let capacity = keys.max(MIN_KEYS);
+        let words = (capacity * BITS_PER_KEY).div_ceil(64);
+        Self {
+            words: (0..words).map(|_| AtomicU64::new(0)).collect(),
+            bits: words as u64 * 64,
+            capacity,
+            added: AtomicUsize::new(0),
+        }
+    }
+
+    fn probes<K: Hash>(&self, key: &K) -> impl Iterator<Item = (usize, u64)> {
+        // Fx leaves the low bits of small integers poorly mixed; the
+        // splitmix64 finalizer spreads them before double hashing.
+        let mut hash = FxBuildHasher.hash_one(key);
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        hash ^= hash >> 31;
+        let step = hash.rotate_left(32) | 1;
+        let bits = self.bits;
+        (0..PROBES).map(move |probe| {
+            let bit = hash.wrapping_add(probe.wrapping_mul(step)) % bits;
+            ((bit / 64) as usize, 1 << (bit % 64))
+        })
+    }
+
+    fn add<K: Hash>(&self, key: &K) {
+        for (word, mask) in self.probes(key) {
+            self.words[word].fetch_or(mask, Ordering::Relaxed);
+        }
+        self.added.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn may_contain<K: Hash>(&self, key: &K) -> bool {
+        self.probes(key)
+            .all(|(word, mask)| self.words[word].load(Ordering::Relaxed) & mask != 0)
+    }
+
+    fn is_full(&self) -> bool {
+        self.added.load(Ordering::Relaxed) > self.capacity
+    }
+
+    fn size(&self) -> usize {
+        self.words.len() * size_of::<AtomicU64>()
+    }
+}
+
+/// A unique index behind a Bloom filter of its keys, declared with
+/// `filter` after `unique`.
+///
+/// Every insert adds its key to the filter before the index, so a lookup
+/// the filter rejects returns `None` without reaching the index; at most
+/// about one absent key in a hundred still does. A Bloom filter cannot
+/// forget keys, so it is rebuilt from the index, sized for twice its keys,
+/// once more keys were added than it was sized for, and by
+/// [`Self::rebuild_filter`], which vacuum, a load and `shrink_to_fit` call.
+/// A rebuild scans the index next to the filter in use, so lookups and
+/// inserts do not wait for it; inserts add their key to both filters. As
+/// the filter doubles its size, the insert running the rebuild pays for
+/// about as many earlier ones.
+///
+/// Everything besides the [`UniqueIndex`], [`TableIndex`] and
+/// [`TableIndexCdc`] operations, such as node access for persistence, is
+/// reached through `Deref` to the wrapped index and bypasses the filter, so
+/// keys must only be added through those traits.
+pub struct FilteredIndex<K, I> {
+    index: I,
+    filters: RwLock<Filters>,
+    /// Held by the rebuild that is running.
+    rebuild: Mutex<()>,
+    phantom_data: PhantomData<fn(K)>,
+}
+
+/// The filter lookups use and the one a running rebuild fills.
+struct Filters {
+    current: Arc<BloomFilter>,
+    next: Option<Arc<BloomFilter>>,
+}
+
+impl Default for Filters {
+    fn default() -> Self {
+        Self {
+            current: Arc::new(BloomFilter::with_capacity(0)),
+            next: None,
+        }
+    }
+}
+
+impl<K, I: Debug> Debug for FilteredIndex<K, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FilteredIndex")
+            .field("index", &self.index)
+            .field("filter_size", &self.filter_size())
+            .finish()
+    }
+}
+
+impl<K, I: Default> Default for FilteredIndex<K, I> {
+    fn default() -> Self {
+        Self {
+            index: I::default(),
+            filters: RwLock::default(),
+            rebuild: Mutex::new(()),
+            phantom_data: PhantomData,
+        }
+    }
+}
+
+impl<K, I> Deref for FilteredIndex<K, I> {
+    type Target = I;
+
+    fn deref(&self) -> &I {
+        &self.index
+    }
+}
+
+impl<K, I> FilteredIndex<K, I> {
+    /// Bytes the filter takes.
+    pub fn filter_size(&self) -> usize {
+        self.filters.read().current.size()
+    }
+}
+
+impl<K, I> FilteredIndex<K, I>
+where
+    K: Clone + Hash + Ord,
+    I: UniqueIndex<K, OffsetEqLink>,
+{
+    /// Wraps an index that already holds keys, such as one read from disk.
+    pub fn new(index: I) -> Self {
+        let filtered = Self {
+            index,
+            filters: RwLock::default(),
+            rebuild: Mutex::new(()),
+            phantom_data: PhantomData,
+        };
+        filtered.rebuild_filter();
+        filtered
+    }
+
+    /// Rebuilds the filter from the keys in the index, dropping the keys of
+    /// removed entries and sizing it for twice the current key count.
+    pub fn rebuild_filter(&self) {
+        let _rebuild = self.rebuild.lock();
+        self.build_filter();
+    }
+
+    /// Fills a new filter from the index and puts it in use. Inserts that
+    /// start once it is installed add their key to it, and those that ran
+    /// before are in the index when the scan starts.
+    fn build_filter(&self) {
+        let next = Arc::new(BloomFilter::with_capacity(self.index.len() * 2));
+        self.filters.write().next = Some(next.clone());
+        for (key, _) in self.index.iter_values() {
+            next.add(&key);
+        }
+        let mut filters = self.filters.write();
+        filters.current = next;
+        filters.next = None;
+    }
+
+    /// Whether the index may hold `key`. `true` while a filter is swapped.
+    #[inline]
+    fn may_contain(&self, key: &K) -> bool {
+        self.filters
+            .try_read()
+            .is_none_or(|filters| filters.current.may_contain(key))
+    }
+
+    /// Adds `key` to the filters and runs `insert` while holding off the
+    /// start of a rebuild, which would otherwise miss a key the index does
+    /// not hold yet. A full filter is rebuilt unless another insert already
+    /// does.
+    fn insert_with<R>(&self, key: &K, insert: impl FnOnce() -> R) -> R {
+        let (result, full) = {
+            let filters = self.filters.read();
+            filters.current.add(key);
+            if let Some(next) = &filters.next {
+                next.add(key);
+            }
+            (insert(), filters.current.is_full())
+        };
+        if full && let Some(_rebuild) = self.rebuild.try_lock() {
+            let full = self.filters.read().current.is_full();
+            if full {
+                self.build_filter();
+            }
+        }
+        result
+    }
+}
+
+impl<K, I> UniqueIndex<K, OffsetEqLink> for FilteredIndex<K, I>
+where
+    K: Clone + Hash + Ord,
+    I: UniqueIndex<K, OffsetEqLink>,
+{
+    #[inline]
+    fn get_value(&self, key: &K) -> Option<OffsetEqLink> {
+        if !self.may_contain(key) {
+            return None;
+        }
+        self.index.get_value(key)
+    }
+
+    #[inline]
+    fn lookup_for_select(&self, key: &K) -> Option<OffsetEqLink> {
+        if !self.may_contain(key) {
+            return None;
+        }
+        self.index.lookup_for_select(key)
+    }
+
+    #[inline]
+    fn with_value<R>(&self, key: &K, read: impl FnOnce(&OffsetEqLink) -> R) -> Option<R> {
+        if !self.may_contain(key) {
+            return None;
+        }
+        self.index.with_value(key, read)
+    }
+
+    #[inline]
+    fn contains_key(&self, key: &K) -> bool {
+        self.may_contain(key) && self.index.contains_key(key)
+    }
+
+    fn insert_value(&self, key: K, value: OffsetEqLink) -> Option<OffsetEqLink> {
+        self.insert_with(&key.clone(), || self.index.insert_value(key, value))
+    }
+
+    fn insert_value_checked(&self, key: K, value: OffsetEqLink) -> Option<()> {
+        self.insert_with(&key.clone(), || self.index.insert_value_checked(key, value))
+    }
+
+    fn remove_value(&self, key: &K) -> Option<(K, OffsetEqLink)> {
+        self.index.remove_value(key)
+    }
+
+    fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    fn iter_values(&self) -> impl DoubleEndedIterator<Item = (K, OffsetEqLink)> + '_ {
+        self.index.iter_values()
+    }
+
+    fn iter_links(&self) -> impl DoubleEndedIterator<Item = OffsetEqLink> + '_ {
+        self.index.iter_links()
+    }
+
+    fn range_values<'a, R>(&'a self, range: R) -> impl DoubleEndedIterator<Item = (K, OffsetEqLink)> + 'a
+    where
+        R: RangeBounds<K> + 'a,
+    {
+        self.index.range_values(range)
+    }
+
+    fn range_links<'a, R>(&'a self, range: R) -> impl DoubleEndedIterator<Item = OffsetEqLink> + 'a
+    where
+        R: RangeBounds<K> + 'a,
+    {
+        self.index.range_links(range)
+    }
+}
+
+impl<K, I> TableIndex<K> for FilteredIndex<K, I>
+where
+    K: Clone + Hash + Ord,
+    I: TableIndex<K> + UniqueIndex<K, OffsetEqLink>,
+{
+    fn insert(&self, value: K, link: Link) -> Option<Link> {
+        self.insert_with(&value.clone(), || TableIndex::insert(&self.index, value, link))
+    }
+
+    fn insert_checked(&self, value: K, link: Link) -> Option<()> {
+        self.insert_with(&value.clone(), || TableIndex::insert_checked(&self.index, value, link))
+    }
+
+    fn remove(&self, value: &K, link: Link) -> Option<(K, Link)> {
+        TableIndex::remove(&self.index, value, link)
+    }
+}
+
+impl<K, I> TableIndexCdc<K> for FilteredIndex<K, I>
+where
+    K: Clone + Hash + Ord,
+    I: TableIndexCdc<K> + UniqueIndex<K, OffsetEqLink>,
+{
+    fn insert_cdc(&self, value: K, link: Link) -> (Option<Link>, Vec<ChangeEvent<Pair<K, Link>>>) {
+        self.insert_with(&value.clone(), || self.index.insert_cdc(value, link))
+    }
+
+    fn insert_checked_cdc(&self, value: K, link: Link) -> Option<Vec<ChangeEvent<Pair<K, Link>>>> {
+        self.insert_with(&value.clone(), || self.index.insert_checked_cdc(value, link))
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn remove_cdc(&self, value: K, link: Link) -> (Option<(K, Link)>, Vec<ChangeEvent<Pair<K, Link>>>) {
+        self.index.remove_cdc(value, link)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data_bucket::Link;
+
+    use super::FilteredIndex;
+    use crate::util::OffsetEqLink;
+    use crate::{HashIndex, IndexMap, TableIndex, UniqueIndex};
+
+    fn link(offset: u32) -> Link {
+        Link {
+            page_id: 1.into(),
+            offset,
+            length: 8,
+        }
+    }
+
+    #[test]
+    fn rejects_most_absent_keys_and_finds_every_present_one() {
+        let index = FilteredIndex::<u64, HashIndex<u64, OffsetEqLink>>::default();
+        for key in 0..10_000 {
+            TableIndex::insert(&index, key * 2, link(key as u32));
+        }
+        for key in 0..10_000 {
+            assert_eq!(index.get_value(&(key * 2)).map(|link| link.0), Some(link(key as u32)));
+        }
+        let passed = (0..10_000u64).filter(|key| index.may_contain(&(key * 2 + 1))).count();
+        assert!(passed < 500, "{passed} absent keys passed the filter");
+    }
+
+    #[test]
+    fn rebuild_drops_removed_keys() {
+        let index = FilteredIndex::<String, IndexMap<String, OffsetEqLink>>::default();
+        for key in 0..100 {
+            TableIndex::insert(&index, format!("key-{key}"), link(key));
+        }
+        for key in 0..100 {
+            TableIndex::remove(&index, &format!("key-{key}"), link(key));
+        }
+        index.rebuild_filter();
+        assert_eq!(
+            (0..100).filter(|key| index.may_contain(&format!("key-{key}"))).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn wraps_an_index_that_already_holds_keys() {
+        let inner = IndexMap::<u64, OffsetEqLink>::default();
+        inner.insert_value(7, OffsetEqLink(link(7)));
+        let index = FilteredIndex::new(inner);
+        assert_eq!(index.get_value(&7).map(|link| link.0), Some(link(7)));
+        assert!(index.get_value(&8).is_none());
+    }
+
+    #[test]
+    fn keys_inserted_during_a_rebuild_pass_the_new_filter() {
+        let index = FilteredIndex::<u64, IndexMap<u64, OffsetEqLink>>::default();
+        for key in 0..5_000 {
+            TableIndex::insert(&index, key, link(key as u32));
+        }
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                for _ in 0..20 {
+                    index.rebuild_filter();
+                }
+            });
+            for key in 5_000..20_000 {
+                TableIndex::insert(&index, key, link(key as u32));
+            }
+        });
+        assert!((0..20_000u64).all(|key| index.get_value(&key).is_some()));
+    }
+}
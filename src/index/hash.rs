@@ -0,0 +1,205 @@
+//! Lock-striped hash map for unique WorkTable indexes that only serve point
+//! lookups.
+
+use std::array;
+use std::fmt::{self, Debug};
+use std::hash::{BuildHasher, Hash};
+use std::ops::RangeBounds;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use parking_lot::RwLock;
+use rustc_hash::{FxBuildHasher, FxHashMap};
+
+use super::UniqueIndex;
+
+const SHARDS: usize = 64;
+
+/// A concurrent hash map with WorkTable's unique-index contract.
+///
+/// Keys are spread over independently locked shards, so point operations on
+/// different shards never contend. The table never range-scans a hash index;
+/// full and range scans collect a sorted snapshot so they still satisfy the
+/// ordered [`UniqueIndex`] interface used by `select_all` and persistence.
+pub struct HashIndex<K, V> {
+    shards: [RwLock<FxHashMap<K, V>>; SHARDS],
+    len: AtomicUsize,
+}
+
+impl<K, V> Debug for HashIndex<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HashIndex")
+            .field("len", &self.len.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K, V> Default for HashIndex<K, V> {
+    fn default() -> Self {
+        Self {
+            shards: array::from_fn(|_| RwLock::new(FxHashMap::default())),
+            len: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V> HashIndex<K, V> {
+    #[inline]
+    fn shard(&self, key: &K) -> &RwLock<FxHashMap<K, V>> {
+        &self.shards[FxBuildHasher.hash_one(key) as usize % SHARDS]
+    }
+
+    /// Number of entries the shards can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.shards.iter().map(|shard| shard.read().capacity()).sum()
+    }
+
+    /// Releases spare capacity left behind by removals.
+    pub fn shrink_to_fit(&self) {
+        for shard in &self.shards {
+            shard.write().shrink_to_fit();
+        }
+    }
+}
+
+impl<K, V> HashIndex<K, V>
+where
+    K: Clone + Hash + Ord,
+    V: Clone,
+{
+    fn collect_sorted(&self, mut keep: impl FnMut(&K) -> bool) -> Vec<(K, V)> {
+        let mut values = Vec::with_capacity(self.len.load(Ordering::Relaxed));
+        for shard in &self.shards {
+            values.extend(
+                shard
+                    .read()
+                    .iter()
+                    .filter(|(key, _)| keep(key))
+                    .map(|(key, value)| (key.clone(), value.clone())),
+            );
+        }
+        values.sort_unstable_by(|(left, _), (right, _)| left.cmp(right));
+        values
+    }
+}
+
+impl<K, V> UniqueIndex<K, V> for HashIndex<K, V>
+where
+    K: Clone + Hash + Ord + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+{
+    #[inline]
+    fn get_value(&self, key: &K) -> Option<V> {
+        self.shard(key).read().get(key).cloned()
+    }
+
+    #[inline]
+    fn with_value<R>(&self, key: &K, read: impl FnOnce(&V) -> R) -> Option<R> {
+        self.shard(key).read().get(key).map(read)
+    }
+
+    #[inline]
+    fn contains_key(&self, key: &K) -> bool {
+        self.shard(key).read().contains_key(key)
+    }
+
+    #[inline]
+    fn insert_value(&self, key: K, value: V) -> Option<V> {
+        let old = self.shard(&key).write().insert(key, value);
+        if old.is_none() {
+            self.len.fetch_add(1, Ordering::Relaxed);
+        }
+        old
+    }
+
+    #[inline]
+    fn insert_value_checked(&self, key: K, value: V) -> Option<()> {
+        let mut shard = self.shard(&key).write();
+        if shard.contains_key(&key) {
+            return None;
+        }
+        shard.insert(key, value);
+        self.len.fetch_add(1, Ordering::Relaxed);
+        Some(())
+    }
+
+    #[inline]
+    fn remove_value(&self, key: &K) -> Option<(K, V)> {
+        let removed = self.shard(key).write().remove_entry(key)?;
+        self.len.fetch_sub(1, Ordering::Relaxed);
+        Some(removed)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    fn iter_values(&self) -> impl DoubleEndedIterator<Item = (K, V)> + '_ {
+        self.collect_sorted(|_| true).into_iter()
+    }
+
+    fn iter_links(&self) -> impl DoubleEndedIterator<Item = V> + '_ {
+        self.iter_values().map(|(_, value)| value)
+    }
+
+    fn range_values<'a, R>(&'a self, range: R) -> impl DoubleEndedIterator<Item = (K, V)> + 'a
+    where
+        R: RangeBounds<K> + 'a,
+    {
+        self.collect_sorted(|key| range.contains(key)).into_iter()
+    }
+
+    fn range_links<'a, R>(&'a self, range: R) -> impl DoubleEndedIterator<Item = V> + 'a
+    where
+        R: RangeBounds<K> + 'a,
+    {
+        self.range_values(range).map(|(_, value)| value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Barrier};
+
+    use super::{HashIndex, UniqueIndex};
+
+    #[test]
+    fn scans_are_sorted_snapshots() {
+        let index = HashIndex::<String, u64>::default();
+        for key in [7, 3, 9, 1] {
+            index.insert_value(format!("key-{key}"), key);
+        }
+        assert_eq!(index.iter_links().collect::<Vec<_>>(), vec![1, 3, 7, 9]);
+        assert_eq!(
+            index
+                .range_links("key-3".to_string()..="key-7".to_string())
+                .collect::<Vec<_>>(),
+            vec![3, 7]
+        );
+    }
+
+    #[test]
+    fn checked_insert_has_one_winner_under_contention() {
+        let index = Arc::new(HashIndex::<u64, u64>::default());
+        let barrier = Arc::new(Barrier::new(9));
+        let mut threads = Vec::new();
+
+        for value in 0..8 {
+            let index = Arc::clone(&index);
+            let barrier = Arc::clone(&barrier);
+            threads.push(std::thread::spawn(move || {
+                barrier.wait();
+                index.insert_value_checked(7, value).is_some()
+            }));
+        }
+
+        barrier.wait();
+        let winners = threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .filter(|won| *won)
+            .count();
+        assert_eq!(winners, 1);
+        assert_eq!(index.len(), 1);
+    }
+}
@@ -1,27 +1,41 @@
 mod arctic;
+mod art_key;
+mod art_multi;
 mod available_index;
 mod congee;
+mod filter;
+mod hash;
 mod multipair;
 mod persistent_art;
 mod persistent_wti;
 mod primary_index;
+mod statistics;
 mod table_index;
 mod table_secondary_index;
 mod unique;
 mod unsized_node;
 
 pub use arctic::{ArcticIndex, ArcticKey};
+pub use art_key::{ArtKeyEncode, ArtWord, decode_art_word, encode_art_word};
+pub use art_multi::{ArcticMultiIndex, ArtMultiIndex, CongeeMultiIndex};
 pub use available_index::AvailableIndex;
 pub use congee::{CongeeIndex, CongeeKey};
+pub use filter::FilteredIndex;
+pub use hash::HashIndex;
 pub use indexset::concurrent::map::BTreeMap as IndexMap;
 pub use indexset::concurrent::multimap::BTreeMultiMap as IndexMultiMap;
 pub use multipair::MultiPairRecreate;
-pub use persistent_art::{PersistentArcticIndex, PersistentArtIndex, PersistentCongeeIndex};
+pub use persistent_art::{
+    PersistentArcticIndex, PersistentArcticMultiIndex, PersistentArtIndex, PersistentCongeeIndex,
+    PersistentCongeeMultiIndex, PersistentHashIndex,
+};
 pub use persistent_wti::PersistentWtiIndex;
 pub use primary_index::PrimaryIndex;
+pub use statistics::{HistogramBucket, IndexStatistics, NodeEntry, StatisticsCache};
 pub use table_index::{TableIndex, TableIndexCdc, convert_change_events, convert_upstream_change_events};
 pub use table_secondary_index::{
     IndexError, TableSecondaryIndex, TableSecondaryIndexCdc, TableSecondaryIndexEventsOps, TableSecondaryIndexInfo,
+    TableSecondaryIndexIntegrity,
 };
 pub use unique::{UniqueIndex, UpstreamIndexMap, UpstreamIndexPair};
 pub use unsized_node::UnsizedNode;
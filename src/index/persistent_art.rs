@@ -18,7 +18,8 @@ use indexset::cdc::change::{ChangeEvent, Id};
 use indexset::core::pair::Pair;
 use parking_lot::Mutex;
 
-use crate::index::{ArcticIndex, CongeeIndex, UniqueIndex};
+use crate::index::{ArcticIndex, ArtMultiIndex, CongeeIndex, HashIndex, UniqueIndex};
+use crate::persistence::ArtPersistenceKey;
 use crate::util::OffsetEqLink;
 use crate::{ArcticKey, CongeeKey, TableIndexCdc};
 
@@ -38,6 +39,16 @@ pub type PersistentArcticIndex<K, V> = PersistentArtIndex<ArcticIndex<K, V>>;
 /// Persisted Congee index selected by the generated DSL.
 pub type PersistentCongeeIndex<K, V> = PersistentArtIndex<CongeeIndex<K, V>>;
 
+/// Persisted hash index selected by the generated DSL. It shares the ART
+/// sequencing wrapper and WAL; only its checkpoint format differs.
+pub type PersistentHashIndex<K, V> = PersistentArtIndex<HashIndex<K, V>>;
+
+/// Persisted non-unique Arctic index selected by the generated DSL.
+pub type PersistentArcticMultiIndex<K, V> = PersistentArtIndex<ArtMultiIndex<ArcticIndex<K, Vec<V>>, K, V>>;
+
+/// Persisted non-unique Congee index selected by the generated DSL.
+pub type PersistentCongeeMultiIndex<K, V> = PersistentArtIndex<ArtMultiIndex<CongeeIndex<K, Vec<V>>, K, V>>;
+
 impl<I: Debug> Debug for PersistentArtIndex<I> {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         formatter
@@ -157,6 +168,56 @@ where
     }
 }
 
+impl<I, K, V> PersistentArtIndex<ArtMultiIndex<I, K, V>>
+where
+    K: Clone + Ord + Hash,
+    V: Clone + PartialEq,
+    I: UniqueIndex<K, Vec<V>>,
+{
+    #[inline]
+    pub fn get(&self, key: &K) -> std::vec::IntoIter<(K, V)> {
+        self.inner.get(key)
+    }
+
+    #[inline]
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.inner.contains_key(key)
+    }
+
+    /// Adds a pair without emitting a change event, e.g. while copying a
+    /// quiescent index for a checkpoint.
+    #[inline]
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        self.inner.insert(key, value)
+    }
+
+    pub fn range<'a, R>(&'a self, range: R) -> impl DoubleEndedIterator<Item = (K, V)> + 'a
+    where
+        R: RangeBounds<K> + 'a,
+    {
+        self.inner.range(range)
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (K, V)> + '_ {
+        self.inner.iter()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    #[inline]
+    pub fn key_count(&self) -> usize {
+        self.inner.key_count()
+    }
+}
+
 macro_rules! impl_persisted_art_cdc {
     ($index:ident, $key_bound:path) => {
         impl<T, const N: usize> TableIndexCdc<T> for PersistentArtIndex<$index<T, OffsetEqLink<N>>>
@@ -221,6 +282,66 @@ macro_rules! impl_persisted_art_cdc {
 
 impl_persisted_art_cdc!(ArcticIndex, ArcticKey);
 impl_persisted_art_cdc!(CongeeIndex, CongeeKey);
+impl_persisted_art_cdc!(HashIndex, ArtPersistenceKey);
+
+/// The link list of a key changes under that key's stripe, so the event order
+/// of one key matches its mutation order. A rejected duplicate or a missing
+/// pair changes nothing and emits no event.
+macro_rules! impl_persisted_art_multi_cdc {
+    ($index:ident, $key_bound:path) => {
+        impl<T, const N: usize> TableIndexCdc<T>
+            for PersistentArtIndex<ArtMultiIndex<$index<T, Vec<OffsetEqLink<N>>>, T, OffsetEqLink<N>>>
+        where
+            T: $key_bound + Eq + Hash,
+        {
+            fn insert_cdc(&self, value: T, link: Link) -> (Option<Link>, Vec<ChangeEvent<Pair<T, Link>>>) {
+                let _sequence_guard = self.mutation_stripe(&value).lock();
+                if let Some(existing) = self.inner.insert(value.clone(), OffsetEqLink(link)) {
+                    return (Some(existing.0), Vec::new());
+                }
+                let pair = Pair {
+                    key: value,
+                    value: link,
+                };
+                let event = ChangeEvent::InsertAt {
+                    event_id: self.next_event_id(),
+                    max_value: pair.clone(),
+                    value: pair,
+                    index: 0,
+                };
+                (None, vec![event])
+            }
+
+            fn insert_checked_cdc(&self, value: T, link: Link) -> Option<Vec<ChangeEvent<Pair<T, Link>>>> {
+                match self.insert_cdc(value, link) {
+                    (None, events) => Some(events),
+                    (Some(_), _) => None,
+                }
+            }
+
+            fn remove_cdc(&self, value: T, link: Link) -> (Option<(T, Link)>, Vec<ChangeEvent<Pair<T, Link>>>) {
+                let _sequence_guard = self.mutation_stripe(&value).lock();
+                let Some((key, old)) = self.inner.remove(&value, &OffsetEqLink(link)) else {
+                    return (None, Vec::new());
+                };
+                let pair = Pair {
+                    key: key.clone(),
+                    value: old.0,
+                };
+                let event = ChangeEvent::RemoveAt {
+                    event_id: self.next_event_id(),
+                    max_value: pair.clone(),
+                    value: pair,
+                    index: 0,
+                };
+                (Some((key, old.0)), vec![event])
+            }
+        }
+    };
+}
+
+impl_persisted_art_multi_cdc!(ArcticIndex, ArcticKey);
+impl_persisted_art_multi_cdc!(CongeeIndex, CongeeKey);
 
 #[cfg(test)]
 mod tests {
@@ -237,6 +358,34 @@ mod tests {
         assert_eq!(index.remove_cdc(7, link).1[0].id(), 1.into());
     }
 
+    #[test]
+    fn multi_events_carry_the_affected_link() {
+        let index = PersistentCongeeMultiIndex::<u64, OffsetEqLink<4096>>::default();
+        let first = Link {
+            offset: 8,
+            ..Link::default()
+        };
+        let second = Link {
+            offset: 16,
+            ..Link::default()
+        };
+        assert_eq!(index.insert_checked_cdc(3, first).unwrap().len(), 1);
+        assert_eq!(index.insert_checked_cdc(3, second).unwrap().len(), 1);
+        assert!(index.insert_checked_cdc(3, first).is_none());
+
+        let (removed, events) = index.remove_cdc(3, second);
+        assert_eq!(removed, Some((3, second)));
+        match &events[..] {
+            [ChangeEvent::RemoveAt { event_id, value, .. }] => {
+                assert_eq!(*event_id, 2.into());
+                assert_eq!(value.value, second);
+            }
+            events => panic!("unexpected events {events:?}"),
+        }
+        assert!(index.remove_cdc(3, second).1.is_empty());
+        assert_eq!(index.get(&3).map(|(_, link)| link.0).collect::<Vec<_>>(), vec![first]);
+    }
+
     #[test]
     fn same_key_events_follow_mutation_order() {
         let index = Arc::new(PersistentArcticIndex::<u64, OffsetEqLink<4096>>::default());
@@ -41,6 +41,24 @@ where
     }
 }
 
+impl<PrimaryKey, const DATA_LENGTH: usize, PkMap> PrimaryIndex<PrimaryKey, DATA_LENGTH, PkMap>
+where
+    PrimaryKey: Debug + Eq + Hash + Clone + Send + Ord + 'static,
+    PkMap: UniqueIndex<PrimaryKey, OffsetEqLink<DATA_LENGTH>>,
+{
+    /// Removes every entry of both indexes.
+    pub fn clear(&self) {
+        let keys: Vec<_> = self.pk_map.iter_values().map(|(pk, _)| pk).collect();
+        for pk in keys {
+            self.pk_map.remove_value(&pk);
+        }
+        let links: Vec<_> = self.reverse_pk_map.iter().map(|(link, _)| *link).collect();
+        for link in links {
+            self.reverse_pk_map.remove(&link);
+        }
+    }
+}
+
 impl<PrimaryKey, const DATA_LENGTH: usize, PkMap> TableIndex<PrimaryKey>
     for PrimaryIndex<PrimaryKey, DATA_LENGTH, PkMap>
 where
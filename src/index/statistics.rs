@@ -0,0 +1,371 @@
+//! Key distribution of an index, for estimating how many rows a lookup or a
+//! range selects.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Formatter};
+use std::ops::{Bound, RangeBounds};
+use std::sync::Arc;
+
+use indexset::core::multipair::{MultiPair, MultiPairLike};
+use indexset::core::node::NodeLike;
+use indexset::core::pair::Pair;
+use parking_lot::Mutex;
+
+/// Buckets of a histogram; an index with fewer entries gets one per entry.
+const HISTOGRAM_BUCKETS: usize = 64;
+/// Nodes of a non-unique index whose keys are counted to estimate its
+/// distinct keys.
+const SAMPLED_NODES: usize = 32;
+/// Fewest row writes after which cached statistics are computed again.
+const REFRESH_WRITES: u64 = 64;
+
+/// Keys `lower..=upper` of an index and the entries holding them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistogramBucket<K> {
+    pub lower: K,
+    pub upper: K,
+    pub entries: usize,
+    pub distinct_keys: usize,
+}
+
+/// Entry and key counts of an index with an equi-depth histogram of its
+/// keys. They are computed when requested and do not follow later writes;
+/// tables keep them in a [`StatisticsCache`].
+///
+/// Every bucket holds about the same number of entries, so densely used key
+/// ranges get narrower buckets. Buckets are in key order; two neighbours
+/// share an end key when the entries of that key span both.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexStatistics<K> {
+    pub entries: usize,
+    pub distinct_keys: usize,
+    pub histogram: Vec<HistogramBucket<K>>,
+}
+
+impl<K> Default for IndexStatistics<K> {
+    fn default() -> Self {
+        Self {
+            entries: 0,
+            distinct_keys: 0,
+            histogram: Vec::new(),
+        }
+    }
+}
+
+/// Entry type of a WorkTablesIndex node.
+pub trait NodeEntry<K> {
+    fn entry_key(&self) -> &K;
+}
+
+impl<K, V> NodeEntry<K> for Pair<K, V> {
+    fn entry_key(&self) -> &K {
+        &self.key
+    }
+}
+
+impl<K, V> NodeEntry<K> for MultiPair<K, V>
+where
+    MultiPair<K, V>: MultiPairLike<K, V>,
+{
+    fn entry_key(&self) -> &K {
+        self.key()
+    }
+}
+
+fn bucket_depth(entries: usize) -> usize {
+    entries.div_ceil(HISTOGRAM_BUCKETS).max(1)
+}
+
+impl<K: Clone + Ord> IndexStatistics<K> {
+    /// Builds the statistics from every key of an index in ascending order,
+    /// `len` being the number of keys expected.
+    pub fn from_sorted_keys(len: usize, keys: impl IntoIterator<Item = K>) -> Self {
+        let depth = bucket_depth(len);
+        let mut statistics = Self::default();
+        let mut current: Option<HistogramBucket<K>> = None;
+        for key in keys {
+            statistics.entries += 1;
+            if let Some(bucket) = current.as_mut()
+                && bucket.upper == key
+            {
+                bucket.entries += 1;
+                continue;
+            }
+            statistics.distinct_keys += 1;
+            if let Some(bucket) = current.as_mut()
+                && bucket.entries < depth
+            {
+                bucket.upper = key;
+                bucket.entries += 1;
+                bucket.distinct_keys += 1;
+                continue;
+            }
+            let bucket = HistogramBucket {
+                lower: key.clone(),
+                upper: key,
+                entries: 1,
+                distinct_keys: 1,
+            };
+            statistics.histogram.extend(current.replace(bucket));
+        }
+        statistics.histogram.extend(current);
+        statistics
+    }
+
+    /// Builds the statistics from the nodes of a WorkTablesIndex map in key
+    /// order, reading only the first and last key of most nodes. The distinct
+    /// keys of a non-`unique` index are extrapolated from the keys of up to
+    /// [`SAMPLED_NODES`] nodes spread over the index.
+    pub fn from_nodes<E, Node>(nodes: impl IntoIterator<Item = Arc<Mutex<Node>>>, unique: bool) -> Self
+    where
+        E: NodeEntry<K> + Ord,
+        Node: NodeLike<E>,
+    {
+        let mut spans = Vec::new();
+        let mut entries = 0;
+        for node in nodes {
+            let guard = node.lock();
+            let (Some(first), Some(last)) = (guard.get_ith(0), guard.get_ith(guard.len().saturating_sub(1))) else {
+                continue;
+            };
+            let span = (first.entry_key().clone(), last.entry_key().clone(), guard.len());
+            drop(guard);
+            entries += span.2;
+            spans.push((span, node));
+        }
+
+        let distinct_share = if unique {
+            1.0
+        } else {
+            let step = spans.len().div_ceil(SAMPLED_NODES).max(1);
+            let (mut sampled, mut distinct) = (0, 0);
+            for (_, node) in spans.iter().step_by(step) {
+                let guard = node.lock();
+                sampled += guard.len();
+                distinct += (0..guard.len())
+                    .filter(|&i| {
+                        i == 0
+                            || guard.get_ith(i - 1).map(NodeEntry::entry_key)
+                                != guard.get_ith(i).map(NodeEntry::entry_key)
+                    })
+                    .count();
+            }
+            if sampled == 0 {
+                1.0
+            } else {
+                distinct as f64 / sampled as f64
+            }
+        };
+
+        let depth = bucket_depth(entries);
+        let mut statistics = Self {
+            entries,
+            ..Self::default()
+        };
+        let mut current: Option<HistogramBucket<K>> = None;
+        for ((first, last, len), _) in spans {
+            match current.as_mut() {
+                Some(bucket) if bucket.entries < depth => {
+                    bucket.upper = last;
+                    bucket.entries += len;
+                }
+                _ => {
+                    let bucket = HistogramBucket {
+                        lower: first,
+                        upper: last,
+                        entries: len,
+                        distinct_keys: 0,
+                    };
+                    statistics.histogram.extend(current.replace(bucket));
+                }
+            }
+        }
+        statistics.histogram.extend(current);
+        for bucket in &mut statistics.histogram {
+            bucket.distinct_keys = ((bucket.entries as f64 * distinct_share).round() as usize).clamp(1, bucket.entries);
+            statistics.distinct_keys += bucket.distinct_keys;
+        }
+        statistics
+    }
+
+    /// Entries per distinct key; 0 for an empty index.
+    pub fn avg_duplicates(&self) -> f64 {
+        if self.distinct_keys == 0 {
+            0.0
+        } else {
+            self.entries as f64 / self.distinct_keys as f64
+        }
+    }
+
+    /// Estimates the entries with keys in `range`. A bucket inside the range
+    /// counts whole. One the range only cuts counts the share of one key for
+    /// a single-key range and half its entries otherwise.
+    pub fn estimate_rows(&self, range: impl RangeBounds<K>) -> usize {
+        let single_key = matches!(
+            (range.start_bound(), range.end_bound()),
+            (Bound::Included(start), Bound::Included(end)) if start == end
+        );
+        let estimate: f64 = self
+            .histogram
+            .iter()
+            .map(|bucket| {
+                let before = match range.start_bound() {
+                    Bound::Included(start) => *start > bucket.upper,
+                    Bound::Excluded(start) => *start >= bucket.upper,
+                    Bound::Unbounded => false,
+                };
+                let after = match range.end_bound() {
+                    Bound::Included(end) => *end < bucket.lower,
+                    Bound::Excluded(end) => *end <= bucket.lower,
+                    Bound::Unbounded => false,
+                };
+                let key_share = bucket.entries as f64 / bucket.distinct_keys.max(1) as f64;
+                if before || after {
+                    0.0
+                } else if range.contains(&bucket.lower) && range.contains(&bucket.upper) {
+                    bucket.entries as f64
+                } else if single_key {
+                    key_share
+                } else {
+                    (bucket.entries as f64 / 2.0).max(key_share)
+                }
+            })
+            .sum();
+        (estimate.round() as usize).min(self.entries)
+    }
+}
+
+/// Statistics of the indexes of a table. Those of an index are computed
+/// again once the table's rows were written as many times as an eighth of
+/// its entries, and at least 64 times, since.
+#[derive(Default)]
+pub struct StatisticsCache {
+    entries: Mutex<HashMap<&'static str, CachedStatistics>>,
+}
+
+struct CachedStatistics {
+    statistics: Arc<dyn Any + Send + Sync>,
+    /// Write count of the table when the statistics were computed.
+    writes: u64,
+}
+
+impl Debug for StatisticsCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StatisticsCache")
+            .field("indexes", &self.entries.lock().keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl StatisticsCache {
+    /// Returns the statistics of `index`, computing them with `compute` if
+    /// none are cached or they are stale at write count `writes`. The
+    /// computation runs without holding the cache.
+    pub fn get_or_compute<K>(
+        &self,
+        index: &'static str,
+        writes: u64,
+        compute: impl FnOnce() -> IndexStatistics<K>,
+    ) -> Arc<IndexStatistics<K>>
+    where
+        K: Send + Sync + 'static,
+    {
+        let cached = self.entries.lock().get(index).and_then(|cached| {
+            let statistics = cached.statistics.clone().downcast::<IndexStatistics<K>>().ok()?;
+            let stale_after = (statistics.entries as u64 / 8).max(REFRESH_WRITES);
+            (writes.saturating_sub(cached.writes) < stale_after).then_some(statistics)
+        });
+        if let Some(statistics) = cached {
+            return statistics;
+        }
+        let statistics = Arc::new(compute());
+        self.entries.lock().insert(
+            index,
+            CachedStatistics {
+                statistics: statistics.clone(),
+                writes,
+            },
+        );
+        statistics
+    }
+
+    /// Drops every cached statistics, so the next request computes them.
+    pub fn clear(&self) {
+        self.entries.lock().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IndexStatistics, StatisticsCache};
+    use crate::util::OffsetEqLink;
+    use crate::{IndexMap, IndexMultiMap};
+    use data_bucket::Link;
+
+    fn link(offset: u32) -> OffsetEqLink {
+        OffsetEqLink(Link {
+            page_id: 1.into(),
+            offset,
+            length: 8,
+        })
+    }
+
+    #[test]
+    fn sorted_keys_fill_equal_depth_buckets() {
+        let statistics = IndexStatistics::from_sorted_keys(6400, (0..6400u64).map(|key| key / 4));
+        assert_eq!(statistics.entries, 6400);
+        assert_eq!(statistics.distinct_keys, 1600);
+        assert_eq!(statistics.avg_duplicates(), 4.0);
+        assert_eq!(statistics.histogram.len(), 64);
+        assert!(statistics.histogram.iter().all(|bucket| bucket.entries == 100));
+        assert_eq!(statistics.estimate_rows(..), 6400);
+        assert_eq!(statistics.estimate_rows(100..200), 400);
+        assert_eq!(statistics.estimate_rows(7..=7), 4);
+        assert_eq!(statistics.estimate_rows(2000..), 0);
+    }
+
+    #[test]
+    fn nodes_give_the_same_counts_as_a_scan() {
+        let index = IndexMultiMap::<u64, OffsetEqLink>::with_maximum_node_size(64);
+        for i in 0..10_000u32 {
+            index.insert(u64::from(i % 2500), link(i));
+        }
+        let statistics = IndexStatistics::from_nodes(index.iter_nodes(), false);
+        assert_eq!(statistics.entries, 10_000);
+        assert!((2000..=3000).contains(&statistics.distinct_keys), "{statistics:?}");
+        let estimate = statistics.estimate_rows(0..1250);
+        assert!((4000..=6000).contains(&estimate), "{estimate}");
+
+        let index = IndexMap::<u64, OffsetEqLink>::with_maximum_node_size(64);
+        for i in 0..1000u32 {
+            index.insert(u64::from(i), link(i));
+        }
+        let statistics = IndexStatistics::from_nodes(index.iter_nodes(), true);
+        assert_eq!((statistics.entries, statistics.distinct_keys), (1000, 1000));
+        assert_eq!(statistics.estimate_rows(..), 1000);
+    }
+
+    #[test]
+    fn empty_index_estimates_nothing() {
+        let statistics = IndexStatistics::<u64>::from_sorted_keys(0, []);
+        assert!(statistics.histogram.is_empty());
+        assert_eq!(statistics.avg_duplicates(), 0.0);
+        assert_eq!(statistics.estimate_rows(..), 0);
+    }
+
+    #[test]
+    fn cached_statistics_are_kept_until_enough_writes() {
+        let cache = StatisticsCache::default();
+        let compute = |len: u64| move || IndexStatistics::from_sorted_keys(len as usize, 0..len);
+
+        assert_eq!(cache.get_or_compute("idx", 0, compute(1000)).entries, 1000);
+        assert_eq!(cache.get_or_compute("idx", 124, compute(2000)).entries, 1000);
+        assert_eq!(cache.get_or_compute("idx", 125, compute(2000)).entries, 2000);
+        assert_eq!(cache.get_or_compute("idx", 125 + 63, compute(10)).entries, 2000);
+        assert_eq!(cache.get_or_compute("other", 125, compute(10)).entries, 10);
+
+        cache.clear();
+        assert_eq!(cache.get_or_compute("idx", 125, compute(10)).entries, 10);
+    }
+}
@@ -12,8 +12,19 @@ use vanilla_indexset::core::pair::Pair as VanillaPair;
 
 use crate::index::table_index::util::{convert_change_events, convert_upstream_change_events};
 use crate::util::OffsetEqLink;
-use crate::{ArcticIndex, ArcticKey, CongeeIndex, CongeeKey, IndexMap, IndexMultiMap, UniqueIndex, UpstreamIndexMap};
-
+use crate::{
+    ArcticIndex, ArcticKey, ArtMultiIndex, CongeeIndex, CongeeKey, HashIndex, IndexMap, IndexMultiMap, UniqueIndex,
+    UpstreamIndexMap,
+};
+
+/// [`TableIndex`](crate::TableIndex) mutations that also report the index
+/// change events persisted tables write to disk. Memory-only backends return
+/// empty event lists.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not report index change events over `{T}`",
+    label = "does not implement `TableIndexCdc<{T}>`",
+    note = "`using custom(Type)` backends implement `UniqueIndex`, `TableIndex`, `TableIndexCdc` and `MemStat`"
+)]
 pub trait TableIndexCdc<T> {
     fn insert_cdc(&self, value: T, link: Link) -> (Option<Link>, Vec<ChangeEvent<Pair<T, Link>>>);
     fn insert_checked_cdc(&self, value: T, link: Link) -> Option<Vec<ChangeEvent<Pair<T, Link>>>>;
@@ -97,7 +108,7 @@ where
     }
 }
 
-/// Memory-only ARTs participate in the common mutation path but emit no
+/// Memory-only ARTs and hash maps participate in the common mutation path but emit no
 /// durable events. The DSL prevents these implementations from appearing in
 /// a persisted table.
 macro_rules! impl_memory_only_cdc {
@@ -125,3 +136,29 @@ macro_rules! impl_memory_only_cdc {
 
 impl_memory_only_cdc!(CongeeIndex<T, OffsetEqLink<N>>, [CongeeKey + Eq + Hash]);
 impl_memory_only_cdc!(ArcticIndex<T, OffsetEqLink<N>>, [ArcticKey + Eq + Hash]);
+impl_memory_only_cdc!(HashIndex<T, OffsetEqLink<N>>, [Clone + Eq + Hash + Ord + Send + Sync + 'static]);
+
+impl<T, I, const N: usize> TableIndexCdc<T> for ArtMultiIndex<I, T, OffsetEqLink<N>>
+where
+    T: Debug + Eq + Hash + Clone + Send + Ord,
+    I: UniqueIndex<T, Vec<OffsetEqLink<N>>>,
+{
+    fn insert_cdc(&self, value: T, link: Link) -> (Option<Link>, Vec<ChangeEvent<Pair<T, Link>>>) {
+        let old = self.insert(value, OffsetEqLink(link)).map(|value| value.0);
+        (old, Vec::new())
+    }
+
+    fn insert_checked_cdc(&self, value: T, link: Link) -> Option<Vec<ChangeEvent<Pair<T, Link>>>> {
+        match self.insert(value, OffsetEqLink(link)) {
+            Some(_) => None,
+            None => Some(Vec::new()),
+        }
+    }
+
+    fn remove_cdc(&self, value: T, link: Link) -> (Option<(T, Link)>, Vec<ChangeEvent<Pair<T, Link>>>) {
+        let removed = self
+            .remove(&value, &OffsetEqLink(link))
+            .map(|(key, value)| (key, value.0));
+        (removed, Vec::new())
+    }
+}
@@ -10,8 +10,9 @@ use vanilla_indexset::core::pair::Pair as VanillaPair;
 
 use crate::util::OffsetEqLink;
 use crate::{
-    ArcticIndex, ArcticKey, CongeeIndex, CongeeKey, IndexMap, IndexMultiMap, PersistentArcticIndex,
-    PersistentCongeeIndex, PersistentWtiIndex, UniqueIndex, UpstreamIndexMap,
+    ArcticIndex, ArcticKey, ArtMultiIndex, CongeeIndex, CongeeKey, HashIndex, IndexMap, IndexMultiMap,
+    PersistentArcticIndex, PersistentArtIndex, PersistentCongeeIndex, PersistentHashIndex, PersistentWtiIndex,
+    UniqueIndex, UpstreamIndexMap,
 };
 
 mod cdc;
@@ -20,6 +21,12 @@ pub mod util;
 pub use cdc::TableIndexCdc;
 pub use util::{convert_change_events, convert_upstream_change_events};
 
+/// Link-level insert and remove used by generated secondary indexes.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be used as a secondary index over `{T}`",
+    label = "does not implement `TableIndex<{T}>`",
+    note = "`using custom(Type)` backends implement `UniqueIndex`, `TableIndex`, `TableIndexCdc` and `MemStat`"
+)]
 pub trait TableIndex<T> {
     fn insert(&self, value: T, link: Link) -> Option<Link>;
     fn insert_checked(&self, value: T, link: Link) -> Option<()>;
@@ -160,3 +167,49 @@ impl_unique_table_index!(PersistentCongeeIndex<T, OffsetEqLink>, [
 impl_unique_table_index!(PersistentArcticIndex<T, OffsetEqLink>, [
     ArcticKey + Eq + Hash
 ]);
+impl_unique_table_index!(HashIndex<T, OffsetEqLink>, [
+    Debug + Eq + Hash + Clone + Send + Sync + Ord + 'static
+]);
+impl_unique_table_index!(PersistentHashIndex<T, OffsetEqLink>, [
+    Debug + Eq + Hash + Clone + Send + Sync + Ord + 'static
+]);
+
+impl<T, I> TableIndex<T> for ArtMultiIndex<I, T, OffsetEqLink>
+where
+    T: Debug + Eq + Hash + Clone + Send + Ord,
+    I: UniqueIndex<T, Vec<OffsetEqLink>>,
+{
+    fn insert(&self, value: T, link: Link) -> Option<Link> {
+        self.insert(value, OffsetEqLink(link)).map(|l| l.0)
+    }
+
+    fn insert_checked(&self, value: T, link: Link) -> Option<()> {
+        if self.insert(value, OffsetEqLink(link)).is_some() {
+            None
+        } else {
+            Some(())
+        }
+    }
+
+    fn remove(&self, value: &T, link: Link) -> Option<(T, Link)> {
+        self.remove(value, &OffsetEqLink(link)).map(|(v, l)| (v, l.0))
+    }
+}
+
+impl<T, I> TableIndex<T> for PersistentArtIndex<ArtMultiIndex<I, T, OffsetEqLink>>
+where
+    T: Debug + Eq + Hash + Clone + Send + Ord,
+    I: UniqueIndex<T, Vec<OffsetEqLink>>,
+{
+    fn insert(&self, value: T, link: Link) -> Option<Link> {
+        TableIndex::insert(self.inner(), value, link)
+    }
+
+    fn insert_checked(&self, value: T, link: Link) -> Option<()> {
+        TableIndex::insert_checked(self.inner(), value, link)
+    }
+
+    fn remove(&self, value: &T, link: Link) -> Option<(T, Link)> {
+        TableIndex::remove(self.inner(), value, link)
+    }
+}
@@ -1,7 +1,9 @@
-use crate::prelude::IndexInfo;
+use crate::prelude::{IndexInfo, StatisticsCache};
 
 pub trait TableSecondaryIndexInfo {
-    fn index_info(&self) -> Vec<IndexInfo>;
+    /// Describes every index. Distinct keys that need the index statistics
+    /// come from `statistics`, `writes` being the table's write count.
+    fn index_info(&self, statistics: &StatisticsCache, writes: u64) -> Vec<IndexInfo>;
     fn is_empty(&self) -> bool;
     fn is_unit() -> bool {
         false
@@ -9,7 +11,7 @@ pub trait TableSecondaryIndexInfo {
 }
 
 impl TableSecondaryIndexInfo for () {
-    fn index_info(&self) -> Vec<IndexInfo> {
+    fn index_info(&self, _: &StatisticsCache, _: u64) -> Vec<IndexInfo> {
         vec![]
     }
 
@@ -0,0 +1,31 @@
+use data_bucket::Link;
+
+use crate::prelude::IntegrityDiscrepancy;
+
+/// Secondary index side of `WorkTable::verify_integrity`.
+pub trait TableSecondaryIndexIntegrity<Row> {
+    /// Names of the indexes that hold no entry for `row` at `link`.
+    fn missing_entries(&self, row: &Row, link: Link) -> Vec<&'static str>;
+
+    /// Checks every entry against the row it references. `row_at` returns the
+    /// live row at a link, or `None` when there is none. An entry removed
+    /// while it was checked is not reported.
+    fn verify_entries(&self, row_at: &dyn Fn(Link) -> Option<Row>) -> Vec<IntegrityDiscrepancy>;
+
+    /// Number of entries held by each index.
+    fn entry_counts(&self) -> Vec<(&'static str, usize)>;
+}
+
+impl<Row> TableSecondaryIndexIntegrity<Row> for () {
+    fn missing_entries(&self, _: &Row, _: Link) -> Vec<&'static str> {
+        vec![]
+    }
+
+    fn verify_entries(&self, _: &dyn Fn(Link) -> Option<Row>) -> Vec<IntegrityDiscrepancy> {
+        vec![]
+    }
+
+    fn entry_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![]
+    }
+}
@@ -1,6 +1,7 @@
 mod cdc;
 mod index_events;
 mod info;
+mod integrity;
 
 use data_bucket::Link;
 use std::collections::HashMap;
@@ -11,6 +12,7 @@ use crate::{AvailableIndex, Difference};
 pub use cdc::TableSecondaryIndexCdc;
 pub use index_events::TableSecondaryIndexEventsOps;
 pub use info::TableSecondaryIndexInfo;
+pub use integrity::TableSecondaryIndexIntegrity;
 
 pub trait TableSecondaryIndex<Row, AvailableTypes, AvailableIndexes> {
     fn save_row(&self, row: Row, link: Link) -> Result<(), IndexError<AvailableIndexes>>;
@@ -42,6 +44,13 @@ pub trait TableSecondaryIndex<Row, AvailableTypes, AvailableIndexes> {
         link: Link,
         differences: HashMap<&str, Difference<AvailableTypes>>,
     ) -> Result<(), IndexError<AvailableIndexes>>;
+
+    /// Rebuilds the key filters of the [`FilteredIndex`](crate::FilteredIndex)
+    /// indexes, dropping the keys of removed rows.
+    fn rebuild_filters(&self) {}
+
+    /// Removes every entry of every index without reading rows.
+    fn clear(&self);
 }
 
 impl<Row, AvailableTypes, AvailableIndexes> TableSecondaryIndex<Row, AvailableTypes, AvailableIndexes> for ()
@@ -70,6 +79,8 @@ where
         Ok(())
     }
 
+    fn clear(&self) {}
+
     fn process_difference_insert(
         &self,
         _: Link,
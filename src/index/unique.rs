@@ -18,6 +18,17 @@ use vanilla_indexset::core::pair::Pair as VanillaPair;
 /// Point, mutation, and ordered-scan operations used by generated unique
 /// indexes. Implementations are statically dispatched; this adds no virtual
 /// call to the lookup path.
+///
+/// A type selected with `using custom(path::to::Index)` is instantiated as
+/// `Index<Key, OffsetEqLink>` and implements this trait together with
+/// [`TableIndex`](crate::TableIndex), [`TableIndexCdc`](crate::TableIndexCdc)
+/// and [`MemStat`](crate::prelude::MemStat). Scans must yield keys in
+/// ascending order.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a unique index backend",
+    label = "does not implement `UniqueIndex<{K}, {V}>`",
+    note = "`using custom(Type)` backends implement `UniqueIndex`, `TableIndex`, `TableIndexCdc` and `MemStat`"
+)]
 pub trait UniqueIndex<K, V>: Default
 where
     K: Clone + Ord,
@@ -209,7 +220,7 @@ mod tests {
     use std::sync::Arc;
 
     use super::{UniqueIndex, UpstreamIndexMap};
-    use crate::{ArcticIndex, CongeeIndex, IndexMap};
+    use crate::{ArcticIndex, CongeeIndex, HashIndex, IndexMap};
 
     fn assert_unique_index_contract<I>()
     where
@@ -327,12 +338,18 @@ mod tests {
         assert_unique_index_contract::<UpstreamIndexMap<u64, u64>>();
     }
 
+    #[test]
+    fn hash_index_implements_contract() {
+        assert_unique_index_contract::<HashIndex<u64, u64>>();
+    }
+
     #[test]
     fn all_backends_preserve_disjoint_concurrent_mutations() {
         assert_disjoint_concurrent_insert_then_remove::<IndexMap<u64, u64>>();
         assert_disjoint_concurrent_insert_then_remove::<UpstreamIndexMap<u64, u64>>();
         assert_disjoint_concurrent_insert_then_remove::<CongeeIndex<u64, u64>>();
         assert_disjoint_concurrent_insert_then_remove::<ArcticIndex<u64, u64>>();
+        assert_disjoint_concurrent_insert_then_remove::<HashIndex<u64, u64>>();
     }
 
     #[test]
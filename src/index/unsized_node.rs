@@ -7,6 +7,8 @@ use std::fmt::Debug;
 use std::ops::Deref;
 use std::slice::Iter;
 
+use crate::mem_stat::ShrinkNode;
+
 pub const UNSIZED_HEADER_LENGTH: u32 = 64;
 
 #[derive(Debug, Clone)]
@@ -60,6 +62,15 @@ where
     }
 }
 
+impl<T> ShrinkNode for UnsizedNode<T>
+where
+    T: SizeMeasurable,
+{
+    fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit()
+    }
+}
+
 impl<T> NodeLike<T> for UnsizedNode<T>
 where
     T: SizeMeasurable + Ord + Default + Debug + VariableSizeMeasurable,
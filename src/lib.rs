@@ -28,39 +28,51 @@ pub use worktable_codegen::worktable_version;
 pub use worktable_codegen::s3_sync_persistence;
 
 pub mod prelude {
-    pub use crate::in_memory::{ArchivedRowWrapper, Data, DataPages, Query, RowWrapper, StorableRow};
+    pub use crate::in_memory::{
+        ArchivedRowWrapper, COMPRESSED_PAGE_FLAG, Data, DataPages, MemoryBudget, MemoryLimitPolicy, PageCompression,
+        PageStore, Query, RowWrapper, SpillFile, StorableRow,
+    };
     pub use crate::lock::FullRowLock;
-    pub use crate::lock::{Lock, RowLock};
-    pub use crate::lock::{LockAcquirer, LockGuard, LockMap};
-    pub use crate::mem_stat::MemStat;
+    pub use crate::lock::{HeldLock, LockAcquirer, LockGuard, LockMap};
+    pub use crate::lock::{LOCK_WAIT_BUCKETS, Lock, LockStatsInfo, RowLock, without_waiting};
+    pub use crate::mem_stat::{MemStat, ShrinkNode};
     pub use crate::persistence::{
         AcknowledgeOperation, ArtPersistenceKey, DeleteOperation, DiskConfig, DiskPersistenceEngine,
         IndexTableOfContents, InsertOperation, LoadMode, Operation, OperationId, PersistedWorkTable, PersistenceConfig,
         PersistenceEngine, PersistenceError, PersistenceIndexCorruption, PersistenceLoadError, PersistenceMonitor,
         PersistenceResult, PersistenceState, PersistenceTask, ReadOnlyPersistenceEngine, SpaceArcticIndex,
-        SpaceCongeeIndex, SpaceData, SpaceDataOps, SpaceIndex, SpaceIndexOps, SpaceIndexUnsized, SpaceLogicalIndex,
-        SpaceLogicalIndexUnsized, SpaceSecondaryIndexOps, UpdateOperation, load_persisted_state,
+        SpaceArcticMultiIndex, SpaceCongeeIndex, SpaceCongeeMultiIndex, SpaceData, SpaceDataOps, SpaceDataPages,
+        SpaceHashIndex, SpaceIndex, SpaceIndexOps, SpaceIndexUnsized, SpaceLogicalIndex, SpaceLogicalIndexUnsized,
+        SpaceSecondaryIndexOps, UnpersistedPages, UpdateOperation, load_persisted_state,
         map_index_pages_to_toc_and_general, map_unsized_index_pages_to_toc_and_general, reconstruct_multi_index_nodes,
-        validate_events,
+        remove_index_files, validate_events,
     };
     pub use crate::primary_key::{PrimaryKeyGenerator, PrimaryKeyGeneratorState, TablePrimaryKey};
+    pub use crate::table::changes::{
+        ChangeFeed, ChangeFeedError, ChangeSubscription, DEFAULT_CHANGE_FEED_CAPACITY, RowChange, RowChangeEvent,
+    };
+    pub use crate::table::hooks::TableHooks;
+    pub use crate::table::integrity::{IntegrityCheck, IntegrityDiscrepancy, IntegrityReport};
+    pub use crate::table::rebuild::{IndexRebuildConflict, IndexRebuildReport};
     pub use crate::table::select::{Order, QueryParams, SelectQueryBuilder, SelectQueryExecutor};
-    pub use crate::table::system_info::{IndexInfo, IndexKind, SystemInfo};
+    pub use crate::table::system_info::{FieldLockInfo, HotRowInfo, IndexInfo, IndexKind, LockInfo, SystemInfo};
     pub use crate::util::{OffsetEqLink, OrderedF32Def, OrderedF64Def};
     pub use crate::{
-        ArcticIndex, ArcticKey, AvailableIndex, CongeeIndex, CongeeKey, Difference, IndexError, IndexMap,
-        IndexMultiMap, MultiPairRecreate, PersistentArcticIndex, PersistentArtIndex, PersistentCongeeIndex,
-        PersistentWtiIndex, PrimaryIndex, TableIndex, TableIndexCdc, TableRow, TableSecondaryIndex,
-        TableSecondaryIndexCdc, TableSecondaryIndexEventsOps, TableSecondaryIndexInfo, UniqueIndex, UnsizedNode,
-        UpstreamIndexMap, UpstreamIndexPair, WorkTable, WorkTableError, vacuum::EmptyDataVacuum,
-        vacuum::VacuumPersistence, vacuum::WorkTableVacuum,
+        ArcticIndex, ArcticKey, ArcticMultiIndex, ArtKeyEncode, ArtMultiIndex, AvailableIndex, CongeeIndex, CongeeKey,
+        CongeeMultiIndex, Difference, FilteredIndex, HashIndex, HistogramBucket, IndexError, IndexMap, IndexMultiMap,
+        IndexStatistics, MultiPairRecreate, PersistentArcticIndex, PersistentArcticMultiIndex, PersistentArtIndex,
+        PersistentCongeeIndex, PersistentCongeeMultiIndex, PersistentHashIndex, PersistentWtiIndex, PrimaryIndex,
+        StatisticsCache, TableIndex, TableIndexCdc, TableRow, TableSecondaryIndex, TableSecondaryIndexCdc,
+        TableSecondaryIndexEventsOps, TableSecondaryIndexInfo, TableSecondaryIndexIntegrity, UniqueIndex, UnsizedNode,
+        UpdatedRow, UpstreamIndexMap, UpstreamIndexPair, WorkTable, WorkTableError, decode_art_word, encode_art_word,
+        vacuum::EmptyDataVacuum, vacuum::VacuumPersistence, vacuum::WorkTableVacuum,
     };
     pub use data_bucket::{
         DATA_VERSION, DataPage, GENERAL_HEADER_SIZE, GeneralHeader, GeneralPage, INNER_PAGE_SIZE, IndexPage, Interval,
         Link, PAGE_SIZE, PageType, Persistable, PersistableIndex, SizeMeasurable, SizeMeasure, SpaceInfoPage,
         TableOfContentsPage, UnsizedIndexPage, VariableSizeMeasurable, VariableSizeMeasure, align,
-        get_index_page_size_from_data_length, map_data_pages_to_general, parse_data_page, parse_page, persist_page,
-        seek_to_page_start, update_at,
+        get_index_page_size_from_data_length, map_data_pages_to_general, parse_data_page,
+        parse_general_header_by_index, parse_page, persist_page, seek_to_page_start, update_at,
     };
     pub use derive_more::{Display as MoreDisplay, From, Into};
     pub use indexset::{
@@ -1,14 +1,20 @@
-use std::collections::HashMap;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
 use std::ops::Deref;
-use std::sync::Arc;
 use std::sync::atomic::{AtomicU16, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
 use parking_lot::RwLock;
 
-use crate::lock::RowLock;
+use crate::lock::stats::HotRows;
+use crate::lock::{Lock, LockStats, RowLock};
+use crate::table::system_info::{FieldLockInfo, HotRowInfo, LockInfo};
+
+/// Number of most contended rows listed in [`LockInfo::hot_rows`].
+const HOT_ROWS_LISTED: usize = 10;
 
 const MUTATION_STRIPE_COUNT: usize = 64;
 
@@ -20,7 +26,7 @@ struct MutationStripe {
 
 /// Synchronous, task-safe gate for one primary-key mutation stripe.
 ///
-/// Generated async row locks and synchronous inserts share these gates so a
+/// Generated row locks and synchronous inserts share these gates so a
 /// synchronous API entry point cannot interleave its multi-structure
 /// publication with an update or delete of the same key.
 #[derive(Debug)]
@@ -29,9 +35,21 @@ pub struct MutationGuard {
     stripe: usize,
 }
 
+/// One held row or column lock, as listed by [`LockMap::held_locks`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeldLock<PrimaryKey> {
+    pub primary_key: PrimaryKey,
+    /// Id of the holding operation, as handed out by [`LockMap::next_id`].
+    pub holder: u16,
+    /// Locked columns, empty if the holder locks the whole row.
+    pub columns: &'static [&'static str],
+    /// Holders this operation still waits for. Empty once it owns the row.
+    pub waiting_for: Vec<u16>,
+}
+
 #[derive(Debug)]
 struct LockEntry<LockType> {
-    lock: Arc<tokio::sync::RwLock<LockType>>,
+    lock: Arc<RwLock<LockType>>,
     acquirers: Arc<AtomicUsize>,
 }
 
@@ -46,7 +64,7 @@ where
     LockType: RowLock,
     PrimaryKey: Hash + Eq + Debug + Clone,
 {
-    lock: Option<Arc<tokio::sync::RwLock<LockType>>>,
+    lock: Option<Arc<RwLock<LockType>>>,
     acquirers: Arc<AtomicUsize>,
     lock_map: Arc<LockMap<LockType, PrimaryKey>>,
     primary_key: PrimaryKey,
@@ -73,7 +91,7 @@ where
     LockType: RowLock,
     PrimaryKey: Hash + Eq + Debug + Clone,
 {
-    type Target = tokio::sync::RwLock<LockType>;
+    type Target = RwLock<LockType>;
 
     fn deref(&self) -> &Self::Target {
         self.lock.as_deref().expect("the acquisition lock exists until drop")
@@ -98,20 +116,31 @@ impl Drop for MutationGuard {
     }
 }
 
-/// Registry for per-row async locks and synchronous mutation stripes.
+/// Registry for per-row locks and synchronous mutation stripes.
 ///
-/// # Sync/async lock boundary
+/// # Lock boundary
 ///
-/// The `parking_lot` map guard is never returned and never crosses an
-/// `.await`. Acquisition clones a tracked `Arc<tokio::sync::RwLock<_>>` before
-/// releasing the map guard. Cleanup may synchronously take the short-lived map
-/// write guard, but only probes the per-row lock with `try_read`; it never waits
-/// on a Tokio lock while holding the map. This one-way boundary prevents a
-/// map-lock/per-row-lock cycle during cancellation and `Drop`.
+/// Both the map and the per-row lock state are `parking_lot` locks, and
+/// neither guard is held across an `.await` or a wait for another operation:
+/// the row state is only locked to register an operation, which then waits on
+/// the returned [`Lock`]s with [`Self::wait_for`] or
+/// [`Self::wait_for_blocking`]. Acquisition clones a tracked
+/// `Arc<RwLock<_>>` before releasing the map guard. Cleanup may
+/// synchronously take the short-lived map write guard, but only probes the
+/// per-row lock with `try_read`; it never waits on a row lock while holding
+/// the map. This one-way boundary prevents a map-lock/per-row-lock cycle
+/// during cancellation and `Drop`.
 #[derive(Debug)]
 pub struct LockMap<LockType, PrimaryKey> {
     map: RwLock<HashMap<PrimaryKey, LockEntry<LockType>>>,
     next_id: AtomicU16,
+    /// Row lock wait timeout in nanoseconds, `0` waits indefinitely.
+    wait_timeout: AtomicU64,
+    /// Contention of the whole table's row locks.
+    stats: LockStats,
+    /// Contention per column of `LockType`, created on first use.
+    column_stats: OnceLock<Vec<(&'static str, LockStats)>>,
+    hot_rows: HotRows<PrimaryKey>,
     mutation_stripes: Arc<[MutationStripe; MUTATION_STRIPE_COUNT]>,
 }
 
@@ -120,6 +149,10 @@ impl<LockType, PrimaryKey> Default for LockMap<LockType, PrimaryKey> {
         Self {
             map: RwLock::new(HashMap::new()),
             next_id: AtomicU16::default(),
+            wait_timeout: AtomicU64::default(),
+            stats: LockStats::default(),
+            column_stats: OnceLock::new(),
+            hot_rows: HotRows::default(),
             mutation_stripes: Arc::new(std::array::from_fn(|_| MutationStripe::default())),
         }
     }
@@ -135,11 +168,7 @@ where
     /// `Arc::strong_count`. Generated operations should prefer
     /// [`Self::get_or_insert_with`], whose [`LockAcquirer`] makes cancellation
     /// tracking explicit.
-    pub fn insert(
-        &self,
-        key: PrimaryKey,
-        lock: Arc<tokio::sync::RwLock<LockType>>,
-    ) -> Option<Arc<tokio::sync::RwLock<LockType>>> {
+    pub fn insert(&self, key: PrimaryKey, lock: Arc<RwLock<LockType>>) -> Option<Arc<RwLock<LockType>>> {
         self.map
             .write()
             .insert(
@@ -154,7 +183,7 @@ where
 
     /// Returns an untracked raw lock clone, which keeps the map entry alive
     /// until that clone is dropped.
-    pub fn get(&self, key: &PrimaryKey) -> Option<Arc<tokio::sync::RwLock<LockType>>> {
+    pub fn get(&self, key: &PrimaryKey) -> Option<Arc<RwLock<LockType>>> {
         self.map.read().get(key).map(|entry| entry.lock.clone())
     }
 
@@ -189,7 +218,7 @@ where
         let mut map = self.map.write();
         // Re-check: another task can insert between the read and write guards.
         let entry = map.entry(key.clone()).or_insert_with(|| LockEntry {
-            lock: Arc::new(tokio::sync::RwLock::new(f())),
+            lock: Arc::new(RwLock::new(f())),
             acquirers: Arc::new(AtomicUsize::new(0)),
         });
         entry.acquirers.fetch_add(1, Ordering::AcqRel);
@@ -211,7 +240,7 @@ where
     {
         let mut set = self.map.write();
         let should_remove = set.get(key).is_some_and(|entry| {
-            let Ok(guard) = entry.lock.try_read() else {
+            let Some(guard) = entry.lock.try_read() else {
                 return false;
             };
             !guard.is_locked()
@@ -232,15 +261,133 @@ where
         self.next_id.fetch_add(1, Ordering::Relaxed)
     }
 
+    /// Sets how long generated operations wait for a row or column lock held
+    /// by another operation before failing with
+    /// [`WorkTableError::LockTimeout`]. `None` waits indefinitely, which is
+    /// the default.
+    ///
+    /// [`WorkTableError::LockTimeout`]: crate::prelude::WorkTableError::LockTimeout
+    pub fn set_wait_timeout(&self, timeout: Option<Duration>) {
+        let nanos = timeout.map_or(0, |t| u64::try_from(t.as_nanos()).unwrap_or(u64::MAX).max(1));
+        self.wait_timeout.store(nanos, Ordering::Relaxed);
+    }
+
+    pub fn wait_timeout(&self) -> Option<Duration> {
+        match self.wait_timeout.load(Ordering::Relaxed) {
+            0 => None,
+            nanos => Some(Duration::from_nanos(nanos)),
+        }
+    }
+
+    pub(crate) fn stats(&self) -> &LockStats {
+        &self.stats
+    }
+
+    pub(crate) fn hot_rows(&self) -> &HotRows<PrimaryKey> {
+        &self.hot_rows
+    }
+
+    /// Per-column counters, one for each column of `LockType`.
+    pub(crate) fn column_stats(&self) -> &[(&'static str, LockStats)]
+    where
+        LockType: RowLock,
+    {
+        self.column_stats
+            .get_or_init(|| LockType::columns().iter().map(|c| (*c, LockStats::default())).collect())
+    }
+
+    /// Returns lock contention counters for the table, its columns and its
+    /// most contended rows, as gathered since creation or the last
+    /// [`Self::reset_lock_stats`].
+    pub fn lock_info(&self) -> LockInfo
+    where
+        LockType: RowLock,
+    {
+        LockInfo {
+            table: self.stats.info(),
+            columns: self
+                .column_stats()
+                .iter()
+                .map(|(name, stats)| FieldLockInfo {
+                    name,
+                    stats: stats.info(),
+                })
+                .collect(),
+            hot_rows: self
+                .hot_rows
+                .top(HOT_ROWS_LISTED)
+                .into_iter()
+                .map(|(key, contended)| HotRowInfo {
+                    primary_key: format!("{key:?}"),
+                    contended,
+                })
+                .collect(),
+        }
+    }
+
+    /// Zeroes the counters reported by [`Self::lock_info`]. Operations that
+    /// wait at the moment stay counted as waiters.
+    pub fn reset_lock_stats(&self)
+    where
+        LockType: RowLock,
+    {
+        self.stats.reset();
+        for (_, stats) in self.column_stats() {
+            stats.reset();
+        }
+        self.hot_rows.reset();
+    }
+
+    /// Lists the row and column locks that are held right now, including
+    /// those whose holder still waits for an earlier holder.
+    ///
+    /// Meant for diagnosing hangs: an operation that never finishes shows up
+    /// as a holder nobody waits on, with the operations queued behind it
+    /// listing its id in `waiting_for`. Rows whose lock is being registered
+    /// at the moment of the call are skipped.
+    pub fn held_locks(&self) -> Vec<HeldLock<PrimaryKey>>
+    where
+        LockType: RowLock,
+    {
+        let map = self.map.read();
+        let mut held = Vec::new();
+        for (key, entry) in map.iter() {
+            let Some(row_lock) = entry.lock.try_read() else {
+                continue;
+            };
+            let mut pending = row_lock.locks();
+            drop(row_lock);
+
+            let mut seen = HashSet::new();
+            while let Some(lock) = pending.pop() {
+                if !lock.is_locked() || !seen.insert(Arc::as_ptr(&lock)) {
+                    continue;
+                }
+                let waiting_for: Vec<Arc<Lock>> = lock.waiting_for().into_iter().filter(|l| l.is_locked()).collect();
+                held.push(HeldLock {
+                    primary_key: key.clone(),
+                    holder: lock.id(),
+                    columns: lock.columns(),
+                    waiting_for: waiting_for.iter().map(|l| l.id()).collect(),
+                });
+                pending.extend(waiting_for);
+            }
+        }
+        held
+    }
+
     /// Serializes the synchronous mutation phase for this key.
     ///
     /// The holder must not perform a suspending `.await`. Generated locked
-    /// operations acquire this only after their async predecessor wait has
+    /// operations acquire this only after their predecessor wait has
     /// completed, and the synchronous `insert` path never awaits.
     pub fn mutation_guard(&self, key: &PrimaryKey) -> MutationGuard {
         let mut hasher = DefaultHasher::new();
         key.hash(&mut hasher);
-        let stripe = (hasher.finish() as usize) % MUTATION_STRIPE_COUNT;
+        self.acquire_stripe((hasher.finish() as usize) % MUTATION_STRIPE_COUNT)
+    }
+
+    fn acquire_stripe(&self, stripe: usize) -> MutationGuard {
         let gate = &self.mutation_stripes[stripe];
         let ticket = gate.next_ticket.fetch_add(1, Ordering::Relaxed);
         let mut spins = 0u32;
@@ -259,6 +406,31 @@ where
             stripe,
         }
     }
+
+    /// Acquires every mutation stripe in index order, excluding all
+    /// synchronous mutation phases of the table until the guards are dropped.
+    ///
+    /// Mutation holders never take a second stripe, so acquiring them in a
+    /// fixed order cannot deadlock with single-key or multi-row operations.
+    pub fn mutation_barrier(&self) -> Vec<MutationGuard> {
+        (0..MUTATION_STRIPE_COUNT)
+            .map(|stripe| self.acquire_stripe(stripe))
+            .collect()
+    }
+
+    /// Drops every lock entry that no operation holds or is registering on.
+    ///
+    /// Entries still owned by in-flight operations are kept: removing them
+    /// would let a second lock be created for the same key.
+    pub fn clear_unlocked(&self)
+    where
+        LockType: RowLock,
+    {
+        let keys: Vec<_> = self.map.read().keys().cloned().collect();
+        for key in keys {
+            self.remove_with_lock_check(&key);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -297,19 +469,14 @@ mod tests {
         assert!(!lock_map.map.read().contains_key(&33));
     }
 
-    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-    async fn cancelling_async_waiter_releases_tracking_without_deadlock() {
+    #[test]
+    fn dropping_acquirer_while_row_state_is_locked_keeps_the_entry() {
         let lock_map: Arc<LockMap<FullRowLock, u64>> = Arc::new(LockMap::default());
         let owner = lock_map.get_or_insert_with(41, FullRowLock::new);
-        let owner_guard = owner.write().await;
+        let owner_guard = owner.write();
         let waiter = lock_map.get_or_insert_with(41, FullRowLock::new);
-        let waiting_task = tokio::spawn(async move {
-            let _guard = waiter.write().await;
-        });
-        tokio::task::yield_now().await;
 
-        waiting_task.abort();
-        assert!(waiting_task.await.unwrap_err().is_cancelled());
+        drop(waiter);
         assert!(lock_map.map.read().contains_key(&41));
 
         drop(owner_guard);
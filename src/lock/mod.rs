@@ -1,5 +1,7 @@
 mod map;
 mod row_lock;
+mod stats;
+mod wait;
 
 use std::cell::Cell;
 use std::fmt::Debug;
@@ -8,14 +10,16 @@ use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use std::pin::Pin;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::task::{Context, Poll};
 
 use futures::task::AtomicWaker;
 use parking_lot::Mutex;
 
-pub use map::{LockAcquirer, LockMap, MutationGuard};
+pub use map::{HeldLock, LockAcquirer, LockMap, MutationGuard};
 pub use row_lock::{FullRowLock, RowLock};
+pub use stats::{LOCK_WAIT_BUCKETS, LockStats, LockStatsInfo};
+pub use wait::without_waiting;
 
 /// Maximum number of spin iterations before falling back to async waiting.
 const MAX_SPINS: u32 = 12;
@@ -95,8 +99,41 @@ where
 #[derive(Debug)]
 pub struct Lock {
     id: u16,
+    /// Columns the holder locks, empty for a full-row lock.
+    columns: &'static [&'static str],
     locked: Arc<AtomicBool>,
     wakers: Mutex<Vec<Arc<AtomicWaker>>>,
+    /// Locks the holder still waits for. Only kept for
+    /// [`LockMap::held_locks`].
+    waiting_for: Mutex<Vec<Arc<Lock>>>,
+    /// Abandoned locks queued behind this one, see [`Lock::release_after`].
+    successors: Mutex<Vec<Arc<AbandonedLock>>>,
+}
+
+/// A lock whose holder gave up waiting. It is released once the last of the
+/// locks it waited for is.
+struct AbandonedLock {
+    lock: Arc<Lock>,
+    pending: AtomicUsize,
+    on_release: Mutex<Option<Box<dyn FnOnce() + Send>>>,
+}
+
+impl AbandonedLock {
+    fn predecessor_released(&self) {
+        if self.pending.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.lock.set_waiting_for(vec![]);
+            self.lock.unlock();
+            if let Some(on_release) = self.on_release.lock().take() {
+                on_release();
+            }
+        }
+    }
+}
+
+impl Debug for AbandonedLock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AbandonedLock").field("lock", &self.lock.id).finish()
+    }
 }
 
 impl PartialEq for Lock {
@@ -121,10 +158,18 @@ impl Drop for Lock {
 
 impl Lock {
     pub fn new(id: u16) -> Self {
+        Self::with_columns(id, &[])
+    }
+
+    /// A held lock that covers only `columns` of the row.
+    pub fn with_columns(id: u16, columns: &'static [&'static str]) -> Self {
         Self {
             id,
+            columns,
             locked: Arc::new(AtomicBool::from(true)),
             wakers: Mutex::new(vec![]),
+            waiting_for: Mutex::new(vec![]),
+            successors: Mutex::new(vec![]),
         }
     }
 
@@ -136,8 +181,11 @@ impl Lock {
     pub fn new_released(id: u16) -> Self {
         Self {
             id,
+            columns: &[],
             locked: Arc::new(AtomicBool::new(false)),
             wakers: Mutex::new(vec![]),
+            waiting_for: Mutex::new(vec![]),
+            successors: Mutex::new(vec![]),
         }
     }
 
@@ -145,12 +193,60 @@ impl Lock {
         self.id
     }
 
+    /// Columns this lock covers, empty for a full-row lock.
+    pub fn columns(&self) -> &'static [&'static str] {
+        self.columns
+    }
+
+    /// Locks the holder of this lock is still waiting for.
+    pub fn waiting_for(&self) -> Vec<Arc<Lock>> {
+        self.waiting_for.lock().clone()
+    }
+
+    fn set_waiting_for(&self, locks: Vec<Arc<Lock>>) {
+        *self.waiting_for.lock() = locks;
+    }
+
     pub fn unlock(&self) {
         self.locked.store(false, Ordering::Release);
-        let guard = self.wakers.lock();
-        for w in guard.iter() {
-            w.wake()
+        {
+            let guard = self.wakers.lock();
+            for w in guard.iter() {
+                w.wake()
+            }
+        }
+        let successors = std::mem::take(&mut *self.successors.lock());
+        for successor in successors {
+            successor.predecessor_released();
+        }
+    }
+
+    /// Releases `lock` once every lock in `predecessors` is released, then
+    /// runs `on_release`. Used when the holder of `lock` stops waiting for
+    /// them: operations queued behind `lock` keep waiting for the
+    /// predecessors, and no task is left behind to wait in its place.
+    pub(crate) fn release_after(
+        lock: Arc<Lock>,
+        predecessors: &[Arc<Lock>],
+        on_release: impl FnOnce() + Send + 'static,
+    ) {
+        let abandoned = Arc::new(AbandonedLock {
+            lock,
+            // Held at one until every predecessor is registered, so a
+            // predecessor released meanwhile cannot release `lock` early.
+            pending: AtomicUsize::new(1),
+            on_release: Mutex::new(Some(Box::new(on_release))),
+        });
+        for predecessor in predecessors {
+            // Checked under the successor list so a concurrent `unlock`
+            // either sees the registration or is seen here.
+            let mut successors = predecessor.successors.lock();
+            if predecessor.is_locked() {
+                abandoned.pending.fetch_add(1, Ordering::AcqRel);
+                successors.push(abandoned.clone());
+            }
         }
+        abandoned.predecessor_released();
     }
 
     pub fn lock(&self) {
@@ -300,7 +396,7 @@ mod tests {
 
         // Create and insert a lock
         let (lock_type, lock) = FullRowLock::with_lock(lock_map.next_id());
-        let rw_lock = Arc::new(tokio::sync::RwLock::new(lock_type));
+        let rw_lock = Arc::new(parking_lot::RwLock::new(lock_type));
         lock_map.insert(pk, rw_lock);
 
         // Verify the lock is in the map
@@ -19,6 +19,13 @@ pub trait RowLock {
     /// Locks full [`RowLock`].
     #[allow(clippy::mutable_key_type)]
     fn lock(&mut self, id: u16) -> (HashSet<Arc<Lock>>, Arc<Lock>);
+    /// Returns the locks currently stored for this row, each one once.
+    fn locks(&self) -> Vec<Arc<Lock>>;
+    /// Names of the columns this type locks separately, empty if it only
+    /// locks whole rows.
+    fn columns() -> &'static [&'static str]
+    where
+        Self: Sized;
     /// Merges two [`RowLock`]'s.
     #[allow(clippy::mutable_key_type)]
     fn merge(&mut self, other: &mut Self) -> HashSet<Arc<Lock>>
@@ -90,6 +97,14 @@ impl RowLock for FullRowLock {
         (set, l)
     }
 
+    fn locks(&self) -> Vec<Arc<Lock>> {
+        vec![self.l.clone()]
+    }
+
+    fn columns() -> &'static [&'static str] {
+        &[]
+    }
+
     fn merge(&mut self, other: &mut Self) -> HashSet<Arc<Lock>>
     where
         Self: Sized,
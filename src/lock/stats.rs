@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+/// Upper bounds of the lock wait histogram buckets. Waits longer than the
+/// last bound are counted in one more, open-ended bucket.
+pub const LOCK_WAIT_BUCKETS: [Duration; 6] = [
+    Duration::from_micros(10),
+    Duration::from_micros(100),
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+];
+
+const HISTOGRAM_LEN: usize = LOCK_WAIT_BUCKETS.len() + 1;
+
+/// Number of rows [`HotRows`] keeps contention counts for.
+const HOT_ROW_CAPACITY: usize = 64;
+
+/// Contention counters of a table's row locks or of one column's locks.
+#[derive(Debug, Default)]
+pub struct LockStats {
+    acquisitions: AtomicU64,
+    contended: AtomicU64,
+    timeouts: AtomicU64,
+    waiters: AtomicU64,
+    wait_nanos: AtomicU64,
+    wait_histogram: [AtomicU64; HISTOGRAM_LEN],
+}
+
+/// Snapshot of [`LockStats`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LockStatsInfo {
+    /// Operations that registered a lock.
+    pub acquisitions: u64,
+    /// Operations that had to wait for an earlier holder.
+    pub contended: u64,
+    /// Waits that gave up with `WorkTableError::LockTimeout`.
+    pub timeouts: u64,
+    /// Operations waiting right now.
+    pub waiters: u64,
+    /// Time spent in all finished waits.
+    pub total_wait: Duration,
+    /// Finished waits per [`LOCK_WAIT_BUCKETS`] bucket, the last entry counts
+    /// the waits longer than every bound.
+    pub wait_histogram: [u64; HISTOGRAM_LEN],
+}
+
+impl LockStats {
+    pub(crate) fn acquired(&self) {
+        self.acquisitions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn wait_started(&self) {
+        self.contended.fetch_add(1, Ordering::Relaxed);
+        self.waiters.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn wait_finished(&self, waited: Duration, timed_out: bool) {
+        self.waiters.fetch_sub(1, Ordering::Relaxed);
+        if timed_out {
+            self.timeouts.fetch_add(1, Ordering::Relaxed);
+        }
+        let nanos = u64::try_from(waited.as_nanos()).unwrap_or(u64::MAX);
+        self.wait_nanos.fetch_add(nanos, Ordering::Relaxed);
+        let bucket = LOCK_WAIT_BUCKETS
+            .iter()
+            .position(|bound| waited <= *bound)
+            .unwrap_or(LOCK_WAIT_BUCKETS.len());
+        self.wait_histogram[bucket].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn info(&self) -> LockStatsInfo {
+        LockStatsInfo {
+            acquisitions: self.acquisitions.load(Ordering::Relaxed),
+            contended: self.contended.load(Ordering::Relaxed),
+            timeouts: self.timeouts.load(Ordering::Relaxed),
+            waiters: self.waiters.load(Ordering::Relaxed),
+            total_wait: Duration::from_nanos(self.wait_nanos.load(Ordering::Relaxed)),
+            wait_histogram: std::array::from_fn(|i| self.wait_histogram[i].load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Zeroes the counters. The waiter count describes the present, so it is
+    /// kept.
+    pub fn reset(&self) {
+        self.acquisitions.store(0, Ordering::Relaxed);
+        self.contended.store(0, Ordering::Relaxed);
+        self.timeouts.store(0, Ordering::Relaxed);
+        self.wait_nanos.store(0, Ordering::Relaxed);
+        for bucket in &self.wait_histogram {
+            bucket.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// One contended wait, counted in `stats` from [`Self::start`] until drop so
+/// that a cancelled wait does not stay counted as a waiter.
+pub(crate) struct WaitRecord<'a> {
+    stats: Vec<&'a LockStats>,
+    started: Instant,
+    timed_out: bool,
+}
+
+impl<'a> WaitRecord<'a> {
+    pub(crate) fn start(stats: Vec<&'a LockStats>) -> Self {
+        for s in &stats {
+            s.wait_started();
+        }
+        Self {
+            stats,
+            started: Instant::now(),
+            timed_out: false,
+        }
+    }
+
+    pub(crate) fn timed_out(&mut self) {
+        self.timed_out = true;
+    }
+}
+
+impl Drop for WaitRecord<'_> {
+    fn drop(&mut self) {
+        let waited = self.started.elapsed();
+        for s in &self.stats {
+            s.wait_finished(waited, self.timed_out);
+        }
+    }
+}
+
+/// Counts contended waits per primary key for the most contended rows.
+///
+/// Tracking every key would grow with the table, so this keeps at most
+/// [`HOT_ROW_CAPACITY`] keys. A new key replaces the least contended one and
+/// inherits its count, which over-estimates rarely contended keys but never
+/// drops a row that is contended more often than the ones kept.
+#[derive(Debug)]
+pub(crate) struct HotRows<PrimaryKey> {
+    counts: Mutex<HashMap<PrimaryKey, u64>>,
+}
+
+impl<PrimaryKey> Default for HotRows<PrimaryKey> {
+    fn default() -> Self {
+        Self {
+            counts: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<PrimaryKey> HotRows<PrimaryKey>
+where
+    PrimaryKey: Hash + Eq + Clone,
+{
+    pub(crate) fn record(&self, key: &PrimaryKey) {
+        let mut counts = self.counts.lock();
+        if let Some(count) = counts.get_mut(key) {
+            *count += 1;
+            return;
+        }
+        let mut count = 1;
+        if counts.len() >= HOT_ROW_CAPACITY {
+            let coldest = counts
+                .iter()
+                .min_by_key(|(_, count)| **count)
+                .map(|(key, count)| (key.clone(), *count))
+                .expect("capacity is not zero");
+            counts.remove(&coldest.0);
+            count += coldest.1;
+        }
+        counts.insert(key.clone(), count);
+    }
+
+    /// Returns up to `limit` keys with their contended wait counts, most
+    /// contended first.
+    pub(crate) fn top(&self, limit: usize) -> Vec<(PrimaryKey, u64)> {
+        let mut rows: Vec<_> = self.counts.lock().iter().map(|(k, c)| (k.clone(), *c)).collect();
+        rows.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        rows.truncate(limit);
+        rows
+    }
+
+    pub(crate) fn reset(&self) {
+        self.counts.lock().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waits_land_in_their_bucket() {
+        let stats = LockStats::default();
+        stats.acquired();
+        stats.wait_started();
+        stats.wait_finished(Duration::from_micros(50), false);
+        stats.wait_started();
+        stats.wait_finished(Duration::from_secs(2), true);
+        stats.wait_started();
+
+        let info = stats.info();
+        assert_eq!(info.acquisitions, 1);
+        assert_eq!(info.contended, 3);
+        assert_eq!(info.timeouts, 1);
+        assert_eq!(info.waiters, 1);
+        assert_eq!(info.wait_histogram, [0, 1, 0, 0, 0, 0, 1]);
+
+        stats.reset();
+        let info = stats.info();
+        assert_eq!(info.contended, 0);
+        assert_eq!(info.waiters, 1);
+        assert_eq!(info.wait_histogram, [0; HISTOGRAM_LEN]);
+    }
+
+    #[test]
+    fn hot_rows_keep_most_contended_keys() {
+        let hot_rows = HotRows::default();
+        for _ in 0..10 {
+            hot_rows.record(&0u64);
+        }
+        for key in 1..=(HOT_ROW_CAPACITY as u64 * 2) {
+            hot_rows.record(&key);
+        }
+
+        let top = hot_rows.top(1);
+        assert_eq!(top, vec![(0, 10)]);
+        assert_eq!(hot_rows.top(usize::MAX).len(), HOT_ROW_CAPACITY);
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::future::Future;
+use std::hash::Hash;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+use futures::FutureExt;
+use futures::future::JoinAll;
+
+use crate::WorkTableError;
+use crate::lock::stats::WaitRecord;
+use crate::lock::{Lock, LockMap, LockStats, LockWait, RowLock};
+
+tokio::task_local! {
+    static WITHOUT_WAITING: ();
+}
+
+/// Runs `f` so that every row lock it needs fails with
+/// [`WorkTableError::LockTimeout`] instead of waiting when another operation
+/// holds it. The generated `try_update*` methods wrap their waiting
+/// counterparts in this.
+pub async fn without_waiting<F: Future>(f: F) -> F::Output {
+    WITHOUT_WAITING.scope((), f).await
+}
+
+/// A wait for predecessors that were still held when the operation
+/// registered.
+struct PendingWait<'a> {
+    record: WaitRecord<'a>,
+    locks: Vec<Arc<Lock>>,
+    wait: Pin<Box<JoinAll<LockWait>>>,
+}
+
+impl<LockType, PrimaryKey> LockMap<LockType, PrimaryKey>
+where
+    LockType: RowLock + Send + Sync + 'static,
+    PrimaryKey: Hash + Eq + Debug + Clone + Send + Sync + 'static,
+{
+    /// Waits until every lock in `locks` is released, so the holder of
+    /// `op_lock` owns the row of `key`.
+    ///
+    /// Gives up after [`Self::wait_timeout`], or right away inside
+    /// [`without_waiting`]. By then `op_lock` is registered on the row and
+    /// later operations queue behind it, so it must not be released before
+    /// `locks` are: it is handed to them with [`Lock::release_after`] and
+    /// released together with the last one.
+    ///
+    /// Every call and every wait is counted in the statistics reported by
+    /// [`Self::lock_info`].
+    #[allow(clippy::mutable_key_type)]
+    pub async fn wait_for(
+        self: &Arc<Self>,
+        key: &PrimaryKey,
+        op_lock: &Arc<Lock>,
+        locks: HashSet<Arc<Lock>>,
+    ) -> Result<(), WorkTableError> {
+        let Some(mut pending) = self.start_wait(key, op_lock, locks) else {
+            return Ok(());
+        };
+        let timeout = if WITHOUT_WAITING.try_with(|_| ()).is_ok() {
+            Some(Duration::ZERO)
+        } else {
+            self.wait_timeout()
+        };
+        let acquired = match timeout {
+            None => {
+                (&mut pending.wait).await;
+                true
+            }
+            Some(timeout) if timeout.is_zero() => (&mut pending.wait).now_or_never().is_some(),
+            Some(timeout) => tokio::time::timeout(timeout, &mut pending.wait).await.is_ok(),
+        };
+        self.finish_wait(key, op_lock, pending, acquired)
+    }
+
+    /// Same as [`Self::wait_for`], but parks the calling thread until the
+    /// holders release the row, so it needs no async runtime. Used by the
+    /// generated `*_blocking` methods.
+    #[allow(clippy::mutable_key_type)]
+    pub fn wait_for_blocking(
+        self: &Arc<Self>,
+        key: &PrimaryKey,
+        op_lock: &Arc<Lock>,
+        locks: HashSet<Arc<Lock>>,
+    ) -> Result<(), WorkTableError> {
+        let Some(mut pending) = self.start_wait(key, op_lock, locks) else {
+            return Ok(());
+        };
+        let deadline = self.wait_timeout().map(|timeout| Instant::now() + timeout);
+        let acquired = park_until(&mut pending.wait, deadline);
+        self.finish_wait(key, op_lock, pending, acquired)
+    }
+
+    /// Counts the acquisition and, if a lock in `locks` is still held,
+    /// starts recording the wait for it.
+    #[allow(clippy::mutable_key_type)]
+    fn start_wait(&self, key: &PrimaryKey, op_lock: &Arc<Lock>, locks: HashSet<Arc<Lock>>) -> Option<PendingWait<'_>> {
+        let covers = |columns: &[&str], column: &str| columns.is_empty() || columns.contains(&column);
+        self.stats().acquired();
+        for (_, stats) in self.column_stats().iter().filter(|(c, _)| covers(op_lock.columns(), c)) {
+            stats.acquired();
+        }
+        if locks.iter().all(|l| !l.is_locked()) {
+            return None;
+        }
+
+        let locks: Vec<_> = locks.into_iter().collect();
+        // Columns both this operation and a still running holder lock.
+        let contended: Vec<&LockStats> = self
+            .column_stats()
+            .iter()
+            .filter(|(c, _)| {
+                covers(op_lock.columns(), c) && locks.iter().any(|l| l.is_locked() && covers(l.columns(), c))
+            })
+            .map(|(_, stats)| stats)
+            .collect();
+        self.hot_rows().record(key);
+        let record = WaitRecord::start(contended.into_iter().chain([self.stats()]).collect());
+        op_lock.set_waiting_for(locks.clone());
+        let wait = Box::pin(futures::future::join_all(locks.iter().map(|l| l.wait())));
+        Some(PendingWait { record, locks, wait })
+    }
+
+    fn finish_wait(
+        self: &Arc<Self>,
+        key: &PrimaryKey,
+        op_lock: &Arc<Lock>,
+        pending: PendingWait<'_>,
+        acquired: bool,
+    ) -> Result<(), WorkTableError> {
+        let PendingWait {
+            mut record,
+            locks,
+            wait,
+        } = pending;
+        if acquired {
+            op_lock.set_waiting_for(vec![]);
+            return Ok(());
+        }
+        record.timed_out();
+        drop(record);
+        drop(wait);
+
+        let lock_map = Arc::downgrade(self);
+        let key = key.clone();
+        Lock::release_after(op_lock.clone(), &locks, move || {
+            if let Some(lock_map) = lock_map.upgrade() {
+                lock_map.remove_with_lock_check(&key);
+            }
+        });
+        Err(WorkTableError::LockTimeout)
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Polls `future` on the calling thread, parking it until a waker fires, and
+/// returns whether it completed before `deadline`.
+fn park_until<F: Future + Unpin>(future: &mut F, deadline: Option<Instant>) -> bool {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if Pin::new(&mut *future).poll(&mut cx).is_ready() {
+            return true;
+        }
+        match deadline {
+            None => thread::park(),
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return false;
+                }
+                thread::park_timeout(deadline - now);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::lock::{FullRowLock, HeldLock, LockGuard};
+
+    async fn acquire(lock_map: &Arc<LockMap<FullRowLock, u64>>, pk: u64) -> Result<Arc<Lock>, WorkTableError> {
+        let lock_id = lock_map.next_id();
+        let lock = lock_map.get_or_insert_with(pk, FullRowLock::new);
+        let (locks, op_lock) = lock.write().lock(lock_id);
+        lock_map.wait_for(&pk, &op_lock, locks).await?;
+        Ok(op_lock)
+    }
+
+    fn acquire_blocking(lock_map: &Arc<LockMap<FullRowLock, u64>>, pk: u64) -> Result<Arc<Lock>, WorkTableError> {
+        let lock_id = lock_map.next_id();
+        let lock = lock_map.get_or_insert_with(pk, FullRowLock::new);
+        let (locks, op_lock) = lock.write().lock(lock_id);
+        lock_map.wait_for_blocking(&pk, &op_lock, locks)?;
+        Ok(op_lock)
+    }
+
+    #[tokio::test]
+    async fn timed_out_waiter_keeps_later_operations_queued() {
+        let lock_map: Arc<LockMap<FullRowLock, u64>> = Arc::new(LockMap::default());
+        lock_map.set_wait_timeout(Some(Duration::from_millis(20)));
+        let first = LockGuard::new(acquire(&lock_map, 1).await.unwrap(), lock_map.clone(), 1);
+
+        assert!(matches!(acquire(&lock_map, 1).await, Err(WorkTableError::LockTimeout)));
+        assert!(matches!(
+            without_waiting(acquire(&lock_map, 1)).await,
+            Err(WorkTableError::LockTimeout)
+        ));
+        let held = lock_map.held_locks();
+        assert!(held.contains(&HeldLock {
+            primary_key: 1,
+            holder: 0,
+            columns: &[],
+            waiting_for: vec![],
+        }));
+        assert!(held.iter().any(|l| l.holder == 1 && l.waiting_for == vec![0]));
+
+        // A waiter behind the abandoned ones still waits for the first holder.
+        lock_map.set_wait_timeout(None);
+        let lock_map_clone = lock_map.clone();
+        let waiter = tokio::spawn(async move { acquire(&lock_map_clone, 1).await.map(|_| ()) });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+
+        drop(first);
+        waiter.await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn timed_out_waiters_are_released_with_the_holder() {
+        let lock_map: Arc<LockMap<FullRowLock, u64>> = Arc::new(LockMap::default());
+        lock_map.set_wait_timeout(Some(Duration::from_millis(1)));
+        let first = acquire_blocking(&lock_map, 1).unwrap();
+        let first = LockGuard::new(first, lock_map.clone(), 1);
+
+        for _ in 0..100 {
+            assert!(matches!(
+                acquire_blocking(&lock_map, 1),
+                Err(WorkTableError::LockTimeout)
+            ));
+        }
+        assert_eq!(lock_map.held_locks().len(), 101);
+
+        drop(first);
+        assert!(lock_map.held_locks().is_empty());
+        acquire_blocking(&lock_map, 1).unwrap();
+    }
+
+    #[test]
+    fn blocking_waiter_wakes_when_the_holder_releases() {
+        let lock_map: Arc<LockMap<FullRowLock, u64>> = Arc::new(LockMap::default());
+        let first = LockGuard::new(acquire_blocking(&lock_map, 1).unwrap(), lock_map.clone(), 1);
+
+        let lock_map_clone = lock_map.clone();
+        let waiter = std::thread::spawn(move || acquire_blocking(&lock_map_clone, 1).map(|_| ()));
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(!waiter.is_finished());
+
+        drop(first);
+        waiter.join().unwrap().unwrap();
+    }
+}
@@ -21,14 +21,29 @@ use crate::persistence::OperationType;
 use crate::prelude::OperationId;
 use crate::util::OffsetEqLink;
 use crate::{
-    ArcticIndex, ArcticKey, CongeeIndex, CongeeKey, IndexMultiMap, PersistentArtIndex, PersistentWtiIndex, UniqueIndex,
-    UpstreamIndexMap,
+    ArcticIndex, ArcticKey, ArtMultiIndex, CongeeIndex, CongeeKey, FilteredIndex, HashIndex, IndexMultiMap,
+    PersistentArtIndex, PersistentWtiIndex, UniqueIndex, UpstreamIndexMap,
 };
 use crate::{IndexMap, impl_memstat_zero};
 
 pub trait MemStat {
     fn heap_size(&self) -> usize;
     fn used_size(&self) -> usize;
+
+    /// Releases the spare capacity counted by [`Self::heap_size`], where the
+    /// value can do so through a shared reference.
+    fn shrink_to_fit(&self) {}
+}
+
+/// Index node whose spare capacity [`MemStat::shrink_to_fit`] releases.
+pub trait ShrinkNode {
+    fn shrink_to_fit(&mut self);
+}
+
+impl<T> ShrinkNode for Vec<T> {
+    fn shrink_to_fit(&mut self) {
+        Vec::shrink_to_fit(self)
+    }
 }
 
 impl<T: MemStat> MemStat for Option<T> {
@@ -62,7 +77,7 @@ impl<K, V, Node> MemStat for IndexMap<K, V, Node>
 where
     K: Debug + Ord + Clone + 'static + MemStat + Send,
     V: Debug + Clone + 'static + MemStat + Send,
-    Node: NodeLike<Pair<K, V>> + Send + 'static,
+    Node: NodeLike<Pair<K, V>> + ShrinkNode + Send + 'static,
 {
     fn heap_size(&self) -> usize {
         let slot_size = std::mem::size_of::<Pair<K, V>>();
@@ -81,13 +96,19 @@ where
 
         base + used
     }
+
+    fn shrink_to_fit(&self) {
+        for node in self.iter_nodes() {
+            node.lock().shrink_to_fit();
+        }
+    }
 }
 
 impl<K, V, Node> MemStat for UpstreamIndexMap<K, V, Node>
 where
     K: Debug + Ord + Clone + 'static + MemStat + Send,
     V: Debug + Clone + 'static + MemStat + Send,
-    Node: VanillaNodeLike<VanillaPair<K, V>> + Send + 'static,
+    Node: VanillaNodeLike<VanillaPair<K, V>> + ShrinkNode + Send + 'static,
 {
     fn heap_size(&self) -> usize {
         let slot_size = std::mem::size_of::<VanillaPair<K, V>>();
@@ -102,6 +123,12 @@ where
         let used: usize = self.iter().map(|(k, v)| k.used_size() + v.used_size()).sum();
         base + used
     }
+
+    fn shrink_to_fit(&self) {
+        for node in self.iter_nodes() {
+            node.lock().shrink_to_fit();
+        }
+    }
 }
 
 impl<K, V> MemStat for CongeeIndex<K, V>
@@ -132,6 +159,59 @@ where
     }
 }
 
+impl<K, V> MemStat for HashIndex<K, V>
+where
+    K: Clone + std::hash::Hash + Ord + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+{
+    fn heap_size(&self) -> usize {
+        // Every slot holds an entry and one control byte, occupied or not.
+        self.capacity() * (std::mem::size_of::<(K, V)>() + 1)
+    }
+
+    fn used_size(&self) -> usize {
+        self.len() * std::mem::size_of::<(K, V)>()
+    }
+
+    fn shrink_to_fit(&self) {
+        HashIndex::shrink_to_fit(self)
+    }
+}
+
+impl<K, I> MemStat for FilteredIndex<K, I>
+where
+    K: Clone + std::hash::Hash + Ord,
+    I: UniqueIndex<K, OffsetEqLink> + MemStat,
+{
+    fn heap_size(&self) -> usize {
+        (**self).heap_size() + self.filter_size()
+    }
+
+    fn used_size(&self) -> usize {
+        (**self).used_size() + self.filter_size()
+    }
+
+    fn shrink_to_fit(&self) {
+        (**self).shrink_to_fit();
+        self.rebuild_filter();
+    }
+}
+
+impl<I, K, V> MemStat for ArtMultiIndex<I, K, V>
+where
+    K: Clone + Ord + std::hash::Hash,
+    V: Clone + PartialEq,
+    I: UniqueIndex<K, Vec<V>> + MemStat,
+{
+    fn heap_size(&self) -> usize {
+        self.inner().heap_size() + self.len() * std::mem::size_of::<V>()
+    }
+
+    fn used_size(&self) -> usize {
+        self.inner().used_size() + self.len() * std::mem::size_of::<V>()
+    }
+}
+
 impl<I: MemStat> MemStat for PersistentArtIndex<I> {
     fn heap_size(&self) -> usize {
         self.inner().heap_size()
@@ -140,13 +220,17 @@ impl<I: MemStat> MemStat for PersistentArtIndex<I> {
     fn used_size(&self) -> usize {
         self.inner().used_size()
     }
+
+    fn shrink_to_fit(&self) {
+        self.inner().shrink_to_fit()
+    }
 }
 
 impl<K, V, Node> MemStat for PersistentWtiIndex<K, V, Node>
 where
     K: Debug + Ord + Clone + 'static + MemStat + Send,
     V: Debug + Clone + 'static + MemStat + Send,
-    Node: NodeLike<Pair<K, V>> + Send + 'static,
+    Node: NodeLike<Pair<K, V>> + ShrinkNode + Send + 'static,
 {
     fn heap_size(&self) -> usize {
         self.inner().heap_size()
@@ -155,13 +239,17 @@ where
     fn used_size(&self) -> usize {
         self.inner().used_size()
     }
+
+    fn shrink_to_fit(&self) {
+        self.inner().shrink_to_fit()
+    }
 }
 
 impl<K, V, Node> MemStat for IndexMultiMap<K, V, Node>
 where
     K: Debug + Ord + Clone + 'static + MemStat + Send,
     V: Debug + Ord + Clone + 'static + MemStat + Send,
-    Node: NodeLike<MultiPair<K, V>> + Send + 'static,
+    Node: NodeLike<MultiPair<K, V>> + ShrinkNode + Send + 'static,
 {
     fn heap_size(&self) -> usize {
         let slot_size = std::mem::size_of::<MultiPair<K, V>>();
@@ -180,6 +268,12 @@ where
 
         base + used
     }
+
+    fn shrink_to_fit(&self) {
+        for node in self.iter_nodes() {
+            node.lock().shrink_to_fit();
+        }
+    }
 }
 
 impl<T: MemStat> MemStat for Box<T> {
@@ -189,6 +283,9 @@ impl<T: MemStat> MemStat for Box<T> {
     fn used_size(&self) -> usize {
         std::mem::size_of::<T>() + (**self).used_size()
     }
+    fn shrink_to_fit(&self) {
+        (**self).shrink_to_fit()
+    }
 }
 
 impl<T: MemStat> MemStat for Arc<T> {
@@ -198,6 +295,9 @@ impl<T: MemStat> MemStat for Arc<T> {
     fn used_size(&self) -> usize {
         std::mem::size_of::<T>() + (**self).used_size()
     }
+    fn shrink_to_fit(&self) {
+        (**self).shrink_to_fit()
+    }
 }
 
 impl<T: MemStat> MemStat for Rc<T> {
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fs;
 use std::future::Future;
@@ -6,16 +7,19 @@ use std::marker::PhantomData;
 use std::panic::{AssertUnwindSafe, resume_unwind};
 use std::path::Path;
 
+use data_bucket::Link;
 use futures::future::Either;
 use futures::stream::FuturesUnordered;
 use futures::{FutureExt, StreamExt};
 
 use crate::TableSecondaryIndexEventsOps;
+use crate::in_memory::PageCompression;
 use crate::persistence::operation::{BatchOperation, Operation};
+use crate::persistence::space::BatchChangeEvent;
 use crate::persistence::{
     PersistenceConfig, PersistenceEngine, PersistenceLoadError, SpaceDataOps, SpaceIndexOps, SpaceSecondaryIndexOps,
 };
-use crate::prelude::{PrimaryKeyGeneratorState, TablePrimaryKey, WT_DATA_EXTENSION};
+use crate::prelude::{PrimaryKeyGeneratorState, TablePrimaryKey, WT_DATA_EXTENSION, WT_INDEX_EXTENSION};
 
 fn classify_existing_store_error<T>(path: &str, existed: bool, result: eyre::Result<T>) -> eyre::Result<T> {
     result.map_err(|error| {
@@ -27,6 +31,78 @@ fn classify_existing_store_error<T>(path: &str, existed: bool, result: eyre::Res
     })
 }
 
+/// Splits an index-only schema change into the names of added and dropped
+/// secondary indexes. An index that keeps its name but changes its key type
+/// is neither: its entries cannot be reused, so the change needs a migration.
+fn secondary_index_changes(
+    stored: &[(String, String)],
+    generated: &[(String, String)],
+) -> eyre::Result<(Vec<String>, Vec<String>)> {
+    for (name, key_type) in generated {
+        if let Some((_, stored_type)) = stored.iter().find(|(stored_name, _)| stored_name == name)
+            && stored_type != key_type
+        {
+            return Err(eyre::eyre!(
+                "secondary index `{name}` changed its key type from {stored_type} to {key_type}; rename the index or migrate the table"
+            ));
+        }
+    }
+    let added = generated
+        .iter()
+        .filter(|(name, _)| !stored.iter().any(|(stored_name, _)| stored_name == name))
+        .map(|(name, _)| name.clone())
+        .collect();
+    let dropped = stored
+        .iter()
+        .filter(|(name, _)| !generated.iter().any(|(generated_name, _)| generated_name == name))
+        .map(|(name, _)| name.clone())
+        .collect();
+    Ok((added, dropped))
+}
+
+/// Splits the rows that the link `changes` of an operation or batch touch,
+/// in order, into those they retire and those still referenced, given the
+/// `written` row links of the same operation or batch. A retired row is one
+/// that was referenced before the changes or whose bytes were written with
+/// them; a row both inserted and removed without being written never reached
+/// the file.
+fn retired_links(changes: Vec<(Link, bool)>, written: &[Link]) -> (Vec<Link>, Vec<Link>) {
+    // Link -> (first change was a removal, last change was a removal).
+    let mut states = HashMap::<Link, (bool, bool)>::new();
+    for (link, removed) in changes {
+        states.entry(link).or_insert((removed, removed)).1 = removed;
+    }
+    let retired = states
+        .iter()
+        .filter(|(link, (first_removed, last_removed))| *last_removed && (*first_removed || written.contains(link)))
+        .map(|(link, _)| *link)
+        .collect();
+    let live = written
+        .iter()
+        .filter(|link| !states.get(link).is_some_and(|(_, last_removed)| *last_removed))
+        .copied()
+        .collect();
+    (retired, live)
+}
+
+async fn remove_file_if_exists(path: &str) -> eyre::Result<()> {
+    match tokio::fs::remove_file(path).await {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Deletes every `.wt.idx` file of a table, leaving `.wt.data` in place.
+pub async fn remove_index_files(table_path: &str) -> eyre::Result<()> {
+    let mut entries = tokio::fs::read_dir(table_path).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        if entry.file_name().to_string_lossy().ends_with(WT_INDEX_EXTENSION) {
+            tokio::fs::remove_file(entry.path()).await?;
+        }
+    }
+    Ok(())
+}
+
 async fn load_store_component<T, F>(path: &str, existed: bool, future: F) -> eyre::Result<T>
 where
     F: Future<Output = eyre::Result<T>>,
@@ -105,9 +181,48 @@ pub struct DiskPersistenceEngine<
     pub primary_index: SpacePrimaryIndex,
     pub secondary_indexes: SpaceSecondaryIndexes,
     created_data_file: bool,
+    page_compression: PageCompression,
+    /// Position of the rows' deleted flag, see
+    /// [`PersistenceEngine::set_deleted_flag_offset`]. Zero while unknown.
+    deleted_flag_offset: u32,
     phantom_data: PhantomData<(PrimaryKey, SecondaryIndexEvents, PrimaryKeyGenState, AvailableIndexes)>,
 }
 
+impl<
+    SpaceData,
+    SpacePrimaryIndex,
+    SpaceSecondaryIndexes,
+    PrimaryKey,
+    SecondaryIndexEvents,
+    AvailableIndexes,
+    PrimaryKeyGenState,
+>
+    DiskPersistenceEngine<
+        SpaceData,
+        SpacePrimaryIndex,
+        SpaceSecondaryIndexes,
+        PrimaryKey,
+        SecondaryIndexEvents,
+        AvailableIndexes,
+        PrimaryKeyGenState,
+    >
+where
+    PrimaryKey: TablePrimaryKey,
+    <PrimaryKey as TablePrimaryKey>::Generator: PrimaryKeyGeneratorState,
+    SpaceData: SpaceDataOps<PrimaryKeyGenState> + Send,
+{
+    /// Flags the `retired` rows deleted in the data file, so the data pages
+    /// alone tell which rows are live when the indexes are rebuilt.
+    async fn mark_retired_rows(&mut self, retired: Vec<Link>, live: Vec<Link>) -> eyre::Result<()> {
+        if self.deleted_flag_offset == 0 || retired.is_empty() {
+            return Ok(());
+        }
+        self.data
+            .mark_rows_deleted(retired, live, self.deleted_flag_offset)
+            .await
+    }
+}
+
 impl<
     SpaceData,
     SpacePrimaryIndex,
@@ -174,6 +289,8 @@ where
             primary_index,
             secondary_indexes,
             created_data_file,
+            page_compression: PageCompression::None,
+            deleted_flag_offset: 0,
             phantom_data: PhantomData,
         })
     }
@@ -182,8 +299,10 @@ where
         &mut self,
         op: Operation<PrimaryKeyGenState, PrimaryKey, SecondaryIndexEvents>,
     ) -> eyre::Result<()> {
+        let changes = op.link_changes();
         match op {
             Operation::Insert(insert) => {
+                let (retired, live) = retired_links(changes, &[insert.link]);
                 self.data.save_data(insert.link, insert.bytes.as_ref()).await?;
                 for event in insert.primary_key_events {
                     self.primary_index.process_change_event(event).await?;
@@ -193,24 +312,29 @@ where
                 self.data.save_info().await?;
                 self.secondary_indexes
                     .process_change_events(insert.secondary_keys_events)
-                    .await
+                    .await?;
+                self.mark_retired_rows(retired, live).await
             }
             Operation::Update(update) => {
+                let (retired, live) = retired_links(changes, &[update.link]);
                 self.data.save_data(update.link, update.bytes.as_ref()).await?;
                 for event in update.primary_key_events {
                     self.primary_index.process_change_event(event).await?;
                 }
                 self.secondary_indexes
                     .process_change_events(update.secondary_keys_events)
-                    .await
+                    .await?;
+                self.mark_retired_rows(retired, live).await
             }
             Operation::Delete(delete) => {
+                let (retired, live) = retired_links(changes, &[]);
                 for event in delete.primary_key_events {
                     self.primary_index.process_change_event(event).await?;
                 }
                 self.secondary_indexes
                     .process_change_events(delete.secondary_keys_events)
-                    .await
+                    .await?;
+                self.mark_retired_rows(retired, live).await
             }
             Operation::Acknowledge(_) => {
                 // Acknowledge operations carry orphaned events for sequence continuity.
@@ -226,6 +350,11 @@ where
         let batch_data_op = batch_op.get_batch_data_op()?;
 
         let (pk_evs, secondary_evs) = batch_op.get_indexes_evs()?;
+        let written = batch_data_op
+            .values()
+            .flat_map(|writes| writes.iter().map(|(link, _)| *link))
+            .collect::<Vec<_>>();
+        let (retired, live) = retired_links(batch_op.link_changes(), &written);
         {
             let data = &mut self.data;
             let primary_index = &mut self.primary_index;
@@ -282,13 +411,42 @@ where
             self.data.save_info().await?;
         }
 
-        Ok(())
+        self.mark_retired_rows(retired, live).await
     }
 
     async fn reclaim_data_pages(&mut self, page_ids: Vec<data_bucket::page::PageId>) -> eyre::Result<()> {
         self.data.reclaim_data_pages(page_ids).await
     }
 
+    async fn truncate(&mut self) -> eyre::Result<()> {
+        let info = self.data.get_mut_info().inner.clone();
+
+        let mut entries = tokio::fs::read_dir(&self.config.tables_path).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+            if file_name.ends_with(WT_DATA_EXTENSION) || file_name.ends_with(WT_INDEX_EXTENSION) {
+                tokio::fs::remove_file(entry.path()).await?;
+            }
+        }
+
+        // Reopening bootstraps fresh files with empty tables of contents.
+        self.data = SpaceData::from_table_files_path(self.config.tables_path.clone(), self.config.version).await?;
+        self.data.set_page_compression(self.page_compression);
+        self.primary_index =
+            SpacePrimaryIndex::primary_from_table_files_path(self.config.tables_path.clone(), self.config.version)
+                .await?;
+        self.secondary_indexes =
+            SpaceSecondaryIndexes::from_table_files_path(self.config.tables_path.clone(), self.config.version).await?;
+
+        let new_info = self.data.get_mut_info();
+        new_info.inner.pk_gen_state = info.pk_gen_state;
+        new_info.inner.row_schema = info.row_schema;
+        new_info.inner.primary_key_fields = info.primary_key_fields;
+        new_info.inner.secondary_index_types = info.secondary_index_types;
+        self.data.save_info().await
+    }
+
     async fn ensure_schema(
         &mut self,
         row_schema: Vec<(String, String)>,
@@ -347,10 +505,7 @@ where
             return Ok(());
         }
 
-        if info.inner.row_schema != row_schema
-            || info.inner.primary_key_fields != primary_key_fields
-            || info.inner.secondary_index_types != secondary_index_types
-        {
+        if info.inner.row_schema != row_schema || info.inner.primary_key_fields != primary_key_fields {
             return Err(eyre::eyre!(
                 "persisted schema mismatch for {}: stored row schema {:?}, primary key {:?}, indexes {:?}; generated row schema {:?}, primary key {:?}, indexes {:?}",
                 info.inner.name,
@@ -363,9 +518,66 @@ where
             ));
         }
 
+        if info.inner.secondary_index_types != secondary_index_types {
+            let (added, _) = secondary_index_changes(&info.inner.secondary_index_types, &secondary_index_types)?;
+            // An interrupted migration may have left part of an added index
+            // on disk. The load rebuilds it from the rows, so start it empty.
+            for name in added {
+                remove_file_if_exists(&format!("{}/{name}{WT_INDEX_EXTENSION}", self.config.tables_path)).await?;
+            }
+            self.secondary_indexes =
+                SpaceSecondaryIndexes::from_table_files_path(self.config.tables_path.clone(), self.config.version)
+                    .await?;
+        }
+
         Ok(())
     }
 
+    async fn migrate_secondary_indexes(
+        &mut self,
+        secondary_index_types: Vec<(String, String)>,
+        events: SecondaryIndexEvents,
+    ) -> eyre::Result<()> {
+        let (_, dropped) = secondary_index_changes(
+            &self.data.get_mut_info().inner.secondary_index_types,
+            &secondary_index_types,
+        )?;
+        self.secondary_indexes.process_change_event_batch(events).await?;
+        for name in dropped {
+            remove_file_if_exists(&format!("{}/{name}{WT_INDEX_EXTENSION}", self.config.tables_path)).await?;
+        }
+        self.data.get_mut_info().inner.secondary_index_types = secondary_index_types;
+        self.data.save_info().await
+    }
+
+    async fn rebuild_indexes(
+        &mut self,
+        primary_key_events: BatchChangeEvent<PrimaryKey>,
+        secondary_index_events: SecondaryIndexEvents,
+    ) -> eyre::Result<()> {
+        remove_index_files(&self.config.tables_path).await?;
+        self.primary_index =
+            SpacePrimaryIndex::primary_from_table_files_path(self.config.tables_path.clone(), self.config.version)
+                .await?;
+        self.secondary_indexes =
+            SpaceSecondaryIndexes::from_table_files_path(self.config.tables_path.clone(), self.config.version).await?;
+        self.primary_index
+            .process_change_event_batch(primary_key_events)
+            .await?;
+        self.secondary_indexes
+            .process_change_event_batch(secondary_index_events)
+            .await
+    }
+
+    fn set_page_compression(&mut self, compression: PageCompression) {
+        self.page_compression = compression;
+        self.data.set_page_compression(compression);
+    }
+
+    fn set_deleted_flag_offset(&mut self, offset: u32) {
+        self.deleted_flag_offset = offset;
+    }
+
     fn config(&self) -> &DiskConfig {
         &self.config
     }
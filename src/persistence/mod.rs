@@ -2,10 +2,12 @@ use std::future::Future;
 
 use data_bucket::page::PageId;
 
+use crate::in_memory::PageCompression;
 use crate::persistence::operation::BatchOperation;
 
 pub use engine::DiskConfig;
 pub use engine::DiskPersistenceEngine;
+pub use engine::remove_index_files;
 pub use error::{
     PersistenceError, PersistenceIndexCorruption, PersistenceLoadError, PersistenceResult, PersistenceState,
     load_persisted_state,
@@ -16,9 +18,11 @@ pub use operation::{
 };
 pub use readonly_engine::ReadOnlyPersistenceEngine;
 pub use space::{
-    ArtPersistenceKey, IndexTableOfContents, SpaceArcticIndex, SpaceCongeeIndex, SpaceData, SpaceDataOps, SpaceIndex,
+    ArtPersistenceKey, BatchChangeEvent, IndexTableOfContents, SpaceArcticIndex, SpaceArcticMultiIndex,
+    SpaceCongeeIndex, SpaceCongeeMultiIndex, SpaceData, SpaceDataOps, SpaceDataPages, SpaceHashIndex, SpaceIndex,
     SpaceIndexOps, SpaceIndexUnsized, SpaceLogicalIndex, SpaceLogicalIndexUnsized, SpaceSecondaryIndexOps,
-    map_index_pages_to_toc_and_general, map_unsized_index_pages_to_toc_and_general, reconstruct_multi_index_nodes,
+    UnpersistedPages, map_index_pages_to_toc_and_general, map_unsized_index_pages_to_toc_and_general,
+    reconstruct_multi_index_nodes,
 };
 pub use task::{PersistenceMonitor, PersistenceTask};
 
@@ -41,7 +45,8 @@ pub trait PersistenceConfig {
 /// Normal application opens must use [`LoadMode::Strict`], which is also the
 /// default used by [`PersistedWorkTable::load`]. Recovery tools may use
 /// [`LoadMode::Recovery`] on a private copy of a rejected store to read rows
-/// through a surviving index and rebuild them into a fresh table.
+/// through a surviving index and rebuild them into a fresh table, or
+/// [`LoadMode::Rebuild`] to rebuild every index from the data pages.
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub enum LoadMode {
     /// Reject disagreement between the primary index, secondary indexes, and
@@ -54,6 +59,21 @@ pub enum LoadMode {
     /// per-entry key/link validation. It is only for offline recovery; never
     /// use the returned table to serve live traffic.
     Recovery,
+    /// Load indexes eagerly and read each data page from the `.wt.data` file
+    /// on its first access.
+    ///
+    /// Index agreement is checked as in [`LoadMode::Strict`], but rows are
+    /// not decoded up front, since that would read every page. Paired with a
+    /// memory budget that evicts, only recently used pages stay resident.
+    Lazy,
+    /// Ignore the `.wt.idx` files and rebuild the primary and every secondary
+    /// index from the rows in `.wt.data`, then write the rebuilt indexes.
+    ///
+    /// Rows are found and validated by checked decoding, without an index.
+    /// The load fails before anything is written if a row has a duplicate
+    /// unique key or written bytes hold no row; the generated
+    /// `rebuild_indexes` lists those instead and rebuilds the rest.
+    Rebuild,
 }
 
 pub trait PersistedWorkTable<E>: Sized
@@ -101,6 +121,16 @@ pub trait PersistenceEngine<PrimaryKeyGenState, PrimaryKey, SecondaryIndexEvents
         async { Ok(()) }
     }
 
+    /// Drops every persisted row and index entry, keeping the table info
+    /// page with its primary key generator state and schema.
+    ///
+    /// The persistence task invokes this only after every operation queued
+    /// before the truncate has reached the engine. The default rejects the
+    /// request, so custom engines never silently keep truncated rows.
+    fn truncate(&mut self) -> impl Future<Output = eyre::Result<()>> + Send {
+        async { Err(eyre::eyre!("truncate is not supported by this persistence engine")) }
+    }
+
     /// Installs the generated table schema and rejects a non-empty schema that
     /// belongs to a different table shape.
     /// Custom engines may keep the default no-op when they do not expose
@@ -118,8 +148,10 @@ pub trait PersistenceEngine<PrimaryKeyGenState, PrimaryKey, SecondaryIndexEvents
     ///
     /// Disk engines leave legacy stores with empty schema metadata unchanged,
     /// so opening an old database does not mutate it as a side effect. A newly
-    /// created store may install its schema from this method. Custom engines
-    /// may keep the default no-op.
+    /// created store may install its schema from this method. Disk engines
+    /// accept added or dropped secondary indexes, which the load then applies
+    /// through [`Self::migrate_secondary_indexes`]. Custom engines may keep
+    /// the default no-op.
     fn validate_schema(
         &mut self,
         _row_schema: Vec<(String, String)>,
@@ -129,5 +161,60 @@ pub trait PersistenceEngine<PrimaryKeyGenState, PrimaryKey, SecondaryIndexEvents
         async { Ok(()) }
     }
 
+    /// Records a changed secondary index list without rewriting the rows.
+    ///
+    /// The generated load calls this after [`Self::validate_schema`] accepted
+    /// a store whose rows and primary key match but whose indexes were added
+    /// or dropped. `events` fill the added indexes from the loaded rows. The
+    /// engine writes them, drops the files of removed indexes, and only then
+    /// stores the new list, so an interrupted migration is redone on the next
+    /// load. The default rejects the change.
+    fn migrate_secondary_indexes(
+        &mut self,
+        _secondary_index_types: Vec<(String, String)>,
+        _events: SecondaryIndexEvents,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        async {
+            Err(eyre::eyre!(
+                "adding or dropping secondary indexes is not supported by this persistence engine"
+            ))
+        }
+    }
+
+    /// Replaces every persisted index with one rebuilt from the rows.
+    ///
+    /// The generated load calls this for [`LoadMode::Rebuild`] with the events
+    /// that fill empty primary and secondary indexes from the rows found in
+    /// the data pages. The engine discards its index files, whatever state
+    /// they are in, and writes the events into fresh ones. The default rejects
+    /// the rebuild.
+    fn rebuild_indexes(
+        &mut self,
+        _primary_key_events: BatchChangeEvent<PrimaryKey>,
+        _secondary_index_events: SecondaryIndexEvents,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        async {
+            Err(eyre::eyre!(
+                "rebuilding indexes is not supported by this persistence engine"
+            ))
+        }
+    }
+
+    /// Sets how the engine stores data pages that are no longer the last one.
+    ///
+    /// Generated tables call this with their configured compression before
+    /// any operation is applied. Custom engines may keep the default no-op and
+    /// store every page raw.
+    fn set_page_compression(&mut self, _compression: PageCompression) {}
+
+    /// Sets where a stored row keeps its deleted flag, in bytes back from the
+    /// end of the row.
+    ///
+    /// Disk engines set this flag in the data file for every row that primary
+    /// key events retire, so [`LoadMode::Rebuild`] can tell live rows from
+    /// deleted ones without an index. Generated tables call this before any
+    /// operation is applied. Custom engines may keep the default no-op.
+    fn set_deleted_flag_offset(&mut self, _offset: u32) {}
+
     fn config(&self) -> &Self::Config;
 }
@@ -455,6 +455,16 @@ where
     pub fn get_batch_data_op(&self) -> eyre::Result<BatchData> {
         Ok(latest_data_writes(&self.ops))
     }
+
+    /// [`Operation::link_changes`] of every operation, in operation order.
+    pub fn link_changes(&self) -> Vec<(Link, bool)> {
+        let mut order = (0..self.ops.len()).collect::<Vec<_>>();
+        order.sort_by_key(|sequence| self.ops[*sequence].operation_id());
+        order
+            .into_iter()
+            .flat_map(|sequence| self.ops[sequence].link_changes())
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -473,6 +483,7 @@ mod tests {
             pk_gen_state: (),
             bytes,
             link,
+            replaced: None,
         })
     }
 
@@ -484,6 +495,7 @@ mod tests {
             pk_gen_state: (),
             bytes,
             link,
+            replaced: None,
         })
     }
 
@@ -60,6 +60,30 @@ impl<PrimaryKeyGenState, PrimaryKey, SecondaryKeys> Operation<PrimaryKeyGenState
         }
     }
 
+    /// The link a moved row left behind, paired with the last primary key
+    /// event of the move.
+    /// Row links this operation makes referenced (`false`) or unreferenced
+    /// (`true`), in the order it does so: its primary key events, then the
+    /// link it writes, then the link a moved row left behind.
+    pub fn link_changes(&self) -> Vec<(Link, bool)> {
+        let (events, written, replaced) = match &self {
+            Operation::Insert(insert) => (&insert.primary_key_events, Some(insert.link), insert.replaced),
+            Operation::Update(update) => (&update.primary_key_events, Some(update.link), None),
+            Operation::Delete(delete) => (&delete.primary_key_events, None, None),
+            Operation::Acknowledge(_) => return Vec::new(),
+        };
+        events
+            .iter()
+            .filter_map(|event| match event {
+                ChangeEvent::InsertAt { value, .. } => Some((value.value, false)),
+                ChangeEvent::RemoveAt { value, .. } => Some((value.value, true)),
+                _ => None,
+            })
+            .chain(written.map(|link| (link, false)))
+            .chain(replaced.map(|link| (link, true)))
+            .collect()
+    }
+
     pub fn bytes(&self) -> Option<&[u8]> {
         match &self {
             Operation::Insert(insert) => Some(&insert.bytes),
@@ -117,6 +141,9 @@ pub struct InsertOperation<PrimaryKeyGenState, PrimaryKey, SecondaryKeys> {
     pub pk_gen_state: PrimaryKeyGenState,
     pub bytes: Vec<u8>,
     pub link: Link,
+    /// Link of the row version this insert replaces when an update moves a
+    /// row. Its primary key events only carry the new link.
+    pub replaced: Option<Link>,
 }
 
 #[derive(Clone, Debug)]
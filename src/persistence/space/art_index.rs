@@ -5,6 +5,13 @@
 //! addresses are process-local. Compaction reconstructs a temporary native ART,
 //! applies the WAL, writes a new native checkpoint atomically, and drops the
 //! temporary tree; no duplicate ART is retained during normal operation.
+//!
+//! Non-unique indexes store the compact link list of every key as its ART
+//! value. Their WAL records carry the affected link, so a `Remove` takes one
+//! link out of the list instead of dropping the whole key.
+//!
+//! Hash indexes reuse the file layout and WAL. A hash map has no topology to
+//! preserve, so their checkpoint is a plain list of key images and links.
 
 use std::fmt::Debug;
 use std::hash::Hash;
@@ -19,8 +26,9 @@ use tokio::fs::{File, OpenOptions};
 use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 
 use crate::index::{
-    ArcticIndex, ArcticKey, CongeeIndex, CongeeKey, PersistentArcticIndex, PersistentArtIndex, PersistentCongeeIndex,
-    UniqueIndex,
+    ArcticIndex, ArcticKey, ArtKeyEncode, ArtMultiIndex, CongeeIndex, CongeeKey, HashIndex, PersistentArcticIndex,
+    PersistentArcticMultiIndex, PersistentArtIndex, PersistentCongeeIndex, PersistentCongeeMultiIndex,
+    PersistentHashIndex, UniqueIndex,
 };
 use crate::persistence::SpaceIndexOps;
 use crate::persistence::space::BatchChangeEvent;
@@ -38,6 +46,9 @@ const COMPACT_WAL_BYTES: u64 = 4 * 1024 * 1024;
 enum Backend {
     Arctic = 1,
     Congee = 2,
+    ArcticMulti = 3,
+    CongeeMulti = 4,
+    Hash = 5,
 }
 
 impl Backend {
@@ -45,6 +56,9 @@ impl Backend {
         match byte {
             1 => Ok(Self::Arctic),
             2 => Ok(Self::Congee),
+            3 => Ok(Self::ArcticMulti),
+            4 => Ok(Self::CongeeMulti),
+            5 => Ok(Self::Hash),
             _ => bail!("unknown ART backend tag {byte}"),
         }
     }
@@ -52,8 +66,8 @@ impl Backend {
 
 /// Stable fixed-width codec used by logical ART WAL records.
 ///
-/// Generated single-column primary-key newtypes delegate this contract to
-/// their supported unsigned integer field.
+/// Every [`ArtKeyEncode`] key implements it with its order-preserving image,
+/// which for unsigned integers is their big-endian bytes.
 pub trait ArtPersistenceKey: Clone + Debug + Eq + Hash + Ord + Send + Sync + 'static {
     /// Number of key bytes written to the WAL.
     const WIDTH: u8;
@@ -65,33 +79,35 @@ pub trait ArtPersistenceKey: Clone + Debug + Eq + Hash + Ord + Send + Sync + 'st
     fn decode_art_key(bytes: &[u8]) -> eyre::Result<Self>;
 }
 
-macro_rules! impl_art_persistence_key {
-    ($($type:ty),+ $(,)?) => {
-        $(
-            impl ArtPersistenceKey for $type {
-                const WIDTH: u8 = std::mem::size_of::<Self>() as u8;
+impl<K> ArtPersistenceKey for K
+where
+    K: ArtKeyEncode + Clone + Debug + Eq + Hash + Ord + Send + Sync + 'static,
+{
+    const WIDTH: u8 = {
+        assert!(<K as ArtKeyEncode>::WIDTH <= u8::MAX as usize);
+        <K as ArtKeyEncode>::WIDTH as u8
+    };
 
-                fn encode_art_key(&self, output: &mut Vec<u8>) {
-                    output.extend_from_slice(&self.to_be_bytes());
-                }
+    fn encode_art_key(&self, output: &mut Vec<u8>) {
+        let start = output.len();
+        output.resize(start + <K as ArtKeyEncode>::WIDTH, 0);
+        self.encode_art(&mut output[start..]);
+    }
 
-                fn decode_art_key(bytes: &[u8]) -> eyre::Result<Self> {
-                    let bytes: [u8; std::mem::size_of::<Self>()] = bytes
-                        .try_into()
-                        .map_err(|_| eyre!("invalid {}-byte ART key", Self::WIDTH))?;
-                    Ok(Self::from_be_bytes(bytes))
-                }
-            }
-        )+
-    };
+    fn decode_art_key(bytes: &[u8]) -> eyre::Result<Self> {
+        if bytes.len() != <K as ArtKeyEncode>::WIDTH {
+            bail!("invalid {}-byte ART key", <K as ArtKeyEncode>::WIDTH);
+        }
+        Ok(Self::decode_art(bytes))
+    }
 }
 
-impl_art_persistence_key!(u8, u16, u32, u64, u128, usize);
-
 #[derive(Clone, Debug, Eq, PartialEq)]
 enum WalOp {
     Set(Link),
-    Remove,
+    /// Unique indexes remove the whole key and ignore the link; non-unique
+    /// indexes remove only this link from the key's list.
+    Remove(Link),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -280,19 +296,18 @@ impl<K: ArtPersistenceKey> ArtFile<K> {
 fn encode_wal_record<K: ArtPersistenceKey>(record: &WalRecord<K>) -> Vec<u8> {
     let mut bytes = Vec::with_capacity(9 + K::WIDTH as usize + 12);
     bytes.extend_from_slice(&record.event_id.to_le_bytes());
-    match record.op {
-        WalOp::Set(_) => bytes.push(1),
-        WalOp::Remove => bytes.push(2),
-    }
-    record.key.encode_art_key(&mut bytes);
     let link = match record.op {
-        WalOp::Set(link) => link,
-        WalOp::Remove => Link::default(),
+        WalOp::Set(link) => {
+            bytes.push(1);
+            link
+        }
+        WalOp::Remove(link) => {
+            bytes.push(2);
+            link
+        }
     };
-    let page_id: usize = link.page_id.into();
-    bytes.extend_from_slice(&(page_id as u32).to_le_bytes());
-    bytes.extend_from_slice(&link.offset.to_le_bytes());
-    bytes.extend_from_slice(&link.length.to_le_bytes());
+    record.key.encode_art_key(&mut bytes);
+    link.encode_topology_value(&mut bytes);
     bytes
 }
 
@@ -305,16 +320,10 @@ fn decode_wal_record<K: ArtPersistenceKey>(bytes: &[u8]) -> eyre::Result<WalReco
     let operation = bytes[8];
     let key_end = 9 + K::WIDTH as usize;
     let key = K::decode_art_key(&bytes[9..key_end])?;
-    let page_id = u32::from_le_bytes(bytes[key_end..key_end + 4].try_into().unwrap());
-    let offset = u32::from_le_bytes(bytes[key_end + 4..key_end + 8].try_into().unwrap());
-    let length = u32::from_le_bytes(bytes[key_end + 8..key_end + 12].try_into().unwrap());
+    let link = Decoder::new(&bytes[key_end..]).link()?;
     let op = match operation {
-        1 => WalOp::Set(Link {
-            page_id: PageId::from(page_id),
-            offset,
-            length,
-        }),
-        2 => WalOp::Remove,
+        1 => WalOp::Set(link),
+        2 => WalOp::Remove(link),
         _ => bail!("invalid ART WAL operation {operation}"),
     };
     Ok(WalRecord { event_id, key, op })
@@ -340,7 +349,7 @@ fn logical_record<K: ArtPersistenceKey>(event: ChangeEvent<Pair<K, Link>>) -> ey
         } if index == 0 && max_value == value => Ok(WalRecord {
             event_id: event_id.inner(),
             key: value.key,
-            op: WalOp::Remove,
+            op: WalOp::Remove(value.value),
         }),
         _ => bail!("native ART persistence received a structural WorkTablesIndex event"),
     }
@@ -357,13 +366,31 @@ where
             WalOp::Set(link) => {
                 index.insert_value(record.key.clone(), wrap(link));
             }
-            WalOp::Remove => {
+            WalOp::Remove(_) => {
                 index.remove_value(&record.key);
             }
         }
     }
 }
 
+fn apply_multi_wal<K, V, I>(index: &ArtMultiIndex<I, K, V>, wal: &[WalRecord<K>], wrap: impl Fn(Link) -> V)
+where
+    K: ArtPersistenceKey,
+    V: Clone + PartialEq,
+    I: UniqueIndex<K, Vec<V>>,
+{
+    for record in wal {
+        match record.op {
+            WalOp::Set(link) => {
+                index.insert(record.key.clone(), wrap(link));
+            }
+            WalOp::Remove(link) => {
+                index.remove(&record.key, &wrap(link));
+            }
+        }
+    }
+}
+
 /// Disk-side Arctic checkpoint and WAL state.
 #[derive(Debug)]
 pub struct SpaceArcticIndex<K: ArtPersistenceKey, const INNER_PAGE_SIZE: u32> {
@@ -416,56 +443,6 @@ where
     }
 }
 
-impl<K, const INNER_PAGE_SIZE: u32> SpaceIndexOps<K> for SpaceArcticIndex<K, INNER_PAGE_SIZE>
-where
-    K: ArtPersistenceKey + ArcticKey,
-    K::Raw: arctic::topology::Key,
-{
-    async fn primary_from_table_files_path<S: AsRef<str> + Send>(path: S, version: u32) -> eyre::Result<Self> {
-        Self::new(
-            PathBuf::from(format!("{}/primary{}", path.as_ref(), WT_INDEX_EXTENSION)),
-            version,
-        )
-        .await
-    }
-
-    async fn secondary_from_table_files_path<S1: AsRef<str> + Send, S2: AsRef<str> + Send>(
-        path: S1,
-        name: S2,
-        version: u32,
-    ) -> eyre::Result<Self> {
-        Self::new(
-            PathBuf::from(format!("{}/{}{}", path.as_ref(), name.as_ref(), WT_INDEX_EXTENSION)),
-            version,
-        )
-        .await
-    }
-
-    async fn bootstrap(_: &mut File, _: String, _: u32) -> eyre::Result<()> {
-        Ok(())
-    }
-
-    async fn process_change_event(&mut self, event: ChangeEvent<Pair<K, Link>>) -> eyre::Result<()> {
-        self.file.append(&[logical_record(event)?]).await?;
-        if self.file.should_compact() {
-            self.compact().await?;
-        }
-        Ok(())
-    }
-
-    async fn process_change_event_batch(&mut self, events: BatchChangeEvent<K>) -> eyre::Result<()> {
-        let records = events
-            .into_iter()
-            .map(logical_record)
-            .collect::<eyre::Result<Vec<_>>>()?;
-        self.file.append(&records).await?;
-        if self.file.should_compact() {
-            self.compact().await?;
-        }
-        Ok(())
-    }
-}
-
 /// Disk-side Congee checkpoint and WAL state.
 #[derive(Debug)]
 pub struct SpaceCongeeIndex<K: ArtPersistenceKey, const INNER_PAGE_SIZE: u32> {
@@ -517,60 +494,306 @@ where
     }
 }
 
-impl<K, const INNER_PAGE_SIZE: u32> SpaceIndexOps<K> for SpaceCongeeIndex<K, INNER_PAGE_SIZE>
+/// Disk-side checkpoint and WAL state of a non-unique Arctic index.
+#[derive(Debug)]
+pub struct SpaceArcticMultiIndex<K: ArtPersistenceKey, const INNER_PAGE_SIZE: u32> {
+    file: ArtFile<K>,
+}
+
+impl<K, const INNER_PAGE_SIZE: u32> SpaceArcticMultiIndex<K, INNER_PAGE_SIZE>
+where
+    K: ArtPersistenceKey + ArcticKey,
+    K::Raw: arctic::topology::Key,
+{
+    async fn new(path: PathBuf, table_version: u32) -> eyre::Result<Self> {
+        let mut empty = ArcticIndex::<K, Vec<Link>>::default();
+        let snapshot = encode_arctic_topology(&empty.export_topology(Vec::clone)?)?;
+        Ok(Self {
+            file: ArtFile::open(path, Backend::ArcticMulti, table_version, snapshot).await?,
+        })
+    }
+
+    /// Reconstructs a persisted non-unique Arctic index from its native
+    /// checkpoint and WAL.
+    pub async fn load_index<const N: usize>(
+        path: impl AsRef<Path>,
+        table_version: u32,
+    ) -> eyre::Result<PersistentArcticMultiIndex<K, OffsetEqLink<N>>> {
+        let image = ArtFile::<K>::read_image(path.as_ref(), Backend::ArcticMulti, table_version).await?;
+        let topology = decode_arctic_topology(&image.snapshot)?;
+        let inner = ArcticIndex::from_topology(topology, |links: Vec<Link>| {
+            links.into_iter().map(OffsetEqLink).collect()
+        })?;
+        let index = ArtMultiIndex::from_inner(inner);
+        apply_multi_wal(&index, &image.wal, OffsetEqLink);
+        Ok(PersistentArtIndex::from_inner(index))
+    }
+
+    /// Writes a complete native non-unique Arctic checkpoint with an empty WAL.
+    pub async fn write_checkpoint<const N: usize>(
+        path: impl AsRef<Path>,
+        table_version: u32,
+        index: &mut PersistentArcticMultiIndex<K, OffsetEqLink<N>>,
+    ) -> eyre::Result<()> {
+        let topology = index
+            .inner_mut()
+            .inner_mut()
+            .export_topology(|links| links.iter().map(|link| link.0).collect::<Vec<_>>())?;
+        let snapshot = encode_arctic_topology(&topology)?;
+        ArtFile::<K>::write_new_file(path.as_ref(), Backend::ArcticMulti, table_version, &snapshot).await
+    }
+
+    async fn compact(&mut self) -> eyre::Result<()> {
+        let image = ArtFile::<K>::read_image(&self.file.path, Backend::ArcticMulti, self.file.table_version).await?;
+        let topology = decode_arctic_topology(&image.snapshot)?;
+        let mut index = ArtMultiIndex::from_inner(ArcticIndex::from_topology(topology, |links| links)?);
+        apply_multi_wal(&index, &image.wal, |link| link);
+        let snapshot = encode_arctic_topology(&index.inner_mut().export_topology(Vec::clone)?)?;
+        self.file.rewrite(&snapshot).await
+    }
+}
+
+/// Disk-side checkpoint and WAL state of a non-unique Congee index.
+#[derive(Debug)]
+pub struct SpaceCongeeMultiIndex<K: ArtPersistenceKey, const INNER_PAGE_SIZE: u32> {
+    file: ArtFile<K>,
+}
+
+impl<K, const INNER_PAGE_SIZE: u32> SpaceCongeeMultiIndex<K, INNER_PAGE_SIZE>
 where
     K: ArtPersistenceKey + CongeeKey,
 {
-    async fn primary_from_table_files_path<S: AsRef<str> + Send>(path: S, version: u32) -> eyre::Result<Self> {
-        Self::new(
-            PathBuf::from(format!("{}/primary{}", path.as_ref(), WT_INDEX_EXTENSION)),
-            version,
-        )
-        .await
-    }
-
-    async fn secondary_from_table_files_path<S1: AsRef<str> + Send, S2: AsRef<str> + Send>(
-        path: S1,
-        name: S2,
-        version: u32,
-    ) -> eyre::Result<Self> {
-        Self::new(
-            PathBuf::from(format!("{}/{}{}", path.as_ref(), name.as_ref(), WT_INDEX_EXTENSION)),
-            version,
-        )
-        .await
-    }
-
-    async fn bootstrap(_: &mut File, _: String, _: u32) -> eyre::Result<()> {
-        Ok(())
+    async fn new(path: PathBuf, table_version: u32) -> eyre::Result<Self> {
+        let mut empty = CongeeIndex::<K, Vec<Link>>::default();
+        let snapshot = encode_congee_topology(&empty.export_topology(Vec::clone)?)?;
+        Ok(Self {
+            file: ArtFile::open(path, Backend::CongeeMulti, table_version, snapshot).await?,
+        })
+    }
+
+    /// Reconstructs a persisted non-unique Congee index from its native
+    /// checkpoint and WAL.
+    pub async fn load_index<const N: usize>(
+        path: impl AsRef<Path>,
+        table_version: u32,
+    ) -> eyre::Result<PersistentCongeeMultiIndex<K, OffsetEqLink<N>>> {
+        let image = ArtFile::<K>::read_image(path.as_ref(), Backend::CongeeMulti, table_version).await?;
+        let topology = decode_congee_topology(&image.snapshot)?;
+        let inner = CongeeIndex::from_topology(topology, |links: Vec<Link>| {
+            links.into_iter().map(OffsetEqLink).collect()
+        })?;
+        let index = ArtMultiIndex::from_inner(inner);
+        apply_multi_wal(&index, &image.wal, OffsetEqLink);
+        Ok(PersistentArtIndex::from_inner(index))
+    }
+
+    /// Writes a complete native non-unique Congee checkpoint with an empty WAL.
+    pub async fn write_checkpoint<const N: usize>(
+        path: impl AsRef<Path>,
+        table_version: u32,
+        index: &mut PersistentCongeeMultiIndex<K, OffsetEqLink<N>>,
+    ) -> eyre::Result<()> {
+        let topology = index
+            .inner_mut()
+            .inner_mut()
+            .export_topology(|links| links.iter().map(|link| link.0).collect::<Vec<_>>())?;
+        let snapshot = encode_congee_topology(&topology)?;
+        ArtFile::<K>::write_new_file(path.as_ref(), Backend::CongeeMulti, table_version, &snapshot).await
+    }
+
+    async fn compact(&mut self) -> eyre::Result<()> {
+        let image = ArtFile::<K>::read_image(&self.file.path, Backend::CongeeMulti, self.file.table_version).await?;
+        let topology = decode_congee_topology(&image.snapshot)?;
+        let mut index = ArtMultiIndex::from_inner(CongeeIndex::from_topology(topology, |links| links)?);
+        apply_multi_wal(&index, &image.wal, |link| link);
+        let snapshot = encode_congee_topology(&index.inner_mut().export_topology(Vec::clone)?)?;
+        self.file.rewrite(&snapshot).await
+    }
+}
+
+/// Disk-side snapshot and WAL state of a hash index.
+#[derive(Debug)]
+pub struct SpaceHashIndex<K: ArtPersistenceKey, const INNER_PAGE_SIZE: u32> {
+    file: ArtFile<K>,
+}
+
+impl<K, const INNER_PAGE_SIZE: u32> SpaceHashIndex<K, INNER_PAGE_SIZE>
+where
+    K: ArtPersistenceKey,
+{
+    async fn new(path: PathBuf, table_version: u32) -> eyre::Result<Self> {
+        let snapshot = encode_hash_snapshot(&HashIndex::<K, Link>::default(), |link| *link);
+        Ok(Self {
+            file: ArtFile::open(path, Backend::Hash, table_version, snapshot).await?,
+        })
+    }
+
+    /// Reconstructs a persisted hash index from its snapshot and WAL.
+    pub async fn load_index<const N: usize>(
+        path: impl AsRef<Path>,
+        table_version: u32,
+    ) -> eyre::Result<PersistentHashIndex<K, OffsetEqLink<N>>> {
+        let image = ArtFile::<K>::read_image(path.as_ref(), Backend::Hash, table_version).await?;
+        let index = decode_hash_snapshot(&image.snapshot, OffsetEqLink)?;
+        apply_wal(&index, &image.wal, OffsetEqLink);
+        Ok(PersistentArtIndex::from_inner(index))
+    }
+
+    /// Writes a complete hash snapshot with an empty WAL.
+    pub async fn write_checkpoint<const N: usize>(
+        path: impl AsRef<Path>,
+        table_version: u32,
+        index: &mut PersistentHashIndex<K, OffsetEqLink<N>>,
+    ) -> eyre::Result<()> {
+        let snapshot = encode_hash_snapshot(index.inner(), |link| link.0);
+        ArtFile::<K>::write_new_file(path.as_ref(), Backend::Hash, table_version, &snapshot).await
     }
 
-    async fn process_change_event(&mut self, event: ChangeEvent<Pair<K, Link>>) -> eyre::Result<()> {
-        self.file.append(&[logical_record(event)?]).await?;
-        if self.file.should_compact() {
-            self.compact().await?;
+    async fn compact(&mut self) -> eyre::Result<()> {
+        let image = ArtFile::<K>::read_image(&self.file.path, Backend::Hash, self.file.table_version).await?;
+        let index = decode_hash_snapshot(&image.snapshot, |link| link)?;
+        apply_wal(&index, &image.wal, |link| link);
+        self.file.rewrite(&encode_hash_snapshot(&index, |link| *link)).await
+    }
+}
+
+/// Every ART space appends logical records and compacts the same way; only
+/// the checkpoint shape differs.
+macro_rules! impl_art_space_index_ops {
+    ($space:ident, [$($bound:tt)*]) => {
+        impl<K, const INNER_PAGE_SIZE: u32> SpaceIndexOps<K> for $space<K, INNER_PAGE_SIZE>
+        where
+            $($bound)*
+        {
+            async fn primary_from_table_files_path<S: AsRef<str> + Send>(path: S, version: u32) -> eyre::Result<Self> {
+                Self::new(
+                    PathBuf::from(format!("{}/primary{}", path.as_ref(), WT_INDEX_EXTENSION)),
+                    version,
+                )
+                .await
+            }
+
+            async fn secondary_from_table_files_path<S1: AsRef<str> + Send, S2: AsRef<str> + Send>(
+                path: S1,
+                name: S2,
+                version: u32,
+            ) -> eyre::Result<Self> {
+                Self::new(
+                    PathBuf::from(format!("{}/{}{}", path.as_ref(), name.as_ref(), WT_INDEX_EXTENSION)),
+                    version,
+                )
+                .await
+            }
+
+            async fn bootstrap(_: &mut File, _: String, _: u32) -> eyre::Result<()> {
+                Ok(())
+            }
+
+            async fn process_change_event(&mut self, event: ChangeEvent<Pair<K, Link>>) -> eyre::Result<()> {
+                self.file.append(&[logical_record(event)?]).await?;
+                if self.file.should_compact() {
+                    self.compact().await?;
+                }
+                Ok(())
+            }
+
+            async fn process_change_event_batch(&mut self, events: BatchChangeEvent<K>) -> eyre::Result<()> {
+                let records = events
+                    .into_iter()
+                    .map(logical_record)
+                    .collect::<eyre::Result<Vec<_>>>()?;
+                self.file.append(&records).await?;
+                if self.file.should_compact() {
+                    self.compact().await?;
+                }
+                Ok(())
+            }
         }
-        Ok(())
+    };
+}
+
+impl_art_space_index_ops!(SpaceArcticIndex, [K: ArtPersistenceKey + ArcticKey, K::Raw: arctic::topology::Key,]);
+impl_art_space_index_ops!(SpaceCongeeIndex, [K: ArtPersistenceKey + CongeeKey,]);
+impl_art_space_index_ops!(SpaceArcticMultiIndex, [K: ArtPersistenceKey + ArcticKey, K::Raw: arctic::topology::Key,]);
+impl_art_space_index_ops!(SpaceCongeeMultiIndex, [K: ArtPersistenceKey + CongeeKey,]);
+impl_art_space_index_ops!(SpaceHashIndex, [K: ArtPersistenceKey,]);
+
+/// Hash checkpoint: the entry count, then every key image and its link in key
+/// order, so equal indexes write identical files.
+fn encode_hash_snapshot<K, V>(index: &HashIndex<K, V>, link: impl Fn(&V) -> Link) -> Vec<u8>
+where
+    K: ArtPersistenceKey,
+    V: Clone + Send + Sync + 'static,
+{
+    let entries = index.iter_values().collect::<Vec<_>>();
+    let mut output = Vec::with_capacity(8 + entries.len() * (K::WIDTH as usize + 12));
+    output.extend_from_slice(&(entries.len() as u64).to_le_bytes());
+    for (key, value) in entries {
+        key.encode_art_key(&mut output);
+        link(&value).encode_topology_value(&mut output);
     }
+    output
+}
 
-    async fn process_change_event_batch(&mut self, events: BatchChangeEvent<K>) -> eyre::Result<()> {
-        let records = events
-            .into_iter()
-            .map(logical_record)
-            .collect::<eyre::Result<Vec<_>>>()?;
-        self.file.append(&records).await?;
-        if self.file.should_compact() {
-            self.compact().await?;
+fn decode_hash_snapshot<K, V>(bytes: &[u8], wrap: impl Fn(Link) -> V) -> eyre::Result<HashIndex<K, V>>
+where
+    K: ArtPersistenceKey,
+    V: Clone + Send + Sync + 'static,
+{
+    let mut decoder = Decoder::new(bytes);
+    let count = decoder.u64()?;
+    let index = HashIndex::default();
+    for _ in 0..count {
+        let key = K::decode_art_key(decoder.take(K::WIDTH as usize)?)?;
+        let link = decoder.link()?;
+        if index.insert_value(key, wrap(link)).is_some() {
+            bail!("hash index snapshot repeats a key");
         }
-        Ok(())
     }
+    decoder.finish()?;
+    Ok(index)
 }
 
-fn encode_link(link: Link, output: &mut Vec<u8>) {
-    let page_id: usize = link.page_id.into();
-    output.extend_from_slice(&(page_id as u32).to_le_bytes());
-    output.extend_from_slice(&link.offset.to_le_bytes());
-    output.extend_from_slice(&link.length.to_le_bytes());
+/// Checkpoint encoding of an ART value: one link for unique indexes, a
+/// counted link list for non-unique ones.
+trait TopologyValue: Sized {
+    fn encode_topology_value(&self, output: &mut Vec<u8>);
+    fn decode_topology_value(decoder: &mut Decoder<'_>) -> eyre::Result<Self>;
+}
+
+impl TopologyValue for Link {
+    fn encode_topology_value(&self, output: &mut Vec<u8>) {
+        let page_id: usize = self.page_id.into();
+        output.extend_from_slice(&(page_id as u32).to_le_bytes());
+        output.extend_from_slice(&self.offset.to_le_bytes());
+        output.extend_from_slice(&self.length.to_le_bytes());
+    }
+
+    fn decode_topology_value(decoder: &mut Decoder<'_>) -> eyre::Result<Self> {
+        decoder.link()
+    }
+}
+
+impl TopologyValue for Vec<Link> {
+    fn encode_topology_value(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&(self.len() as u32).to_le_bytes());
+        for link in self {
+            link.encode_topology_value(output);
+        }
+    }
+
+    fn decode_topology_value(decoder: &mut Decoder<'_>) -> eyre::Result<Self> {
+        let count = decoder.u32()? as usize;
+        if count == 0 {
+            bail!("non-unique ART key has an empty link list");
+        }
+        // Bound the allocation by the bytes actually present.
+        let mut links = Vec::with_capacity(count.min(decoder.remaining() / 12));
+        for _ in 0..count {
+            links.push(decoder.link()?);
+        }
+        Ok(links)
+    }
 }
 
 struct Decoder<'a> {
@@ -604,6 +827,10 @@ impl<'a> Decoder<'a> {
         Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
     }
 
+    fn u32(&mut self) -> eyre::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
     fn u64(&mut self) -> eyre::Result<u64> {
         Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
     }
@@ -619,6 +846,10 @@ impl<'a> Decoder<'a> {
         })
     }
 
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
     fn finish(self) -> eyre::Result<()> {
         if self.position != self.bytes.len() {
             bail!(
@@ -630,7 +861,7 @@ impl<'a> Decoder<'a> {
     }
 }
 
-fn encode_arctic_topology(topology: &arctic::topology::Topology<Link>) -> eyre::Result<Vec<u8>> {
+fn encode_arctic_topology<T: TopologyValue>(topology: &arctic::topology::Topology<T>) -> eyre::Result<Vec<u8>> {
     let mut output = Vec::new();
     output.extend_from_slice(&topology.version.to_le_bytes());
     match &topology.root {
@@ -643,12 +874,12 @@ fn encode_arctic_topology(topology: &arctic::topology::Topology<Link>) -> eyre::
     Ok(output)
 }
 
-fn encode_arctic_edge(edge: &arctic::topology::Edge<Link>, output: &mut Vec<u8>) -> eyre::Result<()> {
+fn encode_arctic_edge<T: TopologyValue>(edge: &arctic::topology::Edge<T>, output: &mut Vec<u8>) -> eyre::Result<()> {
     output.extend_from_slice(&edge.metadata.to_le_bytes());
     match &edge.child {
-        arctic::topology::Child::Value(link) => {
+        arctic::topology::Child::Value(value) => {
             output.push(0);
-            encode_link(*link, output);
+            value.encode_topology_value(output);
         }
         arctic::topology::Child::Node(node) => {
             output.push(1);
@@ -658,7 +889,7 @@ fn encode_arctic_edge(edge: &arctic::topology::Edge<Link>, output: &mut Vec<u8>)
     Ok(())
 }
 
-fn encode_arctic_node(node: &arctic::topology::Node<Link>, output: &mut Vec<u8>) -> eyre::Result<()> {
+fn encode_arctic_node<T: TopologyValue>(node: &arctic::topology::Node<T>, output: &mut Vec<u8>) -> eyre::Result<()> {
     let kind = match node.kind {
         arctic::topology::NodeKind::Node3 => 3,
         arctic::topology::NodeKind::Node15 => 15,
@@ -677,7 +908,7 @@ fn encode_arctic_node(node: &arctic::topology::Node<Link>, output: &mut Vec<u8>)
     Ok(())
 }
 
-fn decode_arctic_topology(bytes: &[u8]) -> eyre::Result<arctic::topology::Topology<Link>> {
+fn decode_arctic_topology<T: TopologyValue>(bytes: &[u8]) -> eyre::Result<arctic::topology::Topology<T>> {
     let mut decoder = Decoder::new(bytes);
     let version = decoder.u16()?;
     let root = match decoder.u8()? {
@@ -689,20 +920,26 @@ fn decode_arctic_topology(bytes: &[u8]) -> eyre::Result<arctic::topology::Topolo
     Ok(arctic::topology::Topology { version, root })
 }
 
-fn decode_arctic_edge(decoder: &mut Decoder<'_>, depth: usize) -> eyre::Result<arctic::topology::Edge<Link>> {
+fn decode_arctic_edge<T: TopologyValue>(
+    decoder: &mut Decoder<'_>,
+    depth: usize,
+) -> eyre::Result<arctic::topology::Edge<T>> {
     if depth > 16 {
         bail!("Arctic topology exceeds maximum key depth");
     }
     let metadata = decoder.u64()?;
     let child = match decoder.u8()? {
-        0 => arctic::topology::Child::Value(decoder.link()?),
+        0 => arctic::topology::Child::Value(T::decode_topology_value(decoder)?),
         1 => arctic::topology::Child::Node(decode_arctic_node(decoder, depth + 1)?),
         tag => bail!("invalid Arctic child tag {tag}"),
     };
     Ok(arctic::topology::Edge { metadata, child })
 }
 
-fn decode_arctic_node(decoder: &mut Decoder<'_>, depth: usize) -> eyre::Result<arctic::topology::Node<Link>> {
+fn decode_arctic_node<T: TopologyValue>(
+    decoder: &mut Decoder<'_>,
+    depth: usize,
+) -> eyre::Result<arctic::topology::Node<T>> {
     let (kind, capacity) = match decoder.u8()? {
         3 => (arctic::topology::NodeKind::Node3, 3),
         15 => (arctic::topology::NodeKind::Node15, 15),
@@ -730,14 +967,14 @@ fn decode_arctic_node(decoder: &mut Decoder<'_>, depth: usize) -> eyre::Result<a
     })
 }
 
-fn encode_congee_topology(topology: &congee::topology::Topology<Link>) -> eyre::Result<Vec<u8>> {
+fn encode_congee_topology<T: TopologyValue>(topology: &congee::topology::Topology<T>) -> eyre::Result<Vec<u8>> {
     let mut output = Vec::new();
     output.extend_from_slice(&topology.version.to_le_bytes());
     encode_congee_node(&topology.root, &mut output)?;
     Ok(output)
 }
 
-fn encode_congee_node(node: &congee::topology::Node<Link>, output: &mut Vec<u8>) -> eyre::Result<()> {
+fn encode_congee_node<T: TopologyValue>(node: &congee::topology::Node<T>, output: &mut Vec<u8>) -> eyre::Result<()> {
     let kind = match node.kind {
         congee::topology::NodeKind::N4 => 4,
         congee::topology::NodeKind::N16 => 16,
@@ -757,9 +994,9 @@ fn encode_congee_node(node: &congee::topology::Node<Link>, output: &mut Vec<u8>)
         output.push(branch.key);
         output.extend_from_slice(&branch.slot.to_le_bytes());
         match &branch.child {
-            congee::topology::Child::Value(link) => {
+            congee::topology::Child::Value(value) => {
                 output.push(0);
-                encode_link(*link, output);
+                value.encode_topology_value(output);
             }
             congee::topology::Child::Node(child) => {
                 output.push(1);
@@ -770,7 +1007,7 @@ fn encode_congee_node(node: &congee::topology::Node<Link>, output: &mut Vec<u8>)
     Ok(())
 }
 
-fn decode_congee_topology(bytes: &[u8]) -> eyre::Result<congee::topology::Topology<Link>> {
+fn decode_congee_topology<T: TopologyValue>(bytes: &[u8]) -> eyre::Result<congee::topology::Topology<T>> {
     let mut decoder = Decoder::new(bytes);
     let version = decoder.u16()?;
     let root = decode_congee_node(&mut decoder, 0)?;
@@ -778,7 +1015,10 @@ fn decode_congee_topology(bytes: &[u8]) -> eyre::Result<congee::topology::Topolo
     Ok(congee::topology::Topology { version, root })
 }
 
-fn decode_congee_node(decoder: &mut Decoder<'_>, depth: usize) -> eyre::Result<congee::topology::Node<Link>> {
+fn decode_congee_node<T: TopologyValue>(
+    decoder: &mut Decoder<'_>,
+    depth: usize,
+) -> eyre::Result<congee::topology::Node<T>> {
     if depth > 8 {
         bail!("Congee topology exceeds its eight-byte key depth");
     }
@@ -808,7 +1048,7 @@ fn decode_congee_node(decoder: &mut Decoder<'_>, depth: usize) -> eyre::Result<c
         let key = decoder.u8()?;
         let slot = decoder.u16()?;
         let child = match decoder.u8()? {
-            0 => congee::topology::Child::Value(decoder.link()?),
+            0 => congee::topology::Child::Value(T::decode_topology_value(decoder)?),
             1 => congee::topology::Child::Node(decode_congee_node(decoder, depth + 1)?),
             tag => bail!("invalid Congee child tag {tag}"),
         };
@@ -844,6 +1084,16 @@ mod tests {
         }
     }
 
+    fn remove_event(id: u64, key: u64, value: Link) -> ChangeEvent<Pair<u64, Link>> {
+        let pair = Pair { key, value };
+        ChangeEvent::RemoveAt {
+            event_id: id.into(),
+            max_value: pair.clone(),
+            value: pair,
+            index: 0,
+        }
+    }
+
     fn congee_contains_kind(node: &congee::topology::Node<Link>, expected: congee::topology::NodeKind) -> bool {
         node.kind == expected
             || node.branches.iter().any(|branch| match &branch.child {
@@ -863,7 +1113,7 @@ mod tests {
             WalRecord {
                 event_id: 18,
                 key: 42u64,
-                op: WalOp::Remove,
+                op: WalOp::Remove(link(3)),
             },
         ] {
             assert_eq!(decode_wal_record(&encode_wal_record(&record)).unwrap(), record);
@@ -894,6 +1144,77 @@ mod tests {
         assert_eq!(decoded, topology);
     }
 
+    #[test]
+    fn multi_topology_codec_preserves_link_lists() {
+        let mut index = ArcticIndex::<u64, Vec<Link>>::default();
+        for key in 0..40 {
+            index.insert_value(
+                key,
+                (0..key as u32 % 4 + 1)
+                    .map(|value| link(key as u32 * 10 + value))
+                    .collect(),
+            );
+        }
+        let topology = index.export_topology(Vec::clone).unwrap();
+        let bytes = encode_arctic_topology(&topology).unwrap();
+        assert_eq!(decode_arctic_topology::<Vec<Link>>(&bytes).unwrap(), topology);
+
+        let mut index = CongeeIndex::<u64, Vec<Link>>::default();
+        for key in 0..40 {
+            index.insert_value(key, vec![link(key as u32), link(key as u32 + 100)]);
+        }
+        let topology = index.export_topology(Vec::clone).unwrap();
+        let bytes = encode_congee_topology(&topology).unwrap();
+        assert_eq!(decode_congee_topology::<Vec<Link>>(&bytes).unwrap(), topology);
+    }
+
+    #[tokio::test]
+    async fn multi_wal_removes_single_links_and_survives_compaction() {
+        let path = std::env::temp_dir().join(format!("worktable-art-multi-{}.wt.idx", uuid::Uuid::new_v4()));
+        let mut space = SpaceCongeeMultiIndex::<u64, 4096>::new(path.clone(), 2).await.unwrap();
+        space
+            .process_change_event_batch(vec![
+                set_event(0, 5, link(1)),
+                set_event(1, 5, link(2)),
+                set_event(2, 5, link(3)),
+                set_event(3, 6, link(4)),
+                remove_event(4, 5, link(2)),
+                remove_event(5, 6, link(4)),
+            ])
+            .await
+            .unwrap();
+        drop(space);
+
+        assert!(ArtFile::<u64>::read_image(&path, Backend::Congee, 2).await.is_err());
+        let index = SpaceCongeeMultiIndex::<u64, 4096>::load_index::<4096>(&path, 2)
+            .await
+            .unwrap();
+        let links = |index: &PersistentCongeeMultiIndex<u64, OffsetEqLink<4096>>, key| {
+            index.get(&key).map(|(_, link)| link.0).collect::<Vec<_>>()
+        };
+        assert_eq!(links(&index, 5), vec![link(1), link(3)]);
+        assert!(!index.contains_key(&6));
+        assert_eq!(index.len(), 2);
+
+        let mut space = SpaceCongeeMultiIndex::<u64, 4096>::new(path.clone(), 2).await.unwrap();
+        space.compact().await.unwrap();
+        assert!(
+            ArtFile::<u64>::read_image(&path, Backend::CongeeMulti, 2)
+                .await
+                .unwrap()
+                .wal
+                .is_empty()
+        );
+        space.process_change_event(set_event(6, 5, link(9))).await.unwrap();
+        drop(space);
+
+        let index = SpaceCongeeMultiIndex::<u64, 4096>::load_index::<4096>(&path, 2)
+            .await
+            .unwrap();
+        assert_eq!(links(&index, 5), vec![link(1), link(3), link(9)]);
+        tokio::fs::remove_file(path).await.unwrap();
+    }
+
     #[tokio::test]
     async fn torn_wal_tail_is_truncated_before_new_appends() {
         let path = std::env::temp_dir().join(format!("worktable-art-torn-{}.wt.idx", uuid::Uuid::new_v4()));
@@ -949,6 +1270,44 @@ mod tests {
         tokio::fs::remove_file(path).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn hash_snapshot_and_wal_round_trip_through_compaction() {
+        let path = std::env::temp_dir().join(format!("worktable-art-hash-{}.wt.idx", uuid::Uuid::new_v4()));
+        let mut space = SpaceHashIndex::<u64, 4096>::new(path.clone(), 4).await.unwrap();
+        let events = (0..64).map(|key| set_event(key, key, link(key as u32 + 1))).collect();
+        space.process_change_event_batch(events).await.unwrap();
+        space
+            .process_change_event(remove_event(64, 10, link(11)))
+            .await
+            .unwrap();
+        space.compact().await.unwrap();
+        assert!(
+            ArtFile::<u64>::read_image(&path, Backend::Hash, 4)
+                .await
+                .unwrap()
+                .wal
+                .is_empty()
+        );
+        space.process_change_event(set_event(65, 99, link(7))).await.unwrap();
+        drop(space);
+
+        let mut index = SpaceHashIndex::<u64, 4096>::load_index::<4096>(&path, 4).await.unwrap();
+        assert_eq!(index.len(), 64);
+        assert!(!index.contains_key(&10));
+        assert_eq!(index.get_value(&99).unwrap().0, link(7));
+        assert_eq!(index.get_value(&63).unwrap().0, link(64));
+
+        SpaceHashIndex::<u64, 4096>::write_checkpoint(&path, 4, &mut index)
+            .await
+            .unwrap();
+        let reloaded = SpaceHashIndex::<u64, 4096>::load_index::<4096>(&path, 4).await.unwrap();
+        assert_eq!(
+            reloaded.iter_values().collect::<Vec<_>>(),
+            index.iter_values().collect::<Vec<_>>()
+        );
+        tokio::fs::remove_file(path).await.unwrap();
+    }
+
     #[tokio::test]
     async fn complete_corruption_and_header_mismatches_are_rejected() {
         let path = std::env::temp_dir().join(format!("worktable-art-corrupt-{}.wt.idx", uuid::Uuid::new_v4()));
@@ -1,14 +1,22 @@
-use std::collections::HashSet;
-use std::io::SeekFrom;
-use std::path::Path;
-
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::in_memory::{
+    COMPRESSED_PAGE_FLAG, PageCompression, PageStore, decompress_page, disk_page_bytes, disk_page_image,
+    is_overflow_link, overflow_segments,
+};
 use crate::persistence::SpaceDataOps;
 use crate::persistence::space::{BatchData, open_or_create_file};
 use crate::prelude::WT_DATA_EXTENSION;
 use convert_case::{Case, Casing};
+use data_bucket::page::PageId;
 use data_bucket::{
-    DataPage, GeneralHeader, GeneralPage, Link, PageType, Persistable, SizeMeasurable, SpaceInfoPage,
-    parse_data_pages_batch, parse_general_header_by_index, parse_page, persist_page, persist_pages_batch, update_at,
+    DataPage, GENERAL_HEADER_SIZE, GeneralHeader, GeneralPage, Link, PageType, Persistable, SizeMeasurable,
+    SpaceInfoPage, parse_data_page, parse_data_pages_batch, parse_general_header_by_index, parse_page, persist_page,
+    persist_pages_batch, update_at,
 };
 use rkyv::api::high::HighDeserializer;
 use rkyv::rancor::Strategy;
@@ -135,12 +143,255 @@ fn subtract_used_ranges(free_ranges: Vec<Link>, used_ranges: impl IntoIterator<I
     (remaining, changed)
 }
 
+/// Splits the writes of overflow rows into one write per page of their chain
+/// and regroups them by the page they land on.
+fn split_overflow_writes<const INNER_PAGE_SIZE: usize>(batch_data: BatchData) -> BatchData {
+    if !batch_data
+        .values()
+        .flatten()
+        .any(|(link, _)| is_overflow_link::<INNER_PAGE_SIZE>(*link))
+    {
+        return batch_data;
+    }
+
+    let mut split = BatchData::with_capacity(batch_data.len());
+    for (link, bytes) in batch_data.into_values().flatten() {
+        if !is_overflow_link::<INNER_PAGE_SIZE>(link) {
+            split.entry(link.page_id).or_default().push((link, bytes));
+            continue;
+        }
+        let mut written = 0;
+        for segment in overflow_segments::<INNER_PAGE_SIZE>(link) {
+            let end = written + segment.length as usize;
+            split
+                .entry(segment.page_id)
+                .or_default()
+                .push((segment, bytes[written..end].to_vec()));
+            written = end;
+        }
+    }
+    split
+}
+
+/// Turns a compressed data page read from disk into its raw form.
+fn decompress_data_page<const INNER_PAGE_SIZE: usize>(
+    page: &mut GeneralPage<DataPage<INNER_PAGE_SIZE>>,
+) -> eyre::Result<()> {
+    let data_length = page.header.data_length;
+    if data_length & COMPRESSED_PAGE_FLAG == 0 {
+        return Ok(());
+    }
+    let mut data = [0; INNER_PAGE_SIZE];
+    disk_page_image(&page.inner.data)
+        .and_then(|image| decompress_page(image, &mut data))
+        .ok_or_else(|| eyre::eyre!("compressed data page {:?} is corrupt", page.header.page_id))?;
+    page.header.data_length = data_length & !COMPRESSED_PAGE_FLAG;
+    page.inner = DataPage {
+        length: page.header.data_length,
+        data,
+    };
+    Ok(())
+}
+
+/// Data pages with writes queued for the persistence engine but not yet in
+/// the `.wt.data` file. The engine queue counts them, see
+/// [`PersistenceTask::run_engine_with_unpersisted`], so that
+/// [`SpaceDataPages`] never drops a page from memory that the file is behind.
+///
+/// [`PersistenceTask::run_engine_with_unpersisted`]: crate::persistence::PersistenceTask::run_engine_with_unpersisted
+#[derive(Debug)]
+pub struct UnpersistedPages {
+    data_length: u32,
+    pages: parking_lot::Mutex<HashMap<PageId, usize>>,
+    /// Queued truncates, which change every page of the file.
+    truncates: AtomicUsize,
+}
+
+impl UnpersistedPages {
+    /// `data_length` is the inner size of the table's data pages, which
+    /// decides the pages an overflow row spans.
+    pub fn new(data_length: usize) -> Self {
+        Self {
+            data_length: data_length as u32,
+            pages: parking_lot::Mutex::new(HashMap::new()),
+            truncates: AtomicUsize::new(0),
+        }
+    }
+
+    fn page_ids(&self, links: &[Link]) -> impl Iterator<Item = PageId> {
+        links.iter().flat_map(|link| {
+            let first_page: u32 = link.page_id.into();
+            let page_count = link.length.div_ceil(self.data_length).max(1);
+            (first_page..first_page + page_count).map(PageId::from)
+        })
+    }
+
+    /// Records queued writes of `links`.
+    pub fn add(&self, links: &[Link]) {
+        let mut pages = self.pages.lock();
+        for page_id in self.page_ids(links) {
+            *pages.entry(page_id).or_default() += 1;
+        }
+    }
+
+    /// Records that the queued writes of `links` reached the file.
+    pub fn remove(&self, links: &[Link]) {
+        let mut pages = self.pages.lock();
+        for page_id in self.page_ids(links) {
+            if let Some(count) = pages.get_mut(&page_id) {
+                *count -= 1;
+                if *count == 0 {
+                    pages.remove(&page_id);
+                }
+            }
+        }
+    }
+
+    pub fn add_truncate(&self) {
+        self.truncates.fetch_add(1, Ordering::AcqRel);
+    }
+
+    pub fn remove_truncate(&self) {
+        self.truncates.fetch_sub(1, Ordering::AcqRel);
+    }
+
+    /// Whether the file may still change under the page.
+    pub fn contains(&self, page_id: PageId) -> bool {
+        self.truncates.load(Ordering::Acquire) != 0 || self.pages.lock().contains_key(&page_id)
+    }
+}
+
+/// [`PageStore`] reading pages from the `.wt.data` file that [`SpaceData`]
+/// writes, so a table loaded with [`LoadMode::Lazy`] reads each page on its
+/// first access, and an evicted page is read back from where the engine
+/// wrote it.
+///
+/// The store never writes the file. Opened with [`Self::evicting`], it takes
+/// a page to evict only when no write of the page is queued and the file
+/// holds the page as it is in memory; other pages are refused with
+/// [`std::io::ErrorKind::WouldBlock`] and stay resident.
+///
+/// [`LoadMode::Lazy`]: crate::persistence::LoadMode::Lazy
+#[derive(Debug)]
+pub struct SpaceDataPages<const PAGE_SIZE: u32> {
+    path: PathBuf,
+    /// Opened on the first access for an evicting store, whose table may
+    /// not have written the file yet.
+    file: parking_lot::Mutex<Option<std::fs::File>>,
+    unpersisted: Option<Arc<UnpersistedPages>>,
+}
+
+impl<const PAGE_SIZE: u32> SpaceDataPages<PAGE_SIZE> {
+    pub fn open(table_path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = table_path.as_ref().join(WT_DATA_EXTENSION);
+        let file = std::fs::File::open(&path)?;
+        Ok(Self {
+            path,
+            file: parking_lot::Mutex::new(Some(file)),
+            unpersisted: None,
+        })
+    }
+
+    /// Creates a store that evicted pages are read back from, with the pages
+    /// `unpersisted` counts kept in memory.
+    pub fn evicting(table_path: impl AsRef<Path>, unpersisted: Arc<UnpersistedPages>) -> Self {
+        Self {
+            path: table_path.as_ref().join(WT_DATA_EXTENSION),
+            file: parking_lot::Mutex::new(None),
+            unpersisted: Some(unpersisted),
+        }
+    }
+}
+
+impl<const PAGE_SIZE: u32> PageStore for SpaceDataPages<PAGE_SIZE> {
+    fn store_page(&self, page_id: PageId, bytes: &[u8]) -> std::io::Result<()> {
+        let Some(unpersisted) = &self.unpersisted else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!("data page {page_id:?} cannot be evicted to a read-only data file"),
+            ));
+        };
+        // Nothing is written: the page is only dropped from memory, so the
+        // file must hold it as it is now. A write queued after the
+        // comparison is for a change the comparison already saw.
+        let mut written = vec![0; bytes.len()];
+        let in_file = !unpersisted.contains(page_id)
+            && match self.load_page(page_id, &mut written) {
+                Ok(()) => true,
+                // Not written yet.
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        std::io::ErrorKind::NotFound | std::io::ErrorKind::UnexpectedEof
+                    ) =>
+                {
+                    false
+                }
+                Err(e) => return Err(e),
+            };
+        if in_file && written == bytes {
+            Ok(())
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::WouldBlock,
+                format!("data page {page_id:?} has changes the data file does not have yet"),
+            ))
+        }
+    }
+
+    fn load_page(&self, page_id: PageId, bytes: &mut [u8]) -> std::io::Result<()> {
+        let mut page = vec![0; GENERAL_HEADER_SIZE + bytes.len()];
+        {
+            let mut file = self.file.lock();
+            if file.is_none() {
+                *file = Some(std::fs::File::open(&self.path)?);
+            }
+            let file = file.as_mut().expect("opened above");
+            file.seek(SeekFrom::Start(u64::from(u32::from(page_id)) * u64::from(PAGE_SIZE)))?;
+            // The last page may end early, as `parse_data_page` allows.
+            let mut read = 0;
+            while read < page.len() {
+                match file.read(&mut page[read..])? {
+                    0 => break,
+                    n => read += n,
+                }
+            }
+            if read < GENERAL_HEADER_SIZE {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!("data page {page_id:?} is past the end of the data file"),
+                ));
+            }
+        }
+        let (header, data) = page.split_at(GENERAL_HEADER_SIZE);
+        let data_length = u32::from_le_bytes(header[24..28].try_into().expect("four bytes"));
+        if data_length & COMPRESSED_PAGE_FLAG == 0 {
+            bytes.copy_from_slice(data);
+            return Ok(());
+        }
+        disk_page_image(data)
+            .and_then(|image| decompress_page(image, bytes))
+            .map(|_| ())
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("compressed data page {page_id:?} is corrupt"),
+                )
+            })
+    }
+}
+
 #[derive(Debug)]
 pub struct SpaceData<PkGenState, const INNER_PAGE_SIZE: usize, const PAGE_SIZE: u32> {
     pub info: GeneralPage<SpaceInfoPage<PkGenState>>,
     pub last_page_id: u32,
     pub current_data_length: u32,
     pub data_file: File,
+    /// Compression of pages that are no longer the last one.
+    pub compression: PageCompression,
+    /// Pages stored compressed, found by scanning the page headers on open.
+    /// Writing into one of them stores it raw again.
+    pub compressed_pages: HashSet<u32>,
 }
 
 impl<PkGenState, const INNER_PAGE_SIZE: usize, const PAGE_SIZE: u32> SpaceData<PkGenState, INNER_PAGE_SIZE, PAGE_SIZE> {
@@ -165,6 +416,72 @@ impl<PkGenState, const INNER_PAGE_SIZE: usize, const PAGE_SIZE: u32> SpaceData<P
         self.info.inner.empty_links_list = remaining;
         changed
     }
+
+    /// Writes the bytes of a link that lies within one page.
+    async fn save_page_data(&mut self, link: Link, bytes: &[u8]) -> eyre::Result<()> {
+        if link.page_id > self.last_page_id.into() {
+            let mut page = GeneralPage {
+                header: GeneralHeader::new(link.page_id, PageType::Data, 0.into()),
+                inner: DataPage {
+                    length: 0,
+                    data: [0; 1],
+                },
+            };
+            persist_page(&mut page, &mut self.data_file).await?;
+            self.current_data_length = 0;
+            let sealed_page_id = self.last_page_id;
+            self.last_page_id += 1;
+            self.compress_page(sealed_page_id).await?;
+        } else if self.compressed_pages.contains(&link.page_id.into()) {
+            self.store_page_raw(link.page_id.into()).await?;
+        }
+        // Rewrites of rows already on the last page must not move its end.
+        if link.page_id == self.last_page_id.into() {
+            self.current_data_length = self.current_data_length.max(link.offset + link.length);
+        }
+        self.update_data_length().await?;
+        update_at::<{ PAGE_SIZE }>(&mut self.data_file, link, bytes).await?;
+        // `update_at` ends with a buffered `write_all` that `tokio::fs::File`
+        // completes on a background blocking task. Flush before reporting the
+        // save done so the bytes are visible to any other handle.
+        self.data_file.flush().await?;
+        Ok(())
+    }
+
+    /// Rewrites a page that is no longer the last one compressed, if the
+    /// table compresses pages and the image is worth keeping.
+    async fn compress_page(&mut self, page_id: u32) -> eyre::Result<()> {
+        if self.compression == PageCompression::None || page_id == 0 || self.compressed_pages.contains(&page_id) {
+            return Ok(());
+        }
+        let mut page = parse_data_page::<PAGE_SIZE, INNER_PAGE_SIZE>(&mut self.data_file, page_id).await?;
+        // Single writes do not keep `data_length` of pages other than the
+        // last one exact, so the image keeps everything up to the last
+        // non-zero byte instead.
+        let used = page.inner.data.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+        let Some(image) = self.compression.compress(&page.inner.data[..used], INNER_PAGE_SIZE) else {
+            return Ok(());
+        };
+        page.header.data_length |= COMPRESSED_PAGE_FLAG;
+        self.data_file
+            .seek(SeekFrom::Start(u64::from(page_id) * u64::from(PAGE_SIZE)))
+            .await?;
+        self.data_file.write_all(page.header.as_bytes().as_ref()).await?;
+        self.data_file.write_all(&disk_page_bytes(&image)).await?;
+        self.data_file.flush().await?;
+        self.compressed_pages.insert(page_id);
+        Ok(())
+    }
+
+    /// Rewrites a compressed page raw, so row bytes can be written into it.
+    async fn store_page_raw(&mut self, page_id: u32) -> eyre::Result<()> {
+        let mut page = parse_data_page::<PAGE_SIZE, INNER_PAGE_SIZE>(&mut self.data_file, page_id).await?;
+        decompress_data_page(&mut page)?;
+        persist_page(&mut page, &mut self.data_file).await?;
+        self.data_file.flush().await?;
+        self.compressed_pages.remove(&page_id);
+        Ok(())
+    }
 }
 
 impl<PkGenState, const INNER_PAGE_SIZE: usize, const PAGE_SIZE: u32> SpaceDataOps<PkGenState>
@@ -199,15 +516,28 @@ where
         let file_length = data_file.metadata().await?.len();
         let page_id = file_length / PAGE_SIZE as u64;
         let last_page_header = parse_general_header_by_index(&mut data_file, page_id as u32).await?;
+        let mut compressed_pages = HashSet::new();
+        for id in 1..page_id as u32 {
+            let header = parse_general_header_by_index(&mut data_file, id).await?;
+            if header.data_length & COMPRESSED_PAGE_FLAG != 0 {
+                compressed_pages.insert(id);
+            }
+        }
 
         Ok(Self {
             data_file,
             info,
             last_page_id: page_id as u32,
             current_data_length: last_page_header.data_length,
+            compression: PageCompression::None,
+            compressed_pages,
         })
     }
 
+    fn set_page_compression(&mut self, compression: PageCompression) {
+        self.compression = compression;
+    }
+
     async fn bootstrap(file: &mut File, table_name: String, version: u32) -> eyre::Result<()> {
         let info = SpaceInfoPage {
             id: 0.into(),
@@ -228,32 +558,20 @@ where
     }
 
     async fn save_data(&mut self, link: Link, bytes: &[u8]) -> eyre::Result<()> {
-        if self.consume_reusable_ranges([link]) {
+        if self.consume_reusable_ranges(overflow_segments::<INNER_PAGE_SIZE>(link)) {
             self.save_info().await?;
         }
-        if link.page_id > self.last_page_id.into() {
-            let mut page = GeneralPage {
-                header: GeneralHeader::new(link.page_id, PageType::Data, 0.into()),
-                inner: DataPage {
-                    length: 0,
-                    data: [0; 1],
-                },
-            };
-            persist_page(&mut page, &mut self.data_file).await?;
-            self.current_data_length = 0;
-            self.last_page_id += 1;
+        let mut written = 0;
+        for segment in overflow_segments::<INNER_PAGE_SIZE>(link) {
+            let end = written + segment.length as usize;
+            self.save_page_data(segment, &bytes[written..end]).await?;
+            written = end;
         }
-        self.current_data_length += link.length;
-        self.update_data_length().await?;
-        update_at::<{ PAGE_SIZE }>(&mut self.data_file, link, bytes).await?;
-        // `update_at` ends with a buffered `write_all` that `tokio::fs::File`
-        // completes on a background blocking task. Flush before reporting the
-        // save done so the bytes are visible to any other handle.
-        self.data_file.flush().await?;
         Ok(())
     }
 
     async fn save_batch_data(&mut self, batch_data: BatchData) -> eyre::Result<()> {
+        let batch_data = split_overflow_writes::<INNER_PAGE_SIZE>(batch_data);
         let used_links = batch_data.values().flat_map(|ops| ops.iter().map(|(link, _)| *link));
         if self.consume_reusable_ranges(used_links) {
             self.save_info().await?;
@@ -276,12 +594,21 @@ where
         // creating several pages could leave `last_page_id` below a page that
         // now exists. The next batch touching that page would see it as "new"
         // and re-create it zero-filled, wiping the rows persisted before.
+        let previous_last_page_id = self.last_page_id;
         if let Some(max) = ids_to_create.iter().max() {
             // High-water mark: every id in `ids_to_create` is > last_page_id by
             // construction, but state the monotonic invariant directly so a
             // future refactor of the filter above cannot regress it.
             self.last_page_id = self.last_page_id.max(*max);
         }
+        let mut sealed_page_ids = ids_to_create
+            .iter()
+            .copied()
+            .filter(|id| *id != self.last_page_id)
+            .collect::<Vec<_>>();
+        if self.last_page_id != previous_last_page_id {
+            sealed_page_ids.push(previous_last_page_id);
+        }
         let created_pages = ids_to_create
             .into_iter()
             .map(|id| GeneralPage {
@@ -292,8 +619,12 @@ where
                 },
             })
             .collect::<Vec<_>>();
-        let parsed_pages =
+        let mut parsed_pages =
             parse_data_pages_batch::<PAGE_SIZE, INNER_PAGE_SIZE>(&mut self.data_file, ids_to_parse).await?;
+        for page in &mut parsed_pages {
+            decompress_data_page(page)?;
+            self.compressed_pages.remove(&page.header.page_id.into());
+        }
 
         let updated_pages = vec![parsed_pages, created_pages]
             .into_iter()
@@ -315,6 +646,49 @@ where
         // batch is visible to other handles once it reports done.
         self.data_file.flush().await?;
 
+        for page_id in sealed_page_ids {
+            self.compress_page(page_id).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn mark_rows_deleted(&mut self, links: Vec<Link>, live: Vec<Link>, flag_offset: u32) -> eyre::Result<()> {
+        let live = live
+            .into_iter()
+            .flat_map(overflow_segments::<INNER_PAGE_SIZE>)
+            .collect::<Vec<_>>();
+        for link in links {
+            if flag_offset == 0 || flag_offset > link.length {
+                continue;
+            }
+            let position = link.length - flag_offset;
+            let flag = if is_overflow_link::<INNER_PAGE_SIZE>(link) {
+                Link {
+                    page_id: (u32::from(link.page_id) + position / INNER_PAGE_SIZE as u32).into(),
+                    offset: position % INNER_PAGE_SIZE as u32,
+                    length: 1,
+                }
+            } else {
+                Link {
+                    page_id: link.page_id,
+                    offset: link.offset + position,
+                    length: 1,
+                }
+            };
+            let page_id: u32 = flag.page_id.into();
+            let overwritten = live.iter().any(|row| {
+                row.page_id == flag.page_id && row.offset <= flag.offset && flag.offset < row.offset + row.length
+            });
+            if overwritten || page_id == 0 || page_id > self.last_page_id {
+                continue;
+            }
+            if self.compressed_pages.contains(&page_id) {
+                self.store_page_raw(page_id).await?;
+            }
+            update_at::<{ PAGE_SIZE }>(&mut self.data_file, flag, &[1]).await?;
+        }
+        self.data_file.flush().await?;
         Ok(())
     }
 
@@ -370,7 +744,7 @@ where
 mod tests {
     use data_bucket::page::PageId;
 
-    use super::subtract_used_ranges;
+    use super::{UnpersistedPages, subtract_used_ranges};
     use crate::prelude::Link;
 
     fn link(page_id: u32, offset: u32, length: u32) -> Link {
@@ -381,6 +755,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn unpersisted_pages_count_every_page_of_a_write() {
+        let unpersisted = UnpersistedPages::new(100);
+        unpersisted.add(&[link(1, 10, 20), link(3, 0, 250)]);
+        unpersisted.add(&[link(1, 40, 20)]);
+        assert!((1..=5).all(|page_id| unpersisted.contains(page_id.into()) != (page_id == 2)));
+
+        unpersisted.remove(&[link(1, 10, 20), link(3, 0, 250)]);
+        assert!(unpersisted.contains(1.into()));
+        assert!(!unpersisted.contains(4.into()));
+        unpersisted.remove(&[link(1, 40, 20)]);
+        assert!(!unpersisted.contains(1.into()));
+
+        unpersisted.add_truncate();
+        assert!(unpersisted.contains(7.into()));
+        unpersisted.remove_truncate();
+        assert!(!unpersisted.contains(7.into()));
+    }
+
     #[test]
     fn reusable_ranges_are_subtracted_in_one_sorted_scan() {
         let free = vec![link(2, 0, 50), link(1, 0, 100)];
@@ -13,8 +13,12 @@ use indexset::cdc::change::ChangeEvent;
 use indexset::core::pair::Pair;
 use tokio::fs::{File, OpenOptions};
 
-pub use art_index::{ArtPersistenceKey, SpaceArcticIndex, SpaceCongeeIndex};
-pub use data::SpaceData;
+use crate::in_memory::PageCompression;
+
+pub use art_index::{
+    ArtPersistenceKey, SpaceArcticIndex, SpaceArcticMultiIndex, SpaceCongeeIndex, SpaceCongeeMultiIndex, SpaceHashIndex,
+};
+pub use data::{SpaceData, SpaceDataPages, UnpersistedPages};
 pub use index::{
     IndexTableOfContents, SpaceIndex, SpaceIndexUnsized, map_index_pages_to_toc_and_general,
     map_unsized_index_pages_to_toc_and_general, reconstruct_multi_index_nodes,
@@ -33,8 +37,18 @@ pub trait SpaceDataOps<PkGenState> {
     where
         Self: Sized;
     fn bootstrap(file: &mut File, table_name: String, version: u32) -> impl Future<Output = eyre::Result<()>> + Send;
+    fn set_page_compression(&mut self, compression: PageCompression);
     fn save_data(&mut self, link: Link, bytes: &[u8]) -> impl Future<Output = eyre::Result<()>> + Send;
     fn save_batch_data(&mut self, batch_data: BatchData) -> impl Future<Output = eyre::Result<()>> + Send;
+    /// Sets the deleted flag of the rows stored at `links`. The flag is the
+    /// byte `flag_offset` bytes before the end of each row. A flag that lies
+    /// in the `live` rows written since, or past the file, is not written.
+    fn mark_rows_deleted(
+        &mut self,
+        links: Vec<Link>,
+        live: Vec<Link>,
+        flag_offset: u32,
+    ) -> impl Future<Output = eyre::Result<()>> + Send;
     fn reclaim_data_pages(&mut self, page_ids: Vec<PageId>) -> impl Future<Output = eyre::Result<()>> + Send;
     fn get_mut_info(&mut self) -> &mut GeneralPage<SpaceInfoPage<PkGenState>>;
     fn save_info(&mut self) -> impl Future<Output = eyre::Result<()>> + Send;
@@ -15,6 +15,7 @@ use worktable_codegen::worktable;
 use crate::persistence::operation::{BatchInnerRow, BatchInnerWorkTable, BatchOperation, OperationId};
 use crate::persistence::{
     PersistenceEngine, PersistenceError, PersistenceIndexCorruption, PersistenceResult, PersistenceState,
+    UnpersistedPages,
 };
 use crate::prelude::*;
 use crate::util::OptimizedVec;
@@ -38,6 +39,11 @@ worktable! (
 
 const MAX_PAGE_AMOUNT: usize = 16;
 
+/// Row links of the data file, from [`Operation::link_changes`].
+fn link_changes(changes: Vec<(Link, bool)>) -> Vec<Link> {
+    changes.into_iter().map(|(link, _)| link).collect()
+}
+
 #[derive(Debug)]
 struct PersistenceLifecycle {
     state: ParkingMutex<PersistenceState>,
@@ -292,13 +298,7 @@ where
                         .page_id;
                     used_page_ids.insert(page_id);
                     let page_ops = self.queue_inner_wt.select_by_page_id(page_id).execute()?;
-                    let max_op_id = &mut next_op_id;
-                    ops_set.extend(page_ops.into_iter().map(move |r| {
-                        if r.operation_id > *max_op_id {
-                            *max_op_id = r.operation_id
-                        }
-                        r.operation_id
-                    }));
+                    ops_set.extend(page_ops.into_iter().map(|r| r.operation_id));
                 }
                 OperationId::Multi(_) => {
                     let mut ops_set_to_extend = HashSet::new();
@@ -340,9 +340,20 @@ where
                     no_more_ops = true;
                 }
             };
-            let mut range = self.queue_inner_wt.0.indexes.operation_id_idx.range(next_op_id..);
-            if let Some((id, _)) = range.nth(1) {
-                next_op_id = *id;
+            // Continue with the next queued operation that is not collected
+            // yet instead of jumping past the newest operation of this page:
+            // operations on other pages in between (e.g. acknowledge
+            // operations, which have no page) carry event ids the batch needs.
+            let next = self
+                .queue_inner_wt
+                .0
+                .indexes
+                .operation_id_idx
+                .range(next_op_id..)
+                .map(|(id, _)| *id)
+                .find(|id| !ops_set.contains(id));
+            if let Some(id) = next {
+                next_op_id = id;
             } else {
                 no_more_ops = true
             }
@@ -543,6 +554,7 @@ mod lifecycle_tests {
                 offset: id as u32,
                 length: 1,
             },
+            replaced: None,
         })
     }
 
@@ -558,6 +570,7 @@ mod lifecycle_tests {
                 offset,
                 length: 8,
             },
+            replaced: None,
         })
     }
 
@@ -768,7 +781,15 @@ mod lifecycle_tests {
 #[derive(Debug)]
 enum PersistenceMessage<PrimaryKeyGenState, PrimaryKey, SecondaryKeys> {
     Operation(Operation<PrimaryKeyGenState, PrimaryKey, SecondaryKeys>),
+    Barrier(PersistenceBarrier),
+}
+
+/// Maintenance work applied only after every operation queued before it has
+/// reached the engine.
+#[derive(Debug)]
+enum PersistenceBarrier {
     ReclaimPages(Vec<PageId>),
+    Truncate,
 }
 
 #[derive(Debug)]
@@ -784,15 +805,17 @@ pub struct Queue<PrimaryKeyGenState, PrimaryKey, SecondaryKeys> {
     // queue that still holds work.
     len: Arc<AtomicUsize>,
     lifecycle: Arc<PersistenceLifecycle>,
+    unpersisted: Option<Arc<UnpersistedPages>>,
 }
 
 impl<PrimaryKeyGenState, PrimaryKey, SecondaryKeys> Queue<PrimaryKeyGenState, PrimaryKey, SecondaryKeys> {
-    fn new(lifecycle: Arc<PersistenceLifecycle>) -> Self {
+    fn new(lifecycle: Arc<PersistenceLifecycle>, unpersisted: Option<Arc<UnpersistedPages>>) -> Self {
         Self {
             queue: ParkingMutex::new(VecDeque::new()),
             notify: Notify::new(),
             len: Arc::new(AtomicUsize::new(0)),
             lifecycle,
+            unpersisted,
         }
     }
 
@@ -811,6 +834,13 @@ impl<PrimaryKeyGenState, PrimaryKey, SecondaryKeys> Queue<PrimaryKeyGenState, Pr
             PersistenceState::Closed => return Err(Arc::new(PersistenceError::Closed)),
             PersistenceState::Failed(error) => return Err(error.clone()),
         }
+        if let Some(unpersisted) = &self.unpersisted {
+            match &value {
+                PersistenceMessage::Operation(op) => unpersisted.add(&link_changes(op.link_changes())),
+                PersistenceMessage::Barrier(PersistenceBarrier::Truncate) => unpersisted.add_truncate(),
+                PersistenceMessage::Barrier(PersistenceBarrier::ReclaimPages(_)) => {}
+            }
+        }
         self.len.fetch_add(1, Ordering::Release);
         self.queue.lock().push_back(value);
         self.notify.notify_one();
@@ -889,7 +919,7 @@ where
     }
 
     fn reclaim_pages(&self, page_ids: Vec<PageId>) -> PersistenceResult {
-        self.push_message(PersistenceMessage::ReclaimPages(page_ids))
+        self.push_message(PersistenceMessage::Barrier(PersistenceBarrier::ReclaimPages(page_ids)))
     }
 }
 
@@ -952,6 +982,13 @@ impl<PrimaryKeyGenState, PrimaryKey, SecondaryKeys, AvailableIndexes>
         self.queue.push(op)
     }
 
+    /// Queues a truncate of the persisted table behind every operation
+    /// already in the queue.
+    pub fn truncate(&self) -> PersistenceResult {
+        self.queue
+            .push_message(PersistenceMessage::Barrier(PersistenceBarrier::Truncate))
+    }
+
     pub fn ensure_running(&self) -> PersistenceResult {
         self.lifecycle.ensure_running()
     }
@@ -986,7 +1023,20 @@ impl<PrimaryKeyGenState, PrimaryKey, SecondaryKeys, AvailableIndexes>
         self.queue.clone()
     }
 
-    pub fn run_engine<E>(mut engine: E) -> Self
+    pub fn run_engine<E>(engine: E) -> Self
+    where
+        E: PersistenceEngine<PrimaryKeyGenState, PrimaryKey, SecondaryKeys, AvailableIndexes> + Send + 'static,
+        SecondaryKeys: Clone + Debug + Default + TableSecondaryIndexEventsOps<AvailableIndexes> + Send + Sync + 'static,
+        PrimaryKeyGenState: Clone + Debug + Send + Sync + 'static,
+        PrimaryKey: Clone + Debug + Send + Sync + 'static,
+        AvailableIndexes: Copy + Clone + Debug + Hash + Eq + Send + Sync + 'static,
+    {
+        Self::run_engine_with_unpersisted(engine, None)
+    }
+
+    /// Same as [`Self::run_engine`], counting the data pages with queued
+    /// writes in `unpersisted` until the engine wrote them.
+    pub fn run_engine_with_unpersisted<E>(mut engine: E, unpersisted: Option<Arc<UnpersistedPages>>) -> Self
     where
         E: PersistenceEngine<PrimaryKeyGenState, PrimaryKey, SecondaryKeys, AvailableIndexes> + Send + 'static,
         SecondaryKeys: Clone + Debug + Default + TableSecondaryIndexEventsOps<AvailableIndexes> + Send + Sync + 'static,
@@ -996,7 +1046,7 @@ impl<PrimaryKeyGenState, PrimaryKey, SecondaryKeys, AvailableIndexes>
     {
         let table_path = engine.config().table_path().to_owned();
         let lifecycle = Arc::new(PersistenceLifecycle::new());
-        let queue = Arc::new(Queue::new(lifecycle.clone()));
+        let queue = Arc::new(Queue::new(lifecycle.clone(), unpersisted.clone()));
 
         let engine_queue = queue.clone();
         let engine_lifecycle = lifecycle.clone();
@@ -1006,16 +1056,16 @@ impl<PrimaryKeyGenState, PrimaryKey, SecondaryKeys, AvailableIndexes>
         let task_analyzer_in_progress = analyzer_in_progress.clone();
 
         let worker = async move {
-            let mut pending_reclaim: Option<Vec<PageId>> = None;
+            let mut pending_barrier: Option<PersistenceBarrier> = None;
             loop {
-                let message = if pending_reclaim.is_none() {
+                let message = if pending_barrier.is_none() {
                     engine_queue.immediate_pop()
                 } else {
                     None
                 };
                 let message = if message.is_some() {
                     message
-                } else if analyzer.len() == 0 && pending_reclaim.is_none() {
+                } else if analyzer.len() == 0 && pending_barrier.is_none() {
                     task_analyzer_in_progress.store(false, Ordering::Release);
                     engine_lifecycle.progress_notify.notify_waiters();
                     if matches!(engine_lifecycle.state(), PersistenceState::Closing) {
@@ -1038,14 +1088,14 @@ impl<PrimaryKeyGenState, PrimaryKey, SecondaryKeys, AvailableIndexes>
                                 return;
                             }
                         }
-                        PersistenceMessage::ReclaimPages(page_ids) => pending_reclaim = Some(page_ids),
+                        PersistenceMessage::Barrier(barrier) => pending_barrier = Some(barrier),
                     }
                 }
 
-                // Pull operations up to, but never past, a reclamation
+                // Pull operations up to, but never past, a maintenance
                 // barrier. This gives the analyzer every CDC event required
                 // for a batch while preserving FIFO ordering for maintenance.
-                while pending_reclaim.is_none() {
+                while pending_barrier.is_none() {
                     match engine_queue.immediate_pop() {
                         Some(PersistenceMessage::Operation(op)) => {
                             if let Err(err) = analyzer.push(op) {
@@ -1053,7 +1103,7 @@ impl<PrimaryKeyGenState, PrimaryKey, SecondaryKeys, AvailableIndexes>
                                 return;
                             }
                         }
-                        Some(PersistenceMessage::ReclaimPages(page_ids)) => pending_reclaim = Some(page_ids),
+                        Some(PersistenceMessage::Barrier(barrier)) => pending_barrier = Some(barrier),
                         None => break,
                     }
                 }
@@ -1064,31 +1114,45 @@ impl<PrimaryKeyGenState, PrimaryKey, SecondaryKeys, AvailableIndexes>
                         engine_lifecycle.fail(e);
                         return;
                     } else if let Some(batch_op) = batch_op.unwrap() {
+                        let written = unpersisted.as_ref().map(|_| link_changes(batch_op.link_changes()));
                         let res = engine.apply_batch_operation(batch_op).await;
                         if let Err(e) = res {
                             engine_lifecycle.fail(e);
                             return;
                         }
+                        if let (Some(unpersisted), Some(written)) = (&unpersisted, written) {
+                            unpersisted.remove(&written);
+                        }
                     } else {
                         tokio::time::sleep(Duration::from_millis(500)).await;
                     }
-                } else if let Some(page_ids) = pending_reclaim.take() {
+                } else if let Some(barrier) = pending_barrier.take() {
                     // `get_first_op_id_available() == None` is only sufficient
                     // when the analyzer itself is empty. If its operation-id
                     // index ever loses an entry, reclaiming here would make a
                     // source page reusable before its buffered row move became
-                    // durable. Fail terminally instead of trusting that state.
+                    // durable, and a truncate would let that operation land on
+                    // the reset files. Fail terminally instead of trusting that
+                    // state.
                     let buffered_operations = analyzer.len();
                     if buffered_operations != 0 {
                         engine_lifecycle.fail(eyre::eyre!(
-                            "persistence reclamation barrier found {buffered_operations} buffered operations without an operation-id index entry"
+                            "persistence maintenance barrier found {buffered_operations} buffered operations without an operation-id index entry"
                         ));
                         return;
                     }
-                    if let Err(error) = engine.reclaim_data_pages(page_ids).await {
+                    let truncate = matches!(barrier, PersistenceBarrier::Truncate);
+                    let res = match barrier {
+                        PersistenceBarrier::ReclaimPages(page_ids) => engine.reclaim_data_pages(page_ids).await,
+                        PersistenceBarrier::Truncate => engine.truncate().await,
+                    };
+                    if let Err(error) = res {
                         engine_lifecycle.fail(error);
                         return;
                     }
+                    if let Some(unpersisted) = unpersisted.as_ref().filter(|_| truncate) {
+                        unpersisted.remove_truncate();
+                    }
                 }
             }
         };
@@ -1,3 +1,12 @@
 pub trait TableRow<Pk> {
     fn get_primary_key(&self) -> Pk;
 }
+
+/// Row versions handed back by the generated `*_returning` update queries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UpdatedRow<Row> {
+    /// Row as it was before the update.
+    pub old: Row,
+    /// Row as it was written by the update.
+    pub new: Row,
+}
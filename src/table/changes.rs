@@ -0,0 +1,144 @@
+use derive_more::{Display, Error};
+use futures::Stream;
+use parking_lot::Mutex;
+use tokio::sync::broadcast;
+
+/// Default number of changes buffered per [`ChangeFeed`] before the slowest
+/// subscriber starts lagging.
+pub const DEFAULT_CHANGE_FEED_CAPACITY: usize = 1024;
+
+/// Committed change of a single row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RowChange<Row, PrimaryKey> {
+    Inserted(Row),
+    Updated { old: Row, new: Row },
+    Deleted(PrimaryKey),
+}
+
+/// [`RowChange`] tagged with its position in the table's change feed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowChangeEvent<Row, PrimaryKey> {
+    pub seq: u64,
+    pub change: RowChange<Row, PrimaryKey>,
+}
+
+#[derive(Clone, Copy, Debug, Display, Error, PartialEq, Eq)]
+pub enum ChangeFeedError {
+    /// Subscriber fell behind and the given number of changes were dropped
+    /// for it. Receiving continues from the oldest change still buffered.
+    #[display("subscriber lagged behind and missed {} changes", _0)]
+    Lagged(#[error(not(source))] u64),
+    /// Table was dropped and every buffered change was already received.
+    #[display("change feed is closed")]
+    Closed,
+}
+
+/// In-process broadcast of the changes committed to a table.
+///
+/// Changes are published while the mutated row's lock is still held, so
+/// changes of one primary key are always observed in commit order. Sequence
+/// numbers are assigned at publish time and grow strictly across the whole
+/// table. Nothing is built or sent while there are no subscribers.
+#[derive(Debug)]
+pub struct ChangeFeed<Row, PrimaryKey> {
+    sender: broadcast::Sender<RowChangeEvent<Row, PrimaryKey>>,
+    seq: Mutex<u64>,
+}
+
+impl<Row, PrimaryKey> Default for ChangeFeed<Row, PrimaryKey>
+where
+    Row: Clone,
+    PrimaryKey: Clone,
+{
+    fn default() -> Self {
+        Self::new(DEFAULT_CHANGE_FEED_CAPACITY)
+    }
+}
+
+impl<Row, PrimaryKey> ChangeFeed<Row, PrimaryKey>
+where
+    Row: Clone,
+    PrimaryKey: Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            sender,
+            seq: Mutex::new(0),
+        }
+    }
+
+    /// Subscribes to changes committed after this call.
+    pub fn subscribe(&self) -> ChangeSubscription<Row, PrimaryKey> {
+        ChangeSubscription {
+            receiver: self.sender.subscribe(),
+        }
+    }
+
+    /// Returns `true` if at least one subscriber is attached. Used by the
+    /// mutation paths to skip capturing row versions nobody will receive.
+    pub fn is_observed(&self) -> bool {
+        self.sender.receiver_count() != 0
+    }
+
+    /// Last sequence number handed out, `0` if nothing was published yet.
+    pub fn last_seq(&self) -> u64 {
+        *self.seq.lock()
+    }
+
+    pub fn publish(&self, change: RowChange<Row, PrimaryKey>) {
+        let mut seq = self.seq.lock();
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+        *seq += 1;
+        // Only fails if the last subscriber left after the check above.
+        let _ = self.sender.send(RowChangeEvent { seq: *seq, change });
+    }
+}
+
+/// Receiving side of a [`ChangeFeed`].
+#[derive(Debug)]
+pub struct ChangeSubscription<Row, PrimaryKey> {
+    receiver: broadcast::Receiver<RowChangeEvent<Row, PrimaryKey>>,
+}
+
+impl<Row, PrimaryKey> ChangeSubscription<Row, PrimaryKey>
+where
+    Row: Clone,
+    PrimaryKey: Clone,
+{
+    /// Waits for the next change.
+    pub async fn recv(&mut self) -> Result<RowChangeEvent<Row, PrimaryKey>, ChangeFeedError> {
+        self.receiver.recv().await.map_err(|e| match e {
+            broadcast::error::RecvError::Lagged(missed) => ChangeFeedError::Lagged(missed),
+            broadcast::error::RecvError::Closed => ChangeFeedError::Closed,
+        })
+    }
+
+    /// Returns the next buffered change without waiting, or `None` if there
+    /// is nothing to receive yet.
+    pub fn try_recv(&mut self) -> Result<Option<RowChangeEvent<Row, PrimaryKey>>, ChangeFeedError> {
+        match self.receiver.try_recv() {
+            Ok(event) => Ok(Some(event)),
+            Err(broadcast::error::TryRecvError::Empty) => Ok(None),
+            Err(broadcast::error::TryRecvError::Lagged(missed)) => Err(ChangeFeedError::Lagged(missed)),
+            Err(broadcast::error::TryRecvError::Closed) => Err(ChangeFeedError::Closed),
+        }
+    }
+
+    /// Converts the subscription into a [`Stream`]. Lag is yielded as an
+    /// `Err` item and the stream keeps going; it ends once the feed is closed.
+    pub fn into_stream(self) -> impl Stream<Item = Result<RowChangeEvent<Row, PrimaryKey>, ChangeFeedError>>
+    where
+        Row: Send + 'static,
+        PrimaryKey: Send + 'static,
+    {
+        futures::stream::unfold(self, |mut subscription| async move {
+            match subscription.recv().await {
+                Err(ChangeFeedError::Closed) => None,
+                res => Some((res, subscription)),
+            }
+        })
+    }
+}
@@ -0,0 +1,156 @@
+use std::fmt::{self, Debug, Formatter};
+
+use crate::in_memory::{RowWrapper, StorableRow};
+use crate::table::WorkTableError;
+use crate::table::changes::RowChange;
+use crate::util::OffsetEqLink;
+use crate::{TableRow, UniqueIndex, WorkTable};
+
+/// Hook that inspects a row and may reject the mutation.
+pub type RowCheck<Row> = fn(&Row) -> Result<(), WorkTableError>;
+
+/// Hook that inspects the old and the new row version and may reject the
+/// update.
+pub type UpdateCheck<Row> = fn(&Row, &Row) -> Result<(), WorkTableError>;
+
+/// Functions called by the generated mutation methods at defined points.
+///
+/// `before_*` hooks run under the row lock before anything is written and
+/// reject the mutation by returning an error, which is handed to the caller
+/// unchanged. `after_*` hooks run under the same lock once the mutation is
+/// committed, so they observe changes of one primary key in commit order.
+/// Update hooks receive the old and the new row version. In-place updates
+/// only run `after_update`, as their closure writes the row directly.
+///
+/// Hooks must not mutate the table they are attached to, as the row lock is
+/// still held while they run.
+pub struct TableHooks<Row> {
+    pub before_insert: Option<RowCheck<Row>>,
+    pub after_insert: Option<fn(&Row)>,
+    pub before_update: Option<UpdateCheck<Row>>,
+    pub after_update: Option<fn(&Row, &Row)>,
+    pub before_delete: Option<RowCheck<Row>>,
+    pub after_delete: Option<fn(&Row)>,
+}
+
+impl<Row> Default for TableHooks<Row> {
+    fn default() -> Self {
+        Self {
+            before_insert: None,
+            after_insert: None,
+            before_update: None,
+            after_update: None,
+            before_delete: None,
+            after_delete: None,
+        }
+    }
+}
+
+impl<Row> Clone for TableHooks<Row> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Row> Copy for TableHooks<Row> {}
+
+impl<Row> Debug for TableHooks<Row> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TableHooks")
+            .field("before_insert", &self.before_insert.is_some())
+            .field("after_insert", &self.after_insert.is_some())
+            .field("before_update", &self.before_update.is_some())
+            .field("after_update", &self.after_update.is_some())
+            .field("before_delete", &self.before_delete.is_some())
+            .field("after_delete", &self.after_delete.is_some())
+            .finish()
+    }
+}
+
+impl<
+    Row,
+    PrimaryKey,
+    AvailableTypes,
+    AvailableIndexes,
+    SecondaryIndexes,
+    LockType,
+    PkGen,
+    const DATA_LENGTH: usize,
+    PkMap,
+> WorkTable<Row, PrimaryKey, AvailableTypes, AvailableIndexes, SecondaryIndexes, LockType, PkGen, DATA_LENGTH, PkMap>
+where
+    Row: TableRow<PrimaryKey> + StorableRow + Send + Clone + 'static,
+    PrimaryKey: Clone + Ord + Send + 'static + std::hash::Hash,
+    PkMap: UniqueIndex<PrimaryKey, OffsetEqLink<DATA_LENGTH>>,
+    <Row as StorableRow>::WrappedRow: RowWrapper<Row>,
+{
+    pub fn check_insert(&self, row: &Row) -> Result<(), WorkTableError> {
+        match self.hooks.before_insert {
+            Some(hook) => hook(row),
+            None => Ok(()),
+        }
+    }
+
+    /// Reports a committed insert to the `after_insert` hook and the change
+    /// feed.
+    pub fn on_inserted(&self, row: Row) {
+        if let Some(hook) = self.hooks.after_insert {
+            hook(&row);
+        }
+        if self.changes.is_observed() {
+            self.changes.publish(RowChange::Inserted(row));
+        }
+    }
+
+    /// Returns `true` if someone receives committed updates, so the mutation
+    /// paths have to capture the old and the new row version.
+    pub fn captures_updates(&self) -> bool {
+        self.hooks.after_update.is_some() || self.changes.is_observed()
+    }
+
+    pub fn checks_updates(&self) -> bool {
+        self.hooks.before_update.is_some()
+    }
+
+    pub fn check_update(&self, old: &Row, new: &Row) -> Result<(), WorkTableError> {
+        match self.hooks.before_update {
+            Some(hook) => hook(old, new),
+            None => Ok(()),
+        }
+    }
+
+    /// Reports a committed update to the `after_update` hook and the change
+    /// feed.
+    pub fn on_updated(&self, old: Row, new: Row) {
+        if let Some(hook) = self.hooks.after_update {
+            hook(&old, &new);
+        }
+        if self.changes.is_observed() {
+            self.changes.publish(RowChange::Updated { old, new });
+        }
+    }
+
+    /// Returns `true` if someone receives committed deletes, so the mutation
+    /// paths have to keep the deleted row.
+    pub fn captures_deletes(&self) -> bool {
+        self.hooks.after_delete.is_some() || self.changes.is_observed()
+    }
+
+    pub fn check_delete(&self, row: &Row) -> Result<(), WorkTableError> {
+        match self.hooks.before_delete {
+            Some(hook) => hook(row),
+            None => Ok(()),
+        }
+    }
+
+    /// Reports a committed delete to the `after_delete` hook and the change
+    /// feed.
+    pub fn on_deleted(&self, row: Row) {
+        if let Some(hook) = self.hooks.after_delete {
+            hook(&row);
+        }
+        if self.changes.is_observed() {
+            self.changes.publish(RowChange::Deleted(row.get_primary_key()));
+        }
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::ops::Bound;
+
+use data_bucket::Link;
+use data_bucket::page::PageId;
+use derive_more::Display;
+use rkyv::api::high::HighDeserializer;
+use rkyv::rancor::Strategy;
+use rkyv::ser::Serializer;
+use rkyv::ser::allocator::ArenaHandle;
+use rkyv::ser::sharing::Share;
+use rkyv::util::AlignedVec;
+use rkyv::{Archive, Deserialize, Portable, Serialize};
+
+use crate::in_memory::{PagesExecutionError, RowWrapper, StorableRow, overflow_segments};
+use crate::prelude::TablePrimaryKey;
+use crate::util::OffsetEqLink;
+use crate::{TableRow, TableSecondaryIndexIntegrity, UniqueIndex, WorkTable};
+
+/// Rows checked within one read grace period, so that a full check does not
+/// hold back the reclamation of retired rows for its whole run.
+const CHECK_BATCH: usize = 1024;
+
+/// How much of a table [`WorkTable::verify_integrity`] checks.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum IntegrityCheck<PrimaryKey> {
+    /// Checks every row, every index entry and the empty-link registry.
+    #[default]
+    Full,
+    /// Checks up to `limit` rows after `after` in primary key order, against
+    /// the primary and secondary indexes. Passing the report's
+    /// [`IntegrityReport::resume_after`] back continues where the last batch
+    /// stopped, so a live table can be covered a slice at a time.
+    Incremental { after: Option<PrimaryKey>, limit: usize },
+}
+
+/// Outcome of [`WorkTable::verify_integrity`].
+#[derive(Debug)]
+pub struct IntegrityReport<PrimaryKey> {
+    /// Rows compared with the primary and secondary indexes.
+    pub rows_checked: usize,
+    /// Last row checked by an incremental check that did not reach the end
+    /// of the table.
+    pub resume_after: Option<PrimaryKey>,
+    pub discrepancies: Vec<IntegrityDiscrepancy>,
+}
+
+impl<PrimaryKey> IntegrityReport<PrimaryKey> {
+    pub fn is_consistent(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+/// One disagreement between the indexes and the data pages. Keys are given
+/// by their `Debug` representation.
+#[derive(Clone, Debug, Display, PartialEq, Eq)]
+pub enum IntegrityDiscrepancy {
+    #[display("primary key {primary_key} references an unreadable row at {link:?}: {reason}")]
+    UnreadableRow {
+        primary_key: String,
+        link: Link,
+        reason: String,
+    },
+    #[display("row at {link:?} does not have primary key {primary_key}")]
+    PrimaryKeyMismatch { primary_key: String, link: Link },
+    #[display("reverse primary index does not map {link:?} to primary key {primary_key}")]
+    ReverseEntryMissing { primary_key: String, link: Link },
+    #[display("reverse primary index maps {link:?} to primary key {primary_key}, which is stored elsewhere")]
+    StaleReverseEntry { primary_key: String, link: Link },
+    #[display("primary indexes hold {forward} forward and {reverse} reverse entries")]
+    ReverseCountMismatch { forward: usize, reverse: usize },
+    #[display("secondary index {index} has no entry for primary key {primary_key}")]
+    SecondaryEntryMissing {
+        index: &'static str,
+        primary_key: String,
+        link: Link,
+    },
+    #[display("secondary index {index} maps {key} to {link:?}, which holds no row")]
+    DanglingSecondaryEntry {
+        index: &'static str,
+        key: String,
+        link: Link,
+    },
+    #[display("secondary index {index} maps {key} to {link:?}, whose row has another key")]
+    SecondaryKeyMismatch {
+        index: &'static str,
+        key: String,
+        link: Link,
+    },
+    #[display("secondary index {index} holds {entries} entries for {rows} rows")]
+    EntryCountMismatch {
+        index: &'static str,
+        entries: usize,
+        rows: usize,
+    },
+    #[display("empty link {link:?} lies outside the written part of its page")]
+    EmptyLinkOutOfBounds { link: Link },
+    #[display("empty link {link:?} overlaps {other:?}")]
+    EmptyLinkOverlap { link: Link, other: Link },
+}
+
+impl<
+    Row,
+    PrimaryKey,
+    AvailableTypes,
+    AvailableIndexes,
+    SecondaryIndexes,
+    LockType,
+    PkGen,
+    const DATA_LENGTH: usize,
+    PkMap,
+> WorkTable<Row, PrimaryKey, AvailableTypes, AvailableIndexes, SecondaryIndexes, LockType, PkGen, DATA_LENGTH, PkMap>
+where
+    Row: TableRow<PrimaryKey>
+        + StorableRow
+        + Archive
+        + Send
+        + Clone
+        + 'static
+        + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+    <Row as StorableRow>::WrappedRow: RowWrapper<Row>,
+    <<Row as StorableRow>::WrappedRow as Archive>::Archived:
+        Portable + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>,
+    PrimaryKey: Debug + Clone + Ord + Send + TablePrimaryKey + std::hash::Hash,
+    PkMap: UniqueIndex<PrimaryKey, OffsetEqLink<DATA_LENGTH>>,
+    SecondaryIndexes: TableSecondaryIndexIntegrity<Row>,
+{
+    /// Compares the primary index, the secondary indexes, the rows and the
+    /// empty-link registry, and lists every disagreement found instead of
+    /// stopping at the first.
+    ///
+    /// The check reads rows in batches, each inside a read grace period, and
+    /// takes no row locks, so writers keep going while it runs. A row that
+    /// disagrees with an index is checked again under its key's mutation
+    /// guard, and an index entry is looked up again, before either is
+    /// reported, which filters out writes that were in progress during the
+    /// check. Count comparisons and the empty-link check cannot be confirmed
+    /// this way and are only reported by [`IntegrityCheck::Full`], whose
+    /// result is exact on a table without concurrent writes.
+    pub fn verify_integrity(&self, check: IntegrityCheck<PrimaryKey>) -> IntegrityReport<PrimaryKey> {
+        let mut discrepancies = vec![];
+        let full = check == IntegrityCheck::Full;
+
+        let (entries, resume_after): (Vec<_>, _) = match check {
+            IntegrityCheck::Full => (self.primary_index.pk_map.iter_values().collect(), None),
+            IntegrityCheck::Incremental { after, limit } => {
+                let limit = limit.max(1);
+                let lower = after.map_or(Bound::Unbounded, Bound::Excluded);
+                let mut entries = self
+                    .primary_index
+                    .pk_map
+                    .range_values((lower, Bound::Unbounded))
+                    .take(limit.saturating_add(1))
+                    .collect::<Vec<_>>();
+                let resume_after = if entries.len() > limit {
+                    entries.truncate(limit);
+                    entries.last().map(|(primary_key, _)| primary_key.clone())
+                } else {
+                    None
+                };
+                (entries, resume_after)
+            }
+        };
+
+        // A link retired before its batch started reads as another row or
+        // none, so its row is checked again below at its current link.
+        for batch in entries.chunks(CHECK_BATCH) {
+            let suspects = {
+                let _read_guard = self.data.read_guard();
+                batch
+                    .iter()
+                    .filter(|(primary_key, offset_link)| !self.row_discrepancies(primary_key, *offset_link).is_empty())
+                    .map(|(primary_key, _)| primary_key)
+                    .collect::<Vec<_>>()
+            };
+            // A writer may be between its data and index writes for a suspect
+            // row. Its mutation phase holds the key's guard, so the row is
+            // checked again once the write is done, at its current link. The
+            // batch's read guard is released first: truncate waits for readers
+            // to leave while it holds the mutation barrier.
+            for primary_key in suspects {
+                let _mutation_guard = self.lock_manager.mutation_guard(primary_key);
+                let _read_guard = self.data.read_guard();
+                if let Some(offset_link) = self.primary_index.pk_map.get_value(primary_key) {
+                    discrepancies.extend(self.row_discrepancies(primary_key, offset_link));
+                }
+            }
+        }
+
+        if full {
+            self.verify_unindexed_state(entries.len(), &mut discrepancies);
+            self.verify_empty_links(&mut discrepancies);
+        }
+
+        IntegrityReport {
+            rows_checked: entries.len(),
+            resume_after,
+            discrepancies,
+        }
+    }
+
+    /// Compares one row with the primary and secondary indexes.
+    fn row_discrepancies(
+        &self,
+        primary_key: &PrimaryKey,
+        offset_link: OffsetEqLink<DATA_LENGTH>,
+    ) -> Vec<IntegrityDiscrepancy> {
+        let link = offset_link.0;
+        let row = match self.data.select_non_ghosted(link) {
+            Ok(row) => row,
+            // An insert that has not been published yet.
+            Err(PagesExecutionError::Ghosted) => return vec![],
+            Err(error) => {
+                return vec![IntegrityDiscrepancy::UnreadableRow {
+                    primary_key: format!("{primary_key:?}"),
+                    link,
+                    reason: error.to_string(),
+                }];
+            }
+        };
+        let mut discrepancies = vec![];
+        if row.get_primary_key() != *primary_key {
+            discrepancies.push(IntegrityDiscrepancy::PrimaryKeyMismatch {
+                primary_key: format!("{primary_key:?}"),
+                link,
+            });
+        }
+        if self.primary_index.reverse_pk_map.get_value(&offset_link).as_ref() != Some(primary_key) {
+            discrepancies.push(IntegrityDiscrepancy::ReverseEntryMissing {
+                primary_key: format!("{primary_key:?}"),
+                link,
+            });
+        }
+        for index in self.indexes.missing_entries(&row, link) {
+            discrepancies.push(IntegrityDiscrepancy::SecondaryEntryMissing {
+                index,
+                primary_key: format!("{primary_key:?}"),
+                link,
+            });
+        }
+        discrepancies
+    }
+
+    /// Checks what cannot be reached from a primary key: reverse primary
+    /// entries, secondary entries and index sizes.
+    fn verify_unindexed_state(&self, rows: usize, discrepancies: &mut Vec<IntegrityDiscrepancy>) {
+        let reverse = self.primary_index.reverse_pk_map.len();
+        if reverse != rows {
+            discrepancies.push(IntegrityDiscrepancy::ReverseCountMismatch { forward: rows, reverse });
+        }
+        // Iterators hold node locks, so entries are looked up again only once
+        // the iteration is over.
+        let reverse_entries = self.primary_index.reverse_pk_map.iter_values().collect::<Vec<_>>();
+        for (offset_link, primary_key) in reverse_entries {
+            if self.primary_index.pk_map.get_value(&primary_key) != Some(offset_link)
+                && self.primary_index.reverse_pk_map.get_value(&offset_link).as_ref() == Some(&primary_key)
+            {
+                discrepancies.push(IntegrityDiscrepancy::StaleReverseEntry {
+                    primary_key: format!("{primary_key:?}"),
+                    link: offset_link.0,
+                });
+            }
+        }
+
+        // Each row is read in a grace period of its own; an entry whose row
+        // was retired meanwhile is looked up again before it is reported.
+        discrepancies.extend(self.indexes.verify_entries(&|link| {
+            let _read_guard = self.data.read_guard();
+            match self.data.select_non_ghosted(link) {
+                Ok(row) => Some(row),
+                // Inserts index a row before publishing it.
+                Err(PagesExecutionError::Ghosted) => self.data.select(link).ok(),
+                Err(_) => None,
+            }
+        }));
+        for (index, entries) in self.indexes.entry_counts() {
+            if entries != rows {
+                discrepancies.push(IntegrityDiscrepancy::EntryCountMismatch { index, entries, rows });
+            }
+        }
+    }
+
+    /// Checks that every registered empty link lies within the written part
+    /// of its page and overlaps neither a row nor another empty link.
+    fn verify_empty_links(&self, discrepancies: &mut Vec<IntegrityDiscrepancy>) {
+        let registry = self.data.empty_links_registry();
+        let mut spans: HashMap<PageId, Vec<(Link, bool)>> = HashMap::new();
+        for link in registry.iter() {
+            if self.data.check_link_bounds(link).is_err() {
+                discrepancies.push(IntegrityDiscrepancy::EmptyLinkOutOfBounds { link });
+                continue;
+            }
+            spans.entry(link.page_id).or_default().push((link, true));
+        }
+        for offset_link in self.primary_index.reverse_pk_map.iter_values().map(|(link, _)| link) {
+            for segment in overflow_segments::<DATA_LENGTH>(offset_link.0) {
+                if let Some(page) = spans.get_mut(&segment.page_id) {
+                    page.push((segment, false));
+                }
+            }
+        }
+
+        // Read once the spans are, for the first overlap found.
+        let mut registered = None;
+        for page in spans.values_mut() {
+            page.sort_by_key(|(link, _)| link.offset);
+            for pair in page.windows(2) {
+                let [(previous, previous_empty), (next, next_empty)] = pair else {
+                    unreachable!("windows(2) yields pairs")
+                };
+                if !previous_empty && !next_empty || previous.offset + previous.length <= next.offset {
+                    continue;
+                }
+                let (link, other) = if *previous_empty {
+                    (*previous, *next)
+                } else {
+                    (*next, *previous)
+                };
+                // A writer may have reused the empty link for a row since the
+                // registry was read.
+                let registered = registered.get_or_insert_with(|| registry.iter().collect::<HashSet<_>>());
+                if registered.contains(&link) {
+                    discrepancies.push(IntegrityDiscrepancy::EmptyLinkOverlap { link, other });
+                }
+            }
+        }
+    }
+}
@@ -1,15 +1,23 @@
+pub mod changes;
+pub mod hooks;
+pub mod integrity;
+pub mod rebuild;
 pub mod select;
 pub mod system_info;
 pub mod vacuum;
 
-use crate::in_memory::{ArchivedRowWrapper, DataPages, RowWrapper, StorableRow};
+use crate::in_memory::{ArchivedRowWrapper, DataPages, MemoryBudget, RowWrapper, StorableRow};
+use crate::mem_stat::MemStat;
 use crate::persistence::{AcknowledgeOperation, InsertOperation, Operation, PersistenceLoadError};
-use crate::prelude::{Link, LockMap, OperationId, PrimaryKeyGeneratorState};
+use crate::prelude::{Link, LockMap, OperationId, PrimaryKeyGeneratorState, RowLock};
 use crate::primary_key::{PrimaryKeyGenerator, TablePrimaryKey};
+use crate::table::changes::ChangeFeed;
+use crate::table::hooks::TableHooks;
 use crate::util::OffsetEqLink;
 use crate::{
-    AvailableIndex, IndexError, IndexMap, PrimaryIndex, TableIndex, TableIndexCdc, TableRow, TableSecondaryIndex,
-    TableSecondaryIndexCdc, TableSecondaryIndexEventsOps, UniqueIndex, convert_change_events, in_memory,
+    AvailableIndex, IndexError, IndexMap, PrimaryIndex, StatisticsCache, TableIndex, TableIndexCdc, TableRow,
+    TableSecondaryIndex, TableSecondaryIndexCdc, TableSecondaryIndexEventsOps, UniqueIndex, convert_change_events,
+    in_memory,
 };
 use data_bucket::INNER_PAGE_SIZE;
 use derive_more::{Display, Error, From};
@@ -59,6 +67,12 @@ pub struct WorkTable<
 
     pub table_name: &'static str,
 
+    pub changes: ChangeFeed<Row, PrimaryKey>,
+
+    pub hooks: TableHooks<Row>,
+
+    pub statistics: StatisticsCache,
+
     pub pk_phantom: PhantomData<(AvailableTypes, AvailableIndexes)>,
 }
 
@@ -102,6 +116,9 @@ where
             lock_manager: Default::default(),
             update_state: IndexMap::default(),
             table_name: "",
+            changes: ChangeFeed::default(),
+            hooks: TableHooks::default(),
+            statistics: StatisticsCache::default(),
             pk_phantom: PhantomData,
         }
     }
@@ -137,7 +154,28 @@ where
             + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>
             + for<'a> rkyv::bytecheck::CheckBytes<rkyv::api::high::HighValidator<'a, rkyv::rancor::Error>>,
     {
-        let path = path.as_ref();
+        self.validate_persisted_index(path.as_ref(), true)
+    }
+
+    /// Audits the persisted primary index like
+    /// [`Self::validate_persisted_state`], but only checks that links stay
+    /// within the written part of their pages instead of decoding the rows,
+    /// so the pages of a lazily loaded table stay on disk.
+    pub fn validate_persisted_links(&self, path: impl AsRef<Path>) -> Result<(), PersistenceLoadError>
+    where
+        <<Row as StorableRow>::WrappedRow as Archive>::Archived: Portable
+            + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>
+            + for<'a> rkyv::bytecheck::CheckBytes<rkyv::api::high::HighValidator<'a, rkyv::rancor::Error>>,
+    {
+        self.validate_persisted_index(path.as_ref(), false)
+    }
+
+    fn validate_persisted_index(&self, path: &Path, check_rows: bool) -> Result<(), PersistenceLoadError>
+    where
+        <<Row as StorableRow>::WrappedRow as Archive>::Archived: Portable
+            + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>
+            + for<'a> rkyv::bytecheck::CheckBytes<rkyv::api::high::HighValidator<'a, rkyv::rancor::Error>>,
+    {
         let mut links = HashSet::with_capacity(self.primary_index.pk_map.len());
 
         for (primary_key, offset_link) in self.primary_index.pk_map.iter_values() {
@@ -148,18 +186,28 @@ where
                 ));
             }
 
-            let row = self.data.select_non_ghosted_checked(offset_link.0).map_err(|error| {
-                PersistenceLoadError::corrupt(
-                    path,
-                    format!("primary key {primary_key:?} references an invalid row: {error}"),
-                )
-            })?;
-            if row.get_primary_key() != primary_key {
-                return Err(PersistenceLoadError::corrupt(
-                    path,
-                    format!("row at {:?} does not match primary key {primary_key:?}", offset_link.0),
-                ));
+            if check_rows {
+                let row = self.data.select_non_ghosted_checked(offset_link.0).map_err(|error| {
+                    PersistenceLoadError::corrupt(
+                        path,
+                        format!("primary key {primary_key:?} references an invalid row: {error}"),
+                    )
+                })?;
+                if row.get_primary_key() != primary_key {
+                    return Err(PersistenceLoadError::corrupt(
+                        path,
+                        format!("row at {:?} does not match primary key {primary_key:?}", offset_link.0),
+                    ));
+                }
+            } else {
+                self.data.check_link_bounds(offset_link.0).map_err(|error| {
+                    PersistenceLoadError::corrupt(
+                        path,
+                        format!("primary key {primary_key:?} references an invalid row: {error}"),
+                    )
+                })?;
             }
+            self.data.seal_overflow_chain(offset_link.0);
 
             let reverse_key = self.primary_index.reverse_pk_map.get_value(&offset_link);
             if reverse_key.as_ref() != Some(&primary_key) {
@@ -221,6 +269,37 @@ where
         None
     }
 
+    /// Runs `op` over the archived row of `pk` without deserializing or
+    /// cloning it. Retries like [`Self::select`] while the row is moved.
+    ///
+    /// The row's page stays pinned for reading while `op` runs, so `op` must
+    /// not call back into the table, not even for reads.
+    pub fn select_with<Op, Res>(&self, pk: PrimaryKey, op: Op) -> Option<Res>
+    where
+        LockType: 'static,
+        <<Row as StorableRow>::WrappedRow as Archive>::Archived: ArchivedRowWrapper,
+        Op: FnOnce(&<<Row as StorableRow>::WrappedRow as Archive>::Archived) -> Res,
+    {
+        let _read_guard = self.data.read_guard();
+        let mut op = Some(op);
+        for _ in 0..64 {
+            let link = self.primary_index.pk_map.lookup_for_select(&pk).map(Into::into)?;
+            let res = self
+                .data
+                .with_non_ghosted_ref(link, |row| op.take().expect("op runs once")(row));
+            if let Ok(res) = res {
+                return Some(res);
+            }
+
+            let current_link: Option<Link> = self.primary_index.pk_map.lookup_for_select(&pk).map(Into::into);
+            if current_link == Some(link) {
+                return None;
+            }
+            std::hint::spin_loop();
+        }
+        None
+    }
+
     #[cfg_attr(feature = "perf_measurements", performance_measurement(prefix_name = "WorkTable"))]
     pub fn insert(&self, row: Row) -> Result<PrimaryKey, WorkTableError>
     where
@@ -240,7 +319,8 @@ where
     {
         let pk = row.get_primary_key().clone();
         let _mutation_guard = self.lock_manager.mutation_guard(&pk);
-        let link = self.data.insert(row.clone()).map_err(WorkTableError::PagesError)?;
+        self.check_insert(&row)?;
+        let link = self.data.insert(row.clone()).map_err(insert_error)?;
         if self.primary_index.insert_checked(pk.clone(), link).is_none() {
             self.data.delete(link).map_err(WorkTableError::PagesError)?;
             return Err(WorkTableError::PrimaryAlreadyExists);
@@ -262,6 +342,7 @@ where
                 .with_mut_ref(link, |r| r.unghost())
                 .map_err(WorkTableError::PagesError)?
         }
+        self.on_inserted(row);
 
         Ok(pk)
     }
@@ -274,6 +355,69 @@ where
         Option<Operation<<PkGen as PrimaryKeyGeneratorState>::State, PrimaryKey, SecondaryEvents>>,
         Result<PrimaryKey, WorkTableError>,
     )
+    where
+        Row: Archive
+            + Clone
+            + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+        <Row as StorableRow>::WrappedRow:
+            Archive + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+        <<Row as StorableRow>::WrappedRow as Archive>::Archived: ArchivedRowWrapper
+            + Portable
+            + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>,
+        PrimaryKey: Clone,
+        SecondaryEvents: Debug + Default + Clone + TableSecondaryIndexEventsOps<AvailableIndexes>,
+        SecondaryIndexes: TableSecondaryIndex<Row, AvailableTypes, AvailableIndexes>
+            + TableSecondaryIndexCdc<Row, AvailableTypes, SecondaryEvents, AvailableIndexes>,
+        PkGen: PrimaryKeyGeneratorState,
+        <PkGen as PrimaryKeyGeneratorState>::State: Debug,
+        AvailableIndexes: Debug + AvailableIndex,
+        PrimaryIndex<PrimaryKey, DATA_LENGTH, PkMap>: TableIndexCdc<PrimaryKey>,
+    {
+        let _mutation_guard = self.lock_manager.mutation_guard(&row.get_primary_key());
+        self.insert_cdc_unguarded(row)
+    }
+
+    /// Same as [`Self::insert_cdc`], but hands the operation to `persist`
+    /// before the per-key mutation gate is released, so it is queued in order
+    /// with table-wide barriers such as [`Self::truncate_with`].
+    pub fn insert_cdc_with<SecondaryEvents, F>(&self, row: Row, persist: F) -> Result<PrimaryKey, WorkTableError>
+    where
+        Row: Archive
+            + Clone
+            + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+        <Row as StorableRow>::WrappedRow:
+            Archive + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+        <<Row as StorableRow>::WrappedRow as Archive>::Archived: ArchivedRowWrapper
+            + Portable
+            + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>,
+        PrimaryKey: Clone,
+        SecondaryEvents: Debug + Default + Clone + TableSecondaryIndexEventsOps<AvailableIndexes>,
+        SecondaryIndexes: TableSecondaryIndex<Row, AvailableTypes, AvailableIndexes>
+            + TableSecondaryIndexCdc<Row, AvailableTypes, SecondaryEvents, AvailableIndexes>,
+        PkGen: PrimaryKeyGeneratorState,
+        <PkGen as PrimaryKeyGeneratorState>::State: Debug,
+        AvailableIndexes: Debug + AvailableIndex,
+        PrimaryIndex<PrimaryKey, DATA_LENGTH, PkMap>: TableIndexCdc<PrimaryKey>,
+        F: FnOnce(
+            Operation<<PkGen as PrimaryKeyGeneratorState>::State, PrimaryKey, SecondaryEvents>,
+        ) -> Result<(), WorkTableError>,
+    {
+        let _mutation_guard = self.lock_manager.mutation_guard(&row.get_primary_key());
+        let (op, res) = self.insert_cdc_unguarded(row);
+        if let Some(op) = op {
+            persist(op)?;
+        }
+        res
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn insert_cdc_unguarded<SecondaryEvents>(
+        &self,
+        row: Row,
+    ) -> (
+        Option<Operation<<PkGen as PrimaryKeyGeneratorState>::State, PrimaryKey, SecondaryEvents>>,
+        Result<PrimaryKey, WorkTableError>,
+    )
     where
         Row: Archive
             + Clone
@@ -293,11 +437,13 @@ where
         PrimaryIndex<PrimaryKey, DATA_LENGTH, PkMap>: TableIndexCdc<PrimaryKey>,
     {
         let pk = row.get_primary_key().clone();
-        let _mutation_guard = self.lock_manager.mutation_guard(&pk);
+        if let Err(e) = self.check_insert(&row) {
+            return (None, Err(e));
+        }
 
         let (link, _) = match self.data.insert_cdc(row.clone()) {
             Ok(result) => result,
-            Err(e) => return (None, Err(WorkTableError::PagesError(e))),
+            Err(e) => return (None, Err(insert_error(e))),
         };
 
         let primary_key_events = self.primary_index.insert_checked_cdc(pk.clone(), link);
@@ -380,7 +526,9 @@ where
             secondary_keys_events: secondary_events,
             bytes,
             link,
+            replaced: None,
         });
+        self.on_inserted(row);
 
         (Some(op), Ok(pk))
     }
@@ -396,6 +544,26 @@ where
     ///
     /// [`Link`]: data_bucket::Link
     pub async fn reinsert(&self, row_old: Row, row_new: Row) -> Result<PrimaryKey, WorkTableError>
+    where
+        Row: Archive
+            + Clone
+            + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+        <Row as StorableRow>::WrappedRow:
+            Archive + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+        <<Row as StorableRow>::WrappedRow as Archive>::Archived: ArchivedRowWrapper
+            + Portable
+            + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>,
+        PrimaryKey: Clone,
+        AvailableTypes: 'static,
+        AvailableIndexes: Debug + AvailableIndex,
+        SecondaryIndexes: TableSecondaryIndex<Row, AvailableTypes, AvailableIndexes>,
+        LockType: 'static,
+    {
+        self.reinsert_blocking(row_old, row_new)
+    }
+
+    /// Same as [`Self::reinsert`], for callers without an async runtime.
+    pub fn reinsert_blocking(&self, row_old: Row, row_new: Row) -> Result<PrimaryKey, WorkTableError>
     where
         Row: Archive
             + Clone
@@ -421,7 +589,7 @@ where
             .get_value(&pk)
             .map(Into::into)
             .ok_or(WorkTableError::NotFound)?;
-        let new_link = self.data.insert(row_new.clone()).map_err(WorkTableError::PagesError)?;
+        let new_link = self.data.insert(row_new.clone()).map_err(insert_error)?;
         unsafe {
             self.data
                 .with_mut_ref(new_link, |r| r.unghost())
@@ -486,7 +654,7 @@ where
         // Insert new data - if this fails, no events to acknowledge
         let (new_link, _) = match self.data.insert_cdc(row_new.clone()) {
             Ok(result) => result,
-            Err(e) => return (None, Err(WorkTableError::PagesError(e))),
+            Err(e) => return (None, Err(insert_error(e))),
         };
 
         // Unghost the new data - if this fails, we have no events yet to acknowledge
@@ -578,10 +746,150 @@ where
             secondary_keys_events: secondary_events,
             bytes,
             link: new_link,
+            replaced: Some(old_link),
         });
 
         (Some(op), Ok(pk))
     }
+
+    /// Removes every row from the table, leaving the primary key generator
+    /// untouched.
+    pub fn truncate(&self) -> Result<(), WorkTableError>
+    where
+        Row: Archive
+            + Clone
+            + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+        <<Row as StorableRow>::WrappedRow as Archive>::Archived:
+            Portable + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>,
+        AvailableIndexes: AvailableIndex,
+        SecondaryIndexes: TableSecondaryIndex<Row, AvailableTypes, AvailableIndexes>,
+        LockType: RowLock,
+    {
+        self.truncate_with(|| Ok(()))
+    }
+
+    /// Removes every row from the table under a table-wide writer barrier.
+    /// Change subscribers receive a `Deleted` change per removed row, and the
+    /// delete hooks run for every row; one rejected row keeps the whole table.
+    ///
+    /// Every per-key mutation gate is held while the table is reset, so no
+    /// insert, update or delete interleaves with it. Operations waiting on a
+    /// row lock observe the row as absent once the barrier is released.
+    /// Rows are only read when hooks or subscribers need them; a rejected row
+    /// or a running vacuum fails the call before anything is removed. The
+    /// indexes are then cleared and the pages reset, which cannot fail.
+    ///
+    /// `persist` runs under the barrier after the in-memory reset. Index
+    /// entries are removed without CDC events, so persisted tables must reset
+    /// their files through `persist` instead. If it fails, the error is
+    /// returned with the table already empty in memory.
+    pub fn truncate_with<F>(&self, persist: F) -> Result<(), WorkTableError>
+    where
+        Row: Archive
+            + Clone
+            + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+        <<Row as StorableRow>::WrappedRow as Archive>::Archived:
+            Portable + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>,
+        AvailableIndexes: AvailableIndex,
+        SecondaryIndexes: TableSecondaryIndex<Row, AvailableTypes, AvailableIndexes>,
+        LockType: RowLock,
+        F: FnOnce() -> Result<(), WorkTableError>,
+    {
+        let _barrier = self.lock_manager.mutation_barrier();
+        let captures = self.captures_deletes();
+        let mut deleted = vec![];
+        if self.hooks.before_delete.is_some() || captures {
+            let links: Vec<_> = self.primary_index.pk_map.iter_links().collect();
+            for link in links {
+                let row = self.data.select(link.0).map_err(WorkTableError::PagesError)?;
+                self.check_delete(&row)?;
+                if captures {
+                    deleted.push(row);
+                }
+            }
+        }
+
+        self.data
+            .truncate(|| {
+                self.indexes.clear();
+                self.primary_index.clear();
+            })
+            .map_err(WorkTableError::PagesError)?;
+        self.lock_manager.clear_unlocked();
+        self.statistics.clear();
+        let persisted = persist();
+
+        for row in deleted {
+            self.on_deleted(row);
+        }
+
+        persisted
+    }
+}
+
+impl<
+    Row,
+    PrimaryKey,
+    AvailableTypes,
+    AvailableIndexes,
+    SecondaryIndexes,
+    LockType,
+    PkGen,
+    const DATA_LENGTH: usize,
+    PkMap,
+> WorkTable<Row, PrimaryKey, AvailableTypes, AvailableIndexes, SecondaryIndexes, LockType, PkGen, DATA_LENGTH, PkMap>
+where
+    PrimaryKey: Clone + Ord + Send + Sync + 'static + std::hash::Hash,
+    Row: StorableRow + Send + Clone + 'static,
+    <Row as StorableRow>::WrappedRow: RowWrapper<Row>,
+    PkMap: UniqueIndex<PrimaryKey, OffsetEqLink<DATA_LENGTH>> + Send + Sync + 'static,
+    SecondaryIndexes: MemStat + Send + Sync + 'static,
+{
+    /// Limits the memory of the data pages and indexes of this table, or
+    /// lifts the limit with `None`. The limit is checked whenever the table
+    /// needs another data page, so inserts that reuse freed row slots always
+    /// succeed, and right away if it evicts; see
+    /// [`MemoryLimitPolicy`](in_memory::MemoryLimitPolicy) for what happens over the limit.
+    ///
+    /// Indexes are measured with [`MemStat::heap_size`].
+    pub fn set_memory_budget(&self, budget: Option<MemoryBudget>)
+    where
+        PrimaryKey: Debug + MemStat,
+        PkMap: MemStat,
+    {
+        let indexes = self.indexes.clone();
+        let primary_index = self.primary_index.clone();
+        self.data.set_memory_budget(budget, move || {
+            let primary_size = primary_index.pk_map.heap_size() + primary_index.reverse_pk_map.heap_size();
+            (indexes.heap_size() + primary_size) as u64
+        });
+        self.data.evict_over_budget();
+    }
+
+    /// Releases memory the table holds but does not use: the bytes of empty
+    /// data pages, such as those a vacuum frees, and the spare capacity of
+    /// index nodes. Returns the data page bytes freed.
+    pub fn shrink_to_fit(&self) -> u64
+    where
+        PrimaryKey: Debug,
+        PkMap: MemStat,
+    {
+        let freed = self.data.shrink_to_fit();
+        MemStat::shrink_to_fit(&self.primary_index.pk_map);
+        for node in self.primary_index.reverse_pk_map.iter_nodes() {
+            node.lock().shrink_to_fit();
+        }
+        self.indexes.shrink_to_fit();
+        freed
+    }
+}
+
+/// Surfaces a page allocation refused by the memory budget as its own error.
+fn insert_error(e: in_memory::PagesExecutionError) -> WorkTableError {
+    match e {
+        in_memory::PagesExecutionError::MemoryLimitExceeded => WorkTableError::MemoryLimitExceeded,
+        e => WorkTableError::PagesError(e),
+    }
 }
 
 #[derive(Debug, Display, Error, From)]
@@ -595,6 +903,18 @@ pub enum WorkTableError {
     SecondaryIndexError,
     PrimaryUpdateTry,
     PagesError(in_memory::PagesExecutionError),
+    /// A `before_*` table hook refused the mutation.
+    #[display("Mutation rejected: {}", _0)]
+    #[from(ignore)]
+    Rejected(#[error(not(source))] String),
+    /// A row or column lock stayed held by another operation for longer than
+    /// the table's lock wait timeout, or at all for a `try_*` method.
+    #[display("Timed out waiting for a row lock")]
+    LockTimeout,
+    /// The table needed another data page over its memory budget, see
+    /// [`WorkTable::set_memory_budget`].
+    #[display("Table memory limit exceeded")]
+    MemoryLimitExceeded,
     #[display("{}", _0)]
     PersistenceError(#[error(not(source))] std::sync::Arc<crate::persistence::PersistenceError>),
 }
@@ -0,0 +1,147 @@
+use std::fmt::Debug;
+
+use data_bucket::Link;
+use indexset::cdc::change::ChangeEvent;
+use indexset::core::pair::Pair;
+use rkyv::api::high::HighDeserializer;
+use rkyv::rancor::Strategy;
+use rkyv::ser::Serializer;
+use rkyv::ser::allocator::ArenaHandle;
+use rkyv::ser::sharing::Share;
+use rkyv::util::AlignedVec;
+use rkyv::{Archive, Deserialize, Portable, Serialize};
+
+use crate::in_memory::{RowWrapper, StorableRow};
+use crate::prelude::TablePrimaryKey;
+use crate::util::OffsetEqLink;
+use crate::{
+    AvailableIndex, IndexError, PrimaryIndex, TableIndexCdc, TableRow, TableSecondaryIndexCdc,
+    TableSecondaryIndexEventsOps, UniqueIndex, WorkTable, convert_change_events,
+};
+
+/// Outcome of rebuilding the indexes of a table from its data pages.
+#[derive(Debug)]
+pub struct IndexRebuildReport<Row> {
+    /// Rows found in the data pages and added to every index.
+    pub rows_indexed: usize,
+    /// Rows left out of the indexes because a unique index already held
+    /// their key for a row found before them.
+    pub conflicts: Vec<IndexRebuildConflict<Row>>,
+    /// Written parts of the data pages that hold no valid row.
+    pub unreadable: Vec<Link>,
+}
+
+impl<Row> IndexRebuildReport<Row> {
+    /// Whether every written byte belonged to a row that was indexed.
+    pub fn is_complete(&self) -> bool {
+        self.conflicts.is_empty() && self.unreadable.is_empty()
+    }
+}
+
+/// A row that could not be indexed because of a duplicate key.
+#[derive(Debug)]
+pub struct IndexRebuildConflict<Row> {
+    /// `primary`, or the unique secondary index as `AlreadyExists` names it.
+    pub index: String,
+    pub link: Link,
+    pub row: Row,
+}
+
+impl<
+    Row,
+    PrimaryKey,
+    AvailableTypes,
+    AvailableIndexes,
+    SecondaryIndexes,
+    LockType,
+    PkGen,
+    const DATA_LENGTH: usize,
+    PkMap,
+> WorkTable<Row, PrimaryKey, AvailableTypes, AvailableIndexes, SecondaryIndexes, LockType, PkGen, DATA_LENGTH, PkMap>
+where
+    Row: TableRow<PrimaryKey>
+        + StorableRow
+        + Archive
+        + Send
+        + Clone
+        + 'static
+        + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+    <Row as StorableRow>::WrappedRow: RowWrapper<Row>
+        + Archive
+        + for<'a> Serialize<Strategy<Serializer<AlignedVec, ArenaHandle<'a>, Share>, rkyv::rancor::Error>>,
+    <<Row as StorableRow>::WrappedRow as Archive>::Archived: Portable
+        + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>
+        + for<'a> rkyv::bytecheck::CheckBytes<rkyv::api::high::HighValidator<'a, rkyv::rancor::Error>>,
+    PrimaryKey: Debug + Clone + Ord + Send + TablePrimaryKey + std::hash::Hash,
+    PkMap: UniqueIndex<PrimaryKey, OffsetEqLink<DATA_LENGTH>>,
+    PrimaryIndex<PrimaryKey, DATA_LENGTH, PkMap>: TableIndexCdc<PrimaryKey>,
+    AvailableIndexes: Debug + AvailableIndex,
+{
+    /// Fills the empty primary and secondary indexes of a loaded table from
+    /// the rows in its data pages, see [`DataPages::scan_rows_checked`].
+    ///
+    /// A row whose primary key or unique secondary key is already indexed is
+    /// left out and reported; its bytes stay in the pages. Returns the report
+    /// with the change events that write the rebuilt indexes.
+    ///
+    /// [`DataPages::scan_rows_checked`]: crate::in_memory::DataPages::scan_rows_checked
+    #[allow(clippy::type_complexity)]
+    pub fn rebuild_indexes_cdc<SecondaryEvents>(
+        &self,
+    ) -> (
+        IndexRebuildReport<Row>,
+        Vec<ChangeEvent<Pair<PrimaryKey, Link>>>,
+        SecondaryEvents,
+    )
+    where
+        SecondaryEvents: Default + TableSecondaryIndexEventsOps<AvailableIndexes>,
+        SecondaryIndexes: TableSecondaryIndexCdc<Row, AvailableTypes, SecondaryEvents, AvailableIndexes>,
+    {
+        let (rows, unreadable) = self.data.scan_rows_checked();
+        let mut report = IndexRebuildReport {
+            rows_indexed: 0,
+            conflicts: vec![],
+            unreadable,
+        };
+        let mut primary_key_events = vec![];
+        let mut secondary_events = SecondaryEvents::default();
+
+        for (link, row) in rows {
+            let primary_key = row.get_primary_key();
+            let Some(events) = self.primary_index.insert_checked_cdc(primary_key.clone(), link) else {
+                report.conflicts.push(IndexRebuildConflict {
+                    index: "primary".to_string(),
+                    link,
+                    row,
+                });
+                continue;
+            };
+            primary_key_events.extend(convert_change_events(events));
+
+            let (events, result) = self.indexes.save_row_cdc(row.clone(), link);
+            secondary_events.extend(events);
+            match result {
+                Ok(()) => {
+                    self.data.seal_overflow_chain(link);
+                    report.rows_indexed += 1;
+                }
+                Err(IndexError::AlreadyExists { at, inserted_already }) => {
+                    let (_, events) = self.primary_index.remove_cdc(primary_key, link);
+                    primary_key_events.extend(convert_change_events(events));
+                    let (events, _) = self
+                        .indexes
+                        .delete_from_indexes_cdc(row.clone(), link, inserted_already);
+                    secondary_events.extend(events);
+                    report.conflicts.push(IndexRebuildConflict {
+                        index: at.to_string_value(),
+                        link,
+                        row,
+                    });
+                }
+                Err(IndexError::NotFound) => unreachable!("inserting into an index does not look a key up"),
+            }
+        }
+
+        (report, primary_key_events, secondary_events)
+    }
+}
@@ -1,20 +1,34 @@
-use prettytable::{Table, format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR, row};
+use prettytable::{Cell, Table, format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR, row};
 use std::fmt::{self, Debug, Display, Formatter};
 
 use crate::in_memory::{RowWrapper, StorableRow};
+use crate::lock::{LOCK_WAIT_BUCKETS, LockStatsInfo, RowLock};
 use crate::mem_stat::MemStat;
 use crate::util::OffsetEqLink;
 use crate::{TableSecondaryIndexInfo, UniqueIndex, WorkTable};
 
+/// Table statistics. `Display` prints the lock section only while operations
+/// wait for row locks, or always with the alternate flag (`{:#}`).
 #[derive(Debug)]
 pub struct SystemInfo {
     pub table_name: &'static str,
     pub page_count: usize,
     pub row_count: usize,
     pub empty_slots: u64,
+    /// Bytes allocated for data pages. Empty pages count until
+    /// `WorkTable::shrink_to_fit` releases them.
     pub memory_usage_bytes: u64,
+    /// Data pages currently held compressed, see `config: { compression }`.
+    pub compressed_pages: usize,
+    /// Bytes the data pages take in memory, compressed pages counted by the
+    /// size of their image.
+    pub resident_data_bytes: u64,
+    /// Data pages currently written out to the page store, see
+    /// `WorkTable::set_memory_budget`.
+    pub evicted_pages: usize,
     pub idx_size: usize,
     pub indexes_info: Vec<IndexInfo>,
+    pub lock_info: LockInfo,
 }
 
 #[derive(Debug)]
@@ -26,6 +40,39 @@ pub struct IndexInfo {
     pub heap_size: usize,
     pub used_size: usize,
     pub node_count: usize,
+    /// Bytes of the index's key filter, included in `heap_size`; 0 for an
+    /// index declared without `filter`.
+    pub filter_size: usize,
+    /// Distinct keys, estimated from sampled nodes for a non-unique
+    /// WorkTablesIndex index.
+    pub distinct_keys: usize,
+    /// Entries per distinct key; 1 for a unique index holding keys.
+    pub avg_duplicates: f64,
+}
+
+/// Row lock contention, see [`crate::lock::LockMap::lock_info`].
+#[derive(Debug)]
+pub struct LockInfo {
+    pub table: LockStatsInfo,
+    /// Contention per column. A whole-row lock counts for every column, and a
+    /// wait counts for the columns both operations lock.
+    pub columns: Vec<FieldLockInfo>,
+    /// Most contended rows, most contended first.
+    pub hot_rows: Vec<HotRowInfo>,
+}
+
+#[derive(Debug)]
+pub struct FieldLockInfo {
+    pub name: &'static str,
+    pub stats: LockStatsInfo,
+}
+
+#[derive(Debug)]
+pub struct HotRowInfo {
+    /// `Debug` representation of the row's primary key.
+    pub primary_key: String,
+    /// Times an operation had to wait for this row.
+    pub contended: u64,
 }
 
 #[derive(Debug)]
@@ -60,6 +107,7 @@ where
     <Row as StorableRow>::WrappedRow: RowWrapper<Row>,
     PkMap: UniqueIndex<PrimaryKey, OffsetEqLink<DATA_LENGTH>>,
     SecondaryIndexes: MemStat + TableSecondaryIndexInfo,
+    LockType: RowLock,
 {
     pub fn system_info(&self) -> SystemInfo {
         let page_count = self.data.get_page_count();
@@ -67,9 +115,7 @@ where
 
         let empty_links = self.data.get_empty_links().len();
 
-        let bytes = self.data.get_bytes();
-
-        let memory_usage_bytes = bytes.iter().map(|(_buf, free_offset)| *free_offset as u64).sum();
+        let memory_usage_bytes = self.data.resident_bytes();
 
         let idx_size = self.indexes.heap_size();
 
@@ -79,10 +125,20 @@ where
             row_count,
             empty_slots: empty_links as u64,
             memory_usage_bytes,
+            compressed_pages: self.data.compressed_page_count(),
+            resident_data_bytes: self.data.resident_bytes(),
+            evicted_pages: self.data.evicted_page_count(),
             idx_size,
-            indexes_info: self.indexes.index_info(),
+            indexes_info: self.indexes.index_info(&self.statistics, self.data.write_count()),
+            lock_info: self.lock_manager.lock_info(),
         }
     }
+
+    /// Drops the cached index statistics, so the next request computes them
+    /// from the indexes as they are now instead of after enough writes.
+    pub fn refresh_statistics(&self) {
+        self.statistics.clear();
+    }
 }
 
 impl Display for SystemInfo {
@@ -101,12 +157,30 @@ impl Display for SystemInfo {
         )?;
         writeln!(
             f,
-            "Allocated Memory: {mem_fmt} (data) + {idx_fmt} (indexes) = {total_fmt} total\n"
+            "Allocated Memory: {mem_fmt} (data) + {idx_fmt} (indexes) = {total_fmt} total"
+        )?;
+        writeln!(
+            f,
+            "Resident Data: {} ({} compressed pages, {} evicted pages)\n",
+            fmt_bytes(self.resident_data_bytes as usize),
+            self.compressed_pages,
+            self.evicted_pages
         )?;
 
         let mut table = Table::new();
         table.set_format(*FORMAT_NO_BORDER_LINE_SEPARATOR);
-        table.add_row(row!["Index", "Type", "Keys", "Capacity", "Node Count", "Heap", "Used"]);
+        table.add_row(row![
+            "Index",
+            "Type",
+            "Keys",
+            "Capacity",
+            "Node Count",
+            "Heap",
+            "Used",
+            "Filter",
+            "Distinct",
+            "Avg Dup"
+        ]);
 
         for idx in &self.indexes_info {
             table.add_row(row![
@@ -117,6 +191,9 @@ impl Display for SystemInfo {
                 idx.node_count,
                 fmt_bytes(idx.heap_size),
                 fmt_bytes(idx.used_size),
+                fmt_bytes(idx.filter_size),
+                idx.distinct_keys,
+                format!("{:.2}", idx.avg_duplicates),
             ]);
         }
 
@@ -124,6 +201,68 @@ impl Display for SystemInfo {
         table.print(&mut buffer).unwrap();
         let table_str = String::from_utf8(buffer).unwrap();
         writeln!(f, "{}", table_str.trim_end())?;
+        if f.alternate() || self.lock_info.table.waiters > 0 {
+            writeln!(f)?;
+            write!(f, "{}", self.lock_info)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for LockInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Locks: {} acquired   {} contended   {} timed out   {} waiting   {:?} waited",
+            self.table.acquisitions,
+            self.table.contended,
+            self.table.timeouts,
+            self.table.waiters,
+            self.table.total_wait,
+        )?;
+
+        let mut bucket_titles = vec![];
+        for bound in LOCK_WAIT_BUCKETS {
+            bucket_titles.push(format!("<={bound:?}"));
+        }
+        bucket_titles.push(format!(">{:?}", LOCK_WAIT_BUCKETS[LOCK_WAIT_BUCKETS.len() - 1]));
+
+        let mut table = Table::new();
+        table.set_format(*FORMAT_NO_BORDER_LINE_SEPARATOR);
+        let mut titles = row!["Column", "Acquired", "Contended", "Timeouts", "Waiting"];
+        for title in &bucket_titles {
+            titles.add_cell(Cell::new(title));
+        }
+        table.add_row(titles);
+        for column in &self.columns {
+            let stats = &column.stats;
+            let mut r = row![
+                column.name,
+                stats.acquisitions,
+                stats.contended,
+                stats.timeouts,
+                stats.waiters
+            ];
+            for count in stats.wait_histogram {
+                r.add_cell(Cell::new(&count.to_string()));
+            }
+            table.add_row(r);
+        }
+
+        let mut buffer = Vec::new();
+        table.print(&mut buffer).unwrap();
+        let table_str = String::from_utf8(buffer).unwrap();
+        writeln!(f, "{}", table_str.trim_end())?;
+
+        if !self.hot_rows.is_empty() {
+            let rows: Vec<_> = self
+                .hot_rows
+                .iter()
+                .map(|r| format!("{} ({})", r.primary_key, r.contended))
+                .collect();
+            writeln!(f, "Hot rows: {}", rows.join(", "))?;
+        }
 
         Ok(())
     }
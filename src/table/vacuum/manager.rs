@@ -22,6 +22,10 @@ pub struct VacuumManagerConfig {
     pub high_fragmentation_threshold: f64,
     #[default(0.7)]
     pub critical_fragmentation_threshold: f64,
+    /// Release the memory of freed pages and spare index capacity after each
+    /// vacuum, see [`WorkTableVacuum::shrink_to_fit`].
+    #[default(false)]
+    pub release_memory: bool,
 }
 
 #[derive(derive_more::Debug, Default)]
@@ -103,6 +107,10 @@ impl VacuumManager {
                                         stats.bytes_freed,
                                         stats.duration_ns as f64 / 1_000_000.0
                                     );
+                                    if self.config.release_memory {
+                                        let released = vacuum.shrink_to_fit();
+                                        log::debug!("Released {} bytes of table '{}'", released, table_name);
+                                    }
                                 }
                                 Err(e) => {
                                     // println!("Vacuum failed for table '{}': {}", table_name, e);
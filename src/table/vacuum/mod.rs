@@ -57,6 +57,9 @@ pub trait WorkTableVacuum {
     fn analyze_fragmentation(&self) -> FragmentationInfo;
     /// Run vacuum operation
     async fn vacuum(&self) -> eyre::Result<VacuumStats>;
+    /// Release memory of the pages and index capacity a vacuum freed,
+    /// returning the data page bytes released
+    fn shrink_to_fit(&self) -> u64;
 }
 
 /// Represents vacuum statistics after a vacuum operation
@@ -15,6 +15,7 @@ use rkyv::{Archive, Deserialize, Serialize};
 
 use crate::in_memory::{ArchivedRowWrapper, DataPages, RowWrapper, StorableRow};
 use crate::lock::{Lock, LockGuard, LockMap, RowLock};
+use crate::mem_stat::MemStat;
 use crate::prelude::{OffsetEqLink, TablePrimaryKey};
 use crate::vacuum::VacuumPersistence;
 use crate::vacuum::VacuumStats;
@@ -224,6 +225,9 @@ where
         for id in defragmented_pages {
             self.data_pages.mark_page_full(id)
         }
+        // Pages vacuum filled are cold now.
+        self.data_pages.compress_cold_pages();
+        self.secondary_indexes.rebuild_filters();
 
         Ok(VacuumStats {
             pages_processed,
@@ -325,10 +329,8 @@ where
         let lock_id = self.lock_manager.next_id();
         // One atomic acquire, no check-then-act: see LockMap::get_or_insert_with.
         let lock = self.lock_manager.get_or_insert_with(pk.clone(), LockType::new);
-        let mut lock_guard = lock.write().await;
         #[allow(clippy::mutable_key_type)]
-        let (locks, op_lock) = lock_guard.lock(lock_id);
-        drop(lock_guard);
+        let (locks, op_lock) = lock.write().lock(lock_id);
         futures::future::join_all(locks.iter().map(|l| l.wait()).collect::<Vec<_>>()).await;
 
         op_lock
@@ -392,7 +394,7 @@ impl<
 where
     Row: TableRow<PrimaryKey> + StorableRow + Send + Sync + Clone + 'static,
     PrimaryKey: Debug + Clone + Ord + Send + Sync + TablePrimaryKey + std::hash::Hash,
-    PkMap: UniqueIndex<PrimaryKey, OffsetEqLink<DATA_LENGTH>> + Send + Sync + 'static,
+    PkMap: UniqueIndex<PrimaryKey, OffsetEqLink<DATA_LENGTH>> + MemStat + Send + Sync + 'static,
     <Row as StorableRow>::WrappedRow: RowWrapper<Row>,
     Row: Archive
         + Clone
@@ -406,6 +408,7 @@ where
         ArchivedRowWrapper + Deserialize<<Row as StorableRow>::WrappedRow, HighDeserializer<rkyv::rancor::Error>>,
     SecondaryIndexes: TableSecondaryIndex<Row, AvailableTypes, AvailableIndexes>
         + TableSecondaryIndexCdc<Row, AvailableTypes, SecondaryEvents, AvailableIndexes>
+        + MemStat
         + Send
         + Sync,
     AvailableIndexes: Debug + AvailableIndex,
@@ -427,6 +430,16 @@ where
     async fn vacuum(&self) -> eyre::Result<VacuumStats> {
         self.defragment().await
     }
+
+    fn shrink_to_fit(&self) -> u64 {
+        let freed = self.data_pages.shrink_to_fit();
+        MemStat::shrink_to_fit(&self.primary_index.pk_map);
+        for node in self.primary_index.reverse_pk_map.iter_nodes() {
+            node.lock().shrink_to_fit();
+        }
+        self.secondary_indexes.shrink_to_fit();
+        freed
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+use std::thread;
+
+use crate::remove_dir_if_exists;
+use worktable::prelude::PersistedWorkTable;
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: BlockingPersist,
+    persist: true,
+    columns: {
+        id: u64 primary_key,
+        group: u64,
+        count: u64,
+        label: String,
+    },
+    indexes: {
+        group_idx: group,
+    },
+    queries: {
+        update: {
+            CountById(count) by id,
+            LabelById(label) by id,
+        },
+        delete: {
+            ByGroup() by group,
+        }
+    }
+);
+
+fn row(id: u64, group: u64) -> BlockingPersistRow {
+    BlockingPersistRow {
+        id,
+        group,
+        count: 0,
+        label: "a".to_string(),
+    }
+}
+
+/// Blocking mutations called from plain threads queue the same persistence
+/// operations as their async counterparts.
+#[test]
+fn blocking_mutations_survive_reload() {
+    let config = DiskConfig::new_with_table_name(
+        "tests/data/blocking/reload",
+        BlockingPersistWorkTable::name_snake_case(),
+        BlockingPersistWorkTable::version(),
+    );
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .enable_io()
+        .enable_time()
+        .build()
+        .unwrap();
+
+    let table = runtime.block_on(async {
+        remove_dir_if_exists("tests/data/blocking/reload".to_string()).await;
+        let engine = BlockingPersistPersistenceEngine::new(config.clone()).await.unwrap();
+        Arc::new(BlockingPersistWorkTable::load(engine).await.unwrap())
+    });
+    for id in 0..20 {
+        table.insert(row(id, id % 2)).unwrap();
+    }
+
+    let threads: Vec<_> = (0..4u64)
+        .map(|t| {
+            let table = table.clone();
+            thread::spawn(move || {
+                for id in (t * 5)..(t * 5 + 5) {
+                    table
+                        .update_count_by_id_blocking(CountByIdQuery { count: id + 1 }, id)
+                        .unwrap();
+                    // A longer label no longer fits the slot and is reinserted.
+                    table
+                        .update_label_by_id_blocking(
+                            LabelByIdQuery {
+                                label: "a much longer label".to_string(),
+                            },
+                            id,
+                        )
+                        .unwrap();
+                }
+            })
+        })
+        .collect();
+    for t in threads {
+        t.join().unwrap();
+    }
+    table.delete_by_group_blocking(1).unwrap();
+    table.upsert_blocking(row(100, 0)).unwrap();
+    table.delete_blocking(0).unwrap();
+
+    runtime.block_on(async {
+        table.wait_for_ops().await.unwrap();
+        drop(table);
+
+        let engine = BlockingPersistPersistenceEngine::new(config).await.unwrap();
+        let table = BlockingPersistWorkTable::load(engine).await.unwrap();
+        assert_eq!(table.count(), 10);
+        assert!(table.select(0).is_none());
+        for id in (2..20).step_by(2) {
+            let row = table.select(id).unwrap();
+            assert_eq!(row.count, id + 1);
+            assert_eq!(row.label, "a much longer label");
+        }
+        assert_eq!(table.select(100).unwrap().count, 0);
+    });
+}
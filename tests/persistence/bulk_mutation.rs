@@ -0,0 +1,73 @@
+use crate::remove_dir_if_exists;
+use worktable::prelude::PersistedWorkTable;
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: BulkMutationPersist,
+    persist: true,
+    columns: {
+        id: u64 primary_key autoincrement,
+        created_at: u64,
+        bucket: u32,
+    },
+    indexes: {
+        created_at_idx: created_at unique,
+        bucket_idx: bucket,
+    },
+);
+
+/// Predicate-based deletes and updates must reach disk through the same CDC
+/// operations as their single-row counterparts.
+#[test]
+fn bulk_mutations_survive_reload() {
+    let config = DiskConfig::new_with_table_name(
+        "tests/data/bulk_mutation/reload",
+        BulkMutationPersistWorkTable::name_snake_case(),
+        BulkMutationPersistWorkTable::version(),
+    );
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .enable_io()
+        .enable_time()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        remove_dir_if_exists("tests/data/bulk_mutation/reload".to_string()).await;
+
+        {
+            let engine = BulkMutationPersistPersistenceEngine::new(config.clone()).await.unwrap();
+            let table = BulkMutationPersistWorkTable::load(engine).await.unwrap();
+            for i in 0..1_000u64 {
+                table
+                    .insert(BulkMutationPersistRow {
+                        id: table.get_next_pk().into(),
+                        created_at: i,
+                        bucket: (i % 10) as u32,
+                    })
+                    .unwrap();
+            }
+
+            assert_eq!(table.delete_where(|row| row.created_at < 100).await.unwrap(), 100);
+            assert_eq!(table.delete_by_created_at_range(900..).await.unwrap(), 100);
+            let updated = table
+                .update_where(|row| row.bucket == 3, |row| row.bucket = 30)
+                .await
+                .unwrap();
+            assert_eq!(updated, 80);
+            table.wait_for_ops().await.unwrap();
+        }
+        {
+            let engine = BulkMutationPersistPersistenceEngine::new(config).await.unwrap();
+            let table = BulkMutationPersistWorkTable::load(engine).await.unwrap();
+            assert_eq!(table.count(), 800);
+            assert!(table.select_by_created_at(50).is_none());
+            assert!(table.select_by_created_at(950).is_none());
+            assert!(table.select_by_created_at(500).is_some());
+            assert!(table.select_by_bucket(3).execute().unwrap().is_empty());
+            assert_eq!(table.select_by_bucket(30).execute().unwrap().len(), 80);
+        }
+    });
+}
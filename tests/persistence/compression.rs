@@ -0,0 +1,156 @@
+use std::path::Path;
+use std::time::Duration;
+
+use tokio::time::timeout;
+
+use crate::remove_dir_if_exists;
+use worktable::prelude::PersistedWorkTable;
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: CompressedPersist,
+    persist: true,
+    columns: {
+        id: u64 primary_key autoincrement,
+        symbol: String,
+        note: String,
+    },
+    indexes: {
+        symbol_idx: symbol,
+    },
+    config: {
+        compression: lz4,
+    }
+);
+
+fn row(table: &CompressedPersistWorkTable, i: u64) -> CompressedPersistRow {
+    CompressedPersistRow {
+        id: table.get_next_pk().into(),
+        symbol: format!("SYM{}", i % 4),
+        note: "filled by the exchange gateway ".repeat(4),
+    }
+}
+
+async fn settle(table: &CompressedPersistWorkTable) {
+    timeout(Duration::from_secs(30), table.wait_for_ops())
+        .await
+        .expect("persistence should catch up")
+        .expect("persistence engine failed");
+}
+
+/// Counts data pages whose header carries the compressed flag.
+fn compressed_pages_on_disk(path: &str) -> usize {
+    let bytes = std::fs::read(Path::new(path).join(WT_DATA_EXTENSION)).unwrap();
+    bytes
+        .chunks(PAGE_SIZE)
+        .skip(1)
+        .filter(|page| {
+            let data_length = u32::from_le_bytes(page[24..28].try_into().unwrap());
+            data_length & COMPRESSED_PAGE_FLAG != 0
+        })
+        .count()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn compressed_pages_survive_update_and_reload() {
+    let path = "tests/data/compression/reload";
+    let config = DiskConfig::new_with_table_name(
+        path,
+        CompressedPersistWorkTable::name_snake_case(),
+        CompressedPersistWorkTable::version(),
+    );
+    remove_dir_if_exists(path.to_string()).await;
+
+    let mut rows = Vec::new();
+    {
+        let engine = CompressedPersistPersistenceEngine::new(config.clone()).await.unwrap();
+        let table = CompressedPersistWorkTable::load(engine).await.unwrap();
+        for i in 0..2_000 {
+            let row = row(&table, i);
+            table.insert(row.clone()).unwrap();
+            rows.push(row);
+        }
+        settle(&table).await;
+        assert!(table.system_info().compressed_pages > 0);
+    }
+    let compressed = compressed_pages_on_disk(&config.tables_path);
+    assert!(compressed > 0);
+
+    {
+        let engine = CompressedPersistPersistenceEngine::new(config.clone()).await.unwrap();
+        let table = CompressedPersistWorkTable::load(engine).await.unwrap();
+        assert!(table.system_info().compressed_pages > 0);
+        for expected in &rows {
+            assert_eq!(table.select(expected.id).as_ref(), Some(expected));
+        }
+
+        // Writes into pages stored compressed.
+        rows[0].note = "amended".to_string();
+        table.update(rows[0].clone()).await.unwrap();
+        let removed = rows.remove(1);
+        table.delete(removed.id).await.unwrap();
+        for i in 0..500 {
+            let row = row(&table, i);
+            table.insert(row.clone()).unwrap();
+            rows.push(row);
+        }
+        settle(&table).await;
+    }
+    assert!(compressed_pages_on_disk(&config.tables_path) >= compressed);
+
+    {
+        let engine = CompressedPersistPersistenceEngine::new(config).await.unwrap();
+        let table = CompressedPersistWorkTable::load(engine).await.unwrap();
+        assert_eq!(table.count(), rows.len());
+        for expected in &rows {
+            assert_eq!(table.select(expected.id).as_ref(), Some(expected));
+        }
+        assert_eq!(
+            table.select_by_symbol("SYM2".to_string()).execute().unwrap().len(),
+            rows.iter().filter(|r| r.symbol == "SYM2").count()
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn corrupt_compressed_page_fails_the_load() {
+    let path = "tests/data/compression/corrupt";
+    let config = DiskConfig::new_with_table_name(
+        path,
+        CompressedPersistWorkTable::name_snake_case(),
+        CompressedPersistWorkTable::version(),
+    );
+    remove_dir_if_exists(path.to_string()).await;
+
+    {
+        let engine = CompressedPersistPersistenceEngine::new(config.clone()).await.unwrap();
+        let table = CompressedPersistWorkTable::load(engine).await.unwrap();
+        for i in 0..2_000 {
+            table.insert(row(&table, i)).unwrap();
+        }
+        settle(&table).await;
+    }
+
+    // Zeroes the image length of the first compressed page.
+    let data_path = Path::new(&config.tables_path).join(WT_DATA_EXTENSION);
+    let mut bytes = std::fs::read(&data_path).unwrap();
+    let page = bytes
+        .chunks_mut(PAGE_SIZE)
+        .skip(1)
+        .find(|page| u32::from_le_bytes(page[24..28].try_into().unwrap()) & COMPRESSED_PAGE_FLAG != 0)
+        .expect("a compressed page is written");
+    page[GENERAL_HEADER_SIZE..][..4].fill(0);
+    std::fs::write(&data_path, bytes).unwrap();
+
+    let engine = CompressedPersistPersistenceEngine::new(config).await.unwrap();
+    let error = CompressedPersistWorkTable::load(engine).await.unwrap_err();
+    let typed = error
+        .downcast_ref::<PersistenceLoadError>()
+        .expect("load must return a typed corruption error");
+    assert!(
+        typed.reason().contains("compressed data page"),
+        "unexpected load reason: {}",
+        typed.reason()
+    );
+}
@@ -0,0 +1,89 @@
+use crate::remove_dir_if_exists;
+use worktable::prelude::PersistedWorkTable;
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: HookPersist,
+    persist: true,
+    columns: {
+        id: u64 primary_key autoincrement,
+        name: String,
+        pinned: bool,
+    },
+    hooks: {
+        before_update: reject_rename_of_pinned,
+        before_delete: reject_pinned,
+    }
+);
+
+fn reject_rename_of_pinned(old: &HookPersistRow, new: &HookPersistRow) -> Result<(), WorkTableError> {
+    if old.pinned && old.name != new.name {
+        return Err(WorkTableError::Rejected("pinned rows keep their name".to_string()));
+    }
+    Ok(())
+}
+
+fn reject_pinned(row: &HookPersistRow) -> Result<(), WorkTableError> {
+    if row.pinned {
+        return Err(WorkTableError::Rejected("pinned rows cannot be deleted".to_string()));
+    }
+    Ok(())
+}
+
+#[test]
+fn loaded_table_runs_hooks_and_persists_only_accepted_changes() {
+    let config = DiskConfig::new_with_table_name(
+        "tests/data/hooks/persisted",
+        HookPersistWorkTable::name_snake_case(),
+        HookPersistWorkTable::version(),
+    );
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .enable_io()
+        .enable_time()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        remove_dir_if_exists("tests/data/hooks/persisted".to_string()).await;
+
+        let pinned = HookPersistRow {
+            id: 0,
+            name: "pinned".to_string(),
+            pinned: true,
+        };
+        let free = HookPersistRow {
+            id: 1,
+            name: "free".to_string(),
+            pinned: false,
+        };
+        {
+            let engine = HookPersistPersistenceEngine::new(config.clone()).await.unwrap();
+            let table = HookPersistWorkTable::load(engine).await.unwrap();
+            table.insert(pinned.clone()).unwrap();
+            table.insert(free.clone()).unwrap();
+
+            let mut renamed = pinned.clone();
+            renamed.name = "renamed".to_string();
+            assert!(matches!(table.update(renamed).await, Err(WorkTableError::Rejected(_))));
+            assert!(table.delete(pinned.id).await.is_err());
+            table.wait_for_ops().await.unwrap();
+        }
+        {
+            let engine = HookPersistPersistenceEngine::new(config).await.unwrap();
+            let table = HookPersistWorkTable::load(engine).await.unwrap();
+            assert_eq!(table.select(pinned.id).unwrap(), pinned);
+
+            // Hooks declared on the table apply to the loaded instance too.
+            assert!(matches!(
+                table.delete(pinned.id).await,
+                Err(WorkTableError::Rejected(_))
+            ));
+            table.delete(free.id).await.unwrap();
+            table.wait_for_ops().await.unwrap();
+            assert_eq!(table.count(), 1);
+        }
+    });
+}
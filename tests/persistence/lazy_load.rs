@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use tokio::time::timeout;
+
+use crate::remove_dir_if_exists;
+use worktable::prelude::PersistedWorkTable;
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: LazyTrade,
+    persist: true,
+    columns: {
+        id: u64 primary_key autoincrement,
+        symbol: String,
+        note: String,
+    },
+    indexes: {
+        symbol_idx: symbol,
+    }
+);
+
+worktable!(
+    name: LazyBounded,
+    persist: true,
+    columns: {
+        id: u64 primary_key autoincrement,
+        note: String,
+    },
+    config: {
+        memory_limit: 1_048_576,
+        on_memory_limit: evict,
+    }
+);
+
+async fn settle(wait: impl Future<Output = PersistenceResult>) {
+    timeout(Duration::from_secs(30), wait)
+        .await
+        .expect("persistence should catch up")
+        .expect("persistence engine failed");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lazy_load_reads_pages_on_first_access() {
+    let path = "tests/data/lazy_load/trades";
+    let config = DiskConfig::new_with_table_name(
+        path,
+        LazyTradeWorkTable::name_snake_case(),
+        LazyTradeWorkTable::version(),
+    );
+    remove_dir_if_exists(path.to_string()).await;
+
+    let mut rows = Vec::new();
+    {
+        let engine = LazyTradePersistenceEngine::new(config.clone()).await.unwrap();
+        let table = LazyTradeWorkTable::load(engine).await.unwrap();
+        for i in 0..3_000 {
+            let row = LazyTradeRow {
+                id: table.get_next_pk().into(),
+                symbol: format!("SYM{}", i % 8),
+                note: "filled by the exchange gateway ".repeat(4),
+            };
+            table.insert(row.clone()).unwrap();
+            rows.push(row);
+        }
+        settle(table.wait_for_ops()).await;
+    }
+
+    {
+        let engine = LazyTradePersistenceEngine::new(config.clone()).await.unwrap();
+        let table = LazyTradeWorkTable::load_with(engine, LoadMode::Lazy).await.unwrap();
+        let info = table.system_info();
+        assert!(info.page_count > 2);
+        assert_eq!(info.evicted_pages, info.page_count);
+        assert_eq!(table.count(), rows.len());
+
+        let last = rows.last().unwrap();
+        assert_eq!(table.select(last.id).as_ref(), Some(last));
+        assert_eq!(table.system_info().evicted_pages, info.page_count - 1);
+
+        for expected in &rows {
+            assert_eq!(table.select(expected.id).as_ref(), Some(expected));
+        }
+        assert_eq!(table.system_info().evicted_pages, 0);
+        assert_eq!(table.select_by_symbol("SYM3".to_string()).execute().unwrap().len(), 375);
+
+        rows[0].note = "amended".to_string();
+        table.update(rows[0].clone()).await.unwrap();
+        let removed = rows.remove(1);
+        table.delete(removed.id).await.unwrap();
+        let row = LazyTradeRow {
+            id: table.get_next_pk().into(),
+            symbol: "SYM0".to_string(),
+            note: "added after a lazy load".to_string(),
+        };
+        table.insert(row.clone()).unwrap();
+        rows.push(row);
+        settle(table.wait_for_ops()).await;
+    }
+
+    {
+        let engine = LazyTradePersistenceEngine::new(config).await.unwrap();
+        let table = LazyTradeWorkTable::load(engine).await.unwrap();
+        assert_eq!(table.system_info().evicted_pages, 0);
+        assert_eq!(table.count(), rows.len());
+        for expected in &rows {
+            assert_eq!(table.select(expected.id).as_ref(), Some(expected));
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lazy_load_keeps_resident_pages_within_budget() {
+    let path = "tests/data/lazy_load/bounded";
+    let config = DiskConfig::new_with_table_name(
+        path,
+        LazyBoundedWorkTable::name_snake_case(),
+        LazyBoundedWorkTable::version(),
+    );
+    remove_dir_if_exists(path.to_string()).await;
+
+    let mut rows = Vec::new();
+    {
+        let engine = LazyBoundedPersistenceEngine::new(config.clone()).await.unwrap();
+        let table = LazyBoundedWorkTable::load(engine).await.unwrap();
+        for _ in 0..5_000 {
+            let row = LazyBoundedRow {
+                id: table.get_next_pk().into(),
+                note: "filled by the exchange gateway ".repeat(16),
+            };
+            // Only pages the engine has written can be evicted.
+            loop {
+                match table.insert(row.clone()) {
+                    Ok(_) => break,
+                    Err(WorkTableError::MemoryLimitExceeded) => settle(table.wait_for_ops()).await,
+                    Err(e) => panic!("insert failed: {e:?}"),
+                }
+            }
+            rows.push(row);
+        }
+        settle(table.wait_for_ops()).await;
+    }
+
+    let engine = LazyBoundedPersistenceEngine::new(config).await.unwrap();
+    let table = LazyBoundedWorkTable::load_with(engine, LoadMode::Lazy).await.unwrap();
+    for expected in &rows {
+        assert_eq!(table.select(expected.id).as_ref(), Some(expected));
+        assert!(table.system_info().resident_data_bytes <= 2 * 1_048_576);
+    }
+    assert!(table.system_info().evicted_pages > 0);
+}
@@ -0,0 +1,92 @@
+use std::path::Path;
+use std::time::Duration;
+
+use tokio::time::timeout;
+
+use crate::remove_dir_if_exists;
+use worktable::prelude::PersistedWorkTable;
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: Evicting,
+    persist: true,
+    columns: {
+        id: u64 primary_key autoincrement,
+        symbol: String,
+        note: String,
+    },
+    config: {
+        memory_limit: 1_048_576,
+        on_memory_limit: evict,
+    }
+);
+
+fn row(table: &EvictingWorkTable, i: u64) -> EvictingRow {
+    EvictingRow {
+        id: table.get_next_pk().into(),
+        symbol: format!("SYM{}", i % 4),
+        note: "filled by the exchange gateway ".repeat(4),
+    }
+}
+
+async fn settle(table: &EvictingWorkTable) {
+    timeout(Duration::from_secs(30), table.wait_for_ops())
+        .await
+        .expect("persistence should catch up")
+        .expect("persistence engine failed");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn cold_pages_are_evicted_and_read_back() {
+    let path = "tests/data/memory_budget/evict";
+    let config =
+        DiskConfig::new_with_table_name(path, EvictingWorkTable::name_snake_case(), EvictingWorkTable::version());
+    remove_dir_if_exists(path.to_string()).await;
+
+    let mut rows = Vec::new();
+    {
+        let engine = EvictingPersistenceEngine::new(config.clone()).await.unwrap();
+        let table = EvictingWorkTable::load(engine).await.unwrap();
+        for i in 0..5_000 {
+            let row = row(&table, i);
+            // Only pages the engine has written can be evicted.
+            loop {
+                match table.insert(row.clone()) {
+                    Ok(_) => break,
+                    Err(WorkTableError::MemoryLimitExceeded) => settle(&table).await,
+                    Err(e) => panic!("insert failed: {e:?}"),
+                }
+            }
+            rows.push(row);
+        }
+
+        let info = table.system_info();
+        assert!(info.evicted_pages > 0);
+        assert!(info.resident_data_bytes <= 1_048_576);
+        assert!(!Path::new(&config.tables_path).join(".wt.spill").exists());
+
+        for expected in &rows {
+            assert_eq!(table.select(expected.id).as_ref(), Some(expected));
+        }
+        rows[0].note = "amended".to_string();
+        table.update(rows[0].clone()).await.unwrap();
+        let removed = rows.remove(1);
+        table.delete(removed.id).await.unwrap();
+        assert_eq!(table.select(rows[0].id).as_ref(), Some(&rows[0]));
+        assert_eq!(table.select(removed.id), None);
+        settle(&table).await;
+    }
+
+    {
+        let engine = EvictingPersistenceEngine::new(config).await.unwrap();
+        let table = EvictingWorkTable::load(engine).await.unwrap();
+        let info = table.system_info();
+        assert!(info.evicted_pages > 0);
+        assert!(info.resident_data_bytes <= 1_048_576);
+        assert_eq!(table.count(), rows.len());
+        for expected in &rows {
+            assert_eq!(table.select(expected.id).as_ref(), Some(expected));
+        }
+    }
+}
@@ -2,21 +2,32 @@ use worktable::prelude::PersistedWorkTable;
 use worktable::prelude::*;
 use worktable::worktable;
 
+mod blocking;
 mod bulk_load_stall;
+mod bulk_mutation;
+mod compression;
 mod concurrent;
 mod duplicate_key_index_reload;
 mod failure;
+mod hooks;
 mod index_page;
+mod lazy_load;
 mod loaded_index_growth;
+mod memory_budget;
 mod multi_row_backend_order;
+mod overflow;
 mod read;
+mod rebuild;
 mod recovery_load;
 mod schema;
 mod space_index;
+mod subscribe;
 mod sync;
 mod toc;
 mod torn_shutdown;
+mod truncate;
 mod tuple_primary_key;
+mod upsert_with;
 mod vacuum;
 
 #[cfg(feature = "s3-support")]
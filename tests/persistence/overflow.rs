@@ -0,0 +1,162 @@
+use std::time::Duration;
+
+use tokio::time::timeout;
+
+use crate::remove_dir_if_exists;
+use worktable::prelude::PersistedWorkTable;
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: OverflowPersist,
+    persist: true,
+    columns: {
+        id: u64 primary_key autoincrement,
+        name: String,
+        payload: String,
+    },
+    indexes: {
+        name_idx: name unique,
+    },
+);
+
+fn row(table: &OverflowPersistWorkTable, name: &str, payload_len: usize) -> OverflowPersistRow {
+    OverflowPersistRow {
+        id: table.get_next_pk().into(),
+        name: name.to_string(),
+        payload: name.repeat(payload_len / name.len() + 1)[..payload_len].to_string(),
+    }
+}
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .enable_io()
+        .enable_time()
+        .build()
+        .unwrap()
+}
+
+async fn settle(table: &OverflowPersistWorkTable) {
+    timeout(Duration::from_secs(30), table.wait_for_ops())
+        .await
+        .expect("persistence should catch up")
+        .expect("persistence engine failed");
+}
+
+#[test]
+fn rows_larger_than_a_page_survive_update_and_reload() {
+    let config = DiskConfig::new_with_table_name(
+        "tests/data/overflow/reload",
+        OverflowPersistWorkTable::name_snake_case(),
+        OverflowPersistWorkTable::version(),
+    );
+
+    runtime().block_on(async {
+        remove_dir_if_exists("tests/data/overflow/reload".to_string()).await;
+
+        let mut rows = Vec::new();
+        {
+            let engine = OverflowPersistPersistenceEngine::new(config.clone()).await.unwrap();
+            let table = OverflowPersistWorkTable::load(engine).await.unwrap();
+            for (i, len) in [10, 40_000, 20, 70_000, 30].into_iter().enumerate() {
+                let row = row(&table, &format!("row{i}"), len);
+                table.insert(row.clone()).unwrap();
+                rows.push(row);
+            }
+
+            // Same size, then a different size that moves the row.
+            rows[1].payload = rows[1].payload.to_uppercase();
+            table.update(rows[1].clone()).await.unwrap();
+            rows[3].payload = "grown".repeat(15_000);
+            table.update(rows[3].clone()).await.unwrap();
+            let removed = rows.remove(4);
+            table.delete(removed.id).await.unwrap();
+
+            for expected in &rows {
+                assert_eq!(table.select(expected.id).as_ref(), Some(expected));
+            }
+            assert_eq!(table.select_by_name("row3".to_string()), Some(rows[3].clone()));
+            settle(&table).await;
+        }
+        {
+            let engine = OverflowPersistPersistenceEngine::new(config.clone()).await.unwrap();
+            let table = OverflowPersistWorkTable::load(engine).await.unwrap();
+            for expected in &rows {
+                assert_eq!(table.select(expected.id).as_ref(), Some(expected));
+            }
+
+            // The last stored row was an overflow row; appends after reload
+            // must not land inside its chain.
+            for (i, len) in [40, 50_000].into_iter().enumerate() {
+                let row = row(&table, &format!("new{i}"), len);
+                table.insert(row.clone()).unwrap();
+                rows.push(row);
+            }
+            settle(&table).await;
+        }
+        {
+            let engine = OverflowPersistPersistenceEngine::new(config).await.unwrap();
+            let table = OverflowPersistWorkTable::load(engine).await.unwrap();
+            assert_eq!(table.count(), rows.len());
+            for expected in &rows {
+                assert_eq!(table.select(expected.id).as_ref(), Some(expected));
+            }
+        }
+    });
+}
+
+#[test]
+fn vacuum_reclaims_deleted_overflow_pages() {
+    let config = DiskConfig::new_with_table_name(
+        "tests/data/overflow/vacuum",
+        OverflowPersistWorkTable::name_snake_case(),
+        OverflowPersistWorkTable::version(),
+    );
+
+    runtime().block_on(async {
+        remove_dir_if_exists("tests/data/overflow/vacuum".to_string()).await;
+
+        let mut kept = Vec::new();
+        {
+            let engine = OverflowPersistPersistenceEngine::new(config.clone()).await.unwrap();
+            let table = OverflowPersistWorkTable::load(engine).await.unwrap();
+            let mut deleted = Vec::new();
+            for i in 0..10 {
+                let row = row(&table, &format!("row{i}"), 25_000);
+                table.insert(row.clone()).unwrap();
+                if i % 2 == 0 {
+                    deleted.push(row);
+                } else {
+                    kept.push(row);
+                }
+            }
+            for row in &deleted {
+                table.delete(row.id).await.unwrap();
+            }
+            settle(&table).await;
+
+            let stats = table.vacuum().vacuum().await.unwrap();
+            assert!(stats.pages_freed >= deleted.len() * 2);
+            settle(&table).await;
+
+            assert!(!table.0.data.get_empty_pages().is_empty());
+            let row = row(&table, "after", 100);
+            table.insert(row.clone()).unwrap();
+            kept.push(row);
+
+            for expected in &kept {
+                assert_eq!(table.select(expected.id).as_ref(), Some(expected));
+            }
+            settle(&table).await;
+        }
+        {
+            let engine = OverflowPersistPersistenceEngine::new(config).await.unwrap();
+            let table = OverflowPersistWorkTable::load(engine).await.unwrap();
+            assert_eq!(table.count(), kept.len());
+            for expected in &kept {
+                assert_eq!(table.select(expected.id).as_ref(), Some(expected));
+            }
+        }
+    });
+}
@@ -0,0 +1,218 @@
+use std::collections::BTreeSet;
+
+use crate::remove_dir_if_exists;
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: Rebuild,
+    persist: true,
+    columns: {
+        id: String primary_key,
+        project_id: String,
+        body: String,
+    },
+    indexes: {
+        project_idx: project_id,
+    },
+);
+
+worktable!(
+    name: RebuildPlain,
+    persist: true,
+    columns: {
+        id: u64 primary_key,
+        email: String,
+    },
+    indexes: {
+        email_plain_idx: email,
+    },
+);
+
+worktable!(
+    name: RebuildUnique,
+    persist: true,
+    columns: {
+        id: u64 primary_key,
+        email: String,
+    },
+    indexes: {
+        email_idx: email unique,
+    },
+);
+
+const LOST_DIR: &str = "tests/data/rebuild/lost_index_files";
+const DAMAGED_DIR: &str = "tests/data/rebuild/damaged_index_file";
+const DUPLICATE_DIR: &str = "tests/data/rebuild/duplicate_unique_key";
+
+fn config(dir: &str) -> DiskConfig {
+    DiskConfig::new_with_table_name(dir, RebuildWorkTable::name_snake_case(), RebuildWorkTable::version())
+}
+
+async fn engine(dir: &str) -> RebuildPersistenceEngine {
+    RebuildPersistenceEngine::new(config(dir)).await.unwrap()
+}
+
+fn unique_config() -> DiskConfig {
+    DiskConfig::new_with_table_name(
+        DUPLICATE_DIR,
+        RebuildUniqueWorkTable::name_snake_case(),
+        RebuildUniqueWorkTable::version(),
+    )
+}
+
+fn row(id: &str, project_id: &str) -> RebuildRow {
+    RebuildRow {
+        id: id.to_owned(),
+        project_id: project_id.to_owned(),
+        body: format!("body-{id}"),
+    }
+}
+
+/// Writes rows, an update that moves a row and a delete, so the data pages
+/// hold deleted bytes and registered empty links besides the live rows.
+async fn write_rows(dir: &str) {
+    remove_dir_if_exists(dir.to_owned()).await;
+
+    let table = RebuildWorkTable::load(engine(dir).await).await.unwrap();
+    for i in 0..100 {
+        table
+            .insert(row(&format!("row-{i}"), &format!("project-{}", i % 3)))
+            .unwrap();
+    }
+    let mut moved = row("row-7", "project-1");
+    moved.body = "a body long enough not to fit in the old slot".repeat(4);
+    table.update(moved).await.unwrap();
+    table.delete("row-8".to_owned()).await.unwrap();
+    table.close().await.unwrap();
+}
+
+fn project_ids(table: &RebuildWorkTable, project_id: &str) -> BTreeSet<String> {
+    table
+        .select_by_project_id(project_id.to_owned())
+        .execute()
+        .unwrap()
+        .into_iter()
+        .map(|row| row.id)
+        .collect()
+}
+
+fn assert_rows(table: &RebuildWorkTable) {
+    assert_eq!(table.count(), 99);
+    assert!(table.select("row-8".to_owned()).is_none());
+    assert_eq!(
+        table.select("row-7".to_owned()).unwrap().body,
+        "a body long enough not to fit in the old slot".repeat(4)
+    );
+    assert_eq!(table.select("row-42".to_owned()), Some(row("row-42", "project-0")));
+    let project_1 = project_ids(table, "project-1");
+    assert_eq!(project_1.len(), 33);
+    assert!(project_1.contains("row-7"));
+    assert_eq!(project_ids(table, "project-2").len(), 32);
+}
+
+#[tokio::test]
+async fn rebuild_mode_restores_lost_index_files() {
+    write_rows(LOST_DIR).await;
+
+    let table_path = format!("{LOST_DIR}/{}", RebuildWorkTable::name_snake_case());
+    remove_index_files(&table_path).await.unwrap();
+
+    let table = RebuildWorkTable::load_with(engine(LOST_DIR).await, LoadMode::Rebuild)
+        .await
+        .unwrap();
+    assert_rows(&table);
+    table.close().await.unwrap();
+
+    let table = RebuildWorkTable::load(engine(LOST_DIR).await).await.unwrap();
+    assert_rows(&table);
+    table.insert(row("row-100", "project-1")).unwrap();
+    assert_eq!(project_ids(&table, "project-1").len(), 34);
+    table.close().await.unwrap();
+
+    remove_dir_if_exists(LOST_DIR.to_owned()).await;
+}
+
+#[tokio::test]
+async fn rebuild_indexes_replaces_a_damaged_index_file() {
+    write_rows(DAMAGED_DIR).await;
+
+    let table_path = format!("{DAMAGED_DIR}/{}", RebuildWorkTable::name_snake_case());
+    tokio::fs::write(
+        format!("{table_path}/project_idx{WT_INDEX_EXTENSION}"),
+        vec![0xAB; 4096],
+    )
+    .await
+    .unwrap();
+
+    let (table, report) = RebuildWorkTable::rebuild_indexes::<RebuildPersistenceEngine, _>(config(DAMAGED_DIR))
+        .await
+        .unwrap();
+    assert!(report.is_complete(), "{report:?}");
+    assert_eq!(report.rows_indexed, 99);
+    assert_rows(&table);
+    table.close().await.unwrap();
+
+    let table = RebuildWorkTable::load(engine(DAMAGED_DIR).await).await.unwrap();
+    assert_rows(&table);
+    table.close().await.unwrap();
+
+    remove_dir_if_exists(DAMAGED_DIR.to_owned()).await;
+}
+
+#[tokio::test]
+async fn rebuild_reports_rows_with_a_duplicate_unique_key() {
+    remove_dir_if_exists(DUPLICATE_DIR.to_owned()).await;
+
+    // The same rows written under a non-unique index hold a duplicate
+    // email once the index is declared unique.
+    let table = RebuildPlainWorkTable::load(RebuildPlainPersistenceEngine::new(unique_config()).await.unwrap())
+        .await
+        .unwrap();
+    for (id, email) in [(1, "a@example.com"), (2, "b@example.com"), (3, "a@example.com")] {
+        table
+            .insert(RebuildPlainRow {
+                id,
+                email: email.to_owned(),
+            })
+            .unwrap();
+    }
+    table.close().await.unwrap();
+
+    let error = RebuildUniqueWorkTable::load_with(
+        RebuildUniquePersistenceEngine::new(unique_config()).await.unwrap(),
+        LoadMode::Rebuild,
+    )
+    .await
+    .unwrap_err();
+    let typed = error
+        .downcast_ref::<PersistenceLoadError>()
+        .expect("rebuild load must return a typed corruption error");
+    assert!(
+        typed.reason().contains("duplicate key"),
+        "unexpected rebuild-load reason: {}",
+        typed.reason()
+    );
+
+    let (table, report) = RebuildUniqueWorkTable::rebuild_indexes::<RebuildUniquePersistenceEngine, _>(unique_config())
+        .await
+        .unwrap();
+    assert_eq!(report.rows_indexed, 2);
+    assert!(report.unreadable.is_empty());
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].index, "EmailIdx");
+    assert_eq!(report.conflicts[0].row.id, 3);
+    assert_eq!(table.count(), 2);
+    assert!(table.select(3).is_none());
+    assert_eq!(table.select_by_email("a@example.com".to_owned()).unwrap().id, 1);
+    table.close().await.unwrap();
+
+    let table = RebuildUniqueWorkTable::load(RebuildUniquePersistenceEngine::new(unique_config()).await.unwrap())
+        .await
+        .unwrap();
+    assert_eq!(table.count(), 2);
+    assert_eq!(table.select_by_email("b@example.com".to_owned()).unwrap().id, 2);
+    table.close().await.unwrap();
+
+    remove_dir_if_exists(DUPLICATE_DIR.to_owned()).await;
+}
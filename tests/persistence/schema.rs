@@ -26,6 +26,22 @@ worktable!(
     },
 );
 
+// `SchemaMetadata` with `score_idx` dropped and a unique index over the same
+// column added. Rows and primary key are unchanged.
+worktable!(
+    name: ReindexedSchema,
+    persist: true,
+    columns: {
+        id: u64 primary_key autoincrement,
+        email: String,
+        score: i64,
+    },
+    indexes: {
+        email_idx: email unique,
+        score_unique_idx: score unique,
+    },
+);
+
 #[tokio::test]
 async fn generated_schema_is_persisted_and_mismatches_are_rejected() {
     let root = "tests/data/persisted_schema_metadata";
@@ -88,3 +104,101 @@ async fn loading_a_legacy_empty_schema_does_not_rewrite_the_file() {
     assert!(info.inner.primary_key_fields.is_empty());
     assert!(info.inner.secondary_index_types.is_empty());
 }
+
+async fn fill_schema_metadata(table_path: &str, scores: &[i64]) {
+    let config = DiskConfig::new(table_path, table_path, SchemaMetadataWorkTable::version());
+    let engine = SchemaMetadataPersistenceEngine::new(config).await.unwrap();
+    let table = SchemaMetadataWorkTable::load(engine).await.unwrap();
+    for (i, score) in scores.iter().enumerate() {
+        table
+            .insert(SchemaMetadataRow {
+                id: table.get_next_pk().into(),
+                email: format!("user-{i}@example.com"),
+                score: *score,
+            })
+            .unwrap();
+    }
+    table.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn index_only_changes_are_applied_without_rewriting_rows() {
+    let table_path = "tests/data/persisted_schema_reindex";
+    remove_dir_if_exists(table_path.to_owned()).await;
+    fill_schema_metadata(table_path, &[30, 10, 20]).await;
+    let data_path = format!("{table_path}/{}", WT_DATA_EXTENSION);
+    let rows_before = tokio::fs::read(&data_path).await.unwrap();
+
+    let config = DiskConfig::new(table_path, table_path, ReindexedSchemaWorkTable::version());
+    let engine = ReindexedSchemaPersistenceEngine::new(config.clone()).await.unwrap();
+    let table = ReindexedSchemaWorkTable::load(engine).await.unwrap();
+    assert_eq!(table.select_by_score(20).unwrap().email, "user-2@example.com");
+    assert_eq!(
+        table.select_by_email("user-0@example.com".to_owned()).unwrap().score,
+        30
+    );
+    table.close().await.unwrap();
+
+    // Only the info page is rewritten; the row pages are left as they were.
+    let rows_after = tokio::fs::read(&data_path).await.unwrap();
+    assert_eq!(rows_before.len(), rows_after.len());
+    assert_eq!(rows_before[PAGE_SIZE..], rows_after[PAGE_SIZE..]);
+    assert!(!std::path::Path::new(&format!("{table_path}/score_idx{WT_INDEX_EXTENSION}")).exists());
+    assert!(std::path::Path::new(&format!("{table_path}/score_unique_idx{WT_INDEX_EXTENSION}")).exists());
+
+    let mut file = File::open(&data_path).await.unwrap();
+    let info = parse_page::<SpaceInfoPage<u64>, { PAGE_SIZE as u32 }>(&mut file, 0)
+        .await
+        .unwrap();
+    assert_eq!(
+        info.inner.secondary_index_types,
+        vec![
+            ("email_idx".to_owned(), "String".to_owned()),
+            ("score_unique_idx".to_owned(), "i64".to_owned()),
+        ]
+    );
+
+    let engine = ReindexedSchemaPersistenceEngine::new(config.clone()).await.unwrap();
+    let table = ReindexedSchemaWorkTable::load(engine).await.unwrap();
+    table
+        .insert(ReindexedSchemaRow {
+            id: table.get_next_pk().into(),
+            email: "user-3@example.com".to_owned(),
+            score: 40,
+        })
+        .unwrap();
+    assert!(
+        table
+            .insert(ReindexedSchemaRow {
+                id: table.get_next_pk().into(),
+                email: "user-4@example.com".to_owned(),
+                score: 10,
+            })
+            .is_err()
+    );
+    table.close().await.unwrap();
+
+    let engine = ReindexedSchemaPersistenceEngine::new(config).await.unwrap();
+    let table = ReindexedSchemaWorkTable::load(engine).await.unwrap();
+    assert_eq!(table.count(), 4);
+    assert_eq!(table.select_by_score(40).unwrap().email, "user-3@example.com");
+    assert_eq!(table.select_by_score(10).unwrap().email, "user-1@example.com");
+}
+
+#[tokio::test]
+async fn adding_a_unique_index_over_duplicates_leaves_the_store_unchanged() {
+    let table_path = "tests/data/persisted_schema_reindex_conflict";
+    remove_dir_if_exists(table_path.to_owned()).await;
+    fill_schema_metadata(table_path, &[10, 20, 10]).await;
+
+    let config = DiskConfig::new(table_path, table_path, ReindexedSchemaWorkTable::version());
+    let engine = ReindexedSchemaPersistenceEngine::new(config).await.unwrap();
+    let error = ReindexedSchemaWorkTable::load(engine).await.unwrap_err();
+    assert!(format!("{error:#}").contains("score_unique_idx"), "{error:#}");
+
+    let config = DiskConfig::new(table_path, table_path, SchemaMetadataWorkTable::version());
+    let engine = SchemaMetadataPersistenceEngine::new(config).await.unwrap();
+    let table = SchemaMetadataWorkTable::load(engine).await.unwrap();
+    assert_eq!(table.count(), 3);
+    assert_eq!(table.select_by_score(10).execute().unwrap().len(), 2);
+}
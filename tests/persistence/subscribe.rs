@@ -0,0 +1,96 @@
+use crate::remove_dir_if_exists;
+use worktable::prelude::PersistedWorkTable;
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: FeedPersist,
+    persist: true,
+    columns: {
+        id: u64 primary_key autoincrement,
+        code: u64,
+        name: String,
+    },
+    indexes: {
+        code_idx: code unique,
+    },
+    queries: {
+        update: {
+            NameByCode(name) by code,
+        },
+    }
+);
+
+#[test]
+fn persisted_table_reports_changes_and_persists_them() {
+    let config = DiskConfig::new_with_table_name(
+        "tests/data/subscribe/persisted",
+        FeedPersistWorkTable::name_snake_case(),
+        FeedPersistWorkTable::version(),
+    );
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .enable_io()
+        .enable_time()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        remove_dir_if_exists("tests/data/subscribe/persisted".to_string()).await;
+
+        {
+            let engine = FeedPersistPersistenceEngine::new(config.clone()).await.unwrap();
+            let table = FeedPersistWorkTable::load(engine).await.unwrap();
+            let mut changes = table.subscribe();
+
+            let row = FeedPersistRow {
+                id: table.get_next_pk().into(),
+                code: 1,
+                name: "first".to_string(),
+            };
+            table.insert(row.clone()).unwrap();
+            table
+                .update_name_by_code(
+                    NameByCodeQuery {
+                        name: "renamed".to_string(),
+                    },
+                    1,
+                )
+                .await
+                .unwrap();
+            let other = FeedPersistRow {
+                id: table.get_next_pk().into(),
+                code: 2,
+                name: "second".to_string(),
+            };
+            table.insert(other.clone()).unwrap();
+            table.delete(other.id).await.unwrap();
+            table.wait_for_ops().await.unwrap();
+
+            assert_eq!(changes.recv().await.unwrap().change, RowChange::Inserted(row.clone()));
+            let updated = changes.recv().await.unwrap();
+            assert_eq!(updated.seq, 2);
+            let RowChange::Updated { old, new } = updated.change else {
+                panic!("expected an update");
+            };
+            assert_eq!(old, row);
+            assert_eq!(new.name, "renamed");
+            assert_eq!(changes.recv().await.unwrap().change, RowChange::Inserted(other.clone()));
+            assert_eq!(
+                changes.recv().await.unwrap().change,
+                RowChange::Deleted(other.id.into())
+            );
+            assert_eq!(changes.try_recv(), Ok(None));
+        }
+        {
+            let engine = FeedPersistPersistenceEngine::new(config).await.unwrap();
+            let table = FeedPersistWorkTable::load(engine).await.unwrap();
+            assert_eq!(table.count(), 1);
+            assert_eq!(table.select_by_code(1).unwrap().name, "renamed");
+            // Loading a table replays nothing into a fresh subscription.
+            let mut changes = table.subscribe();
+            assert_eq!(changes.try_recv(), Ok(None));
+        }
+    });
+}
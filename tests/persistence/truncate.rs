@@ -0,0 +1,112 @@
+use crate::remove_dir_if_exists;
+use worktable::prelude::PersistedWorkTable;
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: TruncatePersist,
+    persist: true,
+    columns: {
+        id: u64 primary_key autoincrement,
+        code: u64,
+        bucket: u32,
+        name: String,
+    },
+    indexes: {
+        code_idx: code unique,
+        bucket_idx: bucket,
+    },
+);
+
+fn fill(table: &TruncatePersistWorkTable, from: u64, count: u64) {
+    for code in from..from + count {
+        table
+            .insert(TruncatePersistRow {
+                id: table.get_next_pk().into(),
+                code,
+                bucket: (code % 4) as u32,
+                name: format!("row-{code}"),
+            })
+            .unwrap();
+    }
+}
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .enable_io()
+        .enable_time()
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn truncate_survives_reload() {
+    let config = DiskConfig::new_with_table_name(
+        "tests/data/truncate/reload",
+        TruncatePersistWorkTable::name_snake_case(),
+        TruncatePersistWorkTable::version(),
+    );
+
+    runtime().block_on(async {
+        remove_dir_if_exists("tests/data/truncate/reload".to_string()).await;
+
+        {
+            let engine = TruncatePersistPersistenceEngine::new(config.clone()).await.unwrap();
+            let table = TruncatePersistWorkTable::load(engine).await.unwrap();
+            fill(&table, 0, 2_000);
+            table.truncate().unwrap();
+            table.wait_for_ops().await.unwrap();
+        }
+        {
+            let engine = TruncatePersistPersistenceEngine::new(config).await.unwrap();
+            let table = TruncatePersistWorkTable::load(engine).await.unwrap();
+            assert_eq!(table.count(), 0);
+            assert!(table.select_by_code(5).is_none());
+            assert!(table.select_by_bucket(1).execute().unwrap().is_empty());
+            // The generator state is kept, so new keys do not collide with
+            // keys handed out before the truncate.
+            assert_eq!(table.get_next_pk(), 2_000.into());
+        }
+    });
+}
+
+/// Operations queued before the truncate must not leak into the reset files,
+/// and operations queued after it must land on them.
+#[test]
+fn writes_after_truncate_survive_reload() {
+    let config = DiskConfig::new_with_table_name(
+        "tests/data/truncate/after",
+        TruncatePersistWorkTable::name_snake_case(),
+        TruncatePersistWorkTable::version(),
+    );
+
+    runtime().block_on(async {
+        remove_dir_if_exists("tests/data/truncate/after".to_string()).await;
+
+        {
+            let engine = TruncatePersistPersistenceEngine::new(config.clone()).await.unwrap();
+            let table = TruncatePersistWorkTable::load(engine).await.unwrap();
+            fill(&table, 0, 500);
+            let mut row = table.select_by_code(3).unwrap();
+            row.name = "updated before truncate".to_string();
+            table.update(row).await.unwrap();
+            table.truncate().unwrap();
+            fill(&table, 0, 300);
+            let mut row = table.select_by_code(3).unwrap();
+            row.name = "updated after truncate".to_string();
+            table.update(row).await.unwrap();
+            table.delete(table.select_by_code(4).unwrap().id).await.unwrap();
+            table.wait_for_ops().await.unwrap();
+        }
+        {
+            let engine = TruncatePersistPersistenceEngine::new(config).await.unwrap();
+            let table = TruncatePersistWorkTable::load(engine).await.unwrap();
+            assert_eq!(table.count(), 299);
+            assert_eq!(table.select_by_code(3).unwrap().name, "updated after truncate");
+            assert!(table.select_by_code(4).is_none());
+            assert_eq!(table.select_by_code(0).unwrap().id, 500);
+            assert_eq!(table.select_by_bucket(1).execute().unwrap().len(), 75);
+        }
+    });
+}
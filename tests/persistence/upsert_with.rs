@@ -0,0 +1,82 @@
+use crate::remove_dir_if_exists;
+use worktable::prelude::PersistedWorkTable;
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: UpsertWithPersist,
+    persist: true,
+    columns: {
+        id: u64 primary_key,
+        code: u64,
+        qty: u64,
+    },
+    indexes: {
+        code_idx: code unique,
+    },
+);
+
+/// Merged upserts and ignored inserts must reach disk through the same CDC
+/// operations as plain inserts and updates.
+#[test]
+fn upsert_with_and_insert_or_ignore_survive_reload() {
+    let config = DiskConfig::new_with_table_name(
+        "tests/data/upsert_with/reload",
+        UpsertWithPersistWorkTable::name_snake_case(),
+        UpsertWithPersistWorkTable::version(),
+    );
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .enable_io()
+        .enable_time()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        remove_dir_if_exists("tests/data/upsert_with/reload".to_string()).await;
+
+        {
+            let engine = UpsertWithPersistPersistenceEngine::new(config.clone()).await.unwrap();
+            let table = UpsertWithPersistWorkTable::load(engine).await.unwrap();
+            for i in 0..100u64 {
+                table
+                    .upsert_with(
+                        UpsertWithPersistRow {
+                            id: i % 10,
+                            code: i % 10,
+                            qty: 1,
+                        },
+                        |existing, incoming| existing.qty += incoming.qty,
+                    )
+                    .await
+                    .unwrap();
+            }
+            let pk = table
+                .insert_or_ignore(UpsertWithPersistRow {
+                    id: 20,
+                    code: 3,
+                    qty: 0,
+                })
+                .unwrap();
+            assert_eq!(pk, 3.into());
+            table
+                .insert_or_ignore(UpsertWithPersistRow {
+                    id: 20,
+                    code: 20,
+                    qty: 0,
+                })
+                .unwrap();
+            table.wait_for_ops().await.unwrap();
+        }
+        {
+            let engine = UpsertWithPersistPersistenceEngine::new(config).await.unwrap();
+            let table = UpsertWithPersistWorkTable::load(engine).await.unwrap();
+            assert_eq!(table.count(), 11);
+            for i in 0..10u64 {
+                assert_eq!(table.select(i).unwrap().qty, 10);
+            }
+            assert_eq!(table.select_by_code(20).unwrap().id, 20);
+        }
+    });
+}
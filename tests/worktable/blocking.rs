@@ -0,0 +1,114 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: Counter,
+    columns: {
+        id: u64 primary_key,
+        count: u64,
+        group: u64,
+    },
+    indexes: {
+        group_idx: group,
+    },
+    queries: {
+        update: {
+            CountById(count) by id,
+        },
+        delete: {
+            ByGroup() by group,
+        }
+    }
+);
+
+fn row(id: u64, group: u64) -> CounterRow {
+    CounterRow { id, count: 0, group }
+}
+
+#[test]
+fn blocking_mutations_run_without_runtime() {
+    let table = CounterWorkTable::default();
+    table.insert(row(0, 0)).unwrap();
+    table.insert(row(1, 1)).unwrap();
+
+    let mut changed = row(0, 0);
+    changed.count = 5;
+    table.update_blocking(changed.clone()).unwrap();
+    assert_eq!(table.select(0).unwrap(), changed);
+
+    table
+        .update_count_by_id_blocking(CountByIdQuery { count: 6 }, 0)
+        .unwrap();
+    assert_eq!(table.select(0).unwrap().count, 6);
+
+    table.upsert_blocking(row(2, 1)).unwrap();
+    assert!(table.select(2).is_some());
+
+    table.delete_by_group_blocking(1).unwrap();
+    assert!(table.select(1).is_none());
+    assert!(table.select(2).is_none());
+
+    table.delete_blocking(0).unwrap();
+    assert_eq!(table.count(), 0);
+}
+
+#[test]
+fn blocking_updates_share_row_locks_across_threads() {
+    let table = Arc::new(CounterWorkTable::default());
+    table.insert(row(0, 0)).unwrap();
+
+    let threads: Vec<_> = (1..=4)
+        .map(|count| {
+            let table = table.clone();
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    table.update_count_by_id_blocking(CountByIdQuery { count }, 0).unwrap();
+                }
+            })
+        })
+        .collect();
+    for t in threads {
+        t.join().unwrap();
+    }
+
+    assert!((1..=4).contains(&table.select(0).unwrap().count));
+    assert!(table.held_locks().is_empty());
+}
+
+#[test]
+fn blocking_update_times_out_on_held_row() {
+    let table = Arc::new(CounterWorkTable::default());
+    table.insert(row(0, 0)).unwrap();
+    table.set_lock_timeout(Some(Duration::from_millis(20)));
+
+    let lock_manager = table.0.lock_manager.clone();
+    let lock_id = lock_manager.next_id();
+    let lock = lock_manager.get_or_insert_with(0.into(), CounterLock::new);
+    let (_, op_lock) = lock.write().lock(lock_id);
+    drop(lock);
+    let stuck = LockGuard::new(op_lock, lock_manager, 0.into());
+
+    let waiter = {
+        let table = table.clone();
+        thread::spawn(move || table.update_blocking(row(0, 0)))
+    };
+    assert!(matches!(waiter.join().unwrap(), Err(WorkTableError::LockTimeout)));
+
+    // Without a timeout the update parks until the holder releases the row.
+    table.set_lock_timeout(None);
+    let waiter = {
+        let table = table.clone();
+        let mut changed = row(0, 0);
+        changed.count = 9;
+        thread::spawn(move || table.update_blocking(changed))
+    };
+    thread::sleep(Duration::from_millis(50));
+    assert!(!waiter.is_finished());
+    drop(stuck);
+    waiter.join().unwrap().unwrap();
+    assert_eq!(table.select(0).unwrap().count, 9);
+}
@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: BulkMutation,
+    persist: false,
+    columns: {
+        id: u64 primary_key autoincrement,
+        created_at: u64,
+        bucket: u32,
+        name: String,
+        score: f64,
+    },
+    indexes: {
+        created_at_idx: created_at unique,
+        bucket_idx: bucket,
+        score_idx: score,
+    },
+);
+
+fn fill(table: &BulkMutationWorkTable, count: u64) {
+    for i in 0..count {
+        table
+            .insert(BulkMutationRow {
+                id: table.get_next_pk().into(),
+                created_at: i,
+                bucket: (i % 4) as u32,
+                name: format!("row-{i}"),
+                score: i as f64 / 2.0,
+            })
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn delete_where_removes_matching_rows_and_index_entries() {
+    let table = BulkMutationWorkTable::default();
+    fill(&table, 100);
+
+    let deleted = table.delete_where(|row| row.created_at < 40).await.unwrap();
+
+    assert_eq!(deleted, 40);
+    assert_eq!(table.count(), 60);
+    assert!(table.select_by_created_at(10).is_none());
+    assert!(table.select_by_created_at(40).is_some());
+    assert_eq!(table.select_by_bucket(0).execute().unwrap().len(), 15);
+}
+
+#[tokio::test]
+async fn delete_where_without_matches_is_a_no_op() {
+    let table = BulkMutationWorkTable::default();
+    fill(&table, 10);
+
+    assert_eq!(table.delete_where(|row| row.created_at > 1_000).await.unwrap(), 0);
+    assert_eq!(table.count(), 10);
+}
+
+#[tokio::test]
+async fn update_where_rewrites_matching_rows() {
+    let table = BulkMutationWorkTable::default();
+    fill(&table, 20);
+
+    let updated = table
+        .update_where(
+            |row| row.bucket == 1,
+            |row| {
+                row.bucket = 9;
+                row.name.push_str("-moved");
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(updated, 5);
+    assert!(table.select_by_bucket(1).execute().unwrap().is_empty());
+    let moved = table.select_by_bucket(9).execute().unwrap();
+    assert_eq!(moved.len(), 5);
+    assert!(moved.iter().all(|row| row.name.ends_with("-moved")));
+}
+
+#[tokio::test]
+async fn update_where_rejects_primary_key_changes() {
+    let table = BulkMutationWorkTable::default();
+    fill(&table, 3);
+
+    let res = table.update_where(|row| row.created_at == 1, |row| row.id += 100).await;
+
+    assert!(matches!(res, Err(WorkTableError::PrimaryUpdateTry)));
+    assert_eq!(table.count(), 3);
+}
+
+#[tokio::test]
+async fn update_where_keeps_rows_updated_before_an_error() {
+    let table = BulkMutationWorkTable::default();
+    fill(&table, 6);
+
+    let res = table
+        .update_where(
+            |row| row.bucket < 4,
+            |row| {
+                if row.created_at == 3 {
+                    row.id += 100;
+                }
+                row.bucket = 9;
+            },
+        )
+        .await;
+
+    assert!(matches!(res, Err(WorkTableError::PrimaryUpdateTry)));
+    let moved = table.select_by_bucket(9).execute().unwrap();
+    let mut moved = moved.iter().map(|row| row.created_at).collect::<Vec<_>>();
+    moved.sort_unstable();
+    assert_eq!(moved, [0, 1, 2]);
+    assert_eq!(table.select_by_created_at(3).unwrap().bucket, 3);
+}
+
+#[tokio::test]
+async fn delete_by_unique_index_range() {
+    let table = BulkMutationWorkTable::default();
+    fill(&table, 50);
+
+    let deleted = table.delete_by_created_at_range(10..20).await.unwrap();
+
+    assert_eq!(deleted, 10);
+    assert_eq!(table.count(), 40);
+    assert!(table.select_by_created_at(9).is_some());
+    assert!(table.select_by_created_at(15).is_none());
+    assert!(table.select_by_created_at(20).is_some());
+}
+
+#[tokio::test]
+async fn delete_by_non_unique_and_float_index_range() {
+    let table = BulkMutationWorkTable::default();
+    fill(&table, 40);
+
+    assert_eq!(table.delete_by_bucket_range(2..).await.unwrap(), 20);
+    assert_eq!(table.count(), 20);
+    assert!(table.select_by_bucket(3).execute().unwrap().is_empty());
+
+    // Remaining scores are the even and odd multiples of 0.5 in buckets 0/1.
+    let deleted = table.delete_by_score_range(..=5.0).await.unwrap();
+    assert_eq!(deleted, 6);
+    assert_eq!(table.count(), 14);
+}
+
+/// A row that leaves the predicate between the snapshot and its row lock must
+/// survive: the predicate is evaluated again under the lock.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn delete_where_rechecks_predicate_under_row_lock() {
+    let table = Arc::new(BulkMutationWorkTable::default());
+    fill(&table, 2_000);
+
+    let updater = {
+        let table = table.clone();
+        tokio::spawn(async move {
+            table
+                .update_where(|row| row.bucket == 0, |row| row.bucket = 7)
+                .await
+                .unwrap()
+        })
+    };
+    let deleted = table.delete_where(|row| row.bucket == 0).await.unwrap();
+    let moved = updater.await.unwrap();
+
+    assert_eq!(deleted + moved, 500);
+    assert_eq!(table.count(), 2_000 - deleted);
+    assert!(table.select_by_bucket(0).execute().unwrap().is_empty());
+    assert_eq!(table.select_by_bucket(7).execute().unwrap().len(), moved);
+}
@@ -0,0 +1,116 @@
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: Compressed,
+    columns: {
+        id: u64 primary_key autoincrement,
+        symbol: String,
+        note: String,
+    },
+    indexes: {
+        symbol_idx: symbol,
+    },
+    config: {
+        compression: lz4,
+    }
+);
+
+fn row(table: &CompressedWorkTable, i: u64) -> CompressedRow {
+    CompressedRow {
+        id: table.get_next_pk().into(),
+        symbol: format!("SYM{}", i % 4),
+        note: "filled by the exchange gateway ".repeat(4),
+    }
+}
+
+#[tokio::test]
+async fn filled_pages_are_compressed_and_stay_readable() {
+    let table = CompressedWorkTable::default();
+    let mut rows = Vec::new();
+    for i in 0..2_000 {
+        let row = row(&table, i);
+        table.insert(row.clone()).unwrap();
+        rows.push(row);
+    }
+
+    let info = table.system_info();
+    assert!(info.page_count > 2);
+    assert!(info.compressed_pages > 0);
+    assert!(info.resident_data_bytes < (info.page_count * COMPRESSED_INNER_SIZE) as u64);
+
+    for expected in &rows {
+        assert_eq!(table.select(expected.id).as_ref(), Some(expected));
+    }
+    assert_eq!(table.select_by_symbol("SYM1".to_string()).execute().unwrap().len(), 500);
+
+    // Archived reads leave compressed pages compressed.
+    for expected in &rows {
+        assert_eq!(
+            table.select_with(expected.id, |r| r.note.len()),
+            Some(expected.note.len())
+        );
+    }
+    assert_eq!(table.system_info().compressed_pages, info.compressed_pages);
+
+    // Writes into a compressed page store it raw again.
+    rows[0].note = "amended".to_string();
+    table.update(rows[0].clone()).await.unwrap();
+    let removed = rows.remove(1);
+    table.delete(removed.id).await.unwrap();
+    for expected in &rows {
+        assert_eq!(table.select(expected.id).as_ref(), Some(expected));
+    }
+    assert_eq!(table.select(removed.id), None);
+}
+
+#[tokio::test]
+async fn vacuum_compresses_pages_it_fills() {
+    let table = CompressedWorkTable::default();
+    let mut rows = Vec::new();
+    for i in 0..2_000 {
+        let row = row(&table, i);
+        table.insert(row.clone()).unwrap();
+        rows.push(row);
+    }
+    let mut kept = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        if i % 2 == 0 {
+            table.delete(row.id).await.unwrap();
+        } else {
+            kept.push(row);
+        }
+    }
+
+    table.vacuum().vacuum().await.unwrap();
+
+    assert!(table.system_info().compressed_pages > 0);
+    assert_eq!(table.count(), kept.len());
+    for expected in &kept {
+        assert_eq!(table.select(expected.id).as_ref(), Some(expected));
+    }
+}
+
+worktable!(
+    name: Plain,
+    columns: {
+        id: u64 primary_key autoincrement,
+        note: String,
+    },
+);
+
+#[test]
+fn pages_stay_raw_without_compression() {
+    let table = PlainWorkTable::default();
+    for _ in 0..2_000 {
+        let row = PlainRow {
+            id: table.get_next_pk().into(),
+            note: "filled by the exchange gateway ".repeat(4),
+        };
+        table.insert(row).unwrap();
+    }
+
+    let info = table.system_info();
+    assert_eq!(info.compressed_pages, 0);
+    assert_eq!(info.resident_data_bytes, (info.page_count * PLAIN_INNER_SIZE) as u64);
+}
@@ -0,0 +1,160 @@
+use worktable::prelude::*;
+use worktable::worktable;
+
+use crate::remove_dir_if_exists;
+
+worktable!(
+    name: Ingest,
+    columns: {
+        id: u64 primary_key autoincrement,
+        external_id: String,
+        value: u64,
+    },
+    indexes: {
+        ext_idx: external_id unique filter,
+        value_idx: value,
+    },
+);
+
+worktable!(
+    name: PersistedIngest,
+    persist: true,
+    columns: {
+        id: u64 primary_key,
+        external_id: String,
+        session: u64,
+    },
+    indexes: {
+        ext_idx: external_id unique filter,
+        session_idx: session unique filter using hash,
+    },
+);
+
+fn ext_idx_info(info: &SystemInfo) -> &IndexInfo {
+    info.indexes_info
+        .iter()
+        .find(|index| index.name == "ext_idx")
+        .expect("ext_idx is declared")
+}
+
+#[tokio::test]
+async fn filtered_unique_index_answers_hits_and_misses() {
+    let table = IngestWorkTable::default();
+    let mut pks = Vec::new();
+    for i in 0..5000u64 {
+        let pk = table
+            .insert(IngestRow {
+                id: table.get_next_pk().into(),
+                external_id: format!("ext-{i}"),
+                value: i % 10,
+            })
+            .unwrap();
+        pks.push(pk);
+    }
+
+    for i in (0..5000).step_by(7) {
+        assert_eq!(table.select_by_external_id(format!("ext-{i}")).unwrap().value, i % 10);
+    }
+    for i in 5000..6000 {
+        assert!(table.select_by_external_id(format!("ext-{i}")).is_none());
+    }
+    assert!(
+        table
+            .insert(IngestRow {
+                id: table.get_next_pk().into(),
+                external_id: "ext-42".to_owned(),
+                value: 0,
+            })
+            .is_err()
+    );
+
+    let grown = table.system_info();
+    let grown_info = ext_idx_info(&grown);
+    assert_eq!(grown_info.key_count, 5000);
+    assert!(grown_info.filter_size > 0);
+    assert!(grown_info.heap_size > grown_info.filter_size);
+    let value_info = grown
+        .indexes_info
+        .iter()
+        .find(|index| index.name == "value_idx")
+        .unwrap();
+    assert_eq!(value_info.filter_size, 0);
+
+    for pk in pks.drain(..4900) {
+        table.delete(pk).await.unwrap();
+    }
+    assert!(table.select_by_external_id("ext-0".to_owned()).is_none());
+    table.vacuum().vacuum().await.unwrap();
+
+    let vacuumed = table.system_info();
+    assert!(ext_idx_info(&vacuumed).filter_size < grown_info.filter_size);
+    for i in 0..4900 {
+        assert!(table.select_by_external_id(format!("ext-{i}")).is_none());
+    }
+    for i in 4900..5000 {
+        assert_eq!(table.select_by_external_id(format!("ext-{i}")).unwrap().value, i % 10);
+    }
+
+    table
+        .insert(IngestRow {
+            id: table.get_next_pk().into(),
+            external_id: "ext-0".to_owned(),
+            value: 3,
+        })
+        .unwrap();
+    assert_eq!(table.select_by_external_id("ext-0".to_owned()).unwrap().value, 3);
+}
+
+#[tokio::test]
+async fn filtered_unique_index_is_rebuilt_on_load() {
+    const ROOT: &str = "tests/data/filtered_index/reload";
+    remove_dir_if_exists(ROOT.to_owned()).await;
+
+    let config = DiskConfig::new_with_table_name(
+        ROOT,
+        PersistedIngestWorkTable::name_snake_case(),
+        PersistedIngestWorkTable::version(),
+    );
+    let engine = PersistedIngestPersistenceEngine::new(config.clone()).await.unwrap();
+    let table = PersistedIngestWorkTable::load(engine).await.unwrap();
+    for i in 0..300u64 {
+        table
+            .insert(PersistedIngestRow {
+                id: i,
+                external_id: format!("ext-{i}"),
+                session: i * 2,
+            })
+            .unwrap();
+    }
+    table.delete(7).await.unwrap();
+    table.close().await.unwrap();
+
+    let engine = PersistedIngestPersistenceEngine::new(config).await.unwrap();
+    let table = PersistedIngestWorkTable::load(engine).await.unwrap();
+    assert_eq!(table.count(), 299);
+    assert!(table.select_by_external_id("ext-7".to_owned()).is_none());
+    assert!(table.select_by_session(14).is_none());
+    for i in (0..300u64).filter(|i| *i != 7) {
+        assert_eq!(table.select_by_external_id(format!("ext-{i}")).unwrap().id, i);
+        assert_eq!(table.select_by_session(i * 2).unwrap().id, i);
+    }
+    for i in 300..400u64 {
+        assert!(table.select_by_external_id(format!("ext-{i}")).is_none());
+        assert!(table.select_by_session(i * 2 + 1).is_none());
+    }
+    let info = table.system_info();
+    assert!(info.indexes_info.iter().all(|index| index.filter_size > 0));
+
+    table
+        .insert(PersistedIngestRow {
+            id: 300,
+            external_id: "ext-7".to_owned(),
+            session: 14,
+        })
+        .unwrap();
+    assert_eq!(table.select_by_external_id("ext-7".to_owned()).unwrap().id, 300);
+    assert_eq!(table.select_by_session(14).unwrap().id, 300);
+    table.close().await.unwrap();
+
+    remove_dir_if_exists(ROOT.to_owned()).await;
+}
@@ -0,0 +1,233 @@
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: Guarded,
+    persist: false,
+    columns: {
+        id: u64 primary_key autoincrement,
+        code: u64,
+        group: u32,
+        name: String,
+        value: i64,
+        locked: bool,
+    },
+    indexes: {
+        code_idx: code unique,
+        group_idx: group,
+    },
+    hooks: {
+        before_insert: reject_negative,
+        before_update: reject_decrease,
+        before_delete: reject_locked,
+    },
+    queries: {
+        update: {
+            ValueById(value) by id,
+            ValueByGroup(value) by group,
+            NameById(name) by id,
+        },
+    }
+);
+
+fn reject_negative(row: &GuardedRow) -> Result<(), WorkTableError> {
+    if row.value < 0 {
+        return Err(WorkTableError::Rejected(format!("negative value {}", row.value)));
+    }
+    Ok(())
+}
+
+fn reject_decrease(old: &GuardedRow, new: &GuardedRow) -> Result<(), WorkTableError> {
+    if new.value < old.value {
+        return Err(WorkTableError::Rejected("value can only grow".to_string()));
+    }
+    Ok(())
+}
+
+fn reject_locked(row: &GuardedRow) -> Result<(), WorkTableError> {
+    if row.locked {
+        return Err(WorkTableError::Rejected(format!("row {} is locked", row.id)));
+    }
+    Ok(())
+}
+
+fn guarded_row(table: &GuardedWorkTable, code: u64, value: i64) -> GuardedRow {
+    GuardedRow {
+        id: table.get_next_pk().into(),
+        code,
+        group: 1,
+        name: format!("row-{code}"),
+        value,
+        locked: false,
+    }
+}
+
+#[tokio::test]
+async fn before_insert_rejects_row() {
+    let table = GuardedWorkTable::default();
+    let mut changes = table.subscribe();
+
+    let res = table.insert(guarded_row(&table, 1, -5));
+    assert!(matches!(res, Err(WorkTableError::Rejected(reason)) if reason == "negative value -5"));
+    assert!(table.upsert(guarded_row(&table, 2, -1)).await.is_err());
+    assert_eq!(table.count(), 0);
+    assert_eq!(changes.try_recv(), Ok(None));
+
+    // A rejected row leaves no index entry behind.
+    table.insert(guarded_row(&table, 1, 5)).unwrap();
+    assert_eq!(table.select_by_code(1).unwrap().value, 5);
+}
+
+#[tokio::test]
+async fn before_update_rejects_full_row_and_custom_updates() {
+    let table = GuardedWorkTable::default();
+    let row = guarded_row(&table, 1, 10);
+    table.insert(row.clone()).unwrap();
+    let mut changes = table.subscribe();
+
+    let mut lower = row.clone();
+    lower.value = 1;
+    assert!(matches!(table.update(lower).await, Err(WorkTableError::Rejected(_))));
+    assert!(
+        table
+            .update_value_by_id(ValueByIdQuery { value: 1 }, row.id)
+            .await
+            .is_err()
+    );
+    assert!(
+        table
+            .update_value_by_group(ValueByGroupQuery { value: 1 }, 1)
+            .await
+            .is_err()
+    );
+    assert!(table.update_where(|_| true, |r| r.value = 0).await.is_err());
+    assert_eq!(table.select(row.id).unwrap(), row);
+    assert_eq!(changes.try_recv(), Ok(None));
+
+    // Updates the hook accepts, including ones that change an unsized
+    // column, go through.
+    table
+        .update_value_by_group(ValueByGroupQuery { value: 20 }, 1)
+        .await
+        .unwrap();
+    table
+        .update_name_by_id(
+            NameByIdQuery {
+                name: "a considerably longer name".to_string(),
+            },
+            row.id,
+        )
+        .await
+        .unwrap();
+    let current = table.select(row.id).unwrap();
+    assert_eq!(current.value, 20);
+    assert_eq!(current.name, "a considerably longer name");
+}
+
+#[tokio::test]
+async fn before_delete_rejects_delete_and_truncate() {
+    let table = GuardedWorkTable::default();
+    let free = guarded_row(&table, 1, 1);
+    table.insert(free.clone()).unwrap();
+    let mut locked = guarded_row(&table, 2, 2);
+    locked.locked = true;
+    table.insert(locked.clone()).unwrap();
+
+    let res = table.delete(locked.id).await;
+    assert!(matches!(res, Err(WorkTableError::Rejected(reason)) if reason == format!("row {} is locked", locked.id)));
+    assert!(table.truncate().is_err());
+    assert_eq!(table.count(), 2);
+    assert_eq!(table.select_by_code(2).unwrap(), locked);
+
+    table.delete(free.id).await.unwrap();
+    assert_eq!(table.count(), 1);
+}
+
+static INSERTED: AtomicU64 = AtomicU64::new(0);
+static TOTAL: AtomicI64 = AtomicI64::new(0);
+static DELETED: AtomicU64 = AtomicU64::new(0);
+
+worktable!(
+    name: Tracked,
+    persist: false,
+    columns: {
+        id: u64 primary_key autoincrement,
+        value: i64,
+        label: String,
+    },
+    hooks: {
+        after_insert: on_insert,
+        after_update: on_update,
+        after_delete: on_delete,
+    },
+    queries: {
+        in_place: {
+            ValueById(value) by id,
+        }
+        update: {
+            LabelById(label) by id,
+        }
+    }
+);
+
+fn on_insert(row: &TrackedRow) {
+    INSERTED.fetch_add(1, Ordering::SeqCst);
+    TOTAL.fetch_add(row.value, Ordering::SeqCst);
+}
+
+fn on_update(old: &TrackedRow, new: &TrackedRow) {
+    TOTAL.fetch_add(new.value - old.value, Ordering::SeqCst);
+}
+
+fn on_delete(row: &TrackedRow) {
+    DELETED.fetch_add(1, Ordering::SeqCst);
+    TOTAL.fetch_sub(row.value, Ordering::SeqCst);
+}
+
+#[tokio::test]
+async fn after_hooks_maintain_derived_state() {
+    let table = TrackedWorkTable::default();
+    let mut rows = Vec::new();
+    for value in 1..=4 {
+        let row = TrackedRow {
+            id: table.get_next_pk().into(),
+            value,
+            label: "x".to_string(),
+        };
+        table.insert(row.clone()).unwrap();
+        rows.push(row);
+    }
+    assert_eq!(INSERTED.load(Ordering::SeqCst), 4);
+    assert_eq!(TOTAL.load(Ordering::SeqCst), 10);
+
+    let mut changed = rows[0].clone();
+    changed.value = 11;
+    table.update(changed).await.unwrap();
+    table
+        .update_value_by_id_in_place(|value| *value += 5, rows[1].id)
+        .await
+        .unwrap();
+    table
+        .update_label_by_id(
+            LabelByIdQuery {
+                label: "a longer label".to_string(),
+            },
+            rows[2].id,
+        )
+        .await
+        .unwrap();
+    assert_eq!(TOTAL.load(Ordering::SeqCst), 25);
+
+    // Failed mutations do not reach the hooks.
+    assert!(table.delete(1_000).await.is_err());
+    assert!(table.insert(rows[3].clone()).is_err());
+    assert_eq!(INSERTED.load(Ordering::SeqCst), 4);
+
+    table.delete(rows[3].id).await.unwrap();
+    assert_eq!(TOTAL.load(Ordering::SeqCst), 21);
+    table.truncate().unwrap();
+    assert_eq!(DELETED.load(Ordering::SeqCst), 4);
+    assert_eq!(TOTAL.load(Ordering::SeqCst), 0);
+}
@@ -1,3 +1,11 @@
+use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+use std::ops::RangeBounds;
+use std::sync::RwLock;
+
+use psc_nanoid::alphabet::Base64UrlAlphabet;
+use psc_nanoid::{Nanoid, packed_nanoid_type};
+use uuid::Uuid;
 use worktable::prelude::*;
 use worktable::worktable;
 
@@ -21,6 +29,101 @@ worktable! {
     },
 }
 
+worktable! {
+    name: NonUniqueArt,
+    persist: false,
+    columns: {
+        id: u64 primary_key autoincrement,
+        account: u64,
+        venue: u32,
+        qty: u64,
+    },
+    indexes: {
+        account_idx: account using arctic,
+        venue_idx: venue using congee,
+    },
+    queries: {
+        update: {
+            QtyByAccount(qty) by account,
+        },
+        delete: {
+            ByAccount() by account,
+        }
+    }
+}
+
+worktable! {
+    name: PersistedNonUniqueArt,
+    persist: true,
+    columns: {
+        id: u64 primary_key autoincrement,
+        account: u64,
+        venue: u64,
+    },
+    indexes: {
+        account_idx: account using arctic,
+        venue_idx: venue using congee,
+    },
+}
+
+worktable! {
+    name: EncodedCongee,
+    persist: false,
+    columns: {
+        account: i32 primary_key using congee,
+        venue: u16 primary_key using congee,
+        balance: i64,
+        price: f64,
+    },
+    indexes: {
+        balance_idx: balance using congee,
+        price_idx: price unique using arctic,
+    },
+}
+
+type PackedNanoid21 = packed_nanoid_type!(21, Base64UrlAlphabet);
+
+worktable! {
+    name: PersistedEncodedArctic,
+    persist: true,
+    columns: {
+        id: PackedNanoid21 primary_key using arctic,
+        external: Uuid,
+        offset: i64,
+    },
+    indexes: {
+        external_idx: external unique using arctic,
+        offset_idx: offset unique using congee,
+    },
+}
+
+worktable! {
+    name: HashBackend,
+    persist: false,
+    columns: {
+        id: u64 primary_key autoincrement using hash,
+        session: String,
+        value: u64,
+    },
+    indexes: {
+        session_idx: session unique using hash,
+        value_idx: value,
+    },
+}
+
+worktable! {
+    name: PersistedHash,
+    persist: true,
+    columns: {
+        id: Uuid primary_key using hash,
+        account: i64,
+        value: u64,
+    },
+    indexes: {
+        account_idx: account unique using hash,
+    },
+}
+
 mod provider_switch_wti {
     use worktable::prelude::*;
     use worktable::worktable;
@@ -118,6 +221,135 @@ worktable! {
     },
 }
 
+/// User-side backend plugged in through `using custom(..)`: a `BTreeMap`
+/// behind one lock, implementing the documented trait set.
+#[derive(Debug)]
+pub struct LockedBTreeIndex<K, V>(RwLock<BTreeMap<K, V>>);
+
+impl<K, V> Default for LockedBTreeIndex<K, V> {
+    fn default() -> Self {
+        Self(RwLock::new(BTreeMap::new()))
+    }
+}
+
+impl<K: Clone + Ord, V: Clone> LockedBTreeIndex<K, V> {
+    fn snapshot(&self, range: impl RangeBounds<K>) -> Vec<(K, V)> {
+        let map = self.0.read().unwrap();
+        map.range(range)
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+impl<K: Clone + Ord, V: Clone> UniqueIndex<K, V> for LockedBTreeIndex<K, V> {
+    fn get_value(&self, key: &K) -> Option<V> {
+        self.0.read().unwrap().get(key).cloned()
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.0.read().unwrap().contains_key(key)
+    }
+
+    fn insert_value(&self, key: K, value: V) -> Option<V> {
+        self.0.write().unwrap().insert(key, value)
+    }
+
+    fn insert_value_checked(&self, key: K, value: V) -> Option<()> {
+        match self.0.write().unwrap().entry(key) {
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                Some(())
+            }
+            Entry::Occupied(_) => None,
+        }
+    }
+
+    fn remove_value(&self, key: &K) -> Option<(K, V)> {
+        self.0.write().unwrap().remove_entry(key)
+    }
+
+    fn len(&self) -> usize {
+        self.0.read().unwrap().len()
+    }
+
+    fn iter_values(&self) -> impl DoubleEndedIterator<Item = (K, V)> + '_ {
+        self.snapshot(..).into_iter()
+    }
+
+    fn iter_links(&self) -> impl DoubleEndedIterator<Item = V> + '_ {
+        self.snapshot(..).into_iter().map(|(_, value)| value)
+    }
+
+    fn range_values<'a, R>(&'a self, range: R) -> impl DoubleEndedIterator<Item = (K, V)> + 'a
+    where
+        R: RangeBounds<K> + 'a,
+    {
+        self.snapshot(range).into_iter()
+    }
+
+    fn range_links<'a, R>(&'a self, range: R) -> impl DoubleEndedIterator<Item = V> + 'a
+    where
+        R: RangeBounds<K> + 'a,
+    {
+        self.snapshot(range).into_iter().map(|(_, value)| value)
+    }
+}
+
+impl<K: Clone + Ord> TableIndex<K> for LockedBTreeIndex<K, OffsetEqLink> {
+    fn insert(&self, value: K, link: Link) -> Option<Link> {
+        self.insert_value(value, OffsetEqLink(link)).map(|link| link.0)
+    }
+
+    fn insert_checked(&self, value: K, link: Link) -> Option<()> {
+        self.insert_value_checked(value, OffsetEqLink(link))
+    }
+
+    fn remove(&self, value: &K, _: Link) -> Option<(K, Link)> {
+        self.remove_value(value).map(|(key, link)| (key, link.0))
+    }
+}
+
+impl<K: Clone + Ord, const N: usize> TableIndexCdc<K> for LockedBTreeIndex<K, OffsetEqLink<N>> {
+    fn insert_cdc(&self, value: K, link: Link) -> (Option<Link>, Vec<IndexChangeEvent<IndexPair<K, Link>>>) {
+        let old = self.insert_value(value, OffsetEqLink(link)).map(|link| link.0);
+        (old, Vec::new())
+    }
+
+    fn insert_checked_cdc(&self, value: K, link: Link) -> Option<Vec<IndexChangeEvent<IndexPair<K, Link>>>> {
+        self.insert_value_checked(value, OffsetEqLink(link))
+            .map(|()| Vec::new())
+    }
+
+    fn remove_cdc(&self, value: K, _: Link) -> (Option<(K, Link)>, Vec<IndexChangeEvent<IndexPair<K, Link>>>) {
+        let removed = self.remove_value(&value).map(|(key, link)| (key, link.0));
+        (removed, Vec::new())
+    }
+}
+
+impl<K, V> MemStat for LockedBTreeIndex<K, V> {
+    fn heap_size(&self) -> usize {
+        self.0.read().unwrap().len() * size_of::<(K, V)>()
+    }
+
+    fn used_size(&self) -> usize {
+        self.heap_size()
+    }
+}
+
+worktable! {
+    name: CustomBackend,
+    persist: false,
+    columns: {
+        id: u64 primary_key autoincrement using custom(LockedBTreeIndex),
+        email: String,
+        score: u64,
+    },
+    indexes: {
+        email_idx: email unique using custom(LockedBTreeIndex),
+        score_idx: score,
+    },
+}
+
 #[tokio::test]
 async fn all_unique_backends_support_crud_ranges_and_conflict_rollback() {
     let table = MixedBackendWorkTable::default();
@@ -207,6 +439,125 @@ async fn all_unique_backends_support_crud_ranges_and_conflict_rollback() {
     assert_eq!(table.count(), 1);
 }
 
+#[tokio::test]
+async fn art_backends_support_non_unique_indexes() {
+    let table = NonUniqueArtWorkTable::default();
+    for i in 0..30 {
+        table
+            .insert(NonUniqueArtRow {
+                id: table.get_next_pk().into(),
+                account: i % 3,
+                venue: (i % 5) as u32,
+                qty: i,
+            })
+            .unwrap();
+    }
+
+    let ids = |rows: Vec<NonUniqueArtRow>| {
+        let mut ids = rows.into_iter().map(|row| row.id).collect::<Vec<_>>();
+        ids.sort();
+        ids
+    };
+    assert_eq!(
+        ids(table.select_by_account(1).execute().unwrap()),
+        (0..30).filter(|i| i % 3 == 1).collect::<Vec<_>>()
+    );
+    assert_eq!(table.select_by_venue(4).execute().unwrap().len(), 6);
+    assert_eq!(table.select_by_account_range(0..=1).execute().unwrap().len(), 20);
+    assert!(table.select_by_account(9).execute().unwrap().is_empty());
+
+    let moved = NonUniqueArtRow {
+        id: 1,
+        account: 2,
+        venue: 1,
+        qty: 100,
+    };
+    table.update(moved.clone()).await.unwrap();
+    assert_eq!(table.select_by_account(1).execute().unwrap().len(), 9);
+    assert!(table.select_by_account(2).execute().unwrap().contains(&moved));
+
+    table
+        .update_qty_by_account(QtyByAccountQuery { qty: 7 }, 2)
+        .await
+        .unwrap();
+    assert!(
+        table
+            .select_by_account(2)
+            .execute()
+            .unwrap()
+            .iter()
+            .all(|row| row.qty == 7)
+    );
+
+    table.delete_by_account(0).await.unwrap();
+    assert_eq!(table.count(), 20);
+    assert!(table.select_by_account(0).execute().unwrap().is_empty());
+    assert_eq!(table.select_by_venue(0).execute().unwrap().len(), 4);
+
+    let info = table.system_info();
+    let account_idx = info
+        .indexes_info
+        .iter()
+        .find(|index| index.name == "account_idx")
+        .unwrap();
+    assert!(matches!(account_idx.index_type, IndexKind::NonUnique));
+    assert_eq!(account_idx.key_count, 20);
+}
+
+#[tokio::test]
+async fn art_backends_order_signed_float_and_composite_keys() {
+    let table = EncodedCongeeWorkTable::default();
+    for account in [2, -1, 0, i32::MIN, -2] {
+        for venue in [3, 0] {
+            table
+                .insert(EncodedCongeeRow {
+                    account,
+                    venue,
+                    balance: account as i64 * 100 - venue as i64,
+                    price: account as f64 + venue as f64 / 4.0,
+                })
+                .unwrap();
+        }
+    }
+
+    let keys = table
+        .select_all()
+        .execute()
+        .unwrap()
+        .into_iter()
+        .map(|row| (row.account, row.venue))
+        .collect::<Vec<_>>();
+    let mut sorted = keys.clone();
+    sorted.sort();
+    assert_eq!(keys, sorted);
+    assert_eq!(table.select((-1, 3)).unwrap().balance, -103);
+    assert!(table.select((-1, 1)).is_none());
+
+    let mut balances = table
+        .select_by_balance_range(-200..=0)
+        .execute()
+        .unwrap()
+        .into_iter()
+        .map(|row| row.balance)
+        .collect::<Vec<_>>();
+    balances.sort();
+    assert_eq!(balances, vec![-200, -103, -100, -3, 0]);
+
+    let mut prices = table
+        .select_by_price_range(-1.0..0.5)
+        .execute()
+        .unwrap()
+        .into_iter()
+        .map(|row| row.price)
+        .collect::<Vec<_>>();
+    prices.sort_by(f64::total_cmp);
+    assert_eq!(prices, vec![-1.0, -0.25, 0.0]);
+    assert_eq!(table.select_by_price(-0.0).unwrap().account, 0);
+
+    table.delete((i32::MIN, 0)).await.unwrap();
+    assert_eq!(table.select_by_balance_range(..-1_000).execute().unwrap().len(), 1);
+}
+
 #[tokio::test]
 async fn alternative_primary_backends_support_point_crud() {
     macro_rules! assert_point_crud {
@@ -237,6 +588,193 @@ async fn alternative_primary_backends_support_point_crud() {
     assert_point_crud!(ArcticPrimaryWorkTable, ArcticPrimaryRow);
 }
 
+#[tokio::test]
+async fn hash_backend_supports_point_crud_and_conflict_rollback() {
+    let table = HashBackendWorkTable::default();
+    let rows = (0..100)
+        .map(|value| HashBackendRow {
+            id: table.get_next_pk().into(),
+            session: format!("session-{value}"),
+            value: value % 10,
+        })
+        .collect::<Vec<_>>();
+    for row in &rows {
+        table.insert(row.clone()).unwrap();
+    }
+
+    assert_eq!(table.select(rows[7].id), Some(rows[7].clone()));
+    assert_eq!(table.select_by_session("session-7".to_string()), Some(rows[7].clone()));
+    assert_eq!(table.select_by_value(3).execute().unwrap().len(), 10);
+
+    let duplicate = HashBackendRow {
+        id: table.get_next_pk().into(),
+        session: "session-7".to_string(),
+        value: 0,
+    };
+    assert!(table.insert(duplicate.clone()).is_err());
+    assert!(table.select(duplicate.id).is_none());
+    assert_eq!(table.count(), 100);
+
+    let updated = HashBackendRow {
+        session: "renamed".to_string(),
+        ..rows[7].clone()
+    };
+    table.update(updated.clone()).await.unwrap();
+    assert!(table.select_by_session("session-7".to_string()).is_none());
+    assert_eq!(table.select_by_session("renamed".to_string()), Some(updated));
+
+    table.delete(rows[7].id).await.unwrap();
+    assert!(table.select(rows[7].id).is_none());
+    assert!(table.select_by_session("renamed".to_string()).is_none());
+
+    // Hash indexes have no order, but full scans still come back in key order.
+    let ids = table
+        .select_all()
+        .execute()
+        .unwrap()
+        .into_iter()
+        .map(|row| row.id)
+        .collect::<Vec<_>>();
+    let mut sorted = ids.clone();
+    sorted.sort();
+    assert_eq!(ids.len(), 99);
+    assert_eq!(ids, sorted);
+
+    let info = table.system_info();
+    let session_info = info
+        .indexes_info
+        .iter()
+        .find(|index| index.name == "session_idx")
+        .unwrap();
+    assert_eq!(session_info.key_count, 99);
+    assert!(session_info.capacity >= 99);
+}
+
+#[tokio::test]
+async fn custom_backend_supports_crud_ranges_and_conflict_rollback() {
+    let table = CustomBackendWorkTable::default();
+    let rows = (0..50)
+        .map(|value| CustomBackendRow {
+            id: table.get_next_pk().into(),
+            email: format!("user-{value:02}@example.com"),
+            score: value % 5,
+        })
+        .collect::<Vec<_>>();
+    for row in &rows {
+        table.insert(row.clone()).unwrap();
+    }
+
+    assert_eq!(table.select(rows[3].id), Some(rows[3].clone()));
+    assert_eq!(
+        table.select_by_email("user-03@example.com".to_string()),
+        Some(rows[3].clone())
+    );
+    let in_range = table
+        .select_by_pk_range(rows[10].id..rows[20].id)
+        .execute()
+        .unwrap()
+        .into_iter()
+        .map(|row| row.id)
+        .collect::<Vec<_>>();
+    assert_eq!(in_range, rows[10..20].iter().map(|row| row.id).collect::<Vec<_>>());
+    assert_eq!(
+        table
+            .select_by_email_range("user-40@example.com".to_string()..)
+            .execute()
+            .unwrap()
+            .len(),
+        10
+    );
+
+    let duplicate = CustomBackendRow {
+        id: table.get_next_pk().into(),
+        email: "user-03@example.com".to_string(),
+        score: 0,
+    };
+    assert!(table.insert(duplicate.clone()).is_err());
+    assert!(table.select(duplicate.id).is_none());
+    assert_eq!(table.count(), 50);
+
+    let updated = CustomBackendRow {
+        email: "renamed@example.com".to_string(),
+        ..rows[3].clone()
+    };
+    table.update(updated.clone()).await.unwrap();
+    assert!(table.select_by_email("user-03@example.com".to_string()).is_none());
+    assert_eq!(table.select_by_email("renamed@example.com".to_string()), Some(updated));
+
+    table.delete(rows[3].id).await.unwrap();
+    assert!(table.select(rows[3].id).is_none());
+    assert!(table.select_by_email("renamed@example.com".to_string()).is_none());
+    assert_eq!(table.select_by_score(3).execute().unwrap().len(), 9);
+
+    let info = table.system_info();
+    let email_info = info
+        .indexes_info
+        .iter()
+        .find(|index| index.name == "email_idx")
+        .unwrap();
+    assert_eq!(email_info.key_count, 49);
+    assert_eq!(email_info.heap_size, 49 * size_of::<(String, OffsetEqLink)>());
+}
+
+#[tokio::test]
+async fn hash_backend_survives_snapshot_reload_and_further_mutation() {
+    const ROOT: &str = "tests/data/index_backend_hash_persistence";
+    remove_dir_if_exists(ROOT.to_string()).await;
+
+    let config = DiskConfig::new_with_table_name(
+        ROOT,
+        PersistedHashWorkTable::name_snake_case(),
+        PersistedHashWorkTable::version(),
+    );
+    let rows = (-50..50)
+        .map(|account| PersistedHashRow {
+            id: Uuid::new_v4(),
+            account,
+            value: account.unsigned_abs(),
+        })
+        .collect::<Vec<_>>();
+
+    let engine = PersistedHashPersistenceEngine::new(config.clone()).await.unwrap();
+    let table = PersistedHashWorkTable::load(engine).await.unwrap();
+    for row in &rows {
+        table.insert(row.clone()).unwrap();
+    }
+    table.delete(rows[0].id).await.unwrap();
+    table.wait_for_ops().await.unwrap();
+    drop(table);
+
+    let engine = PersistedHashPersistenceEngine::new(config.clone()).await.unwrap();
+    let table = PersistedHashWorkTable::load(engine).await.unwrap();
+    assert_eq!(table.count(), 99);
+    assert!(table.select(rows[0].id).is_none());
+    assert!(table.select_by_account(rows[0].account).is_none());
+    for row in &rows[1..] {
+        assert_eq!(table.select(row.id).as_ref(), Some(row));
+        assert_eq!(table.select_by_account(row.account).as_ref(), Some(row));
+    }
+
+    let moved = PersistedHashRow {
+        account: 1_000,
+        ..rows[1].clone()
+    };
+    table.update(moved.clone()).await.unwrap();
+    table.wait_for_ops().await.unwrap();
+    drop(table);
+
+    let engine = PersistedHashPersistenceEngine::new(config).await.unwrap();
+    let table = PersistedHashWorkTable::load(engine).await.unwrap();
+    assert_eq!(table.count(), 99);
+    assert!(table.select_by_account(rows[1].account).is_none());
+    assert_eq!(table.select_by_account(1_000), Some(moved.clone()));
+    assert_eq!(table.select(moved.id), Some(moved));
+    table.wait_for_ops().await.unwrap();
+    drop(table);
+
+    remove_dir_if_exists(ROOT.to_string()).await;
+}
+
 #[tokio::test]
 async fn upstream_indexset_survives_persist_reload_and_more_writes() {
     const ROOT: &str = "tests/data/index_backend_upstream_runtime";
@@ -392,6 +930,158 @@ async fn native_art_backends_survive_wal_reload_and_further_mutation() {
     remove_dir_if_exists(CONGEE_ROOT.to_string()).await;
 }
 
+#[tokio::test]
+async fn non_unique_art_backends_survive_wal_reload_and_further_mutation() {
+    const ROOT: &str = "tests/data/index_backend_non_unique_art_persistence";
+    remove_dir_if_exists(ROOT.to_string()).await;
+
+    let config = DiskConfig::new_with_table_name(
+        ROOT,
+        PersistedNonUniqueArtWorkTable::name_snake_case(),
+        PersistedNonUniqueArtWorkTable::version(),
+    );
+    let venue_ids = |table: &PersistedNonUniqueArtWorkTable, venue| {
+        let mut ids = table
+            .select_by_venue(venue)
+            .execute()
+            .unwrap()
+            .into_iter()
+            .map(|row| row.id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids
+    };
+
+    let engine = PersistedNonUniqueArtPersistenceEngine::new(config.clone())
+        .await
+        .unwrap();
+    let table = PersistedNonUniqueArtWorkTable::load(engine).await.unwrap();
+    for i in 0..512 {
+        table
+            .insert(PersistedNonUniqueArtRow {
+                id: table.get_next_pk().into(),
+                account: i % 8,
+                venue: i % 3,
+            })
+            .unwrap();
+    }
+    table.delete(3).await.unwrap();
+    table
+        .update(PersistedNonUniqueArtRow {
+            id: 4,
+            account: 100,
+            venue: 2,
+        })
+        .await
+        .unwrap();
+    let expected_venue = venue_ids(&table, 2);
+    table.wait_for_ops().await.unwrap();
+    drop(table);
+
+    let engine = PersistedNonUniqueArtPersistenceEngine::new(config.clone())
+        .await
+        .unwrap();
+    let table = PersistedNonUniqueArtWorkTable::load(engine).await.unwrap();
+    assert_eq!(table.count(), 511);
+    assert_eq!(table.select_by_account(3).execute().unwrap().len(), 63);
+    assert_eq!(table.select_by_account(4).execute().unwrap().len(), 63);
+    assert_eq!(table.select_by_account(100).execute().unwrap()[0].id, 4);
+    assert_eq!(venue_ids(&table, 2), expected_venue);
+
+    table.delete(4).await.unwrap();
+    for id in [10, 11] {
+        table
+            .insert(PersistedNonUniqueArtRow {
+                id: table.get_next_pk().into(),
+                account: 100,
+                venue: id,
+            })
+            .unwrap();
+    }
+    table.wait_for_ops().await.unwrap();
+    drop(table);
+
+    let engine = PersistedNonUniqueArtPersistenceEngine::new(config).await.unwrap();
+    let table = PersistedNonUniqueArtWorkTable::load(engine).await.unwrap();
+    assert_eq!(table.count(), 512);
+    let mut accounts = table
+        .select_by_account(100)
+        .execute()
+        .unwrap()
+        .into_iter()
+        .map(|row| row.venue)
+        .collect::<Vec<_>>();
+    accounts.sort();
+    assert_eq!(accounts, vec![10, 11]);
+    assert_eq!(table.select_by_venue_range(0..=2).execute().unwrap().len(), 510);
+    table.wait_for_ops().await.unwrap();
+    drop(table);
+
+    remove_dir_if_exists(ROOT.to_string()).await;
+}
+
+#[tokio::test]
+async fn encoded_art_keys_survive_wal_reload() {
+    const ROOT: &str = "tests/data/index_backend_encoded_art_persistence";
+    remove_dir_if_exists(ROOT.to_string()).await;
+
+    let config = DiskConfig::new_with_table_name(
+        ROOT,
+        PersistedEncodedArcticWorkTable::name_snake_case(),
+        PersistedEncodedArcticWorkTable::version(),
+    );
+    let rows = (-50..50)
+        .map(|offset| PersistedEncodedArcticRow {
+            id: PackedNanoid21::pack(&Nanoid::new()).unwrap(),
+            external: Uuid::new_v4(),
+            offset,
+        })
+        .collect::<Vec<_>>();
+
+    let engine = PersistedEncodedArcticPersistenceEngine::new(config.clone())
+        .await
+        .unwrap();
+    let table = PersistedEncodedArcticWorkTable::load(engine).await.unwrap();
+    for row in &rows {
+        table.insert(row.clone()).unwrap();
+    }
+    table.delete(rows[0].id).await.unwrap();
+    table.wait_for_ops().await.unwrap();
+    drop(table);
+
+    let engine = PersistedEncodedArcticPersistenceEngine::new(config).await.unwrap();
+    let table = PersistedEncodedArcticWorkTable::load(engine).await.unwrap();
+    assert_eq!(table.count(), 99);
+    assert!(table.select(rows[0].id).is_none());
+    for row in &rows[1..] {
+        assert_eq!(table.select(row.id).as_ref(), Some(row));
+        assert_eq!(table.select_by_external(row.external).as_ref(), Some(row));
+    }
+    let offsets = table
+        .select_by_offset_range(-10..10)
+        .execute()
+        .unwrap()
+        .into_iter()
+        .map(|row| row.offset)
+        .collect::<Vec<_>>();
+    assert_eq!(offsets, (-10..10).collect::<Vec<_>>());
+
+    let ids = table
+        .select_all()
+        .execute()
+        .unwrap()
+        .into_iter()
+        .map(|row| row.id)
+        .collect::<Vec<_>>();
+    let mut sorted = ids.clone();
+    sorted.sort();
+    assert_eq!(ids, sorted);
+    table.wait_for_ops().await.unwrap();
+    drop(table);
+
+    remove_dir_if_exists(ROOT.to_string()).await;
+}
+
 #[tokio::test]
 async fn native_art_backends_recover_concurrent_same_row_updates() {
     use std::sync::Arc;
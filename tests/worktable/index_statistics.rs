@@ -0,0 +1,158 @@
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: Trade,
+    persist: false,
+    columns: {
+        id: u64 primary_key autoincrement,
+        account: u64,
+        venue: u32,
+        order_id: u64,
+        session: u64,
+        price: f64,
+    },
+    indexes: {
+        account_idx: account,
+        venue_idx: venue using arctic,
+        order_idx: order_id unique,
+        session_idx: session unique using hash,
+        price_idx: price,
+    },
+);
+
+fn index_info<'a>(info: &'a SystemInfo, name: &str) -> &'a IndexInfo {
+    info.indexes_info
+        .iter()
+        .find(|index| index.name == name)
+        .expect("index is declared")
+}
+
+fn trade_table() -> TradeWorkTable {
+    let table = TradeWorkTable::default();
+    for i in 0..10_000u64 {
+        table
+            .insert(TradeRow {
+                id: table.get_next_pk().into(),
+                account: i % 100,
+                venue: (i % 4) as u32,
+                order_id: i,
+                session: i * 3,
+                price: (i % 50) as f64 / 2.0,
+            })
+            .unwrap();
+    }
+    table
+}
+
+#[test]
+fn statistics_describe_key_distribution() {
+    let table = trade_table();
+
+    let account = table.statistics_by_account();
+    assert_eq!(account.entries, 10_000);
+    assert!((80..=130).contains(&account.distinct_keys), "{account:?}");
+    assert!(!account.histogram.is_empty());
+    assert!(
+        account
+            .histogram
+            .windows(2)
+            .all(|buckets| buckets[0].upper <= buckets[1].lower)
+    );
+    assert_eq!(account.histogram.first().unwrap().lower, 0);
+    assert_eq!(account.histogram.last().unwrap().upper, 99);
+
+    let venue = table.statistics_by_venue();
+    assert_eq!((venue.entries, venue.distinct_keys), (10_000, 4));
+    assert_eq!(venue.avg_duplicates(), 2500.0);
+
+    let order = table.statistics_by_order_id();
+    assert_eq!((order.entries, order.distinct_keys), (10_000, 10_000));
+    assert!(order.histogram.len() <= 64);
+    assert_eq!(
+        order.histogram.iter().map(|bucket| bucket.entries).sum::<usize>(),
+        10_000
+    );
+
+    let session = table.statistics_by_session();
+    assert_eq!((session.entries, session.distinct_keys), (10_000, 10_000));
+    assert_eq!(session.histogram.first().unwrap().lower, 0);
+    assert_eq!(session.histogram.last().unwrap().upper, 29_997);
+
+    let price = table.statistics_by_price();
+    assert_eq!(price.entries, 10_000);
+    assert_eq!(price.histogram.first().unwrap().lower, OrderedFloat(0.0));
+}
+
+#[test]
+fn estimate_rows_follows_ranges() {
+    let table = trade_table();
+
+    assert_eq!(table.estimate_rows_by_account(..), 10_000);
+    let estimate = table.estimate_rows_by_account(10..30);
+    assert!((1500..=2500).contains(&estimate), "{estimate}");
+    let estimate = table.estimate_rows_by_account(5..=5);
+    assert!((50..=200).contains(&estimate), "{estimate}");
+    assert_eq!(table.estimate_rows_by_account(100..), 0);
+
+    assert_eq!(table.estimate_rows_by_venue(1..=1), 2500);
+    let estimate = table.estimate_rows_by_order_id(1000..2000);
+    assert!((900..=1100).contains(&estimate), "{estimate}");
+    assert_eq!(table.estimate_rows_by_order_id(42..=42), 1);
+    assert_eq!(table.estimate_rows_by_session(30_000..), 0);
+
+    let estimate = table.estimate_rows_by_price(0.0..5.0);
+    assert!((1500..=2500).contains(&estimate), "{estimate}");
+}
+
+#[test]
+fn statistics_are_cached_until_enough_writes() {
+    let table = trade_table();
+    assert_eq!(table.statistics_by_account().entries, 10_000);
+
+    for i in 0..10u64 {
+        table
+            .insert(TradeRow {
+                id: table.get_next_pk().into(),
+                account: 500,
+                venue: 0,
+                order_id: 100_000 + i,
+                session: 100_000 + i,
+                price: 0.0,
+            })
+            .unwrap();
+    }
+    assert_eq!(table.statistics_by_account().entries, 10_000);
+    assert_eq!(table.estimate_rows_by_account(500..), 0);
+
+    table.refresh_statistics();
+    let account = table.statistics_by_account();
+    assert_eq!(account.entries, 10_010);
+    assert_eq!(account.histogram.last().unwrap().upper, 500);
+    assert!(table.estimate_rows_by_account(500..) > 0);
+}
+
+#[tokio::test]
+async fn system_info_reports_distinct_keys() {
+    let table = trade_table();
+
+    let info = table.system_info();
+    let account = index_info(&info, "account_idx");
+    assert!((80..=130).contains(&account.distinct_keys), "{account:?}");
+    assert!((75.0..=125.0).contains(&account.avg_duplicates), "{account:?}");
+    let venue = index_info(&info, "venue_idx");
+    assert_eq!((venue.distinct_keys, venue.avg_duplicates), (4, 2500.0));
+    let order = index_info(&info, "order_idx");
+    assert_eq!((order.distinct_keys, order.avg_duplicates), (10_000, 1.0));
+    assert!(info.to_string().contains("Avg Dup"));
+
+    let rows = table.select_all().execute().unwrap();
+    for row in rows.into_iter().filter(|row| row.account >= 50) {
+        table.delete(row.id).await.unwrap();
+    }
+    let info = table.system_info();
+    let account = index_info(&info, "account_idx");
+    assert!((40..=65).contains(&account.distinct_keys), "{account:?}");
+    assert_eq!(table.estimate_rows_by_account(50..), 0);
+    assert_eq!(index_info(&info, "order_idx").distinct_keys, 5000);
+}
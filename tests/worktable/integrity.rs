@@ -0,0 +1,187 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: Audited,
+    columns: {
+        id: u64 primary_key autoincrement,
+        email: String,
+        bucket: u32,
+    },
+    indexes: {
+        email_idx: email unique,
+        bucket_idx: bucket,
+    },
+    queries: {
+        update: {
+            BucketById(bucket) by id,
+        },
+    }
+);
+
+fn filled(count: u64) -> AuditedWorkTable {
+    let table = AuditedWorkTable::default();
+    for i in 0..count {
+        table
+            .insert(AuditedRow {
+                id: table.get_next_pk().into(),
+                email: format!("user-{i}@example.com"),
+                bucket: (i % 4) as u32,
+            })
+            .unwrap();
+    }
+    table
+}
+
+fn link_of(table: &AuditedWorkTable, id: u64) -> Link {
+    table
+        .0
+        .primary_index
+        .pk_map
+        .get_value(&AuditedPrimaryKey(id))
+        .map(|value| value.0)
+        .unwrap()
+}
+
+#[tokio::test]
+async fn consistent_table_reports_nothing() {
+    let table = filled(100);
+    table.delete(AuditedPrimaryKey(10)).await.unwrap();
+
+    let report = table.verify_integrity(IntegrityCheck::Full);
+    assert!(report.is_consistent(), "{:?}", report.discrepancies);
+    assert_eq!(report.rows_checked, 99);
+    assert_eq!(report.resume_after, None);
+}
+
+#[test]
+fn incremental_checks_cover_the_table_in_batches() {
+    let table = filled(100);
+
+    let mut after = None;
+    let mut rows = 0;
+    let mut batches = 0;
+    loop {
+        let report = table.verify_integrity(IntegrityCheck::Incremental { after, limit: 30 });
+        assert!(report.is_consistent(), "{:?}", report.discrepancies);
+        rows += report.rows_checked;
+        batches += 1;
+        after = report.resume_after;
+        if after.is_none() {
+            break;
+        }
+    }
+    assert_eq!(rows, 100);
+    assert_eq!(batches, 4);
+}
+
+#[test]
+fn every_discrepancy_is_listed() {
+    let table = filled(8);
+
+    // Row 1 loses its unique entry and row 2 its non-unique one.
+    TableIndex::remove(
+        &table.0.indexes.email_idx,
+        &"user-1@example.com".to_string(),
+        link_of(&table, 1),
+    );
+    TableIndex::remove(&table.0.indexes.bucket_idx, &2, link_of(&table, 2));
+    // Row 3 gains an entry under a key it does not hold.
+    TableIndex::insert(&table.0.indexes.bucket_idx, 7, link_of(&table, 3));
+    // The row of key 4 also registered as free space.
+    table.0.data.empty_links_registry().push(link_of(&table, 4));
+
+    let report = table.verify_integrity(IntegrityCheck::Full);
+    let expected = [
+        IntegrityDiscrepancy::SecondaryEntryMissing {
+            index: "email_idx",
+            primary_key: format!("{:?}", AuditedPrimaryKey(1)),
+            link: link_of(&table, 1),
+        },
+        IntegrityDiscrepancy::SecondaryEntryMissing {
+            index: "bucket_idx",
+            primary_key: format!("{:?}", AuditedPrimaryKey(2)),
+            link: link_of(&table, 2),
+        },
+        IntegrityDiscrepancy::SecondaryKeyMismatch {
+            index: "bucket_idx",
+            key: "7".to_string(),
+            link: link_of(&table, 3),
+        },
+        IntegrityDiscrepancy::EntryCountMismatch {
+            index: "email_idx",
+            entries: 7,
+            rows: 8,
+        },
+        IntegrityDiscrepancy::EmptyLinkOverlap {
+            link: link_of(&table, 4),
+            other: link_of(&table, 4),
+        },
+    ];
+    for discrepancy in &expected {
+        assert!(
+            report.discrepancies.contains(discrepancy),
+            "{discrepancy} not in {:?}",
+            report.discrepancies
+        );
+    }
+    assert_eq!(report.discrepancies.len(), expected.len(), "{:?}", report.discrepancies);
+
+    // Incremental checks only look at rows and their index entries.
+    let report = table.verify_integrity(IntegrityCheck::Incremental { after: None, limit: 8 });
+    assert_eq!(report.discrepancies, expected[..2]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn incremental_checks_do_not_flag_concurrent_writes() {
+    let table = Arc::new(filled(500));
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let writer = {
+        let table = table.clone();
+        let stop = stop.clone();
+        tokio::spawn(async move {
+            let mut i = 500u64;
+            while !stop.load(Ordering::Relaxed) {
+                table
+                    .insert(AuditedRow {
+                        id: table.get_next_pk().into(),
+                        email: format!("user-{i}@example.com"),
+                        bucket: (i % 4) as u32,
+                    })
+                    .unwrap();
+                table
+                    .update_bucket_by_id(BucketByIdQuery { bucket: 9 }, AuditedPrimaryKey(i - 400))
+                    .await
+                    .unwrap();
+                table.delete(AuditedPrimaryKey(i - 500)).await.unwrap();
+                i += 1;
+            }
+        })
+    };
+
+    let checker = table.clone();
+    tokio::task::spawn_blocking(move || {
+        for _ in 0..20 {
+            let mut after = None;
+            loop {
+                let report = checker.verify_integrity(IntegrityCheck::Incremental { after, limit: 64 });
+                assert!(report.is_consistent(), "{:?}", report.discrepancies);
+                after = report.resume_after;
+                if after.is_none() {
+                    break;
+                }
+            }
+        }
+    })
+    .await
+    .unwrap();
+    stop.store(true, Ordering::Relaxed);
+    writer.await.unwrap();
+
+    let report = table.verify_integrity(IntegrityCheck::Full);
+    assert!(report.is_consistent(), "{:?}", report.discrepancies);
+}
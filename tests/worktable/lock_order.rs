@@ -89,7 +89,7 @@ async fn multi_row_update_locks_in_primary_key_order_not_index_order() {
     table
         .0
         .lock_manager
-        .insert(blocker_pk.clone(), Arc::new(tokio::sync::RwLock::new(blocker_state)));
+        .insert(blocker_pk.clone(), Arc::new(ParkingRwLock::new(blocker_state)));
 
     let update_table = table.clone();
     let update = tokio::spawn(async move {
@@ -109,7 +109,6 @@ async fn multi_row_update_locks_in_primary_key_order_not_index_order() {
         loop {
             let installed_id = blocker_state
                 .read()
-                .await
                 .value_lock
                 .as_ref()
                 .expect("the update locks value")
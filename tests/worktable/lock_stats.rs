@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: Contended,
+    columns: {
+        id: u64 primary_key autoincrement,
+        value: u64,
+        score: u64,
+    },
+    queries: {
+        update: {
+            ValueById(value) by id,
+            ScoreById(score) by id,
+        },
+        in_place: {
+            ValueById(value) by id,
+        }
+    }
+);
+
+fn column<'a>(info: &'a LockInfo, name: &str) -> &'a LockStatsInfo {
+    &info.columns.iter().find(|c| c.name == name).unwrap().stats
+}
+
+#[tokio::test]
+async fn waits_are_counted_for_table_columns_and_rows() {
+    let table = Arc::new(ContendedWorkTable::default());
+    table
+        .insert(ContendedRow {
+            id: 0,
+            value: 0,
+            score: 0,
+        })
+        .unwrap();
+    table
+        .insert(ContendedRow {
+            id: 1,
+            value: 0,
+            score: 0,
+        })
+        .unwrap();
+
+    // Holds the value column of row 0, like a running in-place update.
+    let lock_manager = table.0.lock_manager.clone();
+    let lock_id = lock_manager.next_id();
+    let lock = lock_manager.get_or_insert_with(0.into(), ContendedLock::new);
+    let (_, op_lock) = lock.write().lock_update_in_place_value_by_id(lock_id);
+    drop(lock);
+    let value_guard = LockGuard::new(op_lock, lock_manager, 0.into());
+
+    table.update_score_by_id(ScoreByIdQuery { score: 1 }, 0).await.unwrap();
+    table.update_value_by_id(ValueByIdQuery { value: 1 }, 1).await.unwrap();
+    let waiter = {
+        let table = table.clone();
+        tokio::spawn(async move { table.update_value_by_id(ValueByIdQuery { value: 2 }, 0).await })
+    };
+    while table.system_info().lock_info.table.waiters == 0 {
+        tokio::task::yield_now().await;
+    }
+
+    assert!(table.system_info().to_string().contains("Locks:"));
+    let info = table.system_info().lock_info;
+    assert_eq!(info.table.acquisitions, 3);
+    assert_eq!(info.table.contended, 1);
+    assert_eq!(column(&info, "value").waiters, 1);
+    assert_eq!(column(&info, "value").acquisitions, 2);
+    assert_eq!(column(&info, "score").contended, 0);
+    assert_eq!(column(&info, "score").acquisitions, 1);
+
+    drop(value_guard);
+    waiter.await.unwrap().unwrap();
+
+    let info = table.system_info().lock_info;
+    assert_eq!(info.table.waiters, 0);
+    assert_eq!(column(&info, "value").wait_histogram.iter().sum::<u64>(), 1);
+    assert_eq!(info.hot_rows.len(), 1);
+    assert_eq!(
+        info.hot_rows[0].primary_key,
+        format!("{:?}", ContendedPrimaryKey::from(0))
+    );
+    assert_eq!(info.hot_rows[0].contended, 1);
+    assert!(!table.system_info().to_string().contains("Locks:"));
+    assert!(format!("{:#}", table.system_info()).contains("Hot rows"));
+
+    table.reset_lock_stats();
+    let info = table.system_info().lock_info;
+    assert_eq!(info.table, LockStatsInfo::default());
+    assert!(info.columns.iter().all(|c| c.stats == LockStatsInfo::default()));
+    assert!(info.hot_rows.is_empty());
+}
+
+#[tokio::test]
+async fn full_row_waits_count_for_waiter_columns() {
+    let table = Arc::new(ContendedWorkTable::default());
+    table
+        .insert(ContendedRow {
+            id: 0,
+            value: 0,
+            score: 0,
+        })
+        .unwrap();
+
+    let lock_manager = table.0.lock_manager.clone();
+    let lock_id = lock_manager.next_id();
+    let lock = lock_manager.get_or_insert_with(0.into(), ContendedLock::new);
+    let (_, op_lock) = lock.write().lock(lock_id);
+    drop(lock);
+    let row_guard = LockGuard::new(op_lock, lock_manager, 0.into());
+
+    table.set_lock_timeout(Some(std::time::Duration::from_millis(10)));
+    assert!(matches!(
+        table.update_score_by_id(ScoreByIdQuery { score: 1 }, 0).await,
+        Err(WorkTableError::LockTimeout)
+    ));
+
+    let info = table.system_info().lock_info;
+    assert_eq!(info.table.timeouts, 1);
+    assert_eq!(column(&info, "score").timeouts, 1);
+    assert_eq!(column(&info, "value").contended, 0);
+    assert!(column(&info, "score").total_wait >= std::time::Duration::from_millis(10));
+    drop(row_guard);
+}
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: Stalled,
+    columns: {
+        id: u64 primary_key autoincrement,
+        value: u64,
+        score: u64,
+    },
+    queries: {
+        update: {
+            ValueById(value) by id,
+            ScoreById(score) by id,
+        },
+        in_place: {
+            ValueById(value) by id,
+        }
+    }
+);
+
+/// Registers a full-row lock on `pk` the way a generated update does and
+/// keeps it until the guard is dropped, standing in for a stuck task.
+async fn hold_row(table: &StalledWorkTable, pk: u64) -> LockGuard<StalledLock, StalledPrimaryKey> {
+    let lock_manager = table.0.lock_manager.clone();
+    let lock_id = lock_manager.next_id();
+    let lock = lock_manager.get_or_insert_with(pk.into(), StalledLock::new);
+    let (_, op_lock) = lock.write().lock(lock_id);
+    LockGuard::new(op_lock, lock_manager, pk.into())
+}
+
+fn row(id: u64) -> StalledRow {
+    StalledRow { id, value: 0, score: 0 }
+}
+
+#[tokio::test]
+async fn updates_time_out_on_held_row_and_resume_after_release() {
+    let table = Arc::new(StalledWorkTable::default());
+    table.insert(row(0)).unwrap();
+    table.insert(row(1)).unwrap();
+    table.set_lock_timeout(Some(Duration::from_millis(50)));
+
+    let stuck = hold_row(&table, 0).await;
+    let mut changed = row(0);
+    changed.value = 1;
+    assert!(matches!(
+        table.update(changed.clone()).await,
+        Err(WorkTableError::LockTimeout)
+    ));
+    assert!(matches!(
+        table.update_value_by_id(ValueByIdQuery { value: 2 }, 0).await,
+        Err(WorkTableError::LockTimeout)
+    ));
+    assert!(matches!(table.delete(0).await, Err(WorkTableError::LockTimeout)));
+
+    // Other rows are not affected by the stuck holder.
+    table.update_value_by_id(ValueByIdQuery { value: 3 }, 1).await.unwrap();
+
+    let held = table.held_locks();
+    let stuck_holder = held
+        .iter()
+        .find(|l| l.waiting_for.is_empty())
+        .expect("the stuck holder is listed");
+    assert_eq!(stuck_holder.primary_key, 0.into());
+    assert!(stuck_holder.columns.is_empty());
+    assert!(
+        held.iter()
+            .filter(|l| !l.waiting_for.is_empty())
+            .all(|l| l.primary_key == 0.into())
+    );
+    assert_eq!(held.len(), 4);
+
+    drop(stuck);
+    table.set_lock_timeout(None);
+    table.update(changed.clone()).await.unwrap();
+    assert_eq!(table.select(0).unwrap(), changed);
+    assert!(table.held_locks().is_empty());
+}
+
+#[tokio::test]
+async fn try_updates_fail_fast_only_on_locked_columns() {
+    let table = StalledWorkTable::default();
+    table.insert(row(0)).unwrap();
+
+    let lock_manager = table.0.lock_manager.clone();
+    let lock_id = lock_manager.next_id();
+    let lock = lock_manager.get_or_insert_with(0.into(), StalledLock::new);
+    let (_, op_lock) = lock.write().lock_update_in_place_value_by_id(lock_id);
+    drop(lock);
+    let value_guard = LockGuard::new(op_lock, lock_manager, 0.into());
+
+    let held = table.held_locks();
+    assert_eq!(held.len(), 1);
+    assert_eq!(held[0].columns, ["value"]);
+
+    // A query on other columns does not wait for the value lock.
+    table
+        .try_update_score_by_id(ScoreByIdQuery { score: 1 }, 0)
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        table.try_update_value_by_id(ValueByIdQuery { value: 5 }, 0).await,
+        Err(WorkTableError::LockTimeout)
+    ));
+    assert!(
+        table
+            .try_update_value_by_id_in_place(|value| *value = 5.into(), 0)
+            .await
+            .is_err()
+    );
+    assert!(matches!(
+        table.try_update(row(0)).await,
+        Err(WorkTableError::LockTimeout)
+    ));
+
+    // The abandoned attempts stay queued behind the holder and release the
+    // row once it is done.
+    drop(value_guard);
+    while !table.held_locks().is_empty() {
+        tokio::task::yield_now().await;
+    }
+    table
+        .try_update_value_by_id(ValueByIdQuery { value: 7 }, 0)
+        .await
+        .unwrap();
+    let current = table.select(0).unwrap();
+    assert_eq!(current.value, 7);
+    assert_eq!(current.score, 1);
+}
@@ -0,0 +1,107 @@
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: Budgeted,
+    columns: {
+        id: u64 primary_key autoincrement,
+        symbol: String,
+        note: String,
+    },
+    indexes: {
+        symbol_idx: symbol,
+    },
+    config: {
+        memory_limit: 131_072,
+    }
+);
+
+fn row(table: &BudgetedWorkTable, i: u64) -> BudgetedRow {
+    BudgetedRow {
+        id: table.get_next_pk().into(),
+        symbol: format!("SYM{}", i % 4),
+        note: "filled by the exchange gateway ".repeat(4),
+    }
+}
+
+#[tokio::test]
+async fn inserts_over_the_limit_are_rejected() {
+    let table = BudgetedWorkTable::default();
+    let mut rows = Vec::new();
+    let error = loop {
+        let row = row(&table, rows.len() as u64);
+        match table.insert(row.clone()) {
+            Ok(_) => rows.push(row),
+            Err(e) => break e,
+        }
+        assert!(rows.len() < 10_000, "the memory limit was never hit");
+    };
+    assert!(matches!(error, WorkTableError::MemoryLimitExceeded));
+
+    let info = table.system_info();
+    assert!(info.resident_data_bytes <= 131_072);
+    assert_eq!(table.count(), rows.len());
+    for expected in &rows {
+        assert_eq!(table.select(expected.id).as_ref(), Some(expected));
+    }
+
+    // Freed slots are reused without another page.
+    let removed = rows.pop().unwrap();
+    table.delete(removed.id).await.unwrap();
+    let reused = row(&table, 0);
+    table.insert(reused.clone()).unwrap();
+    assert_eq!(table.select(reused.id), Some(reused));
+
+    table.set_memory_budget(None);
+    table.insert(row(&table, 1)).unwrap();
+}
+
+worktable!(
+    name: Unbudgeted,
+    columns: {
+        id: u64 primary_key autoincrement,
+        note: String,
+    },
+);
+
+#[test]
+fn budget_can_be_set_at_runtime() {
+    let table = UnbudgetedWorkTable::default();
+    for _ in 0..1_000 {
+        let row = UnbudgetedRow {
+            id: table.get_next_pk().into(),
+            note: "filled by the exchange gateway ".repeat(4),
+        };
+        table.insert(row).unwrap();
+    }
+
+    let info = table.system_info();
+    table.set_memory_budget(Some(MemoryBudget {
+        limit_bytes: info.resident_data_bytes,
+        policy: MemoryLimitPolicy::Reject,
+    }));
+    let error = (0..1_000)
+        .find_map(|_| {
+            table
+                .insert(UnbudgetedRow {
+                    id: table.get_next_pk().into(),
+                    note: "filled by the exchange gateway ".repeat(4),
+                })
+                .err()
+        })
+        .expect("the memory limit was never hit");
+    assert!(matches!(error, WorkTableError::MemoryLimitExceeded));
+    assert_eq!(table.system_info().page_count, info.page_count);
+
+    // Without a page store there is nothing to evict to.
+    table.set_memory_budget(Some(MemoryBudget {
+        limit_bytes: info.resident_data_bytes,
+        policy: MemoryLimitPolicy::EvictColdPages,
+    }));
+    let result = table.insert(UnbudgetedRow {
+        id: table.get_next_pk().into(),
+        note: "filled by the exchange gateway ".repeat(4),
+    });
+    assert!(matches!(result, Err(WorkTableError::MemoryLimitExceeded)));
+    assert_eq!(table.system_info().evicted_pages, 0);
+}
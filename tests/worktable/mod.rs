@@ -1,24 +1,39 @@
 mod array;
 mod base;
 mod bench;
+mod blocking;
 mod borrowed_primary_key;
+mod bulk_mutation;
+mod compression;
 mod config;
 mod count;
 mod custom_pk;
 mod delete;
+mod filtered_index;
 mod float;
+mod hooks;
 mod in_place;
 mod index;
 mod index_backends;
+mod index_statistics;
+mod integrity;
 mod leak_probe;
 mod lock_order;
+mod lock_stats;
+mod lock_timeout;
+mod memory_budget;
 mod mutation_gate_deadlock;
 mod nid;
 mod option;
+mod returning;
+mod select_with;
+mod subscribe;
+mod truncate;
 mod tuple_primary_key;
 mod unsized_;
 mod update_in_place_unsized;
 mod upsert;
+mod upsert_with;
 mod uuid;
 mod vacuum;
 mod vacuum_no_row_loss;
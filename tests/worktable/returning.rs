@@ -0,0 +1,214 @@
+use std::sync::Arc;
+
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: Returning,
+    persist: false,
+    columns: {
+        id: u64 primary_key autoincrement,
+        code: u64,
+        group: u32,
+        name: String,
+        value: i64,
+    },
+    indexes: {
+        code_idx: code unique,
+        group_idx: group,
+    },
+    queries: {
+        update: {
+            ValueById(value) by id,
+            NameByCode(name) by code,
+            ValueByGroup(value) by group,
+        },
+    }
+);
+
+worktable!(
+    name: Counter,
+    persist: false,
+    columns: {
+        id: u64 primary_key,
+        hits: u64,
+        misses: u64,
+    },
+    queries: {
+        update: {
+            HitsById(hits) by id,
+            MissesById(misses) by id,
+        },
+    }
+);
+
+fn row(table: &ReturningWorkTable, code: u64, group: u32) -> ReturningRow {
+    ReturningRow {
+        id: table.get_next_pk().into(),
+        code,
+        group,
+        name: format!("row-{code}"),
+        value: code as i64,
+    }
+}
+
+#[tokio::test]
+async fn update_returning_gives_old_and_new_rows() {
+    let table = ReturningWorkTable::default();
+    let original = row(&table, 1, 1);
+    table.insert(original.clone()).unwrap();
+
+    let mut changed = original.clone();
+    changed.name = "a much longer name than before".to_string();
+    changed.value = -5;
+    let updated = table.update_returning(changed.clone()).await.unwrap();
+
+    assert_eq!(updated.old, original);
+    assert_eq!(updated.new, changed);
+    assert_eq!(table.select(original.id).unwrap(), changed);
+}
+
+#[tokio::test]
+async fn update_returning_missing_row_is_not_found() {
+    let table = ReturningWorkTable::default();
+    let missing = row(&table, 1, 1);
+
+    let res = table.update_returning(missing).await;
+
+    assert!(matches!(res, Err(WorkTableError::NotFound)));
+}
+
+#[tokio::test]
+async fn upsert_returning_reports_insert_and_update() {
+    let table = ReturningWorkTable::default();
+    let original = row(&table, 7, 2);
+
+    assert_eq!(table.upsert_returning(original.clone()).await.unwrap(), None);
+
+    let mut changed = original.clone();
+    changed.value = 700;
+    let previous = table.upsert_returning(changed.clone()).await.unwrap();
+
+    assert_eq!(previous, Some(original));
+    assert_eq!(table.select(changed.id).unwrap(), changed);
+}
+
+#[tokio::test]
+async fn delete_returning_gives_deleted_row() {
+    let table = ReturningWorkTable::default();
+    let original = row(&table, 3, 1);
+    table.insert(original.clone()).unwrap();
+
+    let deleted = table.delete_returning(original.id).await.unwrap();
+
+    assert_eq!(deleted, original);
+    assert!(table.select(original.id).is_none());
+    assert!(table.select_by_code(3).is_none());
+    assert!(matches!(
+        table.delete_returning(original.id).await,
+        Err(WorkTableError::NotFound)
+    ));
+}
+
+#[tokio::test]
+async fn custom_update_by_pk_returning() {
+    let table = ReturningWorkTable::default();
+    let original = row(&table, 4, 1);
+    table.insert(original.clone()).unwrap();
+
+    let updated = table
+        .update_value_by_id_returning(ValueByIdQuery { value: 40 }, original.id)
+        .await
+        .unwrap();
+
+    assert_eq!(updated.old, original);
+    assert_eq!(updated.new.value, 40);
+    assert_eq!(updated.new.name, original.name);
+    assert_eq!(table.select(original.id).unwrap(), updated.new);
+}
+
+#[tokio::test]
+async fn custom_update_by_unique_index_returning() {
+    let table = ReturningWorkTable::default();
+    let original = row(&table, 5, 1);
+    table.insert(original.clone()).unwrap();
+
+    let updated = table
+        .update_name_by_code_returning(
+            NameByCodeQuery {
+                name: "renamed".to_string(),
+            },
+            5,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(updated.old, original);
+    assert_eq!(updated.new.name, "renamed");
+    assert_eq!(table.select(original.id).unwrap(), updated.new);
+}
+
+#[tokio::test]
+async fn custom_update_by_non_unique_index_returning() {
+    let table = ReturningWorkTable::default();
+    for code in 0..6 {
+        table.insert(row(&table, code, (code % 2) as u32)).unwrap();
+    }
+
+    let mut updated = table
+        .update_value_by_group_returning(ValueByGroupQuery { value: 100 }, 1)
+        .await
+        .unwrap();
+    updated.sort_by_key(|u| u.old.code);
+
+    assert_eq!(updated.iter().map(|u| u.old.code).collect::<Vec<_>>(), vec![1, 3, 5]);
+    for u in &updated {
+        assert_eq!(u.old.value, u.old.code as i64);
+        assert_eq!(u.new.value, 100);
+        assert_eq!(table.select(u.old.id).unwrap(), u.new);
+    }
+    assert_eq!(table.select_by_code(0).unwrap().value, 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn custom_update_returning_reports_the_stored_row_under_concurrent_updates() {
+    let table = Arc::new(CounterWorkTable::default());
+    table
+        .insert(CounterRow {
+            id: 1,
+            hits: 0,
+            misses: 0,
+        })
+        .unwrap();
+
+    let writer = {
+        let table = table.clone();
+        tokio::spawn(async move {
+            for misses in 1..=500 {
+                table.update_misses_by_id(MissesByIdQuery { misses }, 1).await.unwrap();
+            }
+        })
+    };
+    let mut last_misses = 0;
+    for hits in 1..=500 {
+        let updated = table
+            .update_hits_by_id_returning(HitsByIdQuery { hits }, 1)
+            .await
+            .unwrap();
+        assert_eq!(updated.old.hits, hits - 1);
+        assert_eq!(updated.new.hits, hits);
+        assert_eq!(updated.old.misses, updated.new.misses);
+        assert!(updated.new.misses >= last_misses);
+        last_misses = updated.new.misses;
+    }
+    writer.await.unwrap();
+
+    assert_eq!(
+        table.select(1).unwrap(),
+        CounterRow {
+            id: 1,
+            hits: 500,
+            misses: 500
+        }
+    );
+}
@@ -0,0 +1,114 @@
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: Quote,
+    persist: false,
+    columns: {
+        id: u64 primary_key autoincrement,
+        symbol: String,
+        venue: String,
+        price: i64,
+        note: String,
+    },
+    indexes: {
+        symbol_idx: symbol unique,
+        venue_idx: venue,
+    },
+    queries: {
+        update: {
+            PriceById(price) by id,
+        },
+        delete: {
+            ById() by id,
+        }
+    }
+);
+
+fn quote(table: &QuoteWorkTable, i: i64) -> QuoteRow {
+    QuoteRow {
+        id: table.get_next_pk().into(),
+        symbol: format!("SYM{i}"),
+        venue: format!("VENUE{}", i % 3),
+        price: i * 10,
+        note: "quoted by the exchange gateway ".repeat(8),
+    }
+}
+
+#[tokio::test]
+async fn select_with_reads_archived_row() {
+    let table = QuoteWorkTable::default();
+    let row = quote(&table, 1);
+    let pk = table.insert(row.clone()).unwrap();
+
+    assert_eq!(table.select_with(pk.clone(), |r| r.price.to_native()), Some(10));
+    assert_eq!(
+        table.select_with(pk.clone(), |r| r.symbol.as_str().to_string()),
+        Some(row.symbol.clone())
+    );
+    assert_eq!(table.select_with(u64::MAX, |r| r.price.to_native()), None);
+
+    table
+        .update_price_by_id(PriceByIdQuery { price: 25 }, pk.clone())
+        .await
+        .unwrap();
+    assert_eq!(table.select_with(pk.clone(), |r| r.price.to_native()), Some(25));
+
+    table.delete(pk.clone()).await.unwrap();
+    assert_eq!(table.select_with(pk, |r| r.price.to_native()), None);
+}
+
+#[tokio::test]
+async fn select_by_index_with_reads_archived_rows() {
+    let table = QuoteWorkTable::default();
+    for i in 0..30 {
+        table.insert(quote(&table, i)).unwrap();
+    }
+
+    assert_eq!(
+        table.select_by_symbol_with("SYM7".to_string(), |r| r.price.to_native()),
+        Some(70)
+    );
+    assert_eq!(
+        table.select_by_symbol_with("SYM99".to_string(), |r| r.price.to_native()),
+        None
+    );
+
+    let mut prices = table.select_by_venue_with("VENUE1".to_string(), |r| r.price.to_native());
+    prices.sort();
+    assert_eq!(
+        prices,
+        (0..30).filter(|i| i % 3 == 1).map(|i| i * 10).collect::<Vec<_>>()
+    );
+    assert!(table.select_by_venue_with("VENUE9".to_string(), |_| ()).is_empty());
+
+    let row = table.select_by_symbol("SYM4".to_string()).unwrap();
+    table.delete(row.id).await.unwrap();
+    assert_eq!(table.select_by_symbol_with("SYM4".to_string(), |_| ()), None);
+    assert_eq!(table.select_by_venue_with("VENUE1".to_string(), |_| ()).len(), 9);
+}
+
+#[test]
+fn iter_with_ref_visits_every_row() {
+    let table = QuoteWorkTable::default();
+    for i in 0..500 {
+        table.insert(quote(&table, i)).unwrap();
+    }
+
+    let total = std::sync::atomic::AtomicI64::new(0);
+    table
+        .iter_with_ref(|r| {
+            total.fetch_add(r.price.to_native(), std::sync::atomic::Ordering::Relaxed);
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(total.into_inner(), (0..500).map(|i| i * 10).sum::<i64>());
+
+    let res = table.iter_with_ref(|r| {
+        if r.symbol.as_str() == "SYM3" {
+            return Err(WorkTableError::NotFound);
+        }
+        Ok(())
+    });
+    assert!(matches!(res, Err(WorkTableError::NotFound)));
+}
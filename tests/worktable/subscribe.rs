@@ -0,0 +1,276 @@
+use std::sync::Arc;
+
+use futures::StreamExt;
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: Feed,
+    persist: false,
+    columns: {
+        id: u64 primary_key autoincrement,
+        code: u64,
+        group: u32,
+        name: String,
+        value: i64,
+    },
+    indexes: {
+        code_idx: code unique,
+        group_idx: group,
+    },
+    queries: {
+        update: {
+            ValueById(value) by id,
+            NameByCode(name) by code,
+            ValueByGroup(value) by group,
+        },
+    }
+);
+
+fn row(table: &FeedWorkTable, code: u64, group: u32) -> FeedRow {
+    FeedRow {
+        id: table.get_next_pk().into(),
+        code,
+        group,
+        name: format!("row-{code}"),
+        value: code as i64,
+    }
+}
+
+fn drain(
+    subscription: &mut ChangeSubscription<FeedRow, FeedPrimaryKey>,
+) -> Vec<RowChangeEvent<FeedRow, FeedPrimaryKey>> {
+    let mut events = Vec::new();
+    while let Some(event) = subscription.try_recv().unwrap() {
+        events.push(event);
+    }
+    events
+}
+
+#[tokio::test]
+async fn subscriber_receives_insert_update_and_delete() {
+    let table = FeedWorkTable::default();
+    let mut changes = table.subscribe();
+
+    let original = row(&table, 1, 1);
+    table.insert(original.clone()).unwrap();
+    let mut changed = original.clone();
+    changed.name = "a much longer name than before".to_string();
+    table.update(changed.clone()).await.unwrap();
+    table.delete(original.id).await.unwrap();
+
+    let events = drain(&mut changes);
+    assert_eq!(
+        events.iter().map(|e| e.change.clone()).collect::<Vec<_>>(),
+        vec![
+            RowChange::Inserted(original.clone()),
+            RowChange::Updated {
+                old: original.clone(),
+                new: changed,
+            },
+            RowChange::Deleted(original.id.into()),
+        ]
+    );
+    assert_eq!(events.iter().map(|e| e.seq).collect::<Vec<_>>(), vec![1, 2, 3]);
+}
+
+#[tokio::test]
+async fn custom_updates_report_full_row_versions() {
+    let table = FeedWorkTable::default();
+    let first = row(&table, 1, 7);
+    let second = row(&table, 2, 7);
+    table.insert(first.clone()).unwrap();
+    table.insert(second.clone()).unwrap();
+    let mut changes = table.subscribe();
+
+    table
+        .update_value_by_id(ValueByIdQuery { value: 10 }, first.id)
+        .await
+        .unwrap();
+    table
+        .update_name_by_code(
+            NameByCodeQuery {
+                name: "renamed to something longer".to_string(),
+            },
+            2,
+        )
+        .await
+        .unwrap();
+    table
+        .update_value_by_group(ValueByGroupQuery { value: -1 }, 7)
+        .await
+        .unwrap();
+
+    let updates: Vec<_> = drain(&mut changes)
+        .into_iter()
+        .map(|e| match e.change {
+            RowChange::Updated { old, new } => (old, new),
+            other => panic!("unexpected change {other:?}"),
+        })
+        .collect();
+    assert_eq!(updates.len(), 4);
+    assert_eq!(updates[0].0, first);
+    assert_eq!(updates[0].1.value, 10);
+    assert_eq!(updates[1].0, second);
+    assert_eq!(updates[1].1.name, "renamed to something longer");
+    for (old, new) in &updates[2..] {
+        assert_eq!(new.value, -1);
+        assert_eq!(table.select(old.id).unwrap(), *new);
+    }
+}
+
+#[tokio::test]
+async fn upsert_truncate_and_predicate_mutations_are_reported() {
+    let table = FeedWorkTable::default();
+    let mut changes = table.subscribe();
+
+    let original = row(&table, 1, 1);
+    table.upsert(original.clone()).await.unwrap();
+    let mut changed = original.clone();
+    changed.value = 5;
+    table.upsert(changed.clone()).await.unwrap();
+    table.insert(row(&table, 2, 2)).unwrap();
+    assert_eq!(table.delete_where(|r| r.code == 2).await.unwrap(), 1);
+    table.truncate().unwrap();
+
+    let kinds: Vec<_> = drain(&mut changes)
+        .into_iter()
+        .map(|e| match e.change {
+            RowChange::Inserted(_) => "inserted",
+            RowChange::Updated { .. } => "updated",
+            RowChange::Deleted(_) => "deleted",
+        })
+        .collect();
+    assert_eq!(kinds, vec!["inserted", "updated", "inserted", "deleted", "deleted"]);
+}
+
+#[tokio::test]
+async fn failed_mutations_are_not_reported() {
+    let table = FeedWorkTable::default();
+    let original = row(&table, 1, 1);
+    table.insert(original.clone()).unwrap();
+    let mut changes = table.subscribe();
+
+    let mut duplicate = row(&table, 1, 2);
+    assert!(table.insert(duplicate.clone()).is_err());
+    duplicate.id = 1_000;
+    assert!(table.update(duplicate).await.is_err());
+    assert!(table.delete(1_000).await.is_err());
+
+    assert!(drain(&mut changes).is_empty());
+}
+
+#[tokio::test]
+async fn slow_subscriber_detects_lag() {
+    let table = FeedWorkTable::default();
+    let mut changes = table.subscribe();
+
+    for code in 0..(DEFAULT_CHANGE_FEED_CAPACITY as u64 + 10) {
+        table.insert(row(&table, code, 0)).unwrap();
+    }
+
+    assert_eq!(changes.recv().await, Err(ChangeFeedError::Lagged(10)));
+    let next = changes.recv().await.unwrap();
+    assert_eq!(next.seq, 11);
+}
+
+#[tokio::test]
+async fn stream_ends_when_table_is_dropped() {
+    let table = FeedWorkTable::default();
+    let stream = table.subscribe().into_stream();
+
+    table.insert(row(&table, 1, 1)).unwrap();
+    table.insert(row(&table, 2, 1)).unwrap();
+    drop(table);
+
+    let events: Vec<_> = stream.collect().await;
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|e| matches!(
+        e,
+        Ok(RowChangeEvent {
+            change: RowChange::Inserted(_),
+            ..
+        })
+    )));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_updates_of_one_key_are_reported_in_commit_order() {
+    let table = Arc::new(FeedWorkTable::default());
+    let original = row(&table, 1, 1);
+    table.insert(original.clone()).unwrap();
+    let mut changes = table.subscribe();
+
+    let mut tasks = Vec::new();
+    for worker in 0..4 {
+        let table = table.clone();
+        let id = original.id;
+        tasks.push(tokio::spawn(async move {
+            for i in 0..50 {
+                table
+                    .update_value_by_id(
+                        ValueByIdQuery {
+                            value: worker * 1_000 + i,
+                        },
+                        id,
+                    )
+                    .await
+                    .unwrap();
+            }
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    let events = drain(&mut changes);
+    assert_eq!(events.len(), 200);
+    let mut previous = original;
+    for event in events {
+        let RowChange::Updated { old, new } = event.change else {
+            panic!("unexpected change");
+        };
+        assert_eq!(old, previous);
+        previous = new;
+    }
+    assert_eq!(table.select(previous.id).unwrap(), previous);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_full_row_updates_of_one_key_are_reported_in_commit_order() {
+    let table = Arc::new(FeedWorkTable::default());
+    let original = row(&table, 1, 1);
+    table.insert(original.clone()).unwrap();
+    let mut changes = table.subscribe();
+
+    let mut tasks = Vec::new();
+    for worker in 0..4 {
+        let table = table.clone();
+        let original = original.clone();
+        tasks.push(tokio::spawn(async move {
+            for i in 0..50 {
+                // Names of varying length also move the row on reinsert.
+                let mut changed = original.clone();
+                changed.value = worker * 1_000 + i;
+                changed.name = "x".repeat((worker * 7 + i) as usize % 40);
+                table.update(changed).await.unwrap();
+            }
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    let events = drain(&mut changes);
+    assert_eq!(events.len(), 200);
+    assert!(events.windows(2).all(|w| w[0].seq < w[1].seq));
+    let mut previous = original;
+    for event in events {
+        let RowChange::Updated { old, new } = event.change else {
+            panic!("unexpected change");
+        };
+        assert_eq!(old, previous);
+        previous = new;
+    }
+    assert_eq!(table.select(previous.id).unwrap(), previous);
+}
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: Truncate,
+    persist: false,
+    columns: {
+        id: u64 primary_key autoincrement,
+        code: u64,
+        bucket: u32,
+        name: String,
+    },
+    indexes: {
+        code_idx: code unique,
+        bucket_idx: bucket,
+    },
+);
+
+fn fill(table: &TruncateWorkTable, from: u64, count: u64) {
+    for code in from..from + count {
+        table
+            .insert(TruncateRow {
+                id: table.get_next_pk().into(),
+                code,
+                bucket: (code % 4) as u32,
+                name: format!("row-{code}"),
+            })
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn truncate_removes_rows_and_index_entries() {
+    let table = TruncateWorkTable::default();
+    fill(&table, 0, 1_000);
+
+    table.truncate().unwrap();
+
+    assert_eq!(table.count(), 0);
+    assert!(table.select(0).is_none());
+    assert!(table.select_by_code(10).is_none());
+    assert!(table.select_by_bucket(1).execute().unwrap().is_empty());
+    assert_eq!(table.system_info().page_count, 1);
+}
+
+#[tokio::test]
+async fn table_is_usable_after_truncate() {
+    let table = TruncateWorkTable::default();
+    fill(&table, 0, 100);
+    table.truncate().unwrap();
+
+    // Previously used unique values are free again and the generator keeps
+    // counting from where it stopped.
+    fill(&table, 0, 50);
+
+    assert_eq!(table.count(), 50);
+    assert_eq!(table.select_by_code(0).unwrap().id, 100);
+    assert_eq!(table.select_by_bucket(1).execute().unwrap().len(), 13);
+
+    let mut row = table.select_by_code(7).unwrap();
+    row.name = "a considerably longer name than before".to_string();
+    table.update(row.clone()).await.unwrap();
+    assert_eq!(table.select(row.id).unwrap(), row);
+    table.delete(row.id).await.unwrap();
+    assert_eq!(table.count(), 49);
+}
+
+#[tokio::test]
+async fn truncate_empty_table() {
+    let table = TruncateWorkTable::default();
+
+    table.truncate().unwrap();
+    table.truncate().unwrap();
+
+    assert_eq!(table.count(), 0);
+    fill(&table, 0, 3);
+    assert_eq!(table.count(), 3);
+}
+
+#[tokio::test]
+async fn truncate_fails_while_vacuum_runs() {
+    let table = TruncateWorkTable::default();
+    fill(&table, 0, 10);
+
+    let vacuum = table.0.data.empty_links_registry().lock_vacuum().await;
+    assert!(matches!(
+        table.truncate(),
+        Err(WorkTableError::PagesError(
+            worktable::in_memory::PagesExecutionError::Locked
+        ))
+    ));
+    assert_eq!(table.count(), 10);
+    assert_eq!(table.select_by_code(3).unwrap().name, "row-3");
+
+    drop(vacuum);
+    table.truncate().unwrap();
+    assert_eq!(table.count(), 0);
+}
+
+/// Writers racing the truncate either land before it and are removed, or
+/// after it and survive; none may leave a partial row behind.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn truncate_with_concurrent_writers_leaves_consistent_state() {
+    let table = Arc::new(TruncateWorkTable::default());
+    fill(&table, 0, 500);
+
+    let writers = (0..4u64)
+        .map(|w| {
+            let table = table.clone();
+            tokio::spawn(async move {
+                for i in 0..250u64 {
+                    let code = 10_000 + w * 1_000 + i;
+                    let _ = table.insert(TruncateRow {
+                        id: table.get_next_pk().into(),
+                        code,
+                        bucket: 9,
+                        name: format!("row-{code}"),
+                    });
+                    if let Some(mut row) = table.select_by_code(code) {
+                        row.name.push_str("-updated");
+                        let _ = table.update(row).await;
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    tokio::task::yield_now().await;
+    table.truncate().unwrap();
+    for w in writers {
+        w.await.unwrap();
+    }
+
+    let rows = table.select_all().execute().unwrap();
+    assert_eq!(rows.len(), table.count());
+    for row in rows {
+        assert_eq!(table.select_by_code(row.code).unwrap(), row);
+    }
+    assert_eq!(table.select_by_bucket(9).execute().unwrap().len(), table.count());
+}
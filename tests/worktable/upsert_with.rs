@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use worktable::prelude::*;
+use worktable::worktable;
+
+worktable!(
+    name: Accumulator,
+    persist: false,
+    columns: {
+        id: u64 primary_key,
+        code: u64,
+        qty: u64,
+        note: String,
+    },
+    indexes: {
+        code_idx: code unique,
+    },
+);
+
+fn row(id: u64, code: u64, qty: u64) -> AccumulatorRow {
+    AccumulatorRow {
+        id,
+        code,
+        qty,
+        note: format!("note-{id}"),
+    }
+}
+
+#[tokio::test]
+async fn upsert_with_inserts_missing_and_merges_existing() {
+    let table = AccumulatorWorkTable::default();
+
+    table
+        .upsert_with(row(1, 10, 5), |existing, incoming| existing.qty += incoming.qty)
+        .await
+        .unwrap();
+    table
+        .upsert_with(row(1, 10, 7), |existing, incoming| existing.qty += incoming.qty)
+        .await
+        .unwrap();
+
+    let stored = table.select(1).unwrap();
+    assert_eq!(stored.qty, 12);
+    assert_eq!(stored.note, "note-1");
+}
+
+#[tokio::test]
+async fn upsert_with_rejects_primary_key_change() {
+    let table = AccumulatorWorkTable::default();
+    table.insert(row(1, 10, 5)).unwrap();
+
+    let res = table.upsert_with(row(1, 10, 1), |existing, _| existing.id = 2).await;
+
+    assert!(matches!(res, Err(WorkTableError::PrimaryUpdateTry)));
+    assert_eq!(table.select(1).unwrap().qty, 5);
+}
+
+#[tokio::test]
+async fn upsert_with_reports_unique_index_conflict() {
+    let table = AccumulatorWorkTable::default();
+    table.insert(row(1, 10, 5)).unwrap();
+    table.insert(row(2, 20, 5)).unwrap();
+
+    let res = table.upsert_with(row(2, 20, 1), |existing, _| existing.code = 10).await;
+
+    assert!(matches!(res, Err(WorkTableError::AlreadyExists(_))));
+    assert_eq!(table.select_by_code(20).unwrap().id, 2);
+    assert_eq!(table.select_by_code(10).unwrap().id, 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_upsert_with_never_loses_an_increment() {
+    let table = Arc::new(AccumulatorWorkTable::default());
+
+    let tasks = (0..8)
+        .map(|_| {
+            let table = table.clone();
+            tokio::spawn(async move {
+                for _ in 0..200 {
+                    table
+                        .upsert_with(row(1, 10, 1), |existing, incoming| existing.qty += incoming.qty)
+                        .await
+                        .unwrap();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    assert_eq!(table.select(1).unwrap().qty, 1_600);
+}
+
+#[test]
+fn insert_or_ignore_returns_existing_primary_key() {
+    let table = AccumulatorWorkTable::default();
+
+    assert_eq!(table.insert_or_ignore(row(1, 10, 5)).unwrap(), 1.into());
+    assert_eq!(table.insert_or_ignore(row(1, 11, 9)).unwrap(), 1.into());
+
+    let stored = table.select(1).unwrap();
+    assert_eq!(stored.qty, 5);
+    assert_eq!(stored.code, 10);
+    assert!(table.select_by_code(11).is_none());
+}
+
+#[test]
+fn insert_or_ignore_returns_primary_key_of_unique_index_conflict() {
+    let table = AccumulatorWorkTable::default();
+    table.insert(row(1, 10, 5)).unwrap();
+
+    assert_eq!(table.insert_or_ignore(row(2, 10, 9)).unwrap(), 1.into());
+    assert!(table.select(2).is_none());
+    assert_eq!(table.count(), 1);
+}
@@ -142,6 +142,49 @@ async fn vacuum_parallel_with_inserts() {
     delete_task.await.unwrap();
 }
 
+#[tokio::test]
+async fn shrink_to_fit_releases_vacuumed_pages() {
+    let table = VacuumTestWorkTable::default();
+    let mut rows = Vec::new();
+    for i in 0..3000 {
+        let row = VacuumTestRow {
+            id: table.get_next_pk().into(),
+            value: i,
+            data: format!("test_data_{}", i),
+        };
+        table.insert(row.clone()).unwrap();
+        rows.push(row);
+    }
+    for row in rows.drain(..2700) {
+        table.delete(row.id).await.unwrap();
+    }
+
+    let stats = table.vacuum().vacuum().await.unwrap();
+    assert!(stats.pages_freed > 0);
+    let before = table.system_info();
+    let released = table.shrink_to_fit();
+    assert!(released > 0);
+    let after = table.system_info();
+    assert_eq!(after.memory_usage_bytes, before.memory_usage_bytes - released);
+    assert!(after.idx_size < before.idx_size);
+
+    for row in &rows {
+        assert_eq!(table.select(row.id).as_ref(), Some(row));
+    }
+    for i in 3000..3500 {
+        let row = VacuumTestRow {
+            id: table.get_next_pk().into(),
+            value: i,
+            data: format!("test_data_{}", i),
+        };
+        table.insert(row.clone()).unwrap();
+        rows.push(row);
+    }
+    for row in &rows {
+        assert_eq!(table.select(row.id).as_ref(), Some(row));
+    }
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
 async fn vacuum_parallel_with_upserts() {
     let config = VacuumManagerConfig {